@@ -0,0 +1,168 @@
+// Accessibility bridge - announces key state changes through the platform screen reader.
+// The frameless, short-lived windows are hard to follow with VoiceOver/Narrator/Orca,
+// so important transitions are announced from Rust instead of relying on focus changes.
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::Emitter;
+use tracing::{info, warn};
+
+static ANNOUNCEMENTS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementPriority {
+  Low,
+  Medium,
+  High,
+}
+
+pub fn set_enabled(enabled: bool) {
+  ANNOUNCEMENTS_ENABLED.store(enabled, Ordering::Relaxed);
+  info!(
+    "♿ Screen reader announcements {}",
+    if enabled { "enabled" } else { "disabled" }
+  );
+}
+
+pub fn is_enabled() -> bool {
+  ANNOUNCEMENTS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Announce a message to screen reader users.
+/// Also emits `a11y-announcement` so open webviews can mirror it in an aria-live region.
+pub fn announce(app: &tauri::AppHandle, message: &str, priority: AnnouncementPriority) {
+  if !is_enabled() {
+    return;
+  }
+
+  info!("♿ Announcing: {}", message);
+  let _ = app.emit(
+    "a11y-announcement",
+    serde_json::json!({ "message": message, "priority": priority }),
+  );
+
+  let message = message.to_string();
+  #[cfg(target_os = "macos")]
+  {
+    // AppKit accessibility calls must happen on the main thread
+    let result = app.run_on_main_thread(move || {
+      if let Err(e) = platform_announce(&message, priority) {
+        warn!("⚠️ Screen reader announcement failed: {}", e);
+      }
+    });
+    if let Err(e) = result {
+      warn!("⚠️ Failed to dispatch announcement to main thread: {}", e);
+    }
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    // Speech fallbacks spawn a process - keep them off the caller's thread
+    std::thread::spawn(move || {
+      if let Err(e) = platform_announce(&message, priority) {
+        warn!("⚠️ Screen reader announcement failed: {}", e);
+      }
+    });
+  }
+}
+
+// VoiceOver: NSAccessibilityAnnouncementRequestedNotification on the application element
+#[cfg(target_os = "macos")]
+fn platform_announce(message: &str, priority: AnnouncementPriority) -> Result<(), String> {
+  use cocoa::base::{id, nil};
+  use cocoa::foundation::NSString;
+  use objc::{class, msg_send, sel, sel_impl};
+
+  #[link(name = "AppKit", kind = "framework")]
+  extern "C" {
+    static NSAccessibilityAnnouncementRequestedNotification: id;
+    static NSAccessibilityAnnouncementKey: id;
+    static NSAccessibilityPriorityKey: id;
+    fn NSAccessibilityPostNotificationWithUserInfo(element: id, notification: id, user_info: id);
+  }
+
+  // NSAccessibilityPriorityLow/Medium/High
+  let priority_value: i64 = match priority {
+    AnnouncementPriority::Low => 10,
+    AnnouncementPriority::Medium => 50,
+    AnnouncementPriority::High => 90,
+  };
+
+  unsafe {
+    let text: id = NSString::alloc(nil).init_str(message);
+    let priority_number: id = msg_send![class!(NSNumber), numberWithInteger: priority_value];
+    let keys = [NSAccessibilityAnnouncementKey, NSAccessibilityPriorityKey];
+    let values = [text, priority_number];
+    let user_info: id = msg_send![
+      class!(NSDictionary),
+      dictionaryWithObjects: values.as_ptr()
+      forKeys: keys.as_ptr()
+      count: 2usize
+    ];
+    let application: id = msg_send![class!(NSApplication), sharedApplication];
+    NSAccessibilityPostNotificationWithUserInfo(
+      application,
+      NSAccessibilityAnnouncementRequestedNotification,
+      user_info,
+    );
+    let _: () = msg_send![text, release];
+  }
+  Ok(())
+}
+
+// Narrator/NVDA/JAWS: only speak when Windows reports an active screen reader
+#[cfg(target_os = "windows")]
+fn platform_announce(message: &str, _priority: AnnouncementPriority) -> Result<(), String> {
+  #[link(name = "user32")]
+  extern "system" {
+    fn SystemParametersInfoW(action: u32, param: u32, pv_param: *mut i32, win_ini: u32) -> i32;
+  }
+  const SPI_GETSCREENREADER: u32 = 0x0046;
+
+  let mut screen_reader_active: i32 = 0;
+  unsafe {
+    SystemParametersInfoW(SPI_GETSCREENREADER, 0, &mut screen_reader_active, 0);
+  }
+  if screen_reader_active == 0 {
+    return Ok(());
+  }
+
+  let escaped = message.replace('\'', "''");
+  let script = format!(
+    "Add-Type -AssemblyName System.Speech; \
+     (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+    escaped
+  );
+  std::process::Command::new("powershell")
+    .args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script])
+    .status()
+    .map_err(|e| format!("Failed to run speech synthesizer: {}", e))?;
+  Ok(())
+}
+
+// Orca: speak through speech-dispatcher when the desktop screen reader is enabled
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_announce(message: &str, priority: AnnouncementPriority) -> Result<(), String> {
+  let screen_reader_enabled = std::process::Command::new("gsettings")
+    .args([
+      "get",
+      "org.gnome.desktop.a11y.applications",
+      "screen-reader-enabled",
+    ])
+    .output()
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "true")
+    .unwrap_or(false);
+  if !screen_reader_enabled {
+    return Ok(());
+  }
+
+  let spd_priority = match priority {
+    AnnouncementPriority::Low => "progress",
+    AnnouncementPriority::Medium => "message",
+    AnnouncementPriority::High => "important",
+  };
+  std::process::Command::new("spd-say")
+    .args(["--priority", spd_priority, message])
+    .status()
+    .map_err(|e| format!("Failed to run spd-say: {}", e))?;
+  Ok(())
+}
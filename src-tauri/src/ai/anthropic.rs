@@ -0,0 +1,106 @@
+// Anthropic Messages API
+use super::{
+  provider_error, sse, AiProvider, AnalysisError, AnalysisRequest, AnalysisResult, ProviderKind,
+};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use serde_json::json;
+use std::time::Instant;
+
+const ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
+const API_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 4096;
+
+pub struct AnthropicProvider {
+  api_key: String,
+  network: NetworkSettings,
+}
+
+impl AnthropicProvider {
+  pub fn new(api_key: String, network: NetworkSettings) -> Self {
+    Self { api_key, network }
+  }
+}
+
+impl AiProvider for AnthropicProvider {
+  async fn analyze(
+    &self,
+    model: &str,
+    request: &AnalysisRequest,
+    on_chunk: &mut (dyn FnMut(&str) + Send),
+  ) -> Result<AnalysisResult, AnalysisError> {
+    let started = Instant::now();
+
+    let mut content = Vec::new();
+    if let Some(image_data) = &request.image_data {
+      content.push(json!({
+          "type": "image",
+          "source": {
+              "type": "base64",
+              "media_type": super::media_type(image_data),
+              "data": super::base64_payload(image_data)
+          }
+      }));
+    }
+    content.push(json!({ "type": "text", "text": request.prompt }));
+    let mut body = json!({
+        "model": model,
+        "max_tokens": MAX_TOKENS,
+        // Anthropic's range is 0..1
+        "temperature": request.temperature.min(1.0),
+        "stream": true,
+        "messages": [{ "role": "user", "content": content }]
+    });
+    if let Some(system_prompt) = &request.system_prompt {
+      body["system"] = json!(system_prompt);
+    }
+
+    let response = network::http_client(&self.network)?
+      .post(ENDPOINT)
+      .header("x-api-key", &self.api_key)
+      .header("anthropic-version", API_VERSION)
+      .json(&body)
+      .timeout(super::ANALYZE_TIMEOUT)
+      .send_with_retry()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      return Err(provider_error(ProviderKind::Anthropic, status, &body));
+    }
+
+    let mut answer = String::new();
+    let mut input_tokens = None;
+    let mut output_tokens = None;
+    sse::for_each_event(response, |event| {
+      match event["type"].as_str() {
+        Some("content_block_delta") => {
+          if let Some(text) = event["delta"]["text"].as_str() {
+            on_chunk(text);
+            answer.push_str(text);
+          }
+        }
+        Some("message_start") => input_tokens = event["message"]["usage"]["input_tokens"].as_u64(),
+        Some("message_delta") => output_tokens = event["usage"]["output_tokens"].as_u64(),
+        Some("error") => {
+          let message = format!(
+            "Anthropic error: {}",
+            event["error"]["message"]
+              .as_str()
+              .unwrap_or("unknown error")
+          );
+          // Mid-stream overload/server errors are the streaming form of a 5xx
+          return Err(match event["error"]["type"].as_str() {
+            Some("overloaded_error" | "api_error") => AnalysisError::Unavailable(message),
+            _ => AnalysisError::Failed(message),
+          });
+        }
+        _ => {}
+      }
+      Ok(())
+    })
+    .await?;
+
+    let usage = json!({ "inputTokens": input_tokens, "outputTokens": output_tokens });
+    Ok(super::finish(request, answer, Some(usage), started))
+  }
+}
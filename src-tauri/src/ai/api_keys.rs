@@ -0,0 +1,101 @@
+// User-supplied provider API keys (and other secrets such as share credentials, stored
+// by name). They go to the OS credential store when there is one, otherwise into
+// api_keys.json encrypted with the machine-bound key.
+use super::ProviderKind;
+use crate::auth::keychain;
+use crate::auth::machine_key::{self, SealedData};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const KEYS_FILE: &str = "api_keys.json";
+
+pub struct ApiKeyStore {
+  path: PathBuf,
+  use_keychain: bool,
+}
+
+impl ApiKeyStore {
+  pub fn new(dir: &Path) -> Self {
+    Self {
+      path: dir.join(KEYS_FILE),
+      use_keychain: keychain::is_available(),
+    }
+  }
+
+  fn account(name: &str) -> String {
+    format!("api-key-{}", name)
+  }
+
+  pub fn get(&self, kind: ProviderKind) -> Result<Option<String>, String> {
+    self.get_named(kind.as_str())
+  }
+
+  pub fn set(&self, kind: ProviderKind, key: &str) -> Result<(), String> {
+    if !kind.needs_api_key() {
+      return Err(format!("{} doesn't use an API key", kind.display_name()));
+    }
+    self.set_named(kind.as_str(), key)
+  }
+
+  pub fn remove(&self, kind: ProviderKind) -> Result<(), String> {
+    self.remove_named(kind.as_str())
+  }
+
+  pub fn get_named(&self, name: &str) -> Result<Option<String>, String> {
+    if self.use_keychain {
+      keychain::get_secret(&Self::account(name))
+    } else {
+      Ok(self.read_file()?.remove(name))
+    }
+  }
+
+  pub fn set_named(&self, name: &str, key: &str) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+      return Err("API key cannot be empty".to_string());
+    }
+    if self.use_keychain {
+      keychain::set_secret(&Self::account(name), key)
+    } else {
+      let mut keys = self.read_file()?;
+      keys.insert(name.to_string(), key.to_string());
+      self.write_file(&keys)
+    }
+  }
+
+  pub fn remove_named(&self, name: &str) -> Result<(), String> {
+    if self.use_keychain {
+      keychain::delete_secret(&Self::account(name))
+    } else {
+      let mut keys = self.read_file()?;
+      if keys.remove(name).is_some() {
+        self.write_file(&keys)?;
+      }
+      Ok(())
+    }
+  }
+
+  fn read_file(&self) -> Result<HashMap<String, String>, String> {
+    if !self.path.exists() {
+      return Ok(HashMap::new());
+    }
+    let data = fs::read(&self.path).map_err(|e| format!("Failed to read API keys: {}", e))?;
+    let sealed: SealedData =
+      serde_json::from_slice(&data).map_err(|e| format!("Failed to parse API keys: {}", e))?;
+    serde_json::from_slice(&machine_key::open(&sealed)?)
+      .map_err(|e| format!("Failed to parse API keys: {}", e))
+  }
+
+  fn write_file(&self, keys: &HashMap<String, String>) -> Result<(), String> {
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json =
+      serde_json::to_vec(keys).map_err(|e| format!("Failed to serialize API keys: {}", e))?;
+    let sealed = serde_json::to_vec_pretty(&machine_key::seal(&json)?)
+      .map_err(|e| format!("Failed to serialize API keys: {}", e))?;
+    fs::write(&self.path, sealed).map_err(|e| format!("Failed to save API keys: {}", e))
+  }
+}
@@ -0,0 +1,257 @@
+// Cost estimates for AI requests: an approximate token count (no tokenizer vocabularies
+// are bundled, so text is counted the way BPE tokenizers split it - roughly one token
+// per 4 letters, one per symbol), each provider's image token formula, and a price table
+// in USD per million tokens. Actual spend is tracked per day in spend.json.
+use super::ProviderKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+const SPEND_FILE: &str = "spend.json";
+const SPEND_HISTORY_DAYS: usize = 90;
+// Typical answer length, used when projecting a request's cost
+pub const EXPECTED_OUTPUT_TOKENS: u64 = 400;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+  pub input_per_million: f64,
+  pub output_per_million: f64,
+}
+
+const fn price(input_per_million: f64, output_per_million: f64) -> ModelPricing {
+  ModelPricing {
+    input_per_million,
+    output_per_million,
+  }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct ImageDims {
+  pub width: u32,
+  pub height: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+  pub model: String,
+  pub prompt_tokens: u64,
+  pub image_tokens: u64,
+  pub expected_output_tokens: u64,
+  // None when the model isn't in the price table
+  pub pricing: Option<ModelPricing>,
+  pub estimated_cost_usd: Option<f64>,
+  pub today_spend_usd: f64,
+}
+
+/// Approximate token count of `text` for BPE tokenizers
+pub fn estimate_tokens(text: &str) -> u64 {
+  let mut tokens = 0u64;
+  let mut word_length = 0u64;
+  for c in text.chars() {
+    if c.is_ascii_alphanumeric() {
+      word_length += 1;
+      continue;
+    }
+    tokens += word_length.div_ceil(4);
+    word_length = 0;
+    // Whitespace merges into the next word; symbols and non-Latin characters rarely do
+    if !c.is_whitespace() {
+      tokens += 1;
+    }
+  }
+  tokens + word_length.div_ceil(4)
+}
+
+/// Tokens an image costs with `model`, following each provider's published formula
+pub fn image_tokens(model: &str, dims: ImageDims) -> u64 {
+  let (width, height) = (dims.width.max(1) as f64, dims.height.max(1) as f64);
+  let name = normalized(model);
+  if name.contains("claude") || provider(model) == Some(ProviderKind::Anthropic) {
+    // Scaled to fit 1568px, then width * height / 750
+    let scale = (1568.0 / width.max(height)).min(1.0);
+    ((width * scale) * (height * scale) / 750.0).ceil() as u64
+  } else if name.contains("gemini") || provider(model) == Some(ProviderKind::Google) {
+    258
+  } else if name.contains("pixtral") || name.contains("mistral") {
+    // 16px patches, scaled to fit 1024px
+    let scale = (1024.0 / width.max(height)).min(1.0);
+    ((width * scale / 16.0).ceil() * (height * scale / 16.0).ceil()) as u64
+  } else {
+    // OpenAI high detail: fit 2048, shortest side to 768, 170 per 512px tile + 85
+    let scale = (2048.0 / width.max(height)).min(1.0);
+    let (width, height) = (width * scale, height * scale);
+    let scale = (768.0 / width.min(height)).min(1.0);
+    let tiles = (width * scale / 512.0).ceil() * (height * scale / 512.0).ceil();
+    (85.0 + 170.0 * tiles) as u64
+  }
+}
+
+/// Price of `model` per million tokens; local models are free
+pub fn pricing_for(model: &str) -> Option<ModelPricing> {
+  if provider(model) == Some(ProviderKind::Ollama) {
+    return Some(price(0.0, 0.0));
+  }
+  let name = normalized(model);
+  let has = |fragment: &str| name.contains(fragment);
+  let pricing = if has("gpt-4o-mini") {
+    price(0.15, 0.60)
+  } else if has("gpt-4o") {
+    price(2.50, 10.00)
+  } else if has("gpt-3.5") {
+    price(0.50, 1.50)
+  } else if has("opus") {
+    price(15.00, 75.00)
+  } else if has("sonnet") {
+    price(3.00, 15.00)
+  } else if has("haiku") {
+    price(0.25, 1.25)
+  } else if has("gemini") && has("flash") {
+    price(0.075, 0.30)
+  } else if has("gemini") && has("pro") {
+    price(1.25, 5.00)
+  } else if has("jamba") && has("mini") {
+    price(0.20, 0.40)
+  } else if has("jamba") && has("large") {
+    price(2.00, 8.00)
+  } else if has("pixtral") {
+    price(0.15, 0.15)
+  } else if has("mistral") && has("small") {
+    price(0.20, 0.60)
+  } else if has("mistral") && has("medium") {
+    price(0.40, 2.00)
+  } else if has("mistral") && has("large") {
+    price(2.00, 6.00)
+  } else {
+    return None;
+  };
+  Some(pricing)
+}
+
+pub fn cost_of(model: &str, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+  pricing_for(model).map(|pricing| {
+    (input_tokens as f64 * pricing.input_per_million
+      + output_tokens as f64 * pricing.output_per_million)
+      / 1_000_000.0
+  })
+}
+
+/// Projected cost of sending `prompt` (and an image of `image_dims`) to `model`
+pub fn estimate(
+  model: &str,
+  prompt: &str,
+  image_dims: Option<ImageDims>,
+  today_spend_usd: f64,
+) -> CostEstimate {
+  let prompt_tokens = estimate_tokens(prompt);
+  let image_tokens = image_dims.map_or(0, |dims| image_tokens(model, dims));
+  CostEstimate {
+    model: model.to_string(),
+    prompt_tokens,
+    image_tokens,
+    expected_output_tokens: EXPECTED_OUTPUT_TOKENS,
+    pricing: pricing_for(model),
+    estimated_cost_usd: cost_of(model, prompt_tokens + image_tokens, EXPECTED_OUTPUT_TOKENS),
+    today_spend_usd,
+  }
+}
+
+fn provider(model: &str) -> Option<ProviderKind> {
+  super::split_model(model).map(|(kind, _)| kind)
+}
+
+// "openai:GPT-4o Mini" -> "gpt-4o-mini"
+fn normalized(model: &str) -> String {
+  let name = super::split_model(model).map_or(model, |(_, name)| name);
+  name.trim().to_lowercase().replace([' ', '_'], "-")
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySpend {
+  pub date: String, // YYYY-MM-DD, local time
+  pub cost_usd: f64,
+}
+
+pub struct SpendTracker {
+  storage_path: Option<PathBuf>,
+  days: BTreeMap<String, f64>,
+}
+
+impl SpendTracker {
+  pub fn new() -> Self {
+    Self {
+      storage_path: None,
+      days: BTreeMap::new(),
+    }
+  }
+
+  // Load the saved spend from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(SPEND_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(days) => self.days = days,
+        Err(e) => warn!("⚠️ Ignoring unreadable spend history: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn record(&mut self, cost_usd: f64) -> Result<(), String> {
+    if cost_usd <= 0.0 {
+      return Ok(());
+    }
+    *self.days.entry(today()).or_default() += cost_usd;
+    while self.days.len() > SPEND_HISTORY_DAYS {
+      self.days.pop_first();
+    }
+    self.persist()
+  }
+
+  pub fn today(&self) -> f64 {
+    self.days.get(&today()).copied().unwrap_or(0.0)
+  }
+
+  /// The last `days` days with any spend, newest first
+  pub fn recent(&self, days: usize) -> Vec<DailySpend> {
+    self
+      .days
+      .iter()
+      .rev()
+      .take(days)
+      .map(|(date, cost_usd)| DailySpend {
+        date: date.clone(),
+        cost_usd: *cost_usd,
+      })
+      .collect()
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.days)
+      .map_err(|e| format!("Failed to serialize spend history: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save spend history: {}", e))
+  }
+}
+
+impl Default for SpendTracker {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+fn today() -> String {
+  chrono::Local::now().format("%Y-%m-%d").to_string()
+}
@@ -0,0 +1,123 @@
+// Fallback chains: when the chosen model is unreachable, times out or has a server error,
+// the request is retried on the next model of its chain (or the configured defaults). Without any configuration
+// the cheapest other model the user has access to is tried once.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+const SETTINGS_FILE: &str = "ai_fallback.json";
+const MAX_FALLBACKS: usize = 2;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FallbackSettings {
+  pub enabled: bool,
+  // Model -> models to try after it, in order
+  pub chains: HashMap<String, Vec<String>>,
+  // Tried for models without a chain of their own
+  pub default_fallbacks: Vec<String>,
+}
+
+impl Default for FallbackSettings {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      chains: HashMap::new(),
+      default_fallbacks: Vec::new(),
+    }
+  }
+}
+
+impl FallbackSettings {
+  /// Models to try after `model` fails, best first. `available` are the user's tier
+  /// models, used when nothing is configured.
+  pub fn candidates(&self, model: &str, available: &[String]) -> Vec<String> {
+    if !self.enabled {
+      return Vec::new();
+    }
+    let configured = self
+      .chains
+      .get(model)
+      .filter(|chain| !chain.is_empty())
+      .unwrap_or(&self.default_fallbacks);
+    let mut candidates: Vec<String> = if configured.is_empty() {
+      cheapest(model, available).into_iter().collect()
+    } else {
+      configured.clone()
+    };
+    let mut seen = vec![model.to_string()];
+    candidates.retain(|candidate| {
+      let new = !seen.contains(candidate);
+      seen.push(candidate.clone());
+      new
+    });
+    candidates.truncate(MAX_FALLBACKS);
+    candidates
+  }
+}
+
+// Cheapest priced model in `available` other than `model`
+fn cheapest(model: &str, available: &[String]) -> Option<String> {
+  available
+    .iter()
+    .filter(|candidate| candidate.as_str() != model)
+    .filter_map(|candidate| {
+      super::cost::cost_of(candidate, 1_000_000, 1_000_000).map(|cost| (candidate, cost))
+    })
+    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    .map(|(candidate, _)| candidate.clone())
+}
+
+pub struct FallbackStore {
+  settings: FallbackSettings,
+  storage_path: Option<PathBuf>,
+}
+
+impl FallbackStore {
+  pub fn new() -> Self {
+    Self {
+      settings: FallbackSettings::default(),
+      storage_path: None,
+    }
+  }
+
+  // Load saved settings from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(SETTINGS_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(settings) => self.settings = settings,
+        Err(e) => warn!("⚠️ Ignoring unreadable fallback settings: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn settings(&self) -> &FallbackSettings {
+    &self.settings
+  }
+
+  pub fn update(&mut self, settings: FallbackSettings) -> Result<(), String> {
+    self.settings = settings;
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.settings)
+      .map_err(|e| format!("Failed to serialize fallback settings: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save fallback settings: {}", e))
+  }
+}
+
+impl Default for FallbackStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
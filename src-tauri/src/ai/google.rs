@@ -0,0 +1,82 @@
+// Google Gemini API (generativelanguage.googleapis.com)
+use super::{
+  provider_error, sse, AiProvider, AnalysisError, AnalysisRequest, AnalysisResult, ProviderKind,
+};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use serde_json::json;
+use std::time::Instant;
+
+const API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+pub struct GoogleProvider {
+  api_key: String,
+  network: NetworkSettings,
+}
+
+impl GoogleProvider {
+  pub fn new(api_key: String, network: NetworkSettings) -> Self {
+    Self { api_key, network }
+  }
+}
+
+impl AiProvider for GoogleProvider {
+  async fn analyze(
+    &self,
+    model: &str,
+    request: &AnalysisRequest,
+    on_chunk: &mut (dyn FnMut(&str) + Send),
+  ) -> Result<AnalysisResult, AnalysisError> {
+    let started = Instant::now();
+
+    let mut parts = vec![json!({ "text": request.prompt })];
+    if let Some(image_data) = &request.image_data {
+      parts.push(json!({
+          "inline_data": {
+              "mime_type": super::media_type(image_data),
+              "data": super::base64_payload(image_data)
+          }
+      }));
+    }
+    let mut body = json!({
+        "contents": [{ "role": "user", "parts": parts }],
+        "generationConfig": { "temperature": request.temperature }
+    });
+    if let Some(system_prompt) = &request.system_prompt {
+      body["systemInstruction"] = json!({ "parts": [{ "text": system_prompt }] });
+    }
+
+    let response = network::http_client(&self.network)?
+      .post(format!(
+        "{}/{}:streamGenerateContent?alt=sse",
+        API_BASE, model
+      ))
+      .header("x-goog-api-key", &self.api_key)
+      .json(&body)
+      .timeout(super::ANALYZE_TIMEOUT)
+      .send_with_retry()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      return Err(provider_error(ProviderKind::Google, status, &body));
+    }
+
+    let mut answer = String::new();
+    let mut usage = None;
+    sse::for_each_event(response, |event| {
+      if let Some(parts) = event["candidates"][0]["content"]["parts"].as_array() {
+        for text in parts.iter().filter_map(|part| part["text"].as_str()) {
+          on_chunk(text);
+          answer.push_str(text);
+        }
+      }
+      if !event["usageMetadata"].is_null() {
+        usage = Some(event["usageMetadata"].clone());
+      }
+      Ok(())
+    })
+    .await?;
+
+    Ok(super::finish(request, answer, usage, started))
+  }
+}
@@ -0,0 +1,552 @@
+// AI analysis from Rust: captures go straight to the backend's analyze endpoint with the
+// user's token, so flows like Alt+C -> answer work without the frontend doing the
+// request. The answer streams back in chunks (forwarded as they arrive) and the whole
+// result is returned once complete.
+//
+// Models with a provider prefix (`openai:gpt-4o`, `ollama:llava`, ...) bypass the
+// backend and go to that provider directly, with the user's own API key.
+use tracing::info;
+
+mod anthropic;
+pub mod api_keys;
+pub mod cost;
+pub mod fallback;
+mod google;
+pub mod ollama;
+mod openai;
+pub mod preferences;
+pub mod rate_limit;
+pub mod response_cache;
+mod sse;
+pub mod translate;
+
+use crate::network::{self, NetworkError, NetworkSettings, SendWithRetry};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+// Long answers from slow models still finish; the 30s client default is too short
+const ANALYZE_TIMEOUT: Duration = Duration::from_secs(180);
+// Lines of the stream carrying usage metadata instead of answer text
+const USAGE_MARKER: &str = "[usage]";
+
+/// Analysis failure. `Unavailable` (timeout, connection trouble, 5xx) says nothing about
+/// the request, so another model may answer it; `Failed` (bad key, quota, bad input)
+/// would fail the same way on a fallback.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalysisError {
+  Unavailable(String),
+  Failed(String),
+}
+
+impl AnalysisError {
+  pub fn is_unavailable(&self) -> bool {
+    matches!(self, AnalysisError::Unavailable(_))
+  }
+
+  // 5xx means the model host is struggling, anything else is about the request
+  fn from_status(status: reqwest::StatusCode, message: String) -> Self {
+    if status.is_server_error() {
+      AnalysisError::Unavailable(message)
+    } else {
+      AnalysisError::Failed(message)
+    }
+  }
+}
+
+impl std::fmt::Display for AnalysisError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      AnalysisError::Unavailable(message) | AnalysisError::Failed(message) => {
+        write!(f, "{}", message)
+      }
+    }
+  }
+}
+
+impl From<String> for AnalysisError {
+  fn from(message: String) -> Self {
+    AnalysisError::Failed(message)
+  }
+}
+
+impl From<NetworkError> for AnalysisError {
+  fn from(error: NetworkError) -> Self {
+    if error.is_retryable() {
+      AnalysisError::Unavailable(error.to_string())
+    } else {
+      AnalysisError::Failed(error.to_string())
+    }
+  }
+}
+
+impl From<AnalysisError> for String {
+  fn from(error: AnalysisError) -> Self {
+    error.to_string()
+  }
+}
+
+/// A model host answering analysis requests directly (not through the backend)
+pub trait AiProvider {
+  /// Answer `request` with `model` (the name without the provider prefix), passing
+  /// answer text to `on_chunk` as it streams in
+  async fn analyze(
+    &self,
+    model: &str,
+    request: &AnalysisRequest,
+    on_chunk: &mut (dyn FnMut(&str) + Send),
+  ) -> Result<AnalysisResult, AnalysisError>;
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+  Ollama,
+  #[serde(rename = "openai")]
+  OpenAi,
+  Anthropic,
+  Google,
+  Mistral,
+}
+
+impl ProviderKind {
+  pub const ALL: [ProviderKind; 5] = [
+    ProviderKind::Ollama,
+    ProviderKind::OpenAi,
+    ProviderKind::Anthropic,
+    ProviderKind::Google,
+    ProviderKind::Mistral,
+  ];
+
+  // Also the model prefix, e.g. `anthropic:claude-3-5-sonnet-latest`
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ProviderKind::Ollama => "ollama",
+      ProviderKind::OpenAi => "openai",
+      ProviderKind::Anthropic => "anthropic",
+      ProviderKind::Google => "google",
+      ProviderKind::Mistral => "mistral",
+    }
+  }
+
+  pub fn display_name(&self) -> &'static str {
+    match self {
+      ProviderKind::Ollama => "Ollama",
+      ProviderKind::OpenAi => "OpenAI",
+      ProviderKind::Anthropic => "Anthropic",
+      ProviderKind::Google => "Google",
+      ProviderKind::Mistral => "Mistral",
+    }
+  }
+
+  pub fn needs_api_key(&self) -> bool {
+    *self != ProviderKind::Ollama
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderInfo {
+  pub id: ProviderKind,
+  pub name: String,
+  pub needs_api_key: bool,
+  pub configured: bool,
+}
+
+/// `openai:gpt-4o` -> (OpenAI, "gpt-4o"); backend models have no provider prefix
+pub fn split_model(model: &str) -> Option<(ProviderKind, &str)> {
+  let (prefix, name) = model.split_once(':')?;
+  ProviderKind::ALL
+    .into_iter()
+    .find(|kind| kind.as_str() == prefix)
+    .map(|kind| (kind, name))
+}
+
+/// Answer with a provider directly. `api_key` is required for every provider but Ollama.
+pub async fn analyze_direct(
+  kind: ProviderKind,
+  model: &str,
+  request: &AnalysisRequest,
+  api_key: Option<String>,
+  network: NetworkSettings,
+  on_chunk: &mut (dyn FnMut(&str) + Send),
+) -> Result<AnalysisResult, AnalysisError> {
+  if kind == ProviderKind::Ollama {
+    return ollama::OllamaProvider::new()
+      .analyze(model, request, on_chunk)
+      .await;
+  }
+  let api_key = api_key.ok_or_else(|| format!("Add your {} API key first", kind.display_name()))?;
+  match kind {
+    ProviderKind::OpenAi => {
+      openai::OpenAiCompatibleProvider::openai(api_key, network)
+        .analyze(model, request, on_chunk)
+        .await
+    }
+    ProviderKind::Mistral => {
+      openai::OpenAiCompatibleProvider::mistral(api_key, network)
+        .analyze(model, request, on_chunk)
+        .await
+    }
+    ProviderKind::Anthropic => {
+      anthropic::AnthropicProvider::new(api_key, network)
+        .analyze(model, request, on_chunk)
+        .await
+    }
+    ProviderKind::Google => {
+      google::GoogleProvider::new(api_key, network)
+        .analyze(model, request, on_chunk)
+        .await
+    }
+    ProviderKind::Ollama => unreachable!(),
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisRequest {
+  // PNG as a data URL or plain base64; text-only questions have none
+  pub image_data: Option<String>,
+  pub prompt: String,
+  pub model: String,
+  pub temperature: f32,
+  pub system_prompt: Option<String>,
+  pub conversation_id: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisResult {
+  pub content: String,
+  pub model: String,
+  pub conversation_id: Option<String>,
+  // Token counts etc. as reported by the backend, if it did
+  pub usage: Option<serde_json::Value>,
+  pub duration_ms: u64,
+  // Set when a fallback model answered instead of the requested one
+  pub requested_model: Option<String>,
+  pub fallback_error: Option<String>,
+  // Answered from the response cache, without a new request
+  pub cached: bool,
+}
+
+impl AnalysisResult {
+  /// (input, output) token counts from whichever usage format the answer came with
+  pub fn token_counts(&self) -> (Option<u64>, Option<u64>) {
+    let Some(usage) = &self.usage else {
+      return (None, None);
+    };
+    let first = |keys: &[&str]| keys.iter().find_map(|key| usage[*key].as_u64());
+    (
+      first(&[
+        "inputTokens",
+        "input_tokens",
+        "promptTokens",
+        "prompt_tokens",
+        "promptTokenCount",
+      ]),
+      first(&[
+        "outputTokens",
+        "output_tokens",
+        "completionTokens",
+        "completion_tokens",
+        "candidatesTokenCount",
+      ]),
+    )
+  }
+}
+
+pub struct AiClient {
+  api_url: String,
+  network: NetworkSettings,
+}
+
+impl AiClient {
+  pub fn new(api_url: &str, network: NetworkSettings) -> Self {
+    Self {
+      api_url: api_url.to_string(),
+      network,
+    }
+  }
+
+  /// Send one question (with an optional capture) and collect the streamed answer.
+  /// `on_chunk` sees every piece of answer text as it arrives.
+  pub async fn analyze(
+    &self,
+    token: &str,
+    request: &AnalysisRequest,
+    mut on_chunk: impl FnMut(&str),
+  ) -> Result<AnalysisResult, AnalysisError> {
+    if request.prompt.trim().is_empty() && request.image_data.is_none() {
+      return Err(AnalysisError::Failed("Nothing to analyze".to_string()));
+    }
+    let started = Instant::now();
+
+    let mut form = MultipartForm::new();
+    form.text("question", &request.prompt);
+    form.text(
+      "conversationId",
+      request.conversation_id.as_deref().unwrap_or(""),
+    );
+    form.text("model", &request.model);
+    form.text("temperature", &request.temperature.to_string());
+    if let Some(system_prompt) = &request.system_prompt {
+      form.text("systemPrompt", system_prompt);
+    }
+    if let Some(image_data) = &request.image_data {
+      form.file(
+        "image",
+        "screenshot.png",
+        "image/png",
+        &decode_image(image_data)?,
+      );
+    }
+    let (content_type, body) = form.finish();
+
+    let mut response = network::http_client(&self.network)?
+      .post(format!("{}/api/analyze", self.api_url))
+      .header("Authorization", format!("Bearer {}", token))
+      .header("Content-Type", content_type)
+      .body(body)
+      .timeout(ANALYZE_TIMEOUT)
+      .send_with_retry()
+      .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      return Err(describe_error(status, &body));
+    }
+
+    let mut stream = UsageFilter::default();
+    // Chunks can split UTF-8 sequences; only decode complete ones
+    let mut pending = Vec::new();
+    while let Some(chunk) = response
+      .chunk()
+      .await
+      .map_err(|e| AnalysisError::Unavailable(format!("Analysis interrupted: {}", e)))?
+    {
+      pending.extend_from_slice(&chunk);
+      let valid_up_to = match std::str::from_utf8(&pending) {
+        Ok(text) => text.len(),
+        Err(e) => e.valid_up_to(),
+      };
+      let text = String::from_utf8_lossy(&pending[..valid_up_to]).into_owned();
+      pending.drain(..valid_up_to);
+      stream.push(&text, &mut on_chunk);
+    }
+    stream.finish(&mut on_chunk);
+
+    Ok(finish(request, stream.content, stream.usage, started))
+  }
+}
+
+// Splits the backend stream into answer text and `[usage] {...}` lines. The marker can
+// arrive split over chunks and with answer text in the same chunk, so text is passed on
+// line by line - except a partial line is released as soon as it can't be a marker line,
+// to keep streaming smooth.
+#[derive(Default)]
+struct UsageFilter {
+  line: String,
+  content: String,
+  usage: Option<serde_json::Value>,
+}
+
+impl UsageFilter {
+  fn push(&mut self, text: &str, on_chunk: &mut dyn FnMut(&str)) {
+    self.line.push_str(text);
+    while let Some(end) = self.line.find('\n') {
+      let line: String = self.line.drain(..=end).collect();
+      self.take_line(line, on_chunk);
+    }
+    let start = self.line.trim_start();
+    if !USAGE_MARKER.starts_with(start) && !start.starts_with(USAGE_MARKER) {
+      let text = std::mem::take(&mut self.line);
+      self.emit(&text, on_chunk);
+    }
+  }
+
+  fn finish(&mut self, on_chunk: &mut dyn FnMut(&str)) {
+    let line = std::mem::take(&mut self.line);
+    self.take_line(line, on_chunk);
+  }
+
+  fn take_line(&mut self, line: String, on_chunk: &mut dyn FnMut(&str)) {
+    if line.trim_start().starts_with(USAGE_MARKER) {
+      self.usage = parse_usage(&line).or(self.usage.take());
+    } else {
+      self.emit(&line, on_chunk);
+    }
+  }
+
+  fn emit(&mut self, text: &str, on_chunk: &mut dyn FnMut(&str)) {
+    if !text.is_empty() {
+      on_chunk(text);
+      self.content.push_str(text);
+    }
+  }
+}
+
+fn finish(
+  request: &AnalysisRequest,
+  content: String,
+  usage: Option<serde_json::Value>,
+  started: Instant,
+) -> AnalysisResult {
+  info!(
+    "🤖 Analysis with {} finished in {}ms ({} chars)",
+    request.model,
+    started.elapsed().as_millis(),
+    content.len()
+  );
+  AnalysisResult {
+    content,
+    model: request.model.clone(),
+    conversation_id: request.conversation_id.clone(),
+    usage,
+    duration_ms: started.elapsed().as_millis() as u64,
+    requested_model: None,
+    fallback_error: None,
+    cached: false,
+  }
+}
+
+// Error bodies of all four APIs look like {"error": {"message": ...}}
+fn provider_error(kind: ProviderKind, status: reqwest::StatusCode, body: &str) -> AnalysisError {
+  let message = serde_json::from_str::<serde_json::Value>(body)
+    .ok()
+    .and_then(|json| json["error"]["message"].as_str().map(str::to_string));
+  let message = match status.as_u16() {
+    401 | 403 => format!("{} rejected the API key", kind.display_name()),
+    429 => format!("{} rate limit or quota reached", kind.display_name()),
+    _ => format!(
+      "{} error: {}",
+      kind.display_name(),
+      message.unwrap_or(status.to_string())
+    ),
+  };
+  AnalysisError::from_status(status, message)
+}
+
+// Same messages for every failure the backend can report
+fn describe_error(status: reqwest::StatusCode, body: &str) -> AnalysisError {
+  let message = serde_json::from_str::<serde_json::Value>(body)
+    .ok()
+    .and_then(|json| {
+      json
+        .get("message")
+        .or_else(|| json.get("error"))
+        .and_then(|message| message.as_str())
+        .map(str::to_string)
+    });
+  let message = match status.as_u16() {
+    401 => "Session expired - sign in again".to_string(),
+    402 | 403 => message.unwrap_or("This model isn't included in your plan".to_string()),
+    413 => "Capture is too large to analyze".to_string(),
+    429 => message.unwrap_or("Usage limit reached".to_string()),
+    _ => message.unwrap_or(format!("Analysis failed ({})", status)),
+  };
+  AnalysisError::from_status(status, message)
+}
+
+// `[usage] {...}` -> the JSON part
+fn parse_usage(text: &str) -> Option<serde_json::Value> {
+  let json = text.split(USAGE_MARKER).nth(1)?.trim();
+  serde_json::from_str(json).ok()
+}
+
+// Data URL or plain base64 -> the base64 part
+fn base64_payload(image_data: &str) -> &str {
+  match image_data.split_once(";base64,") {
+    Some((_, data)) => data,
+    None => image_data,
+  }
+}
+
+// `data:image/jpeg;base64,...` -> image/jpeg; captures are PNG otherwise
+fn media_type(image_data: &str) -> &str {
+  image_data
+    .strip_prefix("data:")
+    .and_then(|rest| rest.split_once(";base64,"))
+    .map(|(media_type, _)| media_type)
+    .unwrap_or("image/png")
+}
+
+fn data_url(image_data: &str) -> String {
+  if image_data.starts_with("data:") {
+    image_data.to_string()
+  } else {
+    format!("data:image/png;base64,{}", image_data)
+  }
+}
+
+/// Pixel size of a data-URL/base64 image, read from its header only
+pub fn image_dimensions(image_data: &str) -> Option<cost::ImageDims> {
+  let bytes = decode_image(image_data).ok()?;
+  let (width, height) = image::io::Reader::new(std::io::Cursor::new(bytes))
+    .with_guessed_format()
+    .ok()?
+    .into_dimensions()
+    .ok()?;
+  Some(cost::ImageDims { width, height })
+}
+
+fn decode_image(image_data: &str) -> Result<Vec<u8>, String> {
+  base64::engine::general_purpose::STANDARD
+    .decode(base64_payload(image_data).trim())
+    .map_err(|e| format!("Invalid image data: {}", e))
+}
+
+// multipart/form-data body (reqwest's multipart feature isn't enabled)
+struct MultipartForm {
+  boundary: String,
+  body: Vec<u8>,
+}
+
+impl MultipartForm {
+  fn new() -> Self {
+    Self {
+      boundary: format!(
+        "----FrameSense{:x}",
+        std::time::SystemTime::now()
+          .duration_since(std::time::UNIX_EPOCH)
+          .map(|elapsed| elapsed.as_nanos())
+          .unwrap_or_default()
+      ),
+      body: Vec::new(),
+    }
+  }
+
+  fn text(&mut self, name: &str, value: &str) {
+    self.body.extend_from_slice(
+      format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+        self.boundary, name, value
+      )
+      .as_bytes(),
+    );
+  }
+
+  fn file(&mut self, name: &str, filename: &str, content_type: &str, data: &[u8]) {
+    self.body.extend_from_slice(
+      format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        self.boundary, name, filename, content_type
+      )
+      .as_bytes(),
+    );
+    self.body.extend_from_slice(data);
+    self.body.extend_from_slice(b"\r\n");
+  }
+
+  // (Content-Type header, body)
+  fn finish(mut self) -> (String, Vec<u8>) {
+    self
+      .body
+      .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+    (
+      format!("multipart/form-data; boundary={}", self.boundary),
+      self.body,
+    )
+  }
+}
@@ -0,0 +1,267 @@
+// Local models through Ollama (https://ollama.com) for fully offline analysis. Models
+// are addressed as `ollama:<name>` (e.g. `ollama:llava`) so they can be picked like any
+// hosted model; requests never leave the machine and need no account.
+use super::{
+  base64_payload, AiProvider, AnalysisError, AnalysisRequest, AnalysisResult, ProviderKind,
+};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const DEFAULT_HOST: &str = "http://127.0.0.1:11434";
+const DETECT_TIMEOUT: Duration = Duration::from_secs(2);
+// Local vision models on a CPU can take minutes for a large capture
+const ANALYZE_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModel {
+  // What to pass as `model`, e.g. "ollama:llava:13b"
+  pub id: String,
+  pub name: String,
+  pub size_bytes: u64,
+  pub family: Option<String>,
+  // Accepts images (llava, bakllava, llama3.2-vision, ...)
+  pub vision: bool,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+  models: Vec<TagModel>,
+}
+
+#[derive(Deserialize)]
+struct TagModel {
+  name: String,
+  #[serde(default)]
+  size: u64,
+  #[serde(default)]
+  details: Option<TagDetails>,
+}
+
+#[derive(Deserialize)]
+struct TagDetails {
+  family: Option<String>,
+  families: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+  model: &'a str,
+  messages: Vec<ChatMessage>,
+  stream: bool,
+  options: ChatOptions,
+}
+
+#[derive(Serialize)]
+struct ChatMessage {
+  role: &'static str,
+  content: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  images: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ChatOptions {
+  temperature: f32,
+}
+
+// One line of the NDJSON stream
+#[derive(Deserialize)]
+struct ChatChunk {
+  message: Option<ChunkMessage>,
+  #[serde(default)]
+  done: bool,
+  error: Option<String>,
+  prompt_eval_count: Option<u64>,
+  eval_count: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ChunkMessage {
+  content: String,
+}
+
+pub struct OllamaProvider {
+  host: String,
+}
+
+impl OllamaProvider {
+  // OLLAMA_HOST is what the Ollama CLI itself honors
+  pub fn new() -> Self {
+    let host = std::env::var("OLLAMA_HOST")
+      .ok()
+      .filter(|host| !host.trim().is_empty())
+      .map(|host| {
+        let host = host.trim().trim_end_matches('/');
+        if host.starts_with("http://") || host.starts_with("https://") {
+          host.to_string()
+        } else {
+          format!("http://{}", host)
+        }
+      })
+      .unwrap_or_else(|| DEFAULT_HOST.to_string());
+    Self { host }
+  }
+
+  // Local server: never through a proxy
+  fn client(&self, timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+      .no_proxy()
+      .timeout(timeout)
+      .build()
+      .map_err(|e| format!("Failed to create HTTP client: {}", e))
+  }
+
+  /// Whether an Ollama server is answering
+  pub async fn detect(&self) -> bool {
+    let Ok(client) = self.client(DETECT_TIMEOUT) else {
+      return false;
+    };
+    match client
+      .get(format!("{}/api/version", self.host))
+      .send()
+      .await
+    {
+      Ok(response) => response.status().is_success(),
+      Err(_) => false,
+    }
+  }
+
+  pub async fn list_models(&self) -> Result<Vec<LocalModel>, String> {
+    let response = self
+      .client(DETECT_TIMEOUT)?
+      .get(format!("{}/api/tags", self.host))
+      .send()
+      .await
+      .map_err(|_| format!("Ollama isn't running at {}", self.host))?;
+    if !response.status().is_success() {
+      return Err(format!("Ollama returned {}", response.status()));
+    }
+    let tags: TagsResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("Parse error: {}", e))?;
+
+    Ok(
+      tags
+        .models
+        .into_iter()
+        .map(|model| {
+          let families = model
+            .details
+            .as_ref()
+            .and_then(|details| details.families.clone())
+            .unwrap_or_default();
+          let name = model.name.to_lowercase();
+          let vision = families
+            .iter()
+            .any(|family| family == "clip" || family == "mllama")
+            || ["llava", "vision", "moondream", "minicpm-v"]
+              .iter()
+              .any(|hint| name.contains(hint));
+          LocalModel {
+            id: format!("{}:{}", ProviderKind::Ollama.as_str(), model.name),
+            family: model.details.and_then(|details| details.family),
+            name: model.name,
+            size_bytes: model.size,
+            vision,
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+impl AiProvider for OllamaProvider {
+  async fn analyze(
+    &self,
+    model: &str,
+    request: &AnalysisRequest,
+    on_chunk: &mut (dyn FnMut(&str) + Send),
+  ) -> Result<AnalysisResult, AnalysisError> {
+    let started = Instant::now();
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &request.system_prompt {
+      messages.push(ChatMessage {
+        role: "system",
+        content: system_prompt.clone(),
+        images: Vec::new(),
+      });
+    }
+    // Ollama wants bare base64, not a data URL
+    let images = match &request.image_data {
+      Some(image_data) => vec![base64_payload(image_data).to_string()],
+      None => Vec::new(),
+    };
+    messages.push(ChatMessage {
+      role: "user",
+      content: request.prompt.clone(),
+      images,
+    });
+
+    let mut response = self
+      .client(ANALYZE_TIMEOUT)?
+      .post(format!("{}/api/chat", self.host))
+      .json(&ChatRequest {
+        model,
+        messages,
+        stream: true,
+        options: ChatOptions {
+          temperature: request.temperature,
+        },
+      })
+      .send()
+      .await
+      .map_err(|_| AnalysisError::Unavailable(format!("Ollama isn't running at {}", self.host)))?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      let message = serde_json::from_str::<ChatChunk>(&body)
+        .ok()
+        .and_then(|chunk| chunk.error)
+        .unwrap_or(body);
+      return Err(AnalysisError::from_status(
+        status,
+        format!("Ollama error: {}", message),
+      ));
+    }
+
+    let mut content = String::new();
+    let mut usage = None;
+    let mut buffer = Vec::new();
+    while let Some(bytes) = response
+      .chunk()
+      .await
+      .map_err(|e| AnalysisError::Unavailable(format!("Analysis interrupted: {}", e)))?
+    {
+      buffer.extend_from_slice(&bytes);
+      while let Some(newline) = buffer.iter().position(|&byte| byte == b'\n') {
+        let line: Vec<u8> = buffer.drain(..=newline).collect();
+        let Ok(chunk) = serde_json::from_slice::<ChatChunk>(&line) else {
+          continue;
+        };
+        if let Some(error) = chunk.error {
+          return Err(AnalysisError::Failed(format!("Ollama error: {}", error)));
+        }
+        if let Some(message) = chunk.message.filter(|message| !message.content.is_empty()) {
+          on_chunk(&message.content);
+          content.push_str(&message.content);
+        }
+        if chunk.done {
+          usage = Some(serde_json::json!({
+              "promptTokens": chunk.prompt_eval_count,
+              "completionTokens": chunk.eval_count
+          }));
+        }
+      }
+    }
+
+    Ok(super::finish(request, content, usage, started))
+  }
+}
+
+impl Default for OllamaProvider {
+  fn default() -> Self {
+    Self::new()
+  }
+}
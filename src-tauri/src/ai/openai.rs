@@ -0,0 +1,104 @@
+// OpenAI and Mistral: both speak the chat completions API, with the capture sent as a
+// data-URL image part.
+use super::{
+  provider_error, sse, AiProvider, AnalysisError, AnalysisRequest, AnalysisResult, ProviderKind,
+};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use serde_json::json;
+use std::time::Instant;
+
+pub struct OpenAiCompatibleProvider {
+  kind: ProviderKind,
+  api_key: String,
+  network: NetworkSettings,
+}
+
+impl OpenAiCompatibleProvider {
+  pub fn openai(api_key: String, network: NetworkSettings) -> Self {
+    Self {
+      kind: ProviderKind::OpenAi,
+      api_key,
+      network,
+    }
+  }
+
+  pub fn mistral(api_key: String, network: NetworkSettings) -> Self {
+    Self {
+      kind: ProviderKind::Mistral,
+      api_key,
+      network,
+    }
+  }
+
+  fn endpoint(&self) -> &'static str {
+    match self.kind {
+      ProviderKind::Mistral => "https://api.mistral.ai/v1/chat/completions",
+      _ => "https://api.openai.com/v1/chat/completions",
+    }
+  }
+}
+
+impl AiProvider for OpenAiCompatibleProvider {
+  async fn analyze(
+    &self,
+    model: &str,
+    request: &AnalysisRequest,
+    on_chunk: &mut (dyn FnMut(&str) + Send),
+  ) -> Result<AnalysisResult, AnalysisError> {
+    let started = Instant::now();
+
+    let mut content = vec![json!({ "type": "text", "text": request.prompt })];
+    if let Some(image_data) = &request.image_data {
+      let data_url = super::data_url(image_data);
+      // Mistral takes the URL directly, OpenAI wraps it in an object
+      content.push(match self.kind {
+        ProviderKind::Mistral => json!({ "type": "image_url", "image_url": data_url }),
+        _ => json!({ "type": "image_url", "image_url": { "url": data_url } }),
+      });
+    }
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &request.system_prompt {
+      messages.push(json!({ "role": "system", "content": system_prompt }));
+    }
+    messages.push(json!({ "role": "user", "content": content }));
+
+    let mut body = json!({
+        "model": model,
+        "messages": messages,
+        "temperature": request.temperature,
+        "stream": true
+    });
+    if self.kind == ProviderKind::OpenAi {
+      body["stream_options"] = json!({ "include_usage": true });
+    }
+
+    let response = network::http_client(&self.network)?
+      .post(self.endpoint())
+      .bearer_auth(&self.api_key)
+      .json(&body)
+      .timeout(super::ANALYZE_TIMEOUT)
+      .send_with_retry()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      return Err(provider_error(self.kind, status, &body));
+    }
+
+    let mut answer = String::new();
+    let mut usage = None;
+    sse::for_each_event(response, |event| {
+      if let Some(text) = event["choices"][0]["delta"]["content"].as_str() {
+        on_chunk(text);
+        answer.push_str(text);
+      }
+      if !event["usage"].is_null() {
+        usage = Some(event["usage"].clone());
+      }
+      Ok(())
+    })
+    .await?;
+
+    Ok(super::finish(request, answer, usage, started))
+  }
+}
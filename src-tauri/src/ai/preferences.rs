@@ -0,0 +1,133 @@
+// User AI preferences - a custom system prompt plus response style and language that are
+// folded into the system prompt of every request made from Rust.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+const PREFERENCES_FILE: &str = "ai_preferences.json";
+const MAX_SYSTEM_PROMPT_CHARS: usize = 4000;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseStyle {
+  #[default]
+  Default,
+  Concise,
+  Detailed,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AiPreferences {
+  pub system_prompt: Option<String>,
+  pub style: ResponseStyle,
+  // Language answers should be written in, e.g. "Swedish"; None = the question's language
+  pub language: Option<String>,
+}
+
+impl AiPreferences {
+  pub fn validate(&self) -> Result<(), String> {
+    if let Some(prompt) = &self.system_prompt {
+      if prompt.chars().count() > MAX_SYSTEM_PROMPT_CHARS {
+        return Err(format!(
+          "System prompt is too long (max {} characters)",
+          MAX_SYSTEM_PROMPT_CHARS
+        ));
+      }
+    }
+    if let Some(language) = &self.language {
+      if language.len() > 40 {
+        return Err("Language name is too long".to_string());
+      }
+    }
+    Ok(())
+  }
+
+  /// The system prompt to send: the user's prompt, then any conversation-specific
+  /// prompt, then style and language instructions. None when nothing is set.
+  pub fn apply(&self, conversation_prompt: Option<String>) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    let non_empty = |text: &Option<String>| {
+      text
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+    };
+    parts.extend(non_empty(&self.system_prompt));
+    parts.extend(non_empty(&conversation_prompt));
+    match self.style {
+      ResponseStyle::Default => {}
+      ResponseStyle::Concise => parts.push("Keep answers short and to the point.".to_string()),
+      ResponseStyle::Detailed => {
+        parts.push("Give thorough, detailed answers with explanations.".to_string())
+      }
+    }
+    if let Some(language) = non_empty(&self.language) {
+      parts.push(format!(
+        "Write your answers in {} unless asked otherwise.",
+        language
+      ));
+    }
+
+    if parts.is_empty() {
+      None
+    } else {
+      Some(parts.join("\n\n"))
+    }
+  }
+}
+
+pub struct AiPreferencesStore {
+  preferences: AiPreferences,
+  storage_path: Option<PathBuf>,
+}
+
+impl AiPreferencesStore {
+  pub fn new() -> Self {
+    Self {
+      preferences: AiPreferences::default(),
+      storage_path: None,
+    }
+  }
+
+  // Load saved preferences from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(PREFERENCES_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(preferences) => self.preferences = preferences,
+        Err(e) => warn!("⚠️ Ignoring unreadable AI preferences: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn preferences(&self) -> &AiPreferences {
+    &self.preferences
+  }
+
+  pub fn update(&mut self, preferences: AiPreferences) -> Result<(), String> {
+    preferences.validate()?;
+    self.preferences = preferences;
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.preferences)
+      .map_err(|e| format!("Failed to serialize AI preferences: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save AI preferences: {}", e))
+  }
+}
+
+impl Default for AiPreferencesStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
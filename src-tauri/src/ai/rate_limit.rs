@@ -0,0 +1,71 @@
+// Client-side rate limiting of AI requests: a token bucket per tier, so a runaway
+// frontend loop is stopped locally before it burns through the user's daily quota.
+use crate::auth::guest::GUEST_TIER;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// (burst size, requests per minute once the burst is spent)
+fn tier_limits(tier: &str) -> (f64, f64) {
+  match tier {
+    "enterprise" => (20.0, 60.0),
+    "premium" | "pro" => (10.0, 30.0),
+    GUEST_TIER => (3.0, 5.0),
+    _ => (5.0, 10.0),
+  }
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimited {
+  pub tier: String,
+  pub retry_after_ms: u64,
+}
+
+struct Bucket {
+  tokens: f64,
+  updated_at: Instant,
+}
+
+pub struct RateLimiter {
+  buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+  pub fn new() -> Self {
+    Self {
+      buckets: HashMap::new(),
+    }
+  }
+
+  /// Take one request from `tier`'s bucket, or say how long until one is available
+  pub fn acquire(&mut self, tier: &str) -> Result<(), RateLimited> {
+    let (capacity, per_minute) = tier_limits(tier);
+    let refill_per_sec = per_minute / 60.0;
+    let now = Instant::now();
+    let bucket = self.buckets.entry(tier.to_string()).or_insert(Bucket {
+      tokens: capacity,
+      updated_at: now,
+    });
+
+    let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+    bucket.updated_at = now;
+
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      return Ok(());
+    }
+    let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / refill_per_sec);
+    Err(RateLimited {
+      tier: tier.to_string(),
+      retry_after_ms: wait.as_millis() as u64,
+    })
+  }
+}
+
+impl Default for RateLimiter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,121 @@
+// Recent AI answers keyed by (image, prompt, model, parameters, conversation), so
+// re-sending the same question about the same capture within a few minutes doesn't cost
+// quota again. The conversation is part of the key because earlier turns shape the answer.
+use super::{base64_payload, AnalysisRequest, AnalysisResult};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+const MAX_ENTRIES: usize = 50;
+
+struct CachedResponse {
+  result: AnalysisResult,
+  stored_at: Instant,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCacheStats {
+  pub entries: usize,
+  pub hits: u64,
+  pub misses: u64,
+  pub ttl_secs: u64,
+}
+
+pub struct ResponseCache {
+  entries: HashMap<u64, CachedResponse>,
+  ttl: Duration,
+  hits: u64,
+  misses: u64,
+}
+
+impl ResponseCache {
+  pub fn new() -> Self {
+    Self {
+      entries: HashMap::new(),
+      ttl: DEFAULT_TTL,
+      hits: 0,
+      misses: 0,
+    }
+  }
+
+  fn key(request: &AnalysisRequest) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request
+      .image_data
+      .as_deref()
+      .map(base64_payload)
+      .hash(&mut hasher);
+    request.prompt.trim().hash(&mut hasher);
+    request.model.hash(&mut hasher);
+    request.temperature.to_bits().hash(&mut hasher);
+    request.system_prompt.hash(&mut hasher);
+    request.conversation_id.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  pub fn lookup(&mut self, request: &AnalysisRequest) -> Option<AnalysisResult> {
+    let key = Self::key(request);
+    match self.entries.get(&key) {
+      Some(cached) if cached.stored_at.elapsed() < self.ttl => {
+        self.hits += 1;
+        Some(cached.result.clone())
+      }
+      _ => {
+        self.entries.remove(&key);
+        self.misses += 1;
+        None
+      }
+    }
+  }
+
+  pub fn store(&mut self, request: &AnalysisRequest, result: &AnalysisResult) {
+    let ttl = self.ttl;
+    self
+      .entries
+      .retain(|_, cached| cached.stored_at.elapsed() < ttl);
+    if self.entries.len() >= MAX_ENTRIES {
+      let oldest = self
+        .entries
+        .iter()
+        .min_by_key(|(_, cached)| cached.stored_at)
+        .map(|(key, _)| *key);
+      if let Some(oldest) = oldest {
+        self.entries.remove(&oldest);
+      }
+    }
+    self.entries.insert(
+      Self::key(request),
+      CachedResponse {
+        result: result.clone(),
+        stored_at: Instant::now(),
+      },
+    );
+  }
+
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
+
+  pub fn stats(&self) -> ResponseCacheStats {
+    ResponseCacheStats {
+      entries: self
+        .entries
+        .values()
+        .filter(|cached| cached.stored_at.elapsed() < self.ttl)
+        .count(),
+      hits: self.hits,
+      misses: self.misses,
+      ttl_secs: self.ttl.as_secs(),
+    }
+  }
+}
+
+impl Default for ResponseCache {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,33 @@
+// Server-sent events as streamed by the OpenAI, Anthropic, Google and Mistral APIs:
+// only the `data:` lines matter, each one a JSON object.
+use super::AnalysisError;
+
+/// Feed every `data:` JSON payload of the response body to `handle` as it arrives
+pub async fn for_each_event(
+  mut response: reqwest::Response,
+  mut handle: impl FnMut(serde_json::Value) -> Result<(), AnalysisError>,
+) -> Result<(), AnalysisError> {
+  let mut buffer = Vec::new();
+  while let Some(bytes) = response
+    .chunk()
+    .await
+    .map_err(|e| AnalysisError::Unavailable(format!("Analysis interrupted: {}", e)))?
+  {
+    buffer.extend_from_slice(&bytes);
+    while let Some(newline) = buffer.iter().position(|&byte| byte == b'\n') {
+      let line: Vec<u8> = buffer.drain(..=newline).collect();
+      let line = String::from_utf8_lossy(&line);
+      let Some(data) = line.trim().strip_prefix("data:") else {
+        continue;
+      };
+      let data = data.trim();
+      if data.is_empty() || data == "[DONE]" {
+        continue;
+      }
+      if let Ok(event) = serde_json::from_str(data) {
+        handle(event)?;
+      }
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,59 @@
+// Translating captured text. OCR text is translated as plain text; when OCR finds
+// nothing the capture itself goes to the model, which transcribes and translates it.
+use serde::{Deserialize, Serialize};
+
+const ORIGINAL_MARKER: &str = "ORIGINAL:";
+const TRANSLATION_MARKER: &str = "TRANSLATION:";
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+  pub original_text: String,
+  pub translated_text: String,
+  pub target_lang: String,
+  pub model: String,
+  // Whether the original came from OCR or from the model reading the image
+  pub from_ocr: bool,
+}
+
+pub fn validate_target_lang(target_lang: &str) -> Result<&str, String> {
+  let target_lang = target_lang.trim();
+  if target_lang.is_empty() || target_lang.len() > 40 {
+    return Err("Choose a language to translate to".to_string());
+  }
+  Ok(target_lang)
+}
+
+/// Prompt for text already extracted by OCR
+pub fn text_prompt(text: &str, target_lang: &str) -> String {
+  format!(
+    "Translate the following text to {}. Keep the formatting and line breaks. \
+     Reply with the translation only.\n\n{}",
+    target_lang, text
+  )
+}
+
+/// Prompt asking the model to read the capture itself
+pub fn image_prompt(target_lang: &str) -> String {
+  format!(
+    "Transcribe all text in this image, then translate it to {}. Reply in exactly this \
+     format:\n{}\n<the text as written>\n{}\n<the translation>",
+    target_lang, ORIGINAL_MARKER, TRANSLATION_MARKER
+  )
+}
+
+/// (original, translation) from an answer to `image_prompt`
+pub fn parse_image_answer(answer: &str) -> (String, String) {
+  match answer.split_once(TRANSLATION_MARKER) {
+    Some((original, translation)) => (
+      original
+        .trim()
+        .trim_start_matches(ORIGINAL_MARKER)
+        .trim()
+        .to_string(),
+      translation.trim().to_string(),
+    ),
+    // Format not followed: everything is the translation
+    None => (String::new(), answer.trim().to_string()),
+  }
+}
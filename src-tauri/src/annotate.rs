@@ -0,0 +1,668 @@
+// Burn-in annotations - composites rectangles, arrows, highlights and text into a capture
+// on the Rust side, so exports and uploads contain them without re-encoding a canvas in
+// the webview. Coordinates are logical pixels relative to the capture's top-left corner
+// (the same space as the selection) and are scaled to the image's real resolution.
+// The same pipeline obscures regions (pixelate / blur / fill) before a capture is shared.
+use crate::capture_store::{decode_data_url, StoredCapture};
+use crate::overlay::redaction;
+use crate::overlay::{CaptureBounds, RedactionStyle, ScreenCapture};
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+use tracing::info;
+
+const DEFAULT_COLOR: Rgba<u8> = Rgba([255, 59, 48, 255]);
+const DEFAULT_HIGHLIGHT: Rgba<u8> = Rgba([255, 214, 10, 255]);
+const DEFAULT_STROKE_WIDTH: f32 = 3.0;
+const DEFAULT_TEXT_SIZE: f32 = 16.0;
+const DEFAULT_HIGHLIGHT_OPACITY: f32 = 0.35;
+// Pixelate block size / blur radius in logical pixels
+const DEFAULT_OBSCURE_STRENGTH: u32 = 12;
+
+#[derive(Clone, Deserialize, Debug)]
+#[serde(
+  tag = "type",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum Annotation {
+  Rectangle {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    color: Option<String>,
+    stroke_width: Option<f32>,
+    #[serde(default)]
+    filled: bool,
+  },
+  Arrow {
+    from_x: f32,
+    from_y: f32,
+    to_x: f32,
+    to_y: f32,
+    color: Option<String>,
+    stroke_width: Option<f32>,
+  },
+  Text {
+    x: f32, // Top-left corner of the text
+    y: f32,
+    text: String,
+    color: Option<String>,
+    size: Option<f32>, // Line height
+    background: Option<String>,
+  },
+  Highlight {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    color: Option<String>,
+    opacity: Option<f32>,
+  },
+}
+
+/// Draw the annotations onto the capture and return the new PNG data URL
+pub fn annotate(capture: &StoredCapture, annotations: &[Annotation]) -> Result<String, String> {
+  let png = decode_data_url(&capture.image_data)?;
+  let mut image = image::load_from_memory(&png)
+    .map_err(|e| format!("Failed to read capture image: {}", e))?
+    .to_rgba8();
+
+  // Captures on HiDPI displays have more pixels than the logical selection
+  let scale = capture
+    .bounds
+    .as_ref()
+    .filter(|bounds| bounds.width > 0)
+    .map(|bounds| image.width() as f32 / bounds.width as f32)
+    .unwrap_or(1.0);
+
+  for annotation in annotations {
+    draw_annotation(&mut image, annotation, scale)?;
+  }
+
+  info!(
+    "✏️ Burned {} annotations into capture {}",
+    annotations.len(),
+    capture.id
+  );
+  ScreenCapture::encode_rgba_to_base64(image)
+}
+
+/// Pixelate (or blur / fill) regions of a capture so sensitive content never leaves the
+/// machine. `strength` is the block size or blur radius in logical pixels.
+pub fn obscure(
+  capture: &StoredCapture,
+  regions: &[CaptureBounds],
+  style: RedactionStyle,
+  strength: Option<u32>,
+) -> Result<String, String> {
+  let png = decode_data_url(&capture.image_data)?;
+  let mut image = image::load_from_memory(&png)
+    .map_err(|e| format!("Failed to read capture image: {}", e))?
+    .to_rgba8();
+
+  let scale = capture
+    .bounds
+    .as_ref()
+    .filter(|bounds| bounds.width > 0)
+    .map(|bounds| image.width() as f32 / bounds.width as f32)
+    .unwrap_or(1.0);
+  let strength = (strength.unwrap_or(DEFAULT_OBSCURE_STRENGTH).max(1) as f32 * scale).max(1.0);
+
+  let mut applied = 0;
+  for region in regions {
+    // Clip to the image, in image pixels
+    let left = ((region.x as f32 * scale).floor() as i64).clamp(0, image.width() as i64) as u32;
+    let top = ((region.y as f32 * scale).floor() as i64).clamp(0, image.height() as i64) as u32;
+    let right = (((region.x as f32 + region.width as f32) * scale).ceil() as i64)
+      .clamp(0, image.width() as i64) as u32;
+    let bottom = (((region.y as f32 + region.height as f32) * scale).ceil() as i64)
+      .clamp(0, image.height() as i64) as u32;
+    if right <= left || bottom <= top {
+      continue;
+    }
+
+    let (width, height) = (right - left, bottom - top);
+    match style {
+      RedactionStyle::Pixelate => {
+        redaction::pixelate_rect(&mut image, left, top, width, height, strength as u32)
+      }
+      RedactionStyle::Blur => redaction::blur_rect(&mut image, left, top, width, height, strength),
+      RedactionStyle::Fill => redaction::apply_style(&mut image, left, top, width, height, style),
+    }
+    applied += 1;
+  }
+
+  info!(
+    "🕶️ Obscured {} region(s) of capture {} ({:?})",
+    applied, capture.id, style
+  );
+  ScreenCapture::encode_rgba_to_base64(image)
+}
+
+fn draw_annotation(
+  image: &mut RgbaImage,
+  annotation: &Annotation,
+  scale: f32,
+) -> Result<(), String> {
+  match annotation {
+    Annotation::Rectangle {
+      x,
+      y,
+      width,
+      height,
+      color,
+      stroke_width,
+      filled,
+    } => {
+      let color = parse_color(color.as_deref(), DEFAULT_COLOR)?;
+      let (left, top) = (x * scale, y * scale);
+      let (right, bottom) = ((x + width) * scale, (y + height) * scale);
+      if *filled {
+        fill_rect(image, left, top, right, bottom, color, 1.0);
+      } else {
+        let stroke = stroke_width.unwrap_or(DEFAULT_STROKE_WIDTH) * scale;
+        draw_line(image, left, top, right, top, stroke, color);
+        draw_line(image, right, top, right, bottom, stroke, color);
+        draw_line(image, right, bottom, left, bottom, stroke, color);
+        draw_line(image, left, bottom, left, top, stroke, color);
+      }
+    }
+    Annotation::Arrow {
+      from_x,
+      from_y,
+      to_x,
+      to_y,
+      color,
+      stroke_width,
+    } => {
+      let color = parse_color(color.as_deref(), DEFAULT_COLOR)?;
+      let stroke = stroke_width.unwrap_or(DEFAULT_STROKE_WIDTH) * scale;
+      draw_arrow(
+        image,
+        (from_x * scale, from_y * scale),
+        (to_x * scale, to_y * scale),
+        stroke,
+        color,
+      );
+    }
+    Annotation::Text {
+      x,
+      y,
+      text,
+      color,
+      size,
+      background,
+    } => {
+      let color = parse_color(color.as_deref(), DEFAULT_COLOR)?;
+      let background = background
+        .as_deref()
+        .map(|background| parse_color(Some(background), DEFAULT_COLOR))
+        .transpose()?;
+      let size = size.unwrap_or(DEFAULT_TEXT_SIZE) * scale;
+      draw_text(image, x * scale, y * scale, text, size, color, background);
+    }
+    Annotation::Highlight {
+      x,
+      y,
+      width,
+      height,
+      color,
+      opacity,
+    } => {
+      let color = parse_color(color.as_deref(), DEFAULT_HIGHLIGHT)?;
+      let opacity = opacity.unwrap_or(DEFAULT_HIGHLIGHT_OPACITY).clamp(0.0, 1.0);
+      fill_rect(
+        image,
+        x * scale,
+        y * scale,
+        (x + width) * scale,
+        (y + height) * scale,
+        color,
+        opacity,
+      );
+    }
+  }
+  Ok(())
+}
+
+/// "#RGB", "#RRGGBB" or "#RRGGBBAA"
+fn parse_color(color: Option<&str>, default: Rgba<u8>) -> Result<Rgba<u8>, String> {
+  let color = match color {
+    Some(color) => color,
+    None => return Ok(default),
+  };
+  let hex = color.trim().trim_start_matches('#');
+  if !hex.is_ascii() {
+    return Err(format!("Invalid color '{}'", color));
+  }
+  let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+  let parsed = match hex.len() {
+    3 => {
+      let expanded: String = hex.chars().flat_map(|c| [c, c]).collect();
+      return parse_color(Some(&expanded), default);
+    }
+    6 => (channel(0), channel(2), channel(4), Ok(255)),
+    8 => (channel(0), channel(2), channel(4), channel(6)),
+    _ => return Err(format!("Invalid color '{}'", color)),
+  };
+  match parsed {
+    (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Rgba([r, g, b, a])),
+    _ => Err(format!("Invalid color '{}'", color)),
+  }
+}
+
+// Alpha-blend `color` (scaled by `opacity`) over the pixel, ignoring out-of-bounds points
+fn blend_pixel(image: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>, opacity: f32) {
+  if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+    return;
+  }
+  let alpha = color[3] as f32 / 255.0 * opacity;
+  let pixel = image.get_pixel_mut(x as u32, y as u32);
+  for c in 0..3 {
+    pixel[c] = (color[c] as f32 * alpha + pixel[c] as f32 * (1.0 - alpha)).round() as u8;
+  }
+  pixel[3] = (alpha * 255.0 + pixel[3] as f32 * (1.0 - alpha))
+    .round()
+    .min(255.0) as u8;
+}
+
+fn fill_rect(
+  image: &mut RgbaImage,
+  left: f32,
+  top: f32,
+  right: f32,
+  bottom: f32,
+  color: Rgba<u8>,
+  opacity: f32,
+) {
+  let (left, right) = (
+    left.min(right).round() as i64,
+    left.max(right).round() as i64,
+  );
+  let (top, bottom) = (
+    top.min(bottom).round() as i64,
+    top.max(bottom).round() as i64,
+  );
+  for y in top.max(0)..bottom.min(image.height() as i64) {
+    for x in left.max(0)..right.min(image.width() as i64) {
+      blend_pixel(image, x, y, color, opacity);
+    }
+  }
+}
+
+// Thick line: every pixel within stroke / 2 of the segment, so each pixel is blended once
+fn draw_line(
+  image: &mut RgbaImage,
+  x0: f32,
+  y0: f32,
+  x1: f32,
+  y1: f32,
+  stroke: f32,
+  color: Rgba<u8>,
+) {
+  let radius = (stroke / 2.0).max(0.5);
+  let min_x = (x0.min(x1) - radius).floor().max(0.0) as i64;
+  let max_x = (x0.max(x1) + radius).ceil().min(image.width() as f32) as i64;
+  let min_y = (y0.min(y1) - radius).floor().max(0.0) as i64;
+  let max_y = (y0.max(y1) + radius).ceil().min(image.height() as f32) as i64;
+  let (dx, dy) = (x1 - x0, y1 - y0);
+  let length_squared = dx * dx + dy * dy;
+
+  for y in min_y..max_y {
+    for x in min_x..max_x {
+      let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+      let t = if length_squared > 0.0 {
+        (((px - x0) * dx + (py - y0) * dy) / length_squared).clamp(0.0, 1.0)
+      } else {
+        0.0
+      };
+      let (cx, cy) = (x0 + t * dx, y0 + t * dy);
+      if (px - cx).powi(2) + (py - cy).powi(2) <= radius * radius {
+        blend_pixel(image, x, y, color, 1.0);
+      }
+    }
+  }
+}
+
+fn draw_arrow(
+  image: &mut RgbaImage,
+  from: (f32, f32),
+  to: (f32, f32),
+  stroke: f32,
+  color: Rgba<u8>,
+) {
+  let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+  let length = (dx * dx + dy * dy).sqrt();
+  if length < 1.0 {
+    return;
+  }
+  let (ux, uy) = (dx / length, dy / length);
+  let head_length = (stroke * 4.0).max(10.0).min(length);
+  let head_half_width = head_length * 0.5;
+
+  // The shaft stops at the base of the head so the tip stays sharp
+  let base = (to.0 - ux * head_length, to.1 - uy * head_length);
+  draw_line(image, from.0, from.1, base.0, base.1, stroke, color);
+
+  let left = (base.0 - uy * head_half_width, base.1 + ux * head_half_width);
+  let right = (base.0 + uy * head_half_width, base.1 - ux * head_half_width);
+  fill_triangle(image, to, left, right, color);
+}
+
+fn fill_triangle(
+  image: &mut RgbaImage,
+  a: (f32, f32),
+  b: (f32, f32),
+  c: (f32, f32),
+  color: Rgba<u8>,
+) {
+  let edge = |p: (f32, f32), q: (f32, f32), x: f32, y: f32| {
+    (q.0 - p.0) * (y - p.1) - (q.1 - p.1) * (x - p.0)
+  };
+  let min_x = a.0.min(b.0).min(c.0).floor().max(0.0) as i64;
+  let max_x = a.0.max(b.0).max(c.0).ceil().min(image.width() as f32) as i64;
+  let min_y = a.1.min(b.1).min(c.1).floor().max(0.0) as i64;
+  let max_y = a.1.max(b.1).max(c.1).ceil().min(image.height() as f32) as i64;
+
+  for y in min_y..max_y {
+    for x in min_x..max_x {
+      let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+      let (e0, e1, e2) = (edge(a, b, px, py), edge(b, c, px, py), edge(c, a, px, py));
+      let inside = (e0 >= 0.0 && e1 >= 0.0 && e2 >= 0.0) || (e0 <= 0.0 && e1 <= 0.0 && e2 <= 0.0);
+      if inside {
+        blend_pixel(image, x, y, color, 1.0);
+      }
+    }
+  }
+}
+
+// Text uses a built-in 5x7 bitmap font scaled to `size`; characters outside Latin-1
+// are drawn as '?'. Lines are split on '\n'.
+fn draw_text(
+  image: &mut RgbaImage,
+  x: f32,
+  y: f32,
+  text: &str,
+  size: f32,
+  color: Rgba<u8>,
+  background: Option<Rgba<u8>>,
+) {
+  // 7 glyph rows + 1 row of line spacing per line; 5 columns + 1 column spacing per char
+  let cell = (size / 8.0).max(1.0);
+  let lines: Vec<&str> = text.lines().collect();
+
+  if let Some(background) = background {
+    let longest = lines
+      .iter()
+      .map(|line| line.chars().count())
+      .max()
+      .unwrap_or(0);
+    let padding = cell * 2.0;
+    fill_rect(
+      image,
+      x - padding,
+      y - padding,
+      x + longest as f32 * 6.0 * cell + padding,
+      y + lines.len() as f32 * 8.0 * cell + padding,
+      background,
+      1.0,
+    );
+  }
+
+  for (line_index, line) in lines.iter().enumerate() {
+    let line_y = y + line_index as f32 * 8.0 * cell;
+    for (char_index, character) in line.chars().enumerate() {
+      let glyph = glyph(character);
+      let char_x = x + char_index as f32 * 6.0 * cell;
+      for (column, bits) in glyph.iter().enumerate() {
+        for row in 0..8 {
+          if bits & (1 << row) != 0 {
+            let left = char_x + column as f32 * cell;
+            let top = line_y + row as f32 * cell;
+            fill_rect(image, left, top, left + cell, top + cell, color, 1.0);
+          }
+        }
+      }
+    }
+  }
+}
+
+// Column-major glyph: one byte per column, bit 0 = top row. Bit 7 is the spacing row
+// below the glyph, used by cedillas and a few descenders.
+fn glyph(character: char) -> [u8; 5] {
+  match character {
+    ' '..='~' => FONT_5X7[character as usize - ' ' as usize],
+    '\u{A0}'..='\u{BF}' => LATIN1_SYMBOLS[character as usize - 0xA0],
+    '\u{C0}'..='\u{FF}' => latin1_letter(character),
+    _ => FONT_5X7['?' as usize - ' ' as usize],
+  }
+}
+
+// Accented letters are a base glyph plus a mark in the two top rows; capitals use
+// shortened forms (rows 2-6) so the mark fits above them
+fn latin1_letter(character: char) -> [u8; 5] {
+  let ascii = |c: char| FONT_5X7[c as usize - ' ' as usize];
+  let (base, mark) = match character {
+    'À' => (SHORT_A, GRAVE),
+    'Á' => (SHORT_A, ACUTE),
+    'Â' => (SHORT_A, CIRCUMFLEX),
+    'Ã' => (SHORT_A, TILDE),
+    'Ä' => (SHORT_A, DIAERESIS),
+    'Å' => (SHORT_A, RING),
+    'Æ' => ([0x7E, 0x09, 0x7F, 0x49, 0x41], NO_MARK),
+    'Ç' => (ascii('C'), CEDILLA),
+    'È' => (SHORT_E, GRAVE),
+    'É' => (SHORT_E, ACUTE),
+    'Ê' => (SHORT_E, CIRCUMFLEX),
+    'Ë' => (SHORT_E, DIAERESIS),
+    'Ì' => (SHORT_I, GRAVE),
+    'Í' => (SHORT_I, ACUTE),
+    'Î' => (SHORT_I, CIRCUMFLEX),
+    'Ï' => (SHORT_I, DIAERESIS),
+    'Ð' => ([0x08, 0x7F, 0x49, 0x22, 0x1C], NO_MARK),
+    'Ñ' => (SHORT_N, TILDE),
+    'Ò' => (SHORT_O, GRAVE),
+    'Ó' => (SHORT_O, ACUTE),
+    'Ô' => (SHORT_O, CIRCUMFLEX),
+    'Õ' => (SHORT_O, TILDE),
+    'Ö' => (SHORT_O, DIAERESIS),
+    '×' => ([0x22, 0x14, 0x08, 0x14, 0x22], NO_MARK),
+    'Ø' => ([0x5E, 0x31, 0x49, 0x46, 0x3D], NO_MARK),
+    'Ù' => (SHORT_U, GRAVE),
+    'Ú' => (SHORT_U, ACUTE),
+    'Û' => (SHORT_U, CIRCUMFLEX),
+    'Ü' => (SHORT_U, DIAERESIS),
+    'Ý' => (SHORT_Y, ACUTE),
+    'Þ' => ([0x7F, 0x14, 0x14, 0x14, 0x08], NO_MARK),
+    'ß' => ([0x7E, 0x01, 0x45, 0x4A, 0x30], NO_MARK),
+    'à' => (ascii('a'), GRAVE),
+    'á' => (ascii('a'), ACUTE),
+    'â' => (ascii('a'), CIRCUMFLEX),
+    'ã' => (ascii('a'), TILDE),
+    'ä' => (ascii('a'), DIAERESIS),
+    'å' => (ascii('a'), RING),
+    'æ' => ([0x20, 0x54, 0x7C, 0x54, 0x58], NO_MARK),
+    'ç' => (ascii('c'), CEDILLA),
+    'è' => (ascii('e'), GRAVE),
+    'é' => (ascii('e'), ACUTE),
+    'ê' => (ascii('e'), CIRCUMFLEX),
+    'ë' => (ascii('e'), DIAERESIS),
+    'ì' => (DOTLESS_I, GRAVE),
+    'í' => (DOTLESS_I, ACUTE),
+    'î' => (DOTLESS_I, CIRCUMFLEX),
+    'ï' => (DOTLESS_I, DIAERESIS),
+    'ð' => ([0x30, 0x49, 0x4B, 0x4D, 0x38], NO_MARK),
+    'ñ' => (ascii('n'), TILDE),
+    'ò' => (ascii('o'), GRAVE),
+    'ó' => (ascii('o'), ACUTE),
+    'ô' => (ascii('o'), CIRCUMFLEX),
+    'õ' => (ascii('o'), TILDE),
+    'ö' => (ascii('o'), DIAERESIS),
+    '÷' => ([0x08, 0x08, 0x2A, 0x08, 0x08], NO_MARK),
+    'ø' => ([0x78, 0x64, 0x54, 0x4C, 0x3C], NO_MARK),
+    'ù' => (ascii('u'), GRAVE),
+    'ú' => (ascii('u'), ACUTE),
+    'û' => (ascii('u'), CIRCUMFLEX),
+    'ü' => (ascii('u'), DIAERESIS),
+    'ý' => (ascii('y'), ACUTE),
+    'þ' => ([0xFF, 0x44, 0x44, 0x44, 0x38], NO_MARK),
+    'ÿ' => (ascii('y'), DIAERESIS),
+    _ => (ascii('?'), NO_MARK),
+  };
+  std::array::from_fn(|column| base[column] | mark[column])
+}
+
+// Capitals squeezed into rows 2-6
+const SHORT_A: [u8; 5] = [0x78, 0x14, 0x14, 0x14, 0x78];
+const SHORT_E: [u8; 5] = [0x7C, 0x54, 0x54, 0x54, 0x44];
+const SHORT_I: [u8; 5] = [0x00, 0x44, 0x7C, 0x44, 0x00];
+const SHORT_N: [u8; 5] = [0x7C, 0x08, 0x10, 0x20, 0x7C];
+const SHORT_O: [u8; 5] = [0x38, 0x44, 0x44, 0x44, 0x38];
+const SHORT_U: [u8; 5] = [0x3C, 0x40, 0x40, 0x40, 0x3C];
+const SHORT_Y: [u8; 5] = [0x04, 0x08, 0x70, 0x08, 0x04];
+const DOTLESS_I: [u8; 5] = [0x00, 0x44, 0x7C, 0x40, 0x00];
+
+// Marks in rows 0-1, or the spacing row for the cedilla
+const NO_MARK: [u8; 5] = [0x00; 5];
+const GRAVE: [u8; 5] = [0x00, 0x01, 0x02, 0x00, 0x00];
+const ACUTE: [u8; 5] = [0x00, 0x00, 0x02, 0x01, 0x00];
+const CIRCUMFLEX: [u8; 5] = [0x00, 0x02, 0x01, 0x02, 0x00];
+const TILDE: [u8; 5] = [0x02, 0x01, 0x02, 0x01, 0x00];
+const DIAERESIS: [u8; 5] = [0x00, 0x01, 0x00, 0x01, 0x00];
+const RING: [u8; 5] = [0x00, 0x03, 0x01, 0x03, 0x00];
+const CEDILLA: [u8; 5] = [0x00, 0x00, 0x80, 0x80, 0x00];
+
+// U+00A0 (no-break space) to U+00BF
+const LATIN1_SYMBOLS: [[u8; 5]; 32] = [
+  [0x00, 0x00, 0x00, 0x00, 0x00], // no-break space
+  [0x00, 0x00, 0x7D, 0x00, 0x00], // ¡
+  [0x18, 0x24, 0x7E, 0x24, 0x24], // ¢
+  [0x48, 0x7E, 0x49, 0x41, 0x42], // £
+  [0x22, 0x1C, 0x14, 0x1C, 0x22], // ¤
+  [0x15, 0x16, 0x7C, 0x16, 0x15], // ¥
+  [0x00, 0x00, 0x77, 0x00, 0x00], // ¦
+  [0x0A, 0x55, 0x55, 0x55, 0x28], // §
+  [0x00, 0x01, 0x00, 0x01, 0x00], // ¨
+  [0x3E, 0x5D, 0x55, 0x55, 0x3E], // ©
+  [0x40, 0x46, 0x49, 0x4F, 0x40], // ª
+  [0x08, 0x14, 0x2A, 0x14, 0x22], // «
+  [0x08, 0x08, 0x08, 0x08, 0x38], // ¬
+  [0x08, 0x08, 0x08, 0x08, 0x08], // soft hyphen
+  [0x3E, 0x5D, 0x4D, 0x51, 0x3E], // ®
+  [0x01, 0x01, 0x01, 0x01, 0x01], // ¯
+  [0x00, 0x06, 0x09, 0x09, 0x06], // °
+  [0x44, 0x44, 0x5F, 0x44, 0x44], // ±
+  [0x00, 0x19, 0x15, 0x12, 0x00], // ²
+  [0x00, 0x11, 0x15, 0x0A, 0x00], // ³
+  [0x00, 0x00, 0x02, 0x01, 0x00], // ´
+  [0xFC, 0x40, 0x40, 0x20, 0x7C], // µ
+  [0x06, 0x0F, 0x7F, 0x01, 0x7F], // ¶
+  [0x00, 0x00, 0x08, 0x00, 0x00], // ·
+  [0x00, 0x00, 0x80, 0x80, 0x00], // ¸
+  [0x00, 0x12, 0x1F, 0x10, 0x00], // ¹
+  [0x40, 0x46, 0x49, 0x46, 0x40], // º
+  [0x22, 0x14, 0x2A, 0x14, 0x08], // »
+  [0x17, 0x08, 0x34, 0x2A, 0x79], // ¼
+  [0x17, 0x08, 0x04, 0x6A, 0x59], // ½
+  [0x15, 0x1F, 0x08, 0x34, 0x7A], // ¾
+  [0x30, 0x48, 0x45, 0x40, 0x20], // ¿
+];
+
+const FONT_5X7: [[u8; 5]; 95] = [
+  [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+  [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+  [0x00, 0x07, 0x00, 0x07, 0x00], // "
+  [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+  [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+  [0x23, 0x13, 0x08, 0x64, 0x62], // %
+  [0x36, 0x49, 0x55, 0x22, 0x50], // &
+  [0x00, 0x05, 0x03, 0x00, 0x00], // '
+  [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+  [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+  [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+  [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+  [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+  [0x08, 0x08, 0x08, 0x08, 0x08], // -
+  [0x00, 0x60, 0x60, 0x00, 0x00], // .
+  [0x20, 0x10, 0x08, 0x04, 0x02], // /
+  [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+  [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+  [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+  [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+  [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+  [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+  [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+  [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+  [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+  [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+  [0x00, 0x36, 0x36, 0x00, 0x00], // :
+  [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+  [0x08, 0x14, 0x22, 0x41, 0x00], // <
+  [0x14, 0x14, 0x14, 0x14, 0x14], // =
+  [0x00, 0x41, 0x22, 0x14, 0x08], // >
+  [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+  [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+  [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+  [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+  [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+  [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+  [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+  [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+  [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+  [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+  [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+  [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+  [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+  [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+  [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+  [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+  [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+  [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+  [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+  [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+  [0x46, 0x49, 0x49, 0x49, 0x31], // S
+  [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+  [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+  [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+  [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+  [0x63, 0x14, 0x08, 0x14, 0x63], // X
+  [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+  [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+  [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+  [0x02, 0x04, 0x08, 0x10, 0x20], // \
+  [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+  [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+  [0x40, 0x40, 0x40, 0x40, 0x40], // _
+  [0x00, 0x01, 0x02, 0x04, 0x00], // `
+  [0x20, 0x54, 0x54, 0x54, 0x78], // a
+  [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+  [0x38, 0x44, 0x44, 0x44, 0x20], // c
+  [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+  [0x38, 0x54, 0x54, 0x54, 0x18], // e
+  [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+  [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+  [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+  [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+  [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+  [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+  [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+  [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+  [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+  [0x38, 0x44, 0x44, 0x44, 0x38], // o
+  [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+  [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+  [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+  [0x48, 0x54, 0x54, 0x54, 0x20], // s
+  [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+  [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+  [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+  [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+  [0x44, 0x28, 0x10, 0x28, 0x44], // x
+  [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+  [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+  [0x00, 0x08, 0x36, 0x41, 0x00], // {
+  [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+  [0x00, 0x41, 0x36, 0x08, 0x00], // }
+  [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
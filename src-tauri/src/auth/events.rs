@@ -0,0 +1,89 @@
+// Auth state changes broadcast to every window through the AppHandle, so the main
+// window, the overlay and any later window stay in sync without polling
+// `get_current_user`.
+use super::User;
+use serde::Serialize;
+use serde_json::json;
+use tauri::Emitter;
+use tracing::warn;
+
+pub const LOGIN: &str = "auth:login";
+pub const LOGOUT: &str = "auth:logout";
+pub const TIER_CHANGED: &str = "auth:tier-changed";
+pub const SESSION_EXPIRED: &str = "auth:session-expired";
+
+// What listeners get instead of `User`: events reach every window, so the access
+// and refresh tokens stay out of them
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+struct EventUser<'a> {
+  id: &'a str,
+  email: &'a str,
+  name: &'a str,
+  tier: &'a str,
+  usage: &'a super::UserUsage,
+  created_at: &'a str,
+  subscription_status: Option<&'a str>,
+  usage_daily: Option<i32>,
+  usage_total: Option<i32>,
+  updated_at: Option<&'a str>,
+}
+
+impl<'a> From<&'a User> for EventUser<'a> {
+  fn from(user: &'a User) -> Self {
+    Self {
+      id: &user.id,
+      email: &user.email,
+      name: &user.name,
+      tier: &user.tier,
+      usage: &user.usage,
+      created_at: &user.created_at,
+      subscription_status: user.subscription_status.as_deref(),
+      usage_daily: user.usage_daily,
+      usage_total: user.usage_total,
+      updated_at: user.updated_at.as_deref(),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub enum AuthEvent {
+  // Account login, registration, guest mode or license activation
+  Login { user: User },
+  Logout,
+  TierChanged { previous_tier: String, user: User },
+  SessionExpired { email: String },
+}
+
+impl AuthEvent {
+  pub fn name(&self) -> &'static str {
+    match self {
+      AuthEvent::Login { .. } => LOGIN,
+      AuthEvent::Logout => LOGOUT,
+      AuthEvent::TierChanged { .. } => TIER_CHANGED,
+      AuthEvent::SessionExpired { .. } => SESSION_EXPIRED,
+    }
+  }
+
+  fn payload(&self) -> serde_json::Value {
+    match self {
+      AuthEvent::Login { user } => json!({ "user": EventUser::from(user) }),
+      AuthEvent::Logout => json!({}),
+      AuthEvent::TierChanged {
+        previous_tier,
+        user,
+      } => json!({
+          "previousTier": previous_tier,
+          "tier": user.tier,
+          "user": EventUser::from(user)
+      }),
+      AuthEvent::SessionExpired { email } => json!({ "email": email }),
+    }
+  }
+
+  pub fn emit(&self, app: &tauri::AppHandle) {
+    if let Err(e) = app.emit(self.name(), self.payload()) {
+      warn!("⚠️ Failed to emit {}: {}", self.name(), e);
+    }
+  }
+}
@@ -0,0 +1,79 @@
+// Guest mode: capture, OCR and one free model without an account. Quotas are kept
+// per device in guest_session.json (ending and restarting guest mode doesn't reset
+// them) and the usage is carried over to the account when the guest registers.
+use super::{User, UserUsage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GUEST_FILE: &str = "guest_session.json";
+pub const GUEST_TIER: &str = "guest";
+pub const GUEST_DAILY_LIMIT: i32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestSession {
+  pub device_id: String,
+  pub active: bool,
+  pub usage: UserUsage,
+  pub created_at: String,
+}
+
+impl GuestSession {
+  fn new() -> Self {
+    Self {
+      device_id: super::random_id(),
+      active: false,
+      usage: UserUsage::default(),
+      created_at: chrono::Utc::now().to_rfc3339(),
+    }
+  }
+
+  /// Stand-in user so the UI can treat guests like any signed-in tier
+  pub fn as_user(&self) -> User {
+    User {
+      id: format!("guest-{}", self.device_id),
+      email: String::new(),
+      name: "Guest".to_string(),
+      tier: GUEST_TIER.to_string(),
+      token: String::new(),
+      usage: self.usage.clone(),
+      created_at: self.created_at.clone(),
+      subscription_status: None,
+      stripe_customer_id: None,
+      usage_daily: Some(self.usage.daily),
+      usage_total: Some(self.usage.total),
+      updated_at: None,
+      refresh_token: None,
+    }
+  }
+}
+
+pub struct GuestStore {
+  path: PathBuf,
+}
+
+impl GuestStore {
+  pub fn new(dir: &Path) -> Self {
+    Self {
+      path: dir.join(GUEST_FILE),
+    }
+  }
+
+  /// This device's guest record (created on first use)
+  pub fn load(&self) -> GuestSession {
+    fs::read_to_string(&self.path)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_else(GuestSession::new)
+  }
+
+  pub fn save(&self, session: &GuestSession) -> Result<(), String> {
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(session)
+      .map_err(|e| format!("Failed to serialize guest session: {}", e))?;
+    fs::write(&self.path, json).map_err(|e| format!("Failed to save guest session: {}", e))
+  }
+}
@@ -0,0 +1,393 @@
+// Secrets in the OS credential store: macOS Keychain, Windows Credential Manager and
+// the Secret Service (GNOME Keyring / KWallet) through `secret-tool` on Linux.
+// Entries are generic passwords under SERVICE, one per account name.
+
+const SERVICE: &str = "com.vely.framesense";
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::SERVICE;
+  use std::ffi::c_void;
+
+  #[link(name = "Security", kind = "framework")]
+  extern "C" {
+    fn SecKeychainAddGenericPassword(
+      keychain: *const c_void,
+      service_name_length: u32,
+      service_name: *const u8,
+      account_name_length: u32,
+      account_name: *const u8,
+      password_length: u32,
+      password_data: *const c_void,
+      item_ref: *mut *mut c_void,
+    ) -> i32;
+    fn SecKeychainFindGenericPassword(
+      keychain_or_array: *const c_void,
+      service_name_length: u32,
+      service_name: *const u8,
+      account_name_length: u32,
+      account_name: *const u8,
+      password_length: *mut u32,
+      password_data: *mut *mut c_void,
+      item_ref: *mut *mut c_void,
+    ) -> i32;
+    fn SecKeychainItemModifyAttributesAndData(
+      item_ref: *mut c_void,
+      attr_list: *const c_void,
+      length: u32,
+      data: *const c_void,
+    ) -> i32;
+    fn SecKeychainItemFreeContent(attr_list: *const c_void, data: *mut c_void) -> i32;
+    fn SecKeychainItemDelete(item_ref: *mut c_void) -> i32;
+  }
+  #[link(name = "CoreFoundation", kind = "framework")]
+  extern "C" {
+    fn CFRelease(cf: *const c_void);
+  }
+  const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+  // Existing item for the account (caller releases it), or None
+  unsafe fn find_item(
+    account: &str,
+    want_data: bool,
+  ) -> Result<Option<(*mut c_void, Option<String>)>, String> {
+    let mut length = 0u32;
+    let mut data: *mut c_void = std::ptr::null_mut();
+    let mut item: *mut c_void = std::ptr::null_mut();
+    let status = SecKeychainFindGenericPassword(
+      std::ptr::null(),
+      SERVICE.len() as u32,
+      SERVICE.as_ptr(),
+      account.len() as u32,
+      account.as_ptr(),
+      if want_data {
+        &mut length
+      } else {
+        std::ptr::null_mut()
+      },
+      if want_data {
+        &mut data
+      } else {
+        std::ptr::null_mut()
+      },
+      &mut item,
+    );
+    match status {
+      0 => {
+        let secret = if want_data && !data.is_null() {
+          let bytes = std::slice::from_raw_parts(data as *const u8, length as usize);
+          let secret = String::from_utf8_lossy(bytes).into_owned();
+          SecKeychainItemFreeContent(std::ptr::null(), data);
+          Some(secret)
+        } else {
+          None
+        };
+        Ok(Some((item, secret)))
+      }
+      ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+      status => Err(format!("Keychain lookup failed (OSStatus {})", status)),
+    }
+  }
+
+  pub fn is_available() -> bool {
+    true
+  }
+
+  pub fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    unsafe {
+      let status = match find_item(account, false)? {
+        Some((item, _)) => {
+          let status = SecKeychainItemModifyAttributesAndData(
+            item,
+            std::ptr::null(),
+            secret.len() as u32,
+            secret.as_ptr() as *const c_void,
+          );
+          CFRelease(item);
+          status
+        }
+        None => SecKeychainAddGenericPassword(
+          std::ptr::null(),
+          SERVICE.len() as u32,
+          SERVICE.as_ptr(),
+          account.len() as u32,
+          account.as_ptr(),
+          secret.len() as u32,
+          secret.as_ptr() as *const c_void,
+          std::ptr::null_mut(),
+        ),
+      };
+      match status {
+        0 => Ok(()),
+        status => Err(format!("Failed to save to Keychain (OSStatus {})", status)),
+      }
+    }
+  }
+
+  pub fn get_secret(account: &str) -> Result<Option<String>, String> {
+    unsafe {
+      Ok(find_item(account, true)?.and_then(|(item, secret)| {
+        CFRelease(item);
+        secret
+      }))
+    }
+  }
+
+  pub fn delete_secret(account: &str) -> Result<(), String> {
+    unsafe {
+      if let Some((item, _)) = find_item(account, false)? {
+        let status = SecKeychainItemDelete(item);
+        CFRelease(item);
+        if status != 0 {
+          return Err(format!(
+            "Failed to delete Keychain item (OSStatus {})",
+            status
+          ));
+        }
+      }
+      Ok(())
+    }
+  }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+  use super::SERVICE;
+  use std::ffi::c_void;
+
+  #[repr(C)]
+  struct Credential {
+    flags: u32,
+    kind: u32,
+    target_name: *mut u16,
+    comment: *mut u16,
+    last_written: [u32; 2], // FILETIME
+    credential_blob_size: u32,
+    credential_blob: *mut u8,
+    persist: u32,
+    attribute_count: u32,
+    attributes: *mut c_void,
+    target_alias: *mut u16,
+    user_name: *mut u16,
+  }
+
+  #[link(name = "advapi32")]
+  extern "system" {
+    fn CredWriteW(credential: *const Credential, flags: u32) -> i32;
+    fn CredReadW(
+      target: *const u16,
+      kind: u32,
+      flags: u32,
+      credential: *mut *mut Credential,
+    ) -> i32;
+    fn CredDeleteW(target: *const u16, kind: u32, flags: u32) -> i32;
+    fn CredFree(buffer: *mut c_void);
+  }
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn GetLastError() -> u32;
+  }
+  const CRED_TYPE_GENERIC: u32 = 1;
+  const CRED_PERSIST_LOCAL_MACHINE: u32 = 2;
+  const ERROR_NOT_FOUND: u32 = 1168;
+  // CRED_MAX_CREDENTIAL_BLOB_SIZE; longer secrets (a JWT plus refresh token easily is)
+  // are split over "<account>", "<account>/1", "<account>/2", ...
+  const MAX_BLOB_SIZE: usize = 5 * 512;
+
+  fn target(account: &str, part: usize) -> Vec<u16> {
+    let name = match part {
+      0 => format!("{}/{}", SERVICE, account),
+      part => format!("{}/{}/{}", SERVICE, account, part),
+    };
+    name.encode_utf16().chain(std::iter::once(0)).collect()
+  }
+
+  pub fn is_available() -> bool {
+    true
+  }
+
+  pub fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    let chunks: Vec<&[u8]> = if secret.is_empty() {
+      vec![&[][..]]
+    } else {
+      secret.as_bytes().chunks(MAX_BLOB_SIZE).collect()
+    };
+    for (part, chunk) in chunks.iter().enumerate() {
+      write_part(account, part, chunk)?;
+    }
+    // A previous, longer secret may have left parts behind
+    delete_parts(account, chunks.len())
+  }
+
+  fn write_part(account: &str, part: usize, chunk: &[u8]) -> Result<(), String> {
+    let mut target = target(account, part);
+    let mut blob = chunk.to_vec();
+    let credential = Credential {
+      flags: 0,
+      kind: CRED_TYPE_GENERIC,
+      target_name: target.as_mut_ptr(),
+      comment: std::ptr::null_mut(),
+      last_written: [0, 0],
+      credential_blob_size: blob.len() as u32,
+      credential_blob: blob.as_mut_ptr(),
+      persist: CRED_PERSIST_LOCAL_MACHINE,
+      attribute_count: 0,
+      attributes: std::ptr::null_mut(),
+      target_alias: std::ptr::null_mut(),
+      user_name: std::ptr::null_mut(),
+    };
+    unsafe {
+      if CredWriteW(&credential, 0) == 0 {
+        return Err(format!(
+          "Failed to save to Credential Manager (error {})",
+          GetLastError()
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  pub fn get_secret(account: &str) -> Result<Option<String>, String> {
+    let mut bytes = Vec::new();
+    for part in 0.. {
+      match read_part(account, part)? {
+        Some(chunk) => bytes.extend_from_slice(&chunk),
+        None if part == 0 => return Ok(None),
+        None => break,
+      }
+    }
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+  }
+
+  fn read_part(account: &str, part: usize) -> Result<Option<Vec<u8>>, String> {
+    let target = target(account, part);
+    unsafe {
+      let mut credential: *mut Credential = std::ptr::null_mut();
+      if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+        return match GetLastError() {
+          ERROR_NOT_FOUND => Ok(None),
+          error => Err(format!(
+            "Credential Manager lookup failed (error {})",
+            error
+          )),
+        };
+      }
+      let bytes = std::slice::from_raw_parts(
+        (*credential).credential_blob,
+        (*credential).credential_blob_size as usize,
+      )
+      .to_vec();
+      CredFree(credential as *mut c_void);
+      Ok(Some(bytes))
+    }
+  }
+
+  pub fn delete_secret(account: &str) -> Result<(), String> {
+    delete_parts(account, 0)
+  }
+
+  // Delete parts `from` onwards, stopping at the first that doesn't exist
+  fn delete_parts(account: &str, from: usize) -> Result<(), String> {
+    for part in from.. {
+      let target = target(account, part);
+      unsafe {
+        if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0 {
+          return match GetLastError() {
+            ERROR_NOT_FOUND => Ok(()),
+            error => Err(format!(
+              "Failed to delete from Credential Manager (error {})",
+              error
+            )),
+          };
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+  use super::SERVICE;
+  use std::io::Write;
+  use std::process::{Command, Stdio};
+
+  // Needs libsecret's `secret-tool` and a running Secret Service. `--version` only proves
+  // the former, so look up an item: a missing one fails quietly, while no D-Bus session
+  // or no Secret Service provider fails with an error on stderr.
+  pub fn is_available() -> bool {
+    lookup("availability-probe").is_ok()
+  }
+
+  fn lookup(account: &str) -> Result<Option<String>, String> {
+    let output = Command::new("secret-tool")
+      .args(["lookup", "service", SERVICE, "account", account])
+      .stdin(Stdio::null())
+      .output()
+      .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() && !stderr.trim().is_empty() {
+      return Err(format!("Secret Service lookup failed: {}", stderr.trim()));
+    }
+    // Exit code 1 with no output means there is no such item
+    if !output.status.success() || output.stdout.is_empty() {
+      return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+  }
+
+  pub fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    // The secret goes through stdin so it never shows up in the process list
+    let mut child = Command::new("secret-tool")
+      .args([
+        "store",
+        "--label=FrameSense",
+        "service",
+        SERVICE,
+        "account",
+        account,
+      ])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::null())
+      .stderr(Stdio::piped())
+      .spawn()
+      .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin
+        .write_all(secret.as_bytes())
+        .map_err(|e| format!("Failed to pass secret to secret-tool: {}", e))?;
+    }
+    let output = child
+      .wait_with_output()
+      .map_err(|e| format!("secret-tool failed: {}", e))?;
+    if !output.status.success() {
+      return Err(format!(
+        "Failed to save to Secret Service: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      ));
+    }
+    Ok(())
+  }
+
+  pub fn get_secret(account: &str) -> Result<Option<String>, String> {
+    lookup(account)
+  }
+
+  pub fn delete_secret(account: &str) -> Result<(), String> {
+    let output = Command::new("secret-tool")
+      .args(["clear", "service", SERVICE, "account", account])
+      .stdin(Stdio::null())
+      .output()
+      .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() && !stderr.trim().is_empty() {
+      return Err(format!(
+        "Failed to delete from Secret Service: {}",
+        stderr.trim()
+      ));
+    }
+    Ok(())
+  }
+}
+
+pub use platform::{delete_secret, get_secret, is_available, set_secret};
@@ -0,0 +1,161 @@
+// Offline licenses for air-gapped enterprise installs. A license key is
+// `<payload>.<signature>` (both base64url): the payload is the JSON `LicenseClaims`, the
+// signature an Ed25519 signature over the payload bytes, checked against the public key
+// baked into the build. No backend request is ever made.
+use super::{User, UserUsage};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const LICENSE_FILE: &str = "license.json";
+pub const LICENSE_TIER: &str = "enterprise";
+
+// base64 of the raw 32-byte Ed25519 public key. Release builds can't be made without it;
+// debug builds just can't activate licenses.
+#[cfg(not(debug_assertions))]
+const LICENSE_PUBLIC_KEY: Option<&str> = Some(env!(
+  "FRAMESENSE_LICENSE_PUBLIC_KEY",
+  "FRAMESENSE_LICENSE_PUBLIC_KEY must be set for release builds"
+));
+#[cfg(debug_assertions)]
+const LICENSE_PUBLIC_KEY: Option<&str> = option_env!("FRAMESENSE_LICENSE_PUBLIC_KEY");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseClaims {
+  pub license_id: String,
+  pub customer: String,
+  pub email: Option<String>,
+  pub tier: String,
+  pub seats: Option<u32>,
+  pub issued_at: String,
+  // RFC 3339; perpetual when missing
+  pub expires_at: Option<String>,
+}
+
+impl LicenseClaims {
+  /// Whether the license has run out; an expiry that doesn't parse is an error, not
+  /// a perpetual license
+  pub fn is_expired(&self) -> Result<bool, String> {
+    match self.expires_at.as_deref() {
+      Some(expires_at) => chrono::DateTime::parse_from_rfc3339(expires_at)
+        .map(|expires_at| expires_at < chrono::Utc::now())
+        .map_err(|e| format!("Invalid license expiry '{}': {}", expires_at, e)),
+      None => Ok(false),
+    }
+  }
+
+  /// Stand-in user for the licensed install
+  pub fn as_user(&self, usage: &UserUsage) -> User {
+    User {
+      id: format!("license-{}", self.license_id),
+      email: self.email.clone().unwrap_or_default(),
+      name: self.customer.clone(),
+      tier: self.tier.clone(),
+      token: String::new(),
+      usage: usage.clone(),
+      created_at: self.issued_at.clone(),
+      subscription_status: Some("licensed".to_string()),
+      stripe_customer_id: None,
+      usage_daily: Some(usage.daily),
+      usage_total: Some(usage.total),
+      updated_at: None,
+      refresh_token: None,
+    }
+  }
+}
+
+/// Check the signature and expiry of a license key and return its claims
+pub fn verify_license(key: &str) -> Result<LicenseClaims, String> {
+  let public_key = LICENSE_PUBLIC_KEY
+    .ok_or("This build can't verify offline licenses (no license public key)".to_string())?;
+  let public_key = base64::engine::general_purpose::STANDARD
+    .decode(public_key.trim())
+    .map_err(|e| format!("Invalid license public key: {}", e))?;
+
+  let (payload, signature) = key
+    .trim()
+    .split_once('.')
+    .ok_or("Invalid license key format".to_string())?;
+  let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+  let payload = engine
+    .decode(payload.trim_end_matches('='))
+    .map_err(|_| "Invalid license key format".to_string())?;
+  let signature = engine
+    .decode(signature.trim_end_matches('='))
+    .map_err(|_| "Invalid license key format".to_string())?;
+
+  ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, &public_key)
+    .verify(&payload, &signature)
+    .map_err(|_| "License signature is not valid".to_string())?;
+
+  let claims: LicenseClaims =
+    serde_json::from_slice(&payload).map_err(|e| format!("Invalid license contents: {}", e))?;
+  if claims.tier != LICENSE_TIER {
+    return Err(format!("Unsupported license tier: {}", claims.tier));
+  }
+  if claims.is_expired()? {
+    return Err("License has expired".to_string());
+  }
+  Ok(claims)
+}
+
+// What's kept on disk: the key itself (re-verified on every load) and local usage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivatedLicense {
+  pub key: String,
+  pub usage: UserUsage,
+}
+
+pub struct LicenseStore {
+  path: PathBuf,
+}
+
+impl LicenseStore {
+  pub fn new(dir: &Path) -> Self {
+    Self {
+      path: dir.join(LICENSE_FILE),
+    }
+  }
+
+  /// The activated license with its claims, if it is still valid
+  pub fn load(&self) -> Option<(ActivatedLicense, LicenseClaims)> {
+    let json = fs::read_to_string(&self.path).ok()?;
+    let license: ActivatedLicense = serde_json::from_str(&json).ok()?;
+    match verify_license(&license.key) {
+      Ok(claims) => Some((license, claims)),
+      Err(e) => {
+        warn!("⚠️ Ignoring stored license: {}", e);
+        None
+      }
+    }
+  }
+
+  pub fn save(&self, license: &ActivatedLicense) -> Result<(), String> {
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(license)
+      .map_err(|e| format!("Failed to serialize license: {}", e))?;
+    // Written aside and renamed so a crash mid-write can't truncate the license
+    let temp_path = self.path.with_extension("json.tmp");
+    let mut file =
+      fs::File::create(&temp_path).map_err(|e| format!("Failed to save license: {}", e))?;
+    file
+      .write_all(json.as_bytes())
+      .and_then(|_| file.sync_all())
+      .map_err(|e| format!("Failed to save license: {}", e))?;
+    fs::rename(&temp_path, &self.path).map_err(|e| format!("Failed to save license: {}", e))
+  }
+
+  pub fn remove(&self) -> Result<(), String> {
+    if self.path.exists() {
+      fs::remove_file(&self.path).map_err(|e| format!("Failed to remove license: {}", e))?;
+    }
+    Ok(())
+  }
+}
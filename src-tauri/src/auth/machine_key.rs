@@ -0,0 +1,174 @@
+// Encryption at rest for files that can't go to the OS credential store. The key is
+// derived from this machine's identifier and the OS account (uid / SID), so a copied file
+// is useless on another machine or account. AES-256-GCM also detects truncated or altered
+// files. Version 1 files were keyed on the user name and are still readable.
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::warn;
+
+const KEY_CONTEXT: &[u8] = b"com.vely.framesense/session-key/v1";
+const SEAL_VERSION: u32 = 2;
+// Keyed on the USER/USERNAME environment variable, which anyone can change
+const LEGACY_SEAL_VERSION: u32 = 1;
+
+static KEY: OnceLock<Result<LessSafeKey, String>> = OnceLock::new();
+static LEGACY_KEY: OnceLock<Result<LessSafeKey, String>> = OnceLock::new();
+
+#[derive(Serialize, Deserialize)]
+pub struct SealedData {
+  pub version: u32,
+  pub nonce: String,
+  pub ciphertext: String,
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+  ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+    .iter()
+    .find_map(|path| std::fs::read_to_string(path).ok())
+    .map(|id| id.trim().to_string())
+    .filter(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+  let output = Command::new("ioreg")
+    .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+    .output()
+    .ok()?;
+  String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .find(|line| line.contains("IOPlatformUUID"))
+    .and_then(|line| line.split('"').nth(3))
+    .map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+  let output = Command::new("reg")
+    .args([
+      "query",
+      r"HKLM\SOFTWARE\Microsoft\Cryptography",
+      "/v",
+      "MachineGuid",
+    ])
+    .output()
+    .ok()?;
+  String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .find(|line| line.contains("MachineGuid"))
+    .and_then(|line| line.split_whitespace().last())
+    .map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn machine_id() -> Option<String> {
+  None
+}
+
+fn hostname() -> Option<String> {
+  let output = Command::new("hostname").output().ok()?;
+  Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|name| !name.is_empty())
+}
+
+fn user_name() -> String {
+  std::env::var("USER")
+    .or_else(|_| std::env::var("USERNAME"))
+    .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn account_id() -> Option<String> {
+  let output = Command::new("id").arg("-u").output().ok()?;
+  Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|uid| !uid.is_empty())
+}
+
+#[cfg(windows)]
+fn account_id() -> Option<String> {
+  // "DOMAIN\user","S-1-5-21-..."
+  let output = Command::new("whoami")
+    .args(["/user", "/fo", "csv", "/nh"])
+    .output()
+    .ok()?;
+  String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .rsplit(',')
+    .next()
+    .map(|sid| sid.trim_matches('"').to_string())
+    .filter(|sid| sid.starts_with("S-"))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn account_id() -> Option<String> {
+  None
+}
+
+// Derived once per run; the account and machine can't change under a running process
+fn key(version: u32) -> Result<&'static LessSafeKey, String> {
+  let key = match version {
+    SEAL_VERSION => KEY.get_or_init(|| {
+      // Falls back to the user name where the account id can't be read
+      let account = account_id().unwrap_or_else(|| {
+        warn!("⚠️ Could not read the OS account id, keying on the user name");
+        user_name()
+      });
+      derive_key(&account)
+    }),
+    LEGACY_SEAL_VERSION => LEGACY_KEY.get_or_init(|| derive_key(&user_name())),
+    version => return Err(format!("Unsupported encryption version {}", version)),
+  };
+  key.as_ref().map_err(Clone::clone)
+}
+
+// SHA-256 over the context, machine id (hostname when there is none) and OS account
+fn derive_key(user: &str) -> Result<LessSafeKey, String> {
+  let machine = machine_id().or_else(hostname).unwrap_or_default();
+  let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+  context.update(KEY_CONTEXT);
+  context.update(machine.as_bytes());
+  context.update(&[0]);
+  context.update(user.as_bytes());
+  let key = UnboundKey::new(&AES_256_GCM, context.finish().as_ref())
+    .map_err(|_| "Failed to derive encryption key".to_string())?;
+  Ok(LessSafeKey::new(key))
+}
+
+pub fn seal(plaintext: &[u8]) -> Result<SealedData, String> {
+  let key = key(SEAL_VERSION)?;
+  let mut nonce = [0u8; NONCE_LEN];
+  SystemRandom::new()
+    .fill(&mut nonce)
+    .map_err(|_| "Failed to generate nonce".to_string())?;
+  let mut data = plaintext.to_vec();
+  key
+    .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+    .map_err(|_| "Failed to encrypt data".to_string())?;
+  let engine = base64::engine::general_purpose::STANDARD;
+  Ok(SealedData {
+    version: SEAL_VERSION,
+    nonce: engine.encode(nonce),
+    ciphertext: engine.encode(data),
+  })
+}
+
+/// Decrypt; fails if the data was altered, truncated or sealed on another machine/user
+pub fn open(sealed: &SealedData) -> Result<Vec<u8>, String> {
+  let key = key(sealed.version)?;
+  let engine = base64::engine::general_purpose::STANDARD;
+  let nonce: [u8; NONCE_LEN] = engine
+    .decode(&sealed.nonce)
+    .ok()
+    .and_then(|nonce| nonce.try_into().ok())
+    .ok_or("Invalid nonce".to_string())?;
+  let mut data = engine
+    .decode(&sealed.ciphertext)
+    .map_err(|_| "Invalid ciphertext".to_string())?;
+  let plaintext = key
+    .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+    .map_err(|_| "Decryption failed (corrupted or from another machine)".to_string())?;
+  Ok(plaintext.to_vec())
+}
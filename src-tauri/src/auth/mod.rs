@@ -0,0 +1,1355 @@
+use tracing::{error, info, warn};
+
+pub mod events;
+pub mod guest;
+pub(crate) mod keychain;
+pub mod license;
+pub(crate) mod machine_key;
+pub mod models;
+pub mod oauth;
+pub mod session_store;
+pub mod two_factor;
+pub mod usage;
+
+use chrono;
+use reqwest;
+use serde::{Deserialize, Serialize};
+//use std::collections::HashMap;
+use ring::rand::SecureRandom;
+use std::fs;
+use std::path::PathBuf;
+//use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+
+use crate::network::{NetworkError, NetworkSettings, SendWithRetry};
+pub use events::AuthEvent;
+pub use models::ModelMatrix;
+pub use oauth::OAuthProvider;
+use session_store::SessionStore;
+use std::sync::Arc;
+use std::sync::Mutex;
+pub use two_factor::LoginResult;
+use two_factor::{TrustedDevices, TwoFactorChallenge};
+pub use usage::UsageStatus;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct User {
+  pub id: String,
+  pub email: String,
+  pub name: String,
+  pub tier: String, // "free", "premium", "pro", "enterprise"
+  pub token: String,
+  pub usage: UserUsage,
+  pub created_at: String,
+  pub subscription_status: Option<String>,
+  pub stripe_customer_id: Option<String>,
+  pub usage_daily: Option<i32>,
+  pub usage_total: Option<i32>,
+  pub updated_at: Option<String>,
+  // Long-lived token used to get a new `token` before it expires
+  pub refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserUsage {
+  pub daily: i32,
+  pub total: i32,
+  pub last_reset: String,
+}
+
+// Add the missing Usage struct that main.rs references
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Usage {
+  pub daily: i32,
+  pub total: i32,
+  pub last_reset: String,
+}
+
+impl User {
+  // Convert backend user format to frontend User format
+  fn from_backend(backend_user: BackendUser, token: String, refresh_token: Option<String>) -> Self {
+    Self {
+      id: backend_user.id,
+      email: backend_user.email,
+      name: backend_user.name,
+      tier: backend_user.tier,
+      token,
+      usage: UserUsage {
+        daily: backend_user.usage_daily.unwrap_or(0),
+        total: backend_user.usage_total.unwrap_or(0),
+        last_reset: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+      },
+      created_at: backend_user
+        .created_at
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+      subscription_status: backend_user.subscription_status,
+      stripe_customer_id: backend_user.stripe_customer_id,
+      usage_daily: backend_user.usage_daily,
+      usage_total: backend_user.usage_total,
+      updated_at: backend_user.updated_at,
+      refresh_token,
+    }
+  }
+
+  /// Expiry of the access token (unix seconds), from its JWT `exp` claim
+  pub fn token_expires_at(&self) -> Option<i64> {
+    token_expiry(&self.token)
+  }
+}
+
+impl Default for UserUsage {
+  fn default() -> Self {
+    Self {
+      daily: 0,
+      total: 0,
+      last_reset: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginRequest {
+  pub email: String,
+  pub password: String,
+  // Skips the 2FA code on a device the user trusted before
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub trusted_device_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterRequest {
+  pub email: String,
+  pub password: String,
+  pub name: String,
+  // Guest usage carried over to the new account
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub guest_device_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub guest_usage_total: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendUser {
+  pub id: String,
+  pub email: String,
+  pub name: String,
+  pub tier: String,
+  pub subscription_status: Option<String>,
+  pub stripe_customer_id: Option<String>,
+  pub usage_daily: Option<i32>,
+  pub usage_total: Option<i32>,
+  pub created_at: Option<String>,
+  pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthResponse {
+  pub success: bool,
+  pub user: Option<BackendUser>,
+  pub token: Option<String>,
+  pub refresh_token: Option<String>,
+  pub message: Option<String>,
+  #[serde(rename = "2fa_required", default)]
+  pub two_factor_required: bool,
+  pub challenge_token: Option<String>,
+  // Issued when the user chose to trust this device during 2FA
+  pub trusted_device_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckoutRequest {
+  pub plan: String,
+}
+
+// Stripe checkout / customer portal session created by the backend
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BillingSessionResponse {
+  pub success: bool,
+  pub url: Option<String>,
+  pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshRequest {
+  pub refresh_token: String,
+}
+
+/// Why a session refresh failed: only `Rejected` means the session is gone for good
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefreshError {
+  // 401 / invalid_grant, or there is no refresh token to try
+  Rejected(String),
+  // Offline, timed out, 5xx, ... - the refresh token may still be good
+  Failed(String),
+}
+
+impl std::fmt::Display for RefreshError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RefreshError::Rejected(message) | RefreshError::Failed(message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl From<RefreshError> for String {
+  fn from(error: RefreshError) -> Self {
+    error.to_string()
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogoutRequest {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub refresh_token: Option<String>,
+}
+
+/// Outcome of a logout. The local session is always gone; `revoked` says whether the
+/// backend also invalidated the tokens.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutResult {
+  pub revoked: bool,
+  // Why revocation failed (offline, timed out, rejected)
+  pub revocation_error: Option<String>,
+}
+
+// Only the expiry is needed; the signature is the backend's to check
+#[derive(Debug, Deserialize)]
+struct ExpiryClaim {
+  exp: i64,
+}
+
+fn token_expiry(token: &str) -> Option<i64> {
+  let mut validation = jsonwebtoken::Validation::default();
+  validation.insecure_disable_signature_validation();
+  validation.validate_exp = false;
+  jsonwebtoken::decode::<ExpiryClaim>(
+    token,
+    &jsonwebtoken::DecodingKey::from_secret(&[]),
+    &validation,
+  )
+  .ok()
+  .map(|data| data.claims.exp)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+  pub user_id: String,
+  pub email: String,
+  pub exp: usize,
+}
+
+// How often the background task looks at the session, and how long before expiry it
+// refreshes the token
+pub const SESSION_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+pub const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+// Logout must not hang on a dead network
+const REVOKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+// Picks up upgrades/downgrades made on the website (Stripe) while the app runs
+pub const TIER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+#[derive(Clone)]
+pub struct AuthService {
+  api_url: String,
+  storage_path: Option<PathBuf>,
+  session_store: Option<Arc<dyn SessionStore>>,
+  model_matrix: Option<ModelMatrix>,
+  network: NetworkSettings,
+  // Built from `network` whenever it changes; reqwest clients share their pool on clone
+  client: Result<reqwest::Client, String>,
+  // Shared by every clone of the service
+  usage_queue: Arc<Mutex<usage::UsageQueue>>,
+  usage_counter: Arc<Mutex<usage::UsageCounter>>,
+  // Guards the load-check-save of usage kept on disk (guest mode and licenses)
+  local_usage_lock: Arc<Mutex<()>>,
+  two_factor_challenge: Arc<Mutex<Option<TwoFactorChallenge>>>,
+  trusted_devices: Option<Arc<TrustedDevices>>,
+  guest_store: Option<Arc<guest::GuestStore>>,
+  license_store: Option<Arc<license::LicenseStore>>,
+  // Where auth events go; without one (e.g. before setup) they are dropped
+  app_handle: Option<tauri::AppHandle>,
+}
+
+impl AuthService {
+  pub fn new() -> Self {
+    Self {
+      api_url: crate::settings::DEFAULT_API_URL.to_string(), // Railway backend URL
+      storage_path: None,
+      session_store: None,
+      model_matrix: None,
+      network: NetworkSettings::default(),
+      client: crate::network::http_client(&NetworkSettings::default()),
+      usage_queue: Arc::new(Mutex::new(usage::UsageQueue::default())),
+      usage_counter: Arc::new(Mutex::new(usage::UsageCounter::default())),
+      local_usage_lock: Arc::new(Mutex::new(())),
+      two_factor_challenge: Arc::new(Mutex::new(None)),
+      trusted_devices: None,
+      guest_store: None,
+      license_store: None,
+      app_handle: None,
+    }
+  }
+
+  pub fn with_storage_path(mut self, path: PathBuf) -> Self {
+    self.session_store = Some(session_store::for_storage_dir(path.clone()));
+    // Stale matrices still beat the built-in list until a fresh one is fetched
+    self.model_matrix = ModelMatrix::load(&path);
+    self.usage_queue = Arc::new(Mutex::new(usage::UsageQueue::load(&path)));
+    self.trusted_devices = Some(Arc::new(TrustedDevices::new(path.clone())));
+    self.guest_store = Some(Arc::new(guest::GuestStore::new(&path)));
+    self.license_store = Some(Arc::new(license::LicenseStore::new(&path)));
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn with_network_settings(mut self, network: NetworkSettings) -> Self {
+    self.set_network_settings(network);
+    self
+  }
+
+  pub fn set_network_settings(&mut self, network: NetworkSettings) {
+    self.client = crate::network::http_client(&network);
+    self.network = network;
+  }
+
+  pub fn with_api_url(mut self, api_url: &str) -> Self {
+    self.set_api_url(api_url);
+    self
+  }
+
+  pub fn set_api_url(&mut self, api_url: &str) {
+    self.api_url = api_url.trim_end_matches('/').to_string();
+  }
+
+  pub fn set_app_handle(&mut self, app_handle: tauri::AppHandle) {
+    self.app_handle = Some(app_handle);
+  }
+
+  fn emit(&self, event: AuthEvent) {
+    if let Some(app_handle) = &self.app_handle {
+      event.emit(app_handle);
+    }
+  }
+
+  pub fn api_url(&self) -> &str {
+    &self.api_url
+  }
+
+  /// Open a URL from the backend (sign-in, checkout, billing portal) or a Slack install
+  /// in the default browser. Only https links to the backend, Stripe or Slack are opened,
+  /// so a compromised or spoofed response can't launch arbitrary URLs or local files.
+  pub fn open_in_browser(&self, url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid browser URL: {}", e))?;
+    let host = parsed.host_str().unwrap_or_default();
+    let backend_host = url::Url::parse(&self.api_url)
+      .ok()
+      .and_then(|api| api.host_str().map(str::to_string));
+    let allowed_host = BROWSER_HOSTS.contains(&host) || backend_host.as_deref() == Some(host);
+    // A local development backend is the only plain-http exception
+    let allowed_scheme = parsed.scheme() == "https"
+      || (parsed.scheme() == "http" && matches!(host, "localhost" | "127.0.0.1"));
+    if !allowed_host || !allowed_scheme {
+      warn!("⚠️ Refusing to open {} in the browser", url);
+      return Err("Refusing to open an untrusted link".to_string());
+    }
+    open_in_browser(parsed.as_str())
+  }
+
+  pub fn network_settings(&self) -> &NetworkSettings {
+    &self.network
+  }
+
+  fn client(&self) -> Result<reqwest::Client, String> {
+    self.client.clone()
+  }
+
+  pub async fn login_user(&self, email: String, password: String) -> Result<LoginResult, String> {
+    let client = self.client()?;
+
+    let trusted_device_token = self
+      .trusted_devices
+      .as_ref()
+      .and_then(|devices| devices.token_for(&email));
+    let login_data = LoginRequest {
+      email: email.clone(),
+      password,
+      trusted_device_token,
+    };
+
+    let response = client
+      .post(&format!("{}/api/auth/login", self.api_url))
+      .json(&login_data)
+      .send_with_retry()
+      .await?;
+
+    // A 2FA challenge may come with a 401/403, so read the body either way
+    let status = response.status();
+    let auth_response: AuthResponse = match response.json().await {
+      Ok(auth_response) => auth_response,
+      Err(_) if !status.is_success() => return Err("Authentication failed".to_string()),
+      Err(e) => return Err(format!("Parse error: {}", e)),
+    };
+
+    if auth_response.two_factor_required {
+      let challenge_token = auth_response
+        .challenge_token
+        .ok_or("Invalid response format".to_string())?;
+      *self.two_factor_challenge.lock().unwrap() = Some(TwoFactorChallenge {
+        email: email.clone(),
+        challenge_token,
+      });
+      info!("🔐 Two-factor code required for {}", email);
+      return Ok(LoginResult::TwoFactorRequired { email });
+    }
+    if !status.is_success() {
+      return Err(
+        auth_response
+          .message
+          .unwrap_or("Authentication failed".to_string()),
+      );
+    }
+
+    if auth_response.success {
+      if let (Some(backend_user), Some(token)) = (auth_response.user, auth_response.token) {
+        let user = User::from_backend(backend_user, token, auth_response.refresh_token);
+
+        // Save user session locally
+        self.save_user_session(&user).await?;
+
+        info!(
+          "✅ User logged in successfully: {} ({})",
+          user.email, user.tier
+        );
+        self.emit(AuthEvent::Login { user: user.clone() });
+        Ok(LoginResult::Success {
+          user: Box::new(user),
+        })
+      } else {
+        Err("Invalid response format".to_string())
+      }
+    } else {
+      Err(auth_response.message.unwrap_or("Login failed".to_string()))
+    }
+  }
+
+  /// Finish a login that answered `2fa_required`. With `trust_device` the backend's
+  /// device token is kept so this account skips the code here next time.
+  pub async fn submit_totp_code(&self, code: String, trust_device: bool) -> Result<User, String> {
+    let code = two_factor::normalize_code(&code)?;
+    let challenge = self
+      .two_factor_challenge
+      .lock()
+      .unwrap()
+      .clone()
+      .ok_or("No sign-in is waiting for a code".to_string())?;
+
+    let client = self.client()?;
+    let response = client
+      .post(format!("{}/api/auth/2fa/verify", self.api_url))
+      .json(&two_factor::TotpVerifyRequest {
+        challenge_token: challenge.challenge_token,
+        code,
+        trust_device,
+      })
+      .send_with_retry()
+      .await?;
+
+    let status = response.status();
+    let auth_response: AuthResponse = match response.json().await {
+      Ok(auth_response) => auth_response,
+      Err(_) if !status.is_success() => return Err("Invalid code".to_string()),
+      Err(e) => return Err(format!("Parse error: {}", e)),
+    };
+    if !status.is_success() || !auth_response.success {
+      // The challenge stays valid for another try until the backend expires it
+      return Err(auth_response.message.unwrap_or("Invalid code".to_string()));
+    }
+    let (Some(backend_user), Some(token)) = (auth_response.user, auth_response.token) else {
+      return Err("Invalid response format".to_string());
+    };
+    *self.two_factor_challenge.lock().unwrap() = None;
+
+    let user = User::from_backend(backend_user, token, auth_response.refresh_token);
+    if let (Some(devices), Some(device_token)) =
+      (&self.trusted_devices, auth_response.trusted_device_token)
+    {
+      if let Err(e) = devices.remember(&challenge.email, device_token) {
+        warn!("⚠️ Failed to remember trusted device: {}", e);
+      }
+    }
+    self.save_user_session(&user).await?;
+
+    info!(
+      "✅ User logged in with two-factor code: {} ({})",
+      user.email, user.tier
+    );
+    self.emit(AuthEvent::Login { user: user.clone() });
+    Ok(user)
+  }
+
+  /// Require the 2FA code again on this device for `email`
+  pub fn forget_trusted_device(&self, email: &str) -> Result<(), String> {
+    match &self.trusted_devices {
+      Some(devices) => devices.forget(email),
+      None => Ok(()),
+    }
+  }
+
+  pub fn forget_all_trusted_devices(&self) -> Result<(), String> {
+    match &self.trusted_devices {
+      Some(devices) => devices.forget_all(),
+      None => Ok(()),
+    }
+  }
+
+  pub async fn register_user(
+    &self,
+    email: String,
+    password: String,
+    name: String,
+  ) -> Result<User, String> {
+    let email = email.trim().to_string();
+    let name = name.trim().to_string();
+    validate_registration(&email, &password, &name)?;
+
+    let client = self.client()?;
+    let guest = self.active_guest();
+    let register_data = RegisterRequest {
+      email,
+      password,
+      name,
+      guest_device_id: guest.as_ref().map(|guest| guest.device_id.clone()),
+      guest_usage_total: guest.as_ref().map(|guest| guest.usage.total),
+    };
+
+    let response = client
+      .post(format!("{}/api/auth/register", self.api_url))
+      .json(&register_data)
+      .send_with_retry()
+      .await?;
+
+    let status = response.status();
+    // Error responses (e.g. 409 email taken) still carry a message
+    let auth_response: AuthResponse = match response.json().await {
+      Ok(auth_response) => auth_response,
+      Err(_) if !status.is_success() => return Err("Registration failed".to_string()),
+      Err(e) => return Err(format!("Parse error: {}", e)),
+    };
+
+    if !status.is_success() || !auth_response.success {
+      return Err(
+        auth_response
+          .message
+          .unwrap_or("Registration failed".to_string()),
+      );
+    }
+    if let (Some(backend_user), Some(token)) = (auth_response.user, auth_response.token) {
+      let mut user = User::from_backend(backend_user, token, auth_response.refresh_token);
+
+      // Today's guest requests keep counting against the new account's quota
+      if let Some(mut guest) = guest {
+        usage::reset_if_new_day(&mut guest.usage);
+        user.usage.daily = user.usage.daily.max(guest.usage.daily);
+        user.usage.total = user.usage.total.max(guest.usage.total);
+        user.usage_daily = Some(user.usage.daily);
+        user.usage_total = Some(user.usage.total);
+        self.deactivate_guest()?;
+        info!("🔀 Migrated guest usage to the new account");
+      }
+
+      // Signed in straight away, same as after login
+      self.save_user_session(&user).await?;
+
+      info!(
+        "✅ User registered successfully: {} ({})",
+        user.email, user.tier
+      );
+      self.emit(AuthEvent::Login { user: user.clone() });
+      Ok(user)
+    } else {
+      Err("Invalid response format".to_string())
+    }
+  }
+
+  /// Sign in through Google/GitHub in the browser; the session is saved like a login
+  pub async fn login_with_provider(&self, provider: OAuthProvider) -> Result<User, String> {
+    let server = oauth::CallbackServer::bind().await?;
+    let redirect_uri = server.redirect_uri()?;
+
+    let mut authorize_url = url::Url::parse(&format!(
+      "{}/api/auth/oauth/{}",
+      self.api_url,
+      provider.as_str()
+    ))
+    .map_err(|e| format!("Invalid sign-in URL: {}", e))?;
+    authorize_url
+      .query_pairs_mut()
+      .append_pair("redirect_uri", &redirect_uri)
+      .append_pair("state", server.state());
+
+    info!("🌐 Opening {} sign-in in the browser", provider.as_str());
+    self.open_in_browser(authorize_url.as_str())?;
+    let code = server.wait_for_code().await?;
+
+    let client = self.client()?;
+    let response = client
+      .post(format!(
+        "{}/api/auth/oauth/{}/callback",
+        self.api_url,
+        provider.as_str()
+      ))
+      .json(&oauth::OAuthExchangeRequest { code, redirect_uri })
+      .send_with_retry()
+      .await?;
+
+    if !response.status().is_success() {
+      return Err("Authentication failed".to_string());
+    }
+    let auth_response: AuthResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("Parse error: {}", e))?;
+    if !auth_response.success {
+      return Err(
+        auth_response
+          .message
+          .unwrap_or("Sign-in failed".to_string()),
+      );
+    }
+    if let (Some(backend_user), Some(token)) = (auth_response.user, auth_response.token) {
+      let user = User::from_backend(backend_user, token, auth_response.refresh_token);
+      self.save_user_session(&user).await?;
+
+      info!(
+        "✅ User signed in with {}: {} ({})",
+        provider.as_str(),
+        user.email,
+        user.tier
+      );
+      self.emit(AuthEvent::Login { user: user.clone() });
+      Ok(user)
+    } else {
+      Err("Invalid response format".to_string())
+    }
+  }
+
+  // Payment verification - loads fresh user data from backend. Returns the user with
+  // the tier the saved session had before.
+  pub async fn verify_payment_and_update(&self) -> Result<Option<(User, String)>, String> {
+    // First check if we have a current session
+    if let Some(current_user) = self.load_user_session().await? {
+      // Verify current token with backend to get latest user data
+      let mut updated_user = self.verify_token(current_user.token).await?;
+      updated_user.refresh_token = current_user.refresh_token;
+      //HÄRRR!!
+      // If tier changed, save updated session
+      if updated_user.tier != current_user.tier {
+        info!(
+          "🔄 User tier updated from {} to {}",
+          current_user.tier, updated_user.tier
+        );
+        self.save_user_session(&updated_user).await?;
+        self.emit(AuthEvent::TierChanged {
+          previous_tier: current_user.tier.clone(),
+          user: updated_user.clone(),
+        });
+      }
+
+      Ok(Some((updated_user, current_user.tier)))
+    } else {
+      // No current session
+      Ok(None)
+    }
+  }
+
+  /// Swap the saved session's refresh token for a new access token and save it
+  pub async fn refresh_session(&self) -> Result<User, RefreshError> {
+    let current_user = self
+      .load_user_session()
+      .await
+      .map_err(RefreshError::Failed)?
+      .ok_or(RefreshError::Failed("No session to refresh".to_string()))?;
+    let refresh_token = current_user
+      .refresh_token
+      .clone()
+      .ok_or(RefreshError::Rejected(
+        "Session has no refresh token".to_string(),
+      ))?;
+
+    let client = self.client().map_err(RefreshError::Failed)?;
+    let response = client
+      .post(format!("{}/api/auth/refresh", self.api_url))
+      .json(&RefreshRequest { refresh_token })
+      .send_with_retry()
+      .await
+      .map_err(|e| RefreshError::Failed(e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      let message = format!("Session refresh failed ({})", status);
+      // OAuth-style backends answer a dead refresh token with 400 invalid_grant
+      return Err(
+        if status == reqwest::StatusCode::UNAUTHORIZED || body.contains("invalid_grant") {
+          RefreshError::Rejected(message)
+        } else {
+          RefreshError::Failed(message)
+        },
+      );
+    }
+    let auth_response: AuthResponse = response
+      .json()
+      .await
+      .map_err(|e| RefreshError::Failed(format!("Parse error: {}", e)))?;
+    let token = match auth_response.token {
+      Some(token) if auth_response.success => token,
+      _ => {
+        return Err(RefreshError::Failed(
+          auth_response
+            .message
+            .unwrap_or("Session refresh failed".to_string()),
+        ))
+      }
+    };
+
+    // The backend may rotate the refresh token; keep the old one otherwise
+    let refresh_token = auth_response
+      .refresh_token
+      .or(current_user.refresh_token.clone());
+    let user = match auth_response.user {
+      Some(backend_user) => User::from_backend(backend_user, token, refresh_token),
+      None => User {
+        token,
+        refresh_token,
+        ..current_user
+      },
+    };
+    self
+      .save_user_session(&user)
+      .await
+      .map_err(RefreshError::Failed)?;
+    info!("🔄 Session token refreshed for {}", user.email);
+    Ok(user)
+  }
+
+  pub async fn verify_token(&self, token: String) -> Result<User, String> {
+    let client = self.client()?;
+    //Härr
+    let response = client
+      .get(&format!("{}/api/auth/verify", self.api_url))
+      .header("Authorization", format!("Bearer {}", token))
+      .send_with_retry()
+      .await?;
+
+    if response.status().is_success() {
+      let auth_response: AuthResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))?;
+
+      if auth_response.success {
+        if let Some(backend_user) = auth_response.user {
+          let user = User::from_backend(backend_user, token.clone(), None);
+          Ok(user)
+        } else {
+          Err("Invalid response format".to_string())
+        }
+      } else {
+        Err("Token verification failed".to_string())
+      }
+    } else {
+      Err("Authentication failed".to_string())
+    }
+  }
+
+  /// Revoke the tokens server-side (best effort) and clear the local session. Only a
+  /// failure to clear the local session is an error.
+  pub async fn logout_user(&self) -> Result<LogoutResult, String> {
+    let revocation = match self.load_user_session().await {
+      Ok(Some(user)) => self.revoke_session(&user).await,
+      Ok(None) => Ok(()),
+      Err(e) => Err(e),
+    };
+
+    self.clear_user_session().await?;
+    self.emit(AuthEvent::Logout);
+
+    match revocation {
+      Ok(()) => Ok(LogoutResult {
+        revoked: true,
+        revocation_error: None,
+      }),
+      Err(e) => {
+        warn!(
+          "⚠️ Signed out locally, but the session was not revoked: {}",
+          e
+        );
+        Ok(LogoutResult {
+          revoked: false,
+          revocation_error: Some(e),
+        })
+      }
+    }
+  }
+
+  // Single attempt with a short timeout; the user is waiting to be signed out
+  async fn revoke_session(&self, user: &User) -> Result<(), String> {
+    let response = self
+      .client()?
+      .post(format!("{}/api/auth/logout", self.api_url))
+      .header("Authorization", format!("Bearer {}", user.token))
+      .json(&LogoutRequest {
+        refresh_token: user.refresh_token.clone(),
+      })
+      .timeout(REVOKE_TIMEOUT)
+      .send_once()
+      .await?;
+
+    // Already expired or revoked tokens are just as dead
+    let status = response.status();
+    if status.is_success() || status == reqwest::StatusCode::UNAUTHORIZED {
+      info!("🔒 Session revoked on the server");
+      Ok(())
+    } else {
+      Err(format!("Server returned {}", status))
+    }
+  }
+
+  /// Drop a session whose token ran out and couldn't be refreshed
+  pub async fn expire_session(&self, email: &str) -> Result<(), String> {
+    self.clear_user_session().await?;
+    self.emit(AuthEvent::SessionExpired {
+      email: email.to_string(),
+    });
+    Ok(())
+  }
+
+  /// The signed-in account, else the offline license holder, else the guest stand-in
+  /// while guest mode is on
+  pub async fn get_current_user(&self) -> Result<Option<User>, String> {
+    if let Some(user) = self.load_user_session().await? {
+      return Ok(Some(user));
+    }
+    if let Some((license, claims)) = self.active_license() {
+      return Ok(Some(claims.as_user(&license.usage)));
+    }
+    Ok(self.active_guest().map(|guest| guest.as_user()))
+  }
+
+  /// Unlock the enterprise tier with a signed offline license. Accepts the key itself
+  /// or the path of a license file containing it.
+  pub fn activate_license(&self, key: &str) -> Result<User, String> {
+    let store = self
+      .license_store
+      .as_ref()
+      .ok_or("No storage path configured".to_string())?;
+    let key = match std::path::Path::new(key.trim()) {
+      path if path.is_file() => {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read license file: {}", e))?
+      }
+      _ => key.to_string(),
+    };
+    let claims = license::verify_license(&key)?;
+
+    // Re-activating keeps the local usage counters
+    let usage = self
+      .active_license()
+      .map(|(license, _)| license.usage)
+      .unwrap_or_default();
+    let activated = license::ActivatedLicense {
+      key: key.trim().to_string(),
+      usage,
+    };
+    store.save(&activated)?;
+    info!(
+      "🔑 License {} activated for {}",
+      claims.license_id, claims.customer
+    );
+    let user = claims.as_user(&activated.usage);
+    self.emit(AuthEvent::Login { user: user.clone() });
+    Ok(user)
+  }
+
+  pub fn deactivate_license(&self) -> Result<(), String> {
+    if let Some(store) = &self.license_store {
+      store.remove()?;
+      self.emit(AuthEvent::Logout);
+    }
+    Ok(())
+  }
+
+  fn active_license(&self) -> Option<(license::ActivatedLicense, license::LicenseClaims)> {
+    self.license_store.as_ref().and_then(|store| store.load())
+  }
+
+  // Licensed installs are counted on this device only. Called under `local_usage_lock`.
+  fn record_license_usage(
+    &self,
+    mut license: license::ActivatedLicense,
+    claims: &license::LicenseClaims,
+  ) -> Result<UsageStatus, String> {
+    usage::reset_if_new_day(&mut license.usage);
+    license.usage.daily += 1;
+    license.usage.total += 1;
+    if let Some(store) = &self.license_store {
+      store.save(&license)?;
+    }
+    Ok(usage::status(&claims.tier, &license.usage))
+  }
+
+  /// Try the app without an account, with the device's guest quota
+  pub fn start_guest_session(&self) -> Result<User, String> {
+    let store = self
+      .guest_store
+      .as_ref()
+      .ok_or("No storage path configured".to_string())?;
+    let mut guest = store.load();
+    guest.active = true;
+    usage::reset_if_new_day(&mut guest.usage);
+    store.save(&guest)?;
+    info!(
+      "👤 Guest session started ({} requests used today)",
+      guest.usage.daily
+    );
+    let user = guest.as_user();
+    self.emit(AuthEvent::Login { user: user.clone() });
+    Ok(user)
+  }
+
+  pub fn end_guest_session(&self) -> Result<(), String> {
+    if self.deactivate_guest()? {
+      self.emit(AuthEvent::Logout);
+    }
+    Ok(())
+  }
+
+  // The usage record stays so a new guest session can't reset the quota. True if guest
+  // mode was on.
+  fn deactivate_guest(&self) -> Result<bool, String> {
+    if let Some(store) = &self.guest_store {
+      let mut guest = store.load();
+      if guest.active {
+        guest.active = false;
+        store.save(&guest)?;
+        info!("👤 Guest session ended");
+        return Ok(true);
+      }
+    }
+    Ok(false)
+  }
+
+  fn active_guest(&self) -> Option<guest::GuestSession> {
+    self
+      .guest_store
+      .as_ref()
+      .map(|store| store.load())
+      .filter(|guest| guest.active)
+  }
+
+  // Guests are counted on this device only. Called under `local_usage_lock`.
+  fn record_guest_usage(&self, model: &str) -> Result<UsageStatus, String> {
+    let store = self
+      .guest_store
+      .as_ref()
+      .ok_or("Not signed in".to_string())?;
+    let mut guest = self.active_guest().ok_or("Not signed in".to_string())?;
+    if !self.can_use_model(guest::GUEST_TIER, model) {
+      return Err(format!("{} needs an account - sign up for free", model));
+    }
+    usage::reset_if_new_day(&mut guest.usage);
+    if guest.usage.daily >= guest::GUEST_DAILY_LIMIT {
+      return Err(format!(
+        "Guest limit of {} requests reached today - create a free account to continue",
+        guest::GUEST_DAILY_LIMIT
+      ));
+    }
+    guest.usage.daily += 1;
+    guest.usage.total += 1;
+    store.save(&guest)?;
+    Ok(usage::status(guest::GUEST_TIER, &guest.usage))
+  }
+
+  /// Models the tier may use: the backend matrix when it has the tier, the built-in
+  /// list otherwise
+  pub fn get_available_models(&self, user_tier: &str) -> Vec<String> {
+    match self
+      .model_matrix
+      .as_ref()
+      .and_then(|matrix| matrix.tiers.get(user_tier))
+    {
+      Some(models) => models.clone(),
+      None => models::fallback_models(user_tier)
+        .iter()
+        .map(|model| model.to_string())
+        .collect(),
+    }
+  }
+
+  pub fn model_matrix_is_fresh(&self) -> bool {
+    self
+      .model_matrix
+      .as_ref()
+      .is_some_and(|matrix| matrix.is_fresh())
+  }
+
+  /// Authenticated fetch of the tier -> model matrix from the backend
+  pub async fn fetch_model_matrix(&self) -> Result<ModelMatrix, String> {
+    let user = self
+      .load_user_session()
+      .await?
+      .ok_or("Sign in to fetch the model list".to_string())?;
+
+    let client = self.client()?;
+    let response = client
+      .get(format!("{}/api/models/matrix", self.api_url))
+      .header("Authorization", format!("Bearer {}", user.token))
+      .send_with_retry()
+      .await?;
+    if !response.status().is_success() {
+      return Err(format!("Model list request failed: {}", response.status()));
+    }
+    let matrix_response: models::ModelMatrixResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("Parse error: {}", e))?;
+    if !matrix_response.success || matrix_response.tiers.is_empty() {
+      return Err("Backend returned no model list".to_string());
+    }
+    Ok(ModelMatrix::new(matrix_response.tiers))
+  }
+
+  pub async fn get_usage_status(&self) -> Result<UsageStatus, String> {
+    let Some(user) = self.load_user_session().await? else {
+      if let Some((mut license, claims)) = self.active_license() {
+        usage::reset_if_new_day(&mut license.usage);
+        return Ok(usage::status(&claims.tier, &license.usage));
+      }
+      let mut guest = self.active_guest().ok_or("Not signed in".to_string())?;
+      usage::reset_if_new_day(&mut guest.usage);
+      return Ok(usage::status(guest::GUEST_TIER, &guest.usage));
+    };
+    let usage = self.usage_counter.lock().unwrap().usage_for(&user).clone();
+    Ok(usage::status(&user.tier, &usage))
+  }
+
+  /// Count one AI request against the daily quota. Fails without counting when the
+  /// quota is already used up.
+  pub async fn record_usage(&self, model: &str) -> Result<UsageStatus, String> {
+    let Some(user) = self.load_user_session().await? else {
+      // Loaded, checked and saved under one lock so parallel requests don't lose counts
+      let _guard = self.local_usage_lock.lock().unwrap();
+      if let Some((license, claims)) = self.active_license() {
+        return self.record_license_usage(license, &claims);
+      }
+      return self.record_guest_usage(model);
+    };
+    {
+      // Checked and counted under one lock so parallel requests can't both take the last one
+      let mut counter = self.usage_counter.lock().unwrap();
+      let usage = counter.usage_for(&user);
+      if let Some(limit) = usage::daily_limit(&user.tier).filter(|&limit| usage.daily >= limit) {
+        return Err(format!(
+          "Daily limit of {} requests reached for the {} plan",
+          limit, user.tier
+        ));
+      }
+      usage.daily += 1;
+      usage.total += 1;
+    }
+
+    // Sync to the backend; counting locally still works offline and the record is
+    // queued for later
+    let _ = self.flush_usage_queue().await;
+    match self.sync_usage(&user.token, model, None).await {
+      // The backend also counts requests from other devices
+      Ok(synced) => {
+        self
+          .usage_counter
+          .lock()
+          .unwrap()
+          .raise(&user.id, synced.usage_daily, synced.usage_total)
+      }
+      Err(e) if e.is_retryable() => {
+        let mut queue = self.usage_queue.lock().unwrap();
+        queue.push(model);
+        info!(
+          "📴 Usage sync failed, queued ({} pending): {}",
+          queue.len(),
+          e
+        );
+      }
+      Err(e) => warn!("⚠️ Failed to sync usage to backend: {}", e),
+    }
+    self.schedule_usage_save();
+
+    let usage = self.usage_counter.lock().unwrap().usage_for(&user).clone();
+    let status = usage::status(&user.tier, &usage);
+    info!(
+      "📈 Recorded {} request: {}/{} today",
+      model,
+      status.daily,
+      status
+        .daily_limit
+        .map_or("unlimited".to_string(), |limit| limit.to_string())
+    );
+    Ok(status)
+  }
+
+  // Write the counters back to the session once, shortly after a burst of requests
+  fn schedule_usage_save(&self) {
+    if !self.usage_counter.lock().unwrap().begin_save() {
+      return;
+    }
+    let service = self.clone();
+    tauri::async_runtime::spawn(async move {
+      tokio::time::sleep(usage::SAVE_DELAY).await;
+      service.usage_counter.lock().unwrap().end_save();
+      // Saving a session always takes the current counts
+      let saved = match service.load_user_session().await {
+        Ok(Some(user)) => service.save_user_session(&user).await,
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+      };
+      if let Err(e) = saved {
+        warn!("⚠️ Failed to save usage: {}", e);
+      }
+    });
+  }
+
+  async fn sync_usage(
+    &self,
+    token: &str,
+    model: &str,
+    recorded_at: Option<String>,
+  ) -> Result<usage::UsageRecordResponse, NetworkError> {
+    let client = self.client().map_err(NetworkError::Permanent)?;
+    let response = client
+      .post(format!("{}/api/usage/record", self.api_url))
+      .header("Authorization", format!("Bearer {}", token))
+      .json(&usage::UsageRecordRequest {
+        model: model.to_string(),
+        recorded_at,
+      })
+      .send_once()
+      .await?;
+    if !response.status().is_success() {
+      return Err(NetworkError::Permanent(format!(
+        "Usage sync rejected: {}",
+        response.status()
+      )));
+    }
+    response
+      .json()
+      .await
+      .map_err(|e| NetworkError::Permanent(format!("Parse error: {}", e)))
+  }
+
+  /// Send usage records queued while offline. Stops at the first temporary failure
+  /// and keeps the rest queued; records the backend rejects are dropped.
+  pub async fn flush_usage_queue(&self) -> Result<(), String> {
+    let pending = self.usage_queue.lock().unwrap().take();
+    if pending.is_empty() {
+      return Ok(());
+    }
+    let Some(user) = self.load_user_session().await? else {
+      self.usage_queue.lock().unwrap().requeue(pending);
+      return Ok(());
+    };
+
+    let total = pending.len();
+    let mut remaining = pending.into_iter();
+    while let Some(record) = remaining.next() {
+      match self
+        .sync_usage(&user.token, &record.model, Some(record.recorded_at.clone()))
+        .await
+      {
+        Ok(_) => {}
+        Err(e) if e.is_retryable() => {
+          let mut unsent = vec![record];
+          unsent.extend(remaining);
+          info!(
+            "📴 Usage sync still offline, {} records queued",
+            unsent.len()
+          );
+          self.usage_queue.lock().unwrap().requeue(unsent);
+          return Err(e.into());
+        }
+        Err(e) => warn!("⚠️ Dropping queued usage record: {}", e),
+      }
+    }
+    info!("📤 Sent {} queued usage records", total);
+    Ok(())
+  }
+
+  /// Stripe checkout URL for upgrading to `plan`. Completion comes back through the
+  /// `payment_success` deep link.
+  pub async fn create_checkout_url(&self, plan: &str) -> Result<String, String> {
+    if !matches!(plan, "premium" | "pro" | "enterprise") {
+      return Err(format!("Unknown plan: {}", plan));
+    }
+    self
+      .request_billing_url(
+        "create-checkout-session",
+        Some(CheckoutRequest {
+          plan: plan.to_string(),
+        }),
+      )
+      .await
+  }
+
+  /// Stripe customer portal URL for managing the subscription
+  pub async fn create_billing_portal_url(&self) -> Result<String, String> {
+    self
+      .request_billing_url("create-portal-session", None)
+      .await
+  }
+
+  async fn request_billing_url(
+    &self,
+    endpoint: &str,
+    body: Option<CheckoutRequest>,
+  ) -> Result<String, String> {
+    let user = self
+      .load_user_session()
+      .await?
+      .ok_or("Sign in to manage your subscription".to_string())?;
+
+    let client = self.client()?;
+    let mut request = client
+      .post(format!("{}/api/payments/{}", self.api_url, endpoint))
+      .header("Authorization", format!("Bearer {}", user.token));
+    if let Some(body) = body {
+      request = request.json(&body);
+    }
+    let response = request.send_with_retry().await?;
+    if !response.status().is_success() {
+      return Err(format!("Billing request failed: {}", response.status()));
+    }
+    let billing_response: BillingSessionResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("Parse error: {}", e))?;
+    match billing_response.url {
+      Some(url) if billing_response.success => Ok(url),
+      _ => Err(
+        billing_response
+          .message
+          .unwrap_or("Billing session could not be created".to_string()),
+      ),
+    }
+  }
+
+  // Use a fetched matrix and cache it on disk for the next start
+  pub fn set_model_matrix(&mut self, matrix: ModelMatrix) -> Result<(), String> {
+    info!("🧩 Model matrix updated: {} tiers", matrix.tiers.len());
+    let result = match &self.storage_path {
+      Some(dir) => matrix.save(dir),
+      None => Ok(()),
+    };
+    self.model_matrix = Some(matrix);
+    result
+  }
+
+  pub fn can_use_model(&self, user_tier: &str, model: &str) -> bool {
+    self
+      .get_available_models(user_tier)
+      .iter()
+      .any(|available| available == model)
+  }
+
+  pub async fn save_user_session(&self, user: &User) -> Result<(), String> {
+    let Some(store) = &self.session_store else {
+      error!("❌ No storage path configured!");
+      return Err("No storage path configured".to_string());
+    };
+    let mut user = user.clone();
+    self.usage_counter.lock().unwrap().apply_to(&mut user);
+    store.save(&user)?;
+    info!("✅ User session saved: {} ({})", user.email, user.tier);
+    Ok(())
+  }
+
+  pub async fn clear_user_session(&self) -> Result<(), String> {
+    match &self.session_store {
+      Some(store) => {
+        store.clear()?;
+        self.usage_counter.lock().unwrap().forget();
+        info!("✅ User session cleared");
+      }
+      None => error!("❌ No storage path configured for clearing!"),
+    }
+    Ok(())
+  }
+
+  pub async fn load_user_session(&self) -> Result<Option<User>, String> {
+    match &self.session_store {
+      Some(store) => store.load(),
+      None => {
+        error!("❌ No storage path configured for loading!");
+        Ok(None)
+      }
+    }
+  }
+}
+
+// Hosts besides the backend that `AuthService::open_in_browser` may open
+const BROWSER_HOSTS: &[&str] = &["checkout.stripe.com", "billing.stripe.com", "slack.com"];
+
+fn open_in_browser(url: &str) -> Result<(), String> {
+  #[cfg(target_os = "macos")]
+  let result = std::process::Command::new("open").arg(url).spawn();
+  #[cfg(target_os = "windows")]
+  let result = std::process::Command::new("rundll32")
+    .args(["url.dll,FileProtocolHandler", url])
+    .spawn();
+  #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+  let result = std::process::Command::new("xdg-open").arg(url).spawn();
+
+  result
+    .map(|_| ())
+    .map_err(|e| format!("Failed to open browser: {}", e))
+}
+
+// 128 random bits as hex (OAuth state, guest device id) from the OS generator; both must
+// be unguessable, which a hasher seeded from the clock is not
+fn random_id() -> String {
+  let mut bytes = [0u8; 16];
+  ring::rand::SystemRandom::new()
+    .fill(&mut bytes)
+    .expect("OS random number generator unavailable");
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+// Catch obvious mistakes before a round trip to the backend
+fn validate_registration(email: &str, password: &str, name: &str) -> Result<(), String> {
+  let valid_email = match email.split_once('@') {
+    Some((local, domain)) => {
+      !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.contains(char::is_whitespace)
+    }
+    None => false,
+  };
+  if !valid_email {
+    return Err("Please enter a valid email address".to_string());
+  }
+  if password.chars().count() < MIN_PASSWORD_LENGTH {
+    return Err(format!(
+      "Password must be at least {} characters",
+      MIN_PASSWORD_LENGTH
+    ));
+  }
+  if name.is_empty() {
+    return Err("Please enter your name".to_string());
+  }
+  Ok(())
+}
+
+impl Default for AuthService {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,103 @@
+// Which AI models each subscription tier may use. The matrix comes from the backend so
+// new models don't need an app release; it's cached in model_matrix.json for a day and
+// the built-in list below is only used when no matrix has ever been fetched.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+const MATRIX_FILE: &str = "model_matrix.json";
+pub const MODEL_MATRIX_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMatrix {
+  pub tiers: HashMap<String, Vec<String>>,
+  pub fetched_at: i64, // Unix seconds
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelMatrixResponse {
+  pub success: bool,
+  #[serde(default)]
+  pub tiers: HashMap<String, Vec<String>>,
+}
+
+impl ModelMatrix {
+  pub fn new(tiers: HashMap<String, Vec<String>>) -> Self {
+    Self {
+      tiers,
+      fetched_at: chrono::Utc::now().timestamp(),
+    }
+  }
+
+  pub fn is_fresh(&self) -> bool {
+    chrono::Utc::now().timestamp() - self.fetched_at < MODEL_MATRIX_TTL_SECS
+  }
+
+  pub fn load(dir: &Path) -> Option<Self> {
+    let json = fs::read_to_string(dir.join(MATRIX_FILE)).ok()?;
+    match serde_json::from_str(&json) {
+      Ok(matrix) => Some(matrix),
+      Err(e) => {
+        warn!("⚠️ Ignoring unreadable model matrix cache: {}", e);
+        None
+      }
+    }
+  }
+
+  pub fn save(&self, dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    let json = serde_json::to_string_pretty(self)
+      .map_err(|e| format!("Failed to serialize model matrix: {}", e))?;
+    fs::write(dir.join(MATRIX_FILE), json)
+      .map_err(|e| format!("Failed to save model matrix: {}", e))
+  }
+}
+
+// Built-in tier -> model mapping, used until the backend matrix has been fetched
+pub fn fallback_models(user_tier: &str) -> Vec<&'static str> {
+  match user_tier {
+    "guest" => vec!["Gemini Flash"],
+    "free" => vec!["GPT-3.5-turbo", "Gemini Flash"],
+    "premium" => vec![
+      "GPT-3.5-turbo",
+      "Gemini Flash",
+      "GPT-4o-mini",
+      "Claude 3 Haiku",
+      "Gemini Pro",
+      "Jamba Mini",
+      "Mistral Small",
+    ],
+    "pro" => vec![
+      "GPT-3.5-turbo",
+      "Gemini Flash",
+      "GPT-4o-mini",
+      "Claude 3 Haiku",
+      "Gemini Pro",
+      "Jamba Mini",
+      "Mistral Small",
+      "GPT-4o",
+      "Claude 3.5 Sonnet",
+      "Jamba Large",
+      "Mistral Medium",
+    ],
+    "enterprise" => vec![
+      "GPT-3.5-turbo",
+      "Gemini Flash",
+      "GPT-4o-mini",
+      "Claude 3 Haiku",
+      "Gemini Pro",
+      "Jamba Mini",
+      "Mistral Small",
+      "GPT-4o",
+      "Claude 3.5 Sonnet",
+      "Jamba Large",
+      "Mistral Medium",
+      "GPT-4o 32k",
+      "Claude 3 Opus",
+      "Mistral Large",
+    ],
+    _ => vec!["GPT-3.5-turbo"], // Fallback
+  }
+}
@@ -0,0 +1,226 @@
+// OAuth sign-in (Google, GitHub). The backend owns the provider credentials: we open
+// its authorize endpoint in the browser with a redirect back to a short-lived listener
+// on 127.0.0.1, then hand the returned code to the backend to exchange for a session.
+// The backend may instead finish on framesense://login-callback; `deliver_callback` passes
+// that code to the waiting sign-in.
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+// How long to wait for the user to finish in the browser
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+// Browsers open speculative connections that never send anything; don't wait on them
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_REQUEST_LINE: usize = 8192;
+
+const CALLBACK_PAGE: &str = "<html><body style=\"font-family: sans-serif; text-align: center; \
+  padding-top: 80px\"><h2>Signed in to FrameSense</h2><p>You can close this tab and return \
+  to the app.</p></body></html>";
+
+const CALLBACK_ERROR_PAGE: &str =
+  "<html><body style=\"font-family: sans-serif; text-align: center; \
+  padding-top: 80px\"><h2>Sign-in failed</h2><p>Return to FrameSense and try signing in \
+  again.</p></body></html>";
+
+// The sign-in currently waiting for a callback: its state and where to send the result
+struct PendingCallback {
+  state: String,
+  sender: oneshot::Sender<Result<String, String>>,
+}
+
+static PENDING_CALLBACK: Mutex<Option<PendingCallback>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OAuthProvider {
+  Google,
+  GitHub,
+}
+
+impl OAuthProvider {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      OAuthProvider::Google => "google",
+      OAuthProvider::GitHub => "github",
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OAuthExchangeRequest {
+  pub code: String,
+  pub redirect_uri: String,
+}
+
+/// Localhost listener waiting for the provider redirect
+pub struct CallbackServer {
+  listener: TcpListener,
+  state: String,
+}
+
+impl CallbackServer {
+  pub async fn bind() -> Result<Self, String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+      .await
+      .map_err(|e| format!("Failed to start sign-in listener: {}", e))?;
+    Ok(Self {
+      listener,
+      state: super::random_id(),
+    })
+  }
+
+  pub fn redirect_uri(&self) -> Result<String, String> {
+    let port = self
+      .listener
+      .local_addr()
+      .map_err(|e| format!("Failed to read listener address: {}", e))?
+      .port();
+    Ok(format!("http://127.0.0.1:{}/callback", port))
+  }
+
+  // Anti-CSRF value the callback must echo back
+  pub fn state(&self) -> &str {
+    &self.state
+  }
+
+  /// Wait for the browser to hit /callback (or open a login-callback deep link) and
+  /// return the authorization code
+  pub async fn wait_for_code(self) -> Result<String, String> {
+    let (sender, receiver) = oneshot::channel();
+    *PENDING_CALLBACK.lock().unwrap() = Some(PendingCallback {
+      state: self.state.clone(),
+      sender,
+    });
+    let result = tokio::time::timeout(CALLBACK_TIMEOUT, async {
+      tokio::select! {
+        result = self.accept_callback() => result,
+        delivered = receiver => delivered
+          .unwrap_or_else(|_| Err("Sign-in was cancelled".to_string())),
+      }
+    })
+    .await
+    .unwrap_or_else(|_| Err("Sign-in timed out".to_string()));
+    // Drop our entry unless a newer sign-in replaced it
+    let mut pending = PENDING_CALLBACK.lock().unwrap();
+    if pending
+      .as_ref()
+      .is_some_and(|pending| pending.state == self.state)
+    {
+      *pending = None;
+    }
+    result
+  }
+
+  async fn accept_callback(&self) -> Result<String, String> {
+    loop {
+      let (mut stream, _) = self
+        .listener
+        .accept()
+        .await
+        .map_err(|e| format!("Sign-in listener failed: {}", e))?;
+
+      let Ok(request_line) =
+        tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request_line(&mut stream)).await
+      else {
+        continue;
+      };
+      // "GET /callback?code=...&state=... HTTP/1.1"
+      let Some(path) = request_line.split_whitespace().nth(1) else {
+        continue;
+      };
+      if !path.starts_with("/callback") {
+        // Favicon and other browser noise
+        let _ = stream
+          .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+          .await;
+        continue;
+      }
+
+      // A callback without our state may be forged (or from an older sign-in): turn it
+      // away and keep waiting for the real one
+      let Some(result) = self.parse_callback(path) else {
+        warn!("⚠️ Ignoring sign-in callback with a mismatched state");
+        respond(&mut stream, "400 Bad Request", CALLBACK_ERROR_PAGE).await;
+        continue;
+      };
+      let page = if result.is_ok() {
+        CALLBACK_PAGE
+      } else {
+        CALLBACK_ERROR_PAGE
+      };
+      respond(&mut stream, "200 OK", page).await;
+      return result;
+    }
+  }
+
+  // None when the callback isn't for this sign-in
+  fn parse_callback(&self, path: &str) -> Option<Result<String, String>> {
+    let url = url::Url::parse(&format!("http://127.0.0.1{}", path)).ok()?;
+    let param = |name: &str| {
+      url
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+    };
+
+    if param("state").as_deref() != Some(self.state.as_str()) {
+      return None;
+    }
+    if let Some(error) = param("error") {
+      return Some(Err(format!("Sign-in was not completed: {}", error)));
+    }
+    Some(param("code").ok_or_else(|| "Sign-in callback had no authorization code".to_string()))
+  }
+}
+
+// Read until the end of the request line, which may arrive in several segments
+async fn read_request_line(stream: &mut tokio::net::TcpStream) -> String {
+  let mut request = Vec::new();
+  let mut buffer = [0u8; 1024];
+  while !request.windows(2).any(|window| window == b"\r\n") && request.len() < MAX_REQUEST_LINE {
+    match stream.read(&mut buffer).await {
+      Ok(0) | Err(_) => break,
+      Ok(read) => request.extend_from_slice(&buffer[..read]),
+    }
+  }
+  let request = String::from_utf8_lossy(&request);
+  request.lines().next().unwrap_or_default().to_string()
+}
+
+async fn respond(stream: &mut tokio::net::TcpStream, status: &str, page: &str) {
+  let response = format!(
+    "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    status,
+    page.len(),
+    page
+  );
+  let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Complete the waiting sign-in from a framesense://login-callback link
+pub fn deliver_callback(
+  state: &str,
+  code: Option<String>,
+  error: Option<String>,
+) -> Result<(), String> {
+  let mut slot = PENDING_CALLBACK.lock().unwrap();
+  let pending = match slot.take() {
+    Some(pending) if pending.state == state => pending,
+    other => {
+      // Not ours: leave any waiting sign-in in place
+      *slot = other;
+      return Err("Sign-in callback did not match a pending sign-in".to_string());
+    }
+  };
+  let result = match (error, code) {
+    (Some(error), _) => Err(format!("Sign-in was not completed: {}", error)),
+    (None, Some(code)) => Ok(code),
+    (None, None) => Err("Sign-in callback had no authorization code".to_string()),
+  };
+  let _ = pending.sender.send(result);
+  Ok(())
+}
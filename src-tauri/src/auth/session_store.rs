@@ -0,0 +1,242 @@
+// Where the signed-in session lives. The keychain store keeps the profile in
+// user_session.json but moves the tokens into the OS credential store; the file store
+// (everything in user_session.json, encrypted with the machine-bound key) is the
+// fallback when no credential store is available.
+//
+// Session files are replaced atomically and the previous good copy is kept as a backup,
+// so a half-written or corrupted file falls back to the backup (or a clean sign-out)
+// instead of breaking login.
+use super::keychain;
+use super::machine_key::{self, SealedData};
+use super::User;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const SESSION_FILE: &str = "user_session.json";
+const KEYCHAIN_ACCOUNT: &str = "session";
+
+pub trait SessionStore: Send + Sync {
+  fn load(&self) -> Result<Option<User>, String>;
+  fn save(&self, user: &User) -> Result<(), String>;
+  fn clear(&self) -> Result<(), String>;
+}
+
+/// Keychain-backed store if the platform has a working one, encrypted file otherwise
+pub fn for_storage_dir(dir: PathBuf) -> Arc<dyn SessionStore> {
+  if keychain::is_available() {
+    Arc::new(KeychainSessionStore::new(dir))
+  } else {
+    warn!("⚠️ No OS credential store available, the session file is encrypted instead");
+    Arc::new(FileSessionStore::new(dir))
+  }
+}
+
+pub struct FileSessionStore {
+  path: PathBuf,
+}
+
+impl FileSessionStore {
+  pub fn new(dir: PathBuf) -> Self {
+    Self {
+      path: dir.join(SESSION_FILE),
+    }
+  }
+
+  fn write(&self, user: &User, keep_backup: bool) -> Result<(), String> {
+    let user_json =
+      serde_json::to_vec(user).map_err(|e| format!("Failed to serialize user: {}", e))?;
+    let sealed = serde_json::to_vec_pretty(&machine_key::seal(&user_json)?)
+      .map_err(|e| format!("Failed to serialize user session: {}", e))?;
+    write_session_file(&self.path, &sealed, keep_backup)
+  }
+}
+
+impl SessionStore for FileSessionStore {
+  fn load(&self) -> Result<Option<User>, String> {
+    let mut legacy = false;
+    let user = read_session_file(&self.path, |data| {
+      match serde_json::from_slice::<SealedData>(data) {
+        Ok(sealed) => serde_json::from_slice(&machine_key::open(&sealed)?)
+          .map_err(|e| format!("Failed to parse user session: {}", e)),
+        // Sessions saved before encryption are plain JSON
+        Err(_) => {
+          legacy = true;
+          parse_user(data)
+        }
+      }
+    })?;
+    if let (true, Some(user)) = (legacy, &user) {
+      info!("🔐 Encrypting the existing session file");
+      self.write(user, false)?;
+    }
+    Ok(user)
+  }
+
+  fn save(&self, user: &User) -> Result<(), String> {
+    self.write(user, true)
+  }
+
+  fn clear(&self) -> Result<(), String> {
+    remove_session_file(&self.path)
+  }
+}
+
+// Kept in the credential store instead of the session file
+#[derive(Serialize, Deserialize)]
+struct SessionSecrets {
+  token: String,
+  refresh_token: Option<String>,
+}
+
+pub struct KeychainSessionStore {
+  path: PathBuf,
+}
+
+impl KeychainSessionStore {
+  pub fn new(dir: PathBuf) -> Self {
+    Self {
+      path: dir.join(SESSION_FILE),
+    }
+  }
+
+  fn save_secrets(&self, user: &User) -> Result<(), String> {
+    let secrets = serde_json::to_string(&SessionSecrets {
+      token: user.token.clone(),
+      refresh_token: user.refresh_token.clone(),
+    })
+    .map_err(|e| format!("Failed to serialize session secrets: {}", e))?;
+    keychain::set_secret(KEYCHAIN_ACCOUNT, &secrets)
+  }
+
+  fn write(&self, user: &User, keep_backup: bool) -> Result<(), String> {
+    self.save_secrets(user)?;
+    let user_json = serde_json::to_vec_pretty(&without_secrets(user))
+      .map_err(|e| format!("Failed to serialize user: {}", e))?;
+    write_session_file(&self.path, &user_json, keep_backup)
+  }
+}
+
+impl SessionStore for KeychainSessionStore {
+  fn load(&self) -> Result<Option<User>, String> {
+    let Some(mut user) = read_session_file(&self.path, parse_user)? else {
+      return Ok(None);
+    };
+
+    // Sessions saved before the keychain was used still carry their tokens: move them
+    if !user.token.is_empty() {
+      info!("🔐 Migrating session tokens from the session file to the keychain");
+      self.write(&user, false)?;
+      return Ok(Some(user));
+    }
+
+    match keychain::get_secret(KEYCHAIN_ACCOUNT)? {
+      Some(secrets) => {
+        let secrets: SessionSecrets = serde_json::from_str(&secrets)
+          .map_err(|e| format!("Failed to parse session secrets: {}", e))?;
+        user.token = secrets.token;
+        user.refresh_token = secrets.refresh_token;
+        Ok(Some(user))
+      }
+      // Profile without credentials (e.g. keychain entry removed): signed out
+      None => Ok(None),
+    }
+  }
+
+  fn save(&self, user: &User) -> Result<(), String> {
+    self.write(user, true)
+  }
+
+  fn clear(&self) -> Result<(), String> {
+    // The profile goes even when the keychain can't be reached, so the user is signed out
+    let deleted = keychain::delete_secret(KEYCHAIN_ACCOUNT);
+    remove_session_file(&self.path)?;
+    deleted
+  }
+}
+
+fn without_secrets(user: &User) -> User {
+  User {
+    token: String::new(),
+    refresh_token: None,
+    ..user.clone()
+  }
+}
+
+fn parse_user(data: &[u8]) -> Result<User, String> {
+  serde_json::from_slice(data).map_err(|e| format!("Failed to parse user session: {}", e))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+  path.with_extension("json.bak")
+}
+
+// Unreadable session files are set aside and the backup restored; with no usable
+// backup the user is simply signed out
+fn read_session_file(
+  path: &Path,
+  mut decode: impl FnMut(&[u8]) -> Result<User, String>,
+) -> Result<Option<User>, String> {
+  if !path.exists() {
+    return Ok(None);
+  }
+  let error = match fs::read(path).map_err(|e| format!("Failed to read user session: {}", e)) {
+    Ok(data) => match decode(&data) {
+      Ok(user) => return Ok(Some(user)),
+      Err(e) => e,
+    },
+    Err(e) => e,
+  };
+
+  warn!("⚠️ Session file is unusable, recovering: {}", error);
+  let _ = fs::rename(path, path.with_extension("json.corrupt"));
+  let backup = backup_path(path);
+  match fs::read(&backup).ok().map(|data| decode(&data)) {
+    Some(Ok(user)) => {
+      let _ = fs::copy(&backup, path);
+      info!("✅ Session restored from backup");
+      Ok(Some(user))
+    }
+    _ => {
+      warn!("⚠️ No usable session backup, signing out");
+      Ok(None)
+    }
+  }
+}
+
+// Write to a temp file and rename over the old one so a crash mid-write never leaves a
+// truncated session. The replaced file becomes the backup, unless it's being migrated
+// away from (it still holds plain tokens): then it and its backup are dropped.
+fn write_session_file(path: &Path, data: &[u8], keep_backup: bool) -> Result<(), String> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create storage directory: {}", e))?;
+  }
+  let temp_path = path.with_extension("json.tmp");
+  let mut file =
+    fs::File::create(&temp_path).map_err(|e| format!("Failed to write user session: {}", e))?;
+  file
+    .write_all(data)
+    .and_then(|_| file.sync_all())
+    .map_err(|e| format!("Failed to write user session: {}", e))?;
+  if !keep_backup {
+    let _ = fs::remove_file(backup_path(path));
+    let _ = fs::remove_file(path.with_extension("json.corrupt"));
+  } else if path.exists() {
+    let _ = fs::copy(path, backup_path(path));
+  }
+  fs::rename(&temp_path, path).map_err(|e| format!("Failed to write user session: {}", e))
+}
+
+// Every copy of the session goes: backup, set-aside corrupt file and leftover temp file
+fn remove_session_file(path: &Path) -> Result<(), String> {
+  if path.exists() {
+    fs::remove_file(path).map_err(|e| format!("Failed to remove user session: {}", e))?;
+  }
+  for extension in ["json.bak", "json.corrupt", "json.tmp"] {
+    let _ = fs::remove_file(path.with_extension(extension));
+  }
+  Ok(())
+}
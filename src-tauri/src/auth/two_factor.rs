@@ -0,0 +1,110 @@
+// Two-factor (TOTP) login. A login for an account with 2FA enabled answers
+// `2fa_required` with a short-lived challenge token instead of a session; the code
+// from the authenticator app is then sent with `submit_totp_code`. Devices the user
+// chooses to trust get a token that skips the code on later logins for that account.
+use super::keychain;
+use super::User;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const TRUSTED_DEVICES_FILE: &str = "trusted_devices.json";
+const KEYCHAIN_ACCOUNT: &str = "trusted-devices";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LoginResult {
+  // Signed in, session saved
+  Success { user: Box<User> },
+  // Waiting for `submit_totp_code`
+  TwoFactorRequired { email: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct TwoFactorChallenge {
+  pub email: String,
+  pub challenge_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpVerifyRequest {
+  pub challenge_token: String,
+  pub code: String,
+  pub trust_device: bool,
+}
+
+/// Authenticator codes are 6 digits; spaces ("123 456") are allowed when typing
+pub fn normalize_code(code: &str) -> Result<String, String> {
+  let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+  if code.len() != 6 || !code.chars().all(|c| c.is_ascii_digit()) {
+    return Err("Enter the 6-digit code from your authenticator app".to_string());
+  }
+  Ok(code)
+}
+
+/// Trusted-device tokens per account email, in the OS credential store when there is one
+pub struct TrustedDevices {
+  path: PathBuf,
+}
+
+impl TrustedDevices {
+  pub fn new(dir: PathBuf) -> Self {
+    Self {
+      path: dir.join(TRUSTED_DEVICES_FILE),
+    }
+  }
+
+  pub fn token_for(&self, email: &str) -> Option<String> {
+    self.load().remove(&email.to_lowercase())
+  }
+
+  pub fn remember(&self, email: &str, token: String) -> Result<(), String> {
+    let mut tokens = self.load();
+    tokens.insert(email.to_lowercase(), token);
+    self.save(&tokens)
+  }
+
+  pub fn forget(&self, email: &str) -> Result<(), String> {
+    let mut tokens = self.load();
+    if tokens.remove(&email.to_lowercase()).is_some() {
+      self.save(&tokens)?;
+    }
+    Ok(())
+  }
+
+  pub fn forget_all(&self) -> Result<(), String> {
+    if keychain::is_available() {
+      keychain::delete_secret(KEYCHAIN_ACCOUNT)?;
+    }
+    if self.path.exists() {
+      fs::remove_file(&self.path)
+        .map_err(|e| format!("Failed to remove trusted devices: {}", e))?;
+    }
+    Ok(())
+  }
+
+  fn load(&self) -> HashMap<String, String> {
+    let json = if keychain::is_available() {
+      keychain::get_secret(KEYCHAIN_ACCOUNT).ok().flatten()
+    } else {
+      fs::read_to_string(&self.path).ok()
+    };
+    json
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default()
+  }
+
+  fn save(&self, tokens: &HashMap<String, String>) -> Result<(), String> {
+    let json = serde_json::to_string(tokens)
+      .map_err(|e| format!("Failed to serialize trusted devices: {}", e))?;
+    if keychain::is_available() {
+      return keychain::set_secret(KEYCHAIN_ACCOUNT, &json);
+    }
+    if let Some(parent) = self.path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    fs::write(&self.path, json).map_err(|e| format!("Failed to save trusted devices: {}", e))
+  }
+}
@@ -0,0 +1,225 @@
+// Daily request quotas per tier. Counters live in the saved session (`User::usage`),
+// reset at midnight UTC and are reported to the backend after every request. While the
+// app runs they are counted in memory (`UsageCounter`) and written back in batches.
+use super::{User, UserUsage};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStatus {
+  pub tier: String,
+  pub daily: i32,
+  pub total: i32,
+  // None = unlimited
+  pub daily_limit: Option<i32>,
+  pub remaining: Option<i32>,
+  pub resets_at: String, // RFC 3339, next midnight UTC
+  pub exceeded: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageRecordRequest {
+  pub model: String,
+  // Set for queued records so they count towards the day they happened
+  pub recorded_at: Option<String>,
+}
+
+// Backend totals after recording; either may be missing
+#[derive(Debug, Deserialize)]
+pub struct UsageRecordResponse {
+  pub usage_daily: Option<i32>,
+  pub usage_total: Option<i32>,
+}
+
+pub fn daily_limit(tier: &str) -> Option<i32> {
+  match tier {
+    "premium" | "pro" => Some(5000),
+    "enterprise" => None,
+    super::guest::GUEST_TIER => Some(super::guest::GUEST_DAILY_LIMIT),
+    _ => Some(50),
+  }
+}
+
+fn today() -> String {
+  Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// Start a new day's count if the last reset was before today (UTC)
+pub fn reset_if_new_day(usage: &mut UserUsage) -> bool {
+  let today = today();
+  if usage.last_reset == today {
+    return false;
+  }
+  usage.daily = 0;
+  usage.last_reset = today;
+  true
+}
+
+// How long after a request the counters are written back to the session
+pub const SAVE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// The signed-in user's counters, shared by every clone of the service so concurrent
+/// requests can't lose increments between loading and saving the session
+#[derive(Default)]
+pub struct UsageCounter {
+  current: Option<(String, UserUsage)>, // User id and counts
+  save_pending: bool,
+}
+
+impl UsageCounter {
+  /// Today's counters for `user`, combined with what their saved session says
+  pub fn usage_for(&mut self, user: &User) -> &mut UserUsage {
+    if self.current.as_ref().is_none_or(|(id, _)| *id != user.id) {
+      self.current = Some((user.id.clone(), user.usage.clone()));
+    }
+    let (_, usage) = self.current.get_or_insert_with(Default::default);
+    merge(usage, &user.usage);
+    reset_if_new_day(usage);
+    usage
+  }
+
+  /// Take the backend's counts when they are higher (requests from other devices)
+  pub fn raise(&mut self, user_id: &str, daily: Option<i32>, total: Option<i32>) {
+    if let Some((id, usage)) = &mut self.current {
+      if id == user_id {
+        usage.daily = usage.daily.max(daily.unwrap_or(0));
+        usage.total = usage.total.max(total.unwrap_or(0));
+      }
+    }
+  }
+
+  /// Bring a session about to be saved up to date with the counters
+  pub fn apply_to(&mut self, user: &mut User) {
+    if let Some((id, usage)) = &mut self.current {
+      if *id == user.id {
+        merge(usage, &user.usage);
+        user.usage = usage.clone();
+        user.usage_daily = Some(usage.daily);
+        user.usage_total = Some(usage.total);
+      }
+    }
+  }
+
+  pub fn forget(&mut self) {
+    self.current = None;
+  }
+
+  /// True when the caller should schedule a save; false if one is already coming
+  pub fn begin_save(&mut self) -> bool {
+    !std::mem::replace(&mut self.save_pending, true)
+  }
+
+  pub fn end_save(&mut self) {
+    self.save_pending = false;
+  }
+}
+
+// Keep the higher of two sets of counts; a later day's count replaces an earlier one
+fn merge(usage: &mut UserUsage, other: &UserUsage) {
+  if other.last_reset > usage.last_reset {
+    usage.daily = other.daily;
+    usage.last_reset = other.last_reset.clone();
+  } else if other.last_reset == usage.last_reset {
+    usage.daily = usage.daily.max(other.daily);
+  }
+  usage.total = usage.total.max(other.total);
+}
+
+pub fn status(tier: &str, usage: &UserUsage) -> UsageStatus {
+  let daily_limit = daily_limit(tier);
+  let tomorrow = Utc::now().date_naive() + Duration::days(1);
+  UsageStatus {
+    tier: tier.to_string(),
+    daily: usage.daily,
+    total: usage.total,
+    daily_limit,
+    remaining: daily_limit.map(|limit| (limit - usage.daily).max(0)),
+    resets_at: tomorrow
+      .and_hms_opt(0, 0, 0)
+      .map(|midnight| midnight.and_utc().to_rfc3339())
+      .unwrap_or_default(),
+    exceeded: daily_limit.is_some_and(|limit| usage.daily >= limit),
+  }
+}
+
+// Usage records that couldn't be sent (offline, backend down); retried later so the
+// backend's counts catch up. Oldest records are dropped past the cap.
+const QUEUE_FILE: &str = "usage_queue.json";
+const MAX_QUEUED: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedUsage {
+  pub model: String,
+  pub recorded_at: String, // RFC 3339
+}
+
+#[derive(Default)]
+pub struct UsageQueue {
+  pending: Vec<QueuedUsage>,
+  storage_path: Option<PathBuf>,
+}
+
+impl UsageQueue {
+  pub fn load(dir: &Path) -> Self {
+    let path = dir.join(QUEUE_FILE);
+    let pending = fs::read_to_string(&path)
+      .ok()
+      .and_then(|json| serde_json::from_str(&json).ok())
+      .unwrap_or_default();
+    Self {
+      pending,
+      storage_path: Some(path),
+    }
+  }
+
+  pub fn push(&mut self, model: &str) {
+    self.pending.push(QueuedUsage {
+      model: model.to_string(),
+      recorded_at: Utc::now().to_rfc3339(),
+    });
+    if self.pending.len() > MAX_QUEUED {
+      self.pending.remove(0);
+    }
+    self.persist();
+  }
+
+  pub fn take(&mut self) -> Vec<QueuedUsage> {
+    let pending = std::mem::take(&mut self.pending);
+    if !pending.is_empty() {
+      self.persist();
+    }
+    pending
+  }
+
+  // Put back records that still couldn't be sent, ahead of anything queued meanwhile
+  pub fn requeue(&mut self, mut records: Vec<QueuedUsage>) {
+    if records.is_empty() {
+      return;
+    }
+    records.append(&mut self.pending);
+    let overflow = records.len().saturating_sub(MAX_QUEUED);
+    records.drain(..overflow);
+    self.pending = records;
+    self.persist();
+  }
+
+  pub fn len(&self) -> usize {
+    self.pending.len()
+  }
+
+  fn persist(&self) {
+    let Some(path) = &self.storage_path else {
+      return;
+    };
+    let result = serde_json::to_string(&self.pending)
+      .map_err(|e| e.to_string())
+      .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+      warn!("⚠️ Failed to save usage queue: {}", e);
+    }
+  }
+}
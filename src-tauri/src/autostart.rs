@@ -0,0 +1,160 @@
+// Launch at login through each platform's own mechanism: a LaunchAgent plist on macOS,
+// the HKCU Run key on Windows and an XDG autostart entry on Linux. The entry points at
+// the running executable, so it follows the app when it is reinstalled elsewhere.
+use std::path::PathBuf;
+use tracing::info;
+
+fn current_exe() -> Result<PathBuf, String> {
+  std::env::current_exe().map_err(|e| format!("Failed to locate the app: {}", e))
+}
+
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+  if enabled {
+    enable(&current_exe()?)?;
+  } else {
+    disable()?;
+  }
+  info!(
+    "🔁 Launch at login {}",
+    if enabled { "enabled" } else { "disabled" }
+  );
+  Ok(())
+}
+
+#[cfg(target_os = "macos")]
+const APP_ID: &str = "com.vely.framesense";
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path() -> Result<PathBuf, String> {
+  dirs::home_dir()
+    .map(|home| {
+      home
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", APP_ID))
+    })
+    .ok_or_else(|| "Could not find the home directory".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn enable(exe: &std::path::Path) -> Result<(), String> {
+  let path = launch_agent_path()?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)
+      .map_err(|e| format!("Failed to create LaunchAgents folder: {}", e))?;
+  }
+  let exe = crate::print::escape_html(&exe.to_string_lossy());
+  let plist = format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{}</string>
+  </array>
+  <key>RunAtLoad</key>
+  <true/>
+</dict>
+</plist>
+"#,
+    APP_ID, exe
+  );
+  std::fs::write(&path, plist).map_err(|e| format!("Failed to write LaunchAgent: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn disable() -> Result<(), String> {
+  let path = launch_agent_path()?;
+  if path.exists() {
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove LaunchAgent: {}", e))?;
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_enabled() -> bool {
+  launch_agent_path().map(|p| p.exists()).unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(target_os = "windows")]
+const RUN_VALUE: &str = "FrameSense";
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<bool, String> {
+  std::process::Command::new("reg")
+    .args(args)
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .status()
+    .map(|status| status.success())
+    .map_err(|e| format!("Failed to run reg: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn enable(exe: &std::path::Path) -> Result<(), String> {
+  let command = format!("\"{}\"", exe.to_string_lossy());
+  if reg(&[
+    "add", RUN_KEY, "/v", RUN_VALUE, "/t", "REG_SZ", "/d", &command, "/f",
+  ])? {
+    Ok(())
+  } else {
+    Err("Failed to add FrameSense to startup programs".to_string())
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn disable() -> Result<(), String> {
+  if is_enabled() && !reg(&["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])? {
+    return Err("Failed to remove FrameSense from startup programs".to_string());
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_enabled() -> bool {
+  reg(&["query", RUN_KEY, "/v", RUN_VALUE]).unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn desktop_entry_path() -> Result<PathBuf, String> {
+  dirs::config_dir()
+    .map(|dir| dir.join("autostart").join("framesense.desktop"))
+    .ok_or_else(|| "Could not find the config directory".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn enable(exe: &std::path::Path) -> Result<(), String> {
+  let path = desktop_entry_path()?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)
+      .map_err(|e| format!("Failed to create autostart folder: {}", e))?;
+  }
+  // Exec needs quoting for paths with spaces; quotes and backslashes are escaped
+  let exe = exe
+    .to_string_lossy()
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"");
+  let entry = format!(
+    "[Desktop Entry]\nType=Application\nName=FrameSense\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\nNoDisplay=true\n",
+    exe
+  );
+  std::fs::write(&path, entry).map_err(|e| format!("Failed to write autostart entry: {}", e))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn disable() -> Result<(), String> {
+  let path = desktop_entry_path()?;
+  if path.exists() {
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove autostart entry: {}", e))?;
+  }
+  Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn is_enabled() -> bool {
+  desktop_entry_path().map(|p| p.exists()).unwrap_or(false)
+}
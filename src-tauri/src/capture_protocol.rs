@@ -0,0 +1,81 @@
+// `framesense-capture://localhost/<id>` serves capture PNGs straight from the capture
+// store or the history, so the webview can use a plain <img src> instead of decoding
+// multi-megabyte data URLs. Windows and Android webviews reach the same handler at
+// http://framesense-capture.localhost/<id>.
+use crate::{capture_store, SharedCaptureHistory, SharedCaptureStore};
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeResponder};
+use tracing::debug;
+
+pub const SCHEME: &str = "framesense-capture";
+
+/// URL the webview loads a capture from
+pub fn capture_url(id: &str) -> String {
+  let id = urlencoding::encode(id);
+  if cfg!(windows) {
+    format!("http://{}.localhost/{}", SCHEME, id)
+  } else {
+    format!("{}://localhost/{}", SCHEME, id)
+  }
+}
+
+// Decoding and disk reads happen off the webview's thread
+pub fn handle(app: AppHandle, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+  tauri::async_runtime::spawn_blocking(move || {
+    let response = match capture_id(&request) {
+      Some(id) => match load_png(&app, &id) {
+        Ok(png) => {
+          debug!("🖼️ Serving capture {} ({}KB)", id, png.len() / 1024);
+          Response::builder()
+            .header(header::CONTENT_TYPE, "image/png")
+            // Capture images never change once stored (edits get new ids)
+            .header(
+              header::CACHE_CONTROL,
+              "private, max-age=31536000, immutable",
+            )
+            .body(png)
+        }
+        Err(e) => error_response(StatusCode::NOT_FOUND, &e),
+      },
+      None => error_response(StatusCode::BAD_REQUEST, "Missing capture id"),
+    };
+    responder.respond(response.unwrap_or_else(|_| Response::new(Vec::new())));
+  });
+}
+
+// "/<id>" on every platform; "framesense-capture://<id>" puts the id in the host
+fn capture_id(request: &Request<Vec<u8>>) -> Option<String> {
+  let uri = request.uri();
+  let path = uri.path().trim_matches('/');
+  let raw = if path.is_empty() {
+    uri.host().filter(|host| *host != "localhost")?
+  } else {
+    path
+  };
+  urlencoding::decode(raw).ok().map(|id| id.into_owned())
+}
+
+fn load_png(app: &AppHandle, id: &str) -> Result<Vec<u8>, String> {
+  let in_store = app
+    .state::<SharedCaptureStore>()
+    .lock()
+    .unwrap()
+    .get(id)
+    .map(|capture| capture.image_data.clone());
+  match in_store {
+    Some(image_data) => capture_store::decode_data_url(&image_data),
+    None => app
+      .try_state::<SharedCaptureHistory>()
+      .ok_or("Capture history unavailable")?
+      .lock()
+      .unwrap()
+      .read_png(id),
+  }
+}
+
+fn error_response(status: StatusCode, message: &str) -> tauri::http::Result<Response<Vec<u8>>> {
+  Response::builder()
+    .status(status)
+    .header(header::CONTENT_TYPE, "text/plain")
+    .body(message.as_bytes().to_vec())
+}
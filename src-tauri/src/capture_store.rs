@@ -0,0 +1,134 @@
+// In-memory store of recent captures, addressed by id so later commands
+// (print, export, share...) can refer to a capture without re-sending its image data.
+use crate::overlay::ScreenCapture;
+use crate::CaptureBounds;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const MAX_STORED_CAPTURES: usize = 20;
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCapture {
+  pub id: String,
+  pub image_data: String, // Base64 PNG data URL
+  pub bounds: Option<CaptureBounds>,
+  pub created_at: String, // RFC 3339
+  pub ocr_text: Option<String>,
+  pub source_app: Option<String>, // App in front when the capture was taken
+  pub metadata: Option<CaptureMetadata>,
+}
+
+/// Where and how a capture was taken; optionally embedded in saved PNGs
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureMetadata {
+  pub monitor_id: Option<u32>,
+  pub scale_factor: Option<f32>,
+  pub duration_ms: u64,
+  pub app_under_cursor: Option<String>,
+  pub captured_at: String, // ISO 8601
+}
+
+impl CaptureMetadata {
+  /// Collect metadata right after capturing. `fallback_app` is used when the app under
+  /// the cursor can't be determined (e.g. our own overlay is still on top).
+  pub fn collect(
+    bounds: Option<&CaptureBounds>,
+    duration: Duration,
+    fallback_app: Option<String>,
+  ) -> Self {
+    let physical = bounds.and_then(ScreenCapture::physical_bounds);
+    Self {
+      monitor_id: physical.as_ref().map(|p| p.display_id),
+      scale_factor: physical.as_ref().map(|p| p.scale_factor),
+      duration_ms: duration.as_millis() as u64,
+      app_under_cursor: crate::save::app_under_cursor().or(fallback_app),
+      captured_at: chrono::Utc::now().to_rfc3339(),
+    }
+  }
+}
+
+/// Decode a `data:image/...;base64,` URL (or bare base64) into raw image bytes
+pub fn decode_data_url(data: &str) -> Result<Vec<u8>, String> {
+  let base64_data = data.split_once(',').map(|(_, b)| b).unwrap_or(data);
+  base64::engine::general_purpose::STANDARD
+    .decode(base64_data)
+    .map_err(|e| format!("Failed to decode image data: {}", e))
+}
+
+pub struct CaptureStore {
+  captures: VecDeque<StoredCapture>,
+  next_id: u64,
+}
+
+impl CaptureStore {
+  pub fn new() -> Self {
+    Self {
+      captures: VecDeque::new(),
+      next_id: 1,
+    }
+  }
+
+  /// Store a capture and return its id. The oldest capture is dropped when full.
+  pub fn add(
+    &mut self,
+    image_data: String,
+    bounds: Option<CaptureBounds>,
+    source_app: Option<String>,
+    metadata: Option<CaptureMetadata>,
+  ) -> String {
+    let now = chrono::Utc::now();
+    let id = format!("cap-{}-{}", now.format("%Y%m%d%H%M%S"), self.next_id);
+    self.next_id += 1;
+
+    self.captures.push_back(StoredCapture {
+      id: id.clone(),
+      image_data,
+      bounds,
+      created_at: now.to_rfc3339(),
+      ocr_text: None,
+      source_app,
+      metadata,
+    });
+    while self.captures.len() > MAX_STORED_CAPTURES {
+      self.captures.pop_front();
+    }
+    id
+  }
+
+  /// Put a previously stored capture (e.g. re-opened from history) back under its own id
+  pub fn restore(&mut self, capture: StoredCapture) {
+    self.captures.retain(|c| c.id != capture.id);
+    self.captures.push_back(capture);
+    while self.captures.len() > MAX_STORED_CAPTURES {
+      self.captures.pop_front();
+    }
+  }
+
+  pub fn get(&self, id: &str) -> Option<&StoredCapture> {
+    self.captures.iter().find(|c| c.id == id)
+  }
+
+  pub fn latest(&self) -> Option<&StoredCapture> {
+    self.captures.back()
+  }
+
+  pub fn set_ocr_text(&mut self, id: &str, text: String) -> bool {
+    match self.captures.iter_mut().find(|c| c.id == id) {
+      Some(capture) => {
+        capture.ocr_text = Some(text);
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+impl Default for CaptureStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
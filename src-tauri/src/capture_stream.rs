@@ -0,0 +1,98 @@
+// Chunked transfer of large captures. Base64-in-JSON for a 4K capture can stall the IPC
+// bridge, so `stream_capture` returns a small handle right away and the image follows as
+// a series of chunks on the `tauri::ipc::Channel` the caller passed in. Chunks split the
+// base64 payload on 4-character boundaries, so the receiver can simply concatenate them
+// in index order. Only the calling webview gets them, and nothing is missed by
+// subscribing late.
+use crate::capture_store::StoredCapture;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::ipc::Channel;
+use tracing::{info, warn};
+
+const DEFAULT_CHUNK_SIZE: usize = 256 * 1024;
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+static NEXT_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureStreamHandle {
+  pub stream_id: String,
+  pub capture_id: String,
+  pub mime_type: String,
+  pub total_length: usize, // Base64 characters
+  pub chunk_count: usize,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureChunk {
+  index: usize,
+  data: String, // Base64 slice of the image
+  is_last: bool,
+}
+
+/// Start streaming a capture to `channel`; returns the handle before any chunk is sent
+pub fn start(
+  channel: Channel<CaptureChunk>,
+  capture: StoredCapture,
+  chunk_size: Option<usize>,
+) -> CaptureStreamHandle {
+  let (mime_type, base64_data) = match capture.image_data.split_once(',') {
+    Some((header, data)) => (
+      header
+        .trim_start_matches("data:")
+        .trim_end_matches(";base64")
+        .to_string(),
+      data.to_string(),
+    ),
+    None => ("image/png".to_string(), capture.image_data),
+  };
+
+  // Multiple of 4 so every chunk is valid base64 on its own
+  let chunk_size = chunk_size
+    .unwrap_or(DEFAULT_CHUNK_SIZE)
+    .clamp(MIN_CHUNK_SIZE, MAX_CHUNK_SIZE)
+    / 4
+    * 4;
+  let chunk_count = base64_data.len().div_ceil(chunk_size).max(1);
+  let stream_id = format!("stream-{}", NEXT_STREAM_ID.fetch_add(1, Ordering::SeqCst));
+
+  let handle = CaptureStreamHandle {
+    stream_id: stream_id.clone(),
+    capture_id: capture.id,
+    mime_type,
+    total_length: base64_data.len(),
+    chunk_count,
+  };
+
+  info!(
+    "📦 Streaming capture {} as {} chunks ({}KB)",
+    handle.capture_id,
+    chunk_count,
+    base64_data.len() / 1024
+  );
+
+  tauri::async_runtime::spawn(async move {
+    for index in 0..chunk_count {
+      let start = index * chunk_size;
+      let end = (start + chunk_size).min(base64_data.len());
+      let sent = channel.send(CaptureChunk {
+        index,
+        data: base64_data[start..end].to_string(),
+        is_last: index + 1 == chunk_count,
+      });
+      if let Err(e) = sent {
+        warn!("⚠️ Capture stream {} abandoned: {}", stream_id, e);
+        return;
+      }
+      // Let other IPC traffic through between chunks
+      tokio::task::yield_now().await;
+    }
+    info!("📦 Capture stream {} complete", stream_id);
+  });
+
+  handle
+}
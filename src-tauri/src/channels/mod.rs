@@ -0,0 +1,131 @@
+// Posting captures to team chat channels. Slack is supported today through a bot token,
+// either from installing the FrameSense Slack app in the browser (`connect`) or pasted
+// (the "Bot User OAuth Token", scopes chat:write and files:write); other providers slot
+// in as further variants. Tokens are stored by name in the API key store, never in
+// settings.json.
+mod slack;
+
+use crate::network::NetworkSettings;
+use serde::{Deserialize, Serialize};
+
+// Longest OCR excerpt posted along with the capture
+const MAX_OCR_CHARS: usize = 3000;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelProvider {
+  Slack,
+}
+
+impl ChannelProvider {
+  pub const ALL: [ChannelProvider; 1] = [ChannelProvider::Slack];
+
+  pub fn display_name(&self) -> &'static str {
+    match self {
+      ChannelProvider::Slack => "Slack",
+    }
+  }
+
+  // Name of the provider's token in the API key store
+  pub fn secret_name(&self) -> String {
+    match self {
+      ChannelProvider::Slack => "channel-slack".to_string(),
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlackSettings {
+  // Channel id (C0123456789); the bot has to be a member of it
+  pub channel: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelSettings {
+  pub slack: SlackSettings,
+}
+
+impl ChannelSettings {
+  pub fn validate(&self) -> Result<(), String> {
+    let channel = self.slack.channel.trim();
+    if !channel.is_empty() && !channel.chars().all(|c| c.is_ascii_alphanumeric()) {
+      return Err("Slack channel must be a channel id like C0123456789".to_string());
+    }
+    Ok(())
+  }
+}
+
+/// Result of installing the app to a workspace
+pub struct ChannelConnection {
+  pub token: String,
+  pub workspace: Option<String>,
+  // Channel picked during the install, if the provider asks for one
+  pub channel: Option<String>,
+}
+
+/// Install the provider's app in the browser and return its token
+pub async fn connect(
+  provider: ChannelProvider,
+  network: NetworkSettings,
+  open_browser: impl FnOnce(&str) -> Result<(), String>,
+) -> Result<ChannelConnection, String> {
+  match provider {
+    ChannelProvider::Slack => slack::install(network, open_browser).await,
+  }
+}
+
+/// A capture ready to post
+pub struct ChannelPost {
+  pub capture_id: String,
+  pub png: Vec<u8>,
+  pub message: Option<String>,
+  pub ocr_text: Option<String>,
+}
+
+impl ChannelPost {
+  // The user's message followed by the recognized text
+  fn comment(&self) -> String {
+    let mut parts = Vec::new();
+    if let Some(message) = self.message.as_deref().map(str::trim) {
+      if !message.is_empty() {
+        parts.push(message.to_string());
+      }
+    }
+    if let Some(text) = self.ocr_text.as_deref().map(str::trim) {
+      if !text.is_empty() {
+        let mut excerpt: String = text.chars().take(MAX_OCR_CHARS).collect();
+        if excerpt.len() < text.len() {
+          excerpt.push('…');
+        }
+        parts.push(format!("```{}```", excerpt));
+      }
+    }
+    parts.join("\n")
+  }
+}
+
+/// Post `post` with `provider`; `channel` overrides the configured one
+pub async fn post(
+  provider: ChannelProvider,
+  settings: &ChannelSettings,
+  channel: Option<String>,
+  token: Option<String>,
+  network: NetworkSettings,
+  post: ChannelPost,
+) -> Result<(), String> {
+  let token =
+    token.ok_or_else(|| format!("Connect {} in settings first", provider.display_name()))?;
+  match provider {
+    ChannelProvider::Slack => {
+      let channel = channel
+        .filter(|channel| !channel.trim().is_empty())
+        .unwrap_or_else(|| settings.slack.channel.clone());
+      if channel.trim().is_empty() {
+        return Err("Choose a Slack channel in settings first".to_string());
+      }
+      slack::post(&token, channel.trim(), network, &post).await
+    }
+  }
+}
@@ -0,0 +1,155 @@
+// Slack file upload flow (https://api.slack.com/messaging/files#uploading_files):
+// files.getUploadURLExternal reserves a file, the PNG is POSTed to the returned URL and
+// files.completeUploadExternal shares it to the channel with the comment.
+// Installing (https://api.slack.com/authentication/oauth-v2) sends the user to Slack's
+// authorize page with a redirect to the loopback sign-in listener, then trades the code
+// for a bot token with oauth.v2.access.
+use super::{ChannelConnection, ChannelPost};
+use crate::auth::oauth::CallbackServer;
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::info;
+
+const API_URL: &str = "https://slack.com/api";
+const AUTHORIZE_URL: &str = "https://slack.com/oauth/v2/authorize";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+// incoming-webhook makes Slack ask which channel to post to during the install
+const BOT_SCOPES: &str = "chat:write,files:write,incoming-webhook";
+// The FrameSense Slack app's credentials, provided by the release build
+const CLIENT_ID: Option<&str> = option_env!("FRAMESENSE_SLACK_CLIENT_ID");
+const CLIENT_SECRET: Option<&str> = option_env!("FRAMESENSE_SLACK_CLIENT_SECRET");
+
+/// Install the app to a workspace; `open_browser` shows Slack's authorize page
+pub async fn install(
+  network: NetworkSettings,
+  open_browser: impl FnOnce(&str) -> Result<(), String>,
+) -> Result<ChannelConnection, String> {
+  let (Some(client_id), Some(client_secret)) = (CLIENT_ID, CLIENT_SECRET) else {
+    return Err("This build has no Slack app configured - paste a bot token instead".to_string());
+  };
+  let server = CallbackServer::bind().await?;
+  let redirect_uri = server.redirect_uri()?;
+  let mut authorize_url =
+    url::Url::parse(AUTHORIZE_URL).map_err(|e| format!("Invalid Slack URL: {}", e))?;
+  authorize_url
+    .query_pairs_mut()
+    .append_pair("client_id", client_id)
+    .append_pair("scope", BOT_SCOPES)
+    .append_pair("redirect_uri", &redirect_uri)
+    .append_pair("state", server.state());
+
+  info!("🌐 Opening Slack install in the browser");
+  open_browser(authorize_url.as_str())?;
+  let code = server.wait_for_code().await?;
+
+  let access = api_result(
+    network::http_client(&network)?
+      .post(format!("{}/oauth.v2.access", API_URL))
+      .basic_auth(client_id, Some(client_secret))
+      .form(&[
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+      ])
+      .send_with_retry()
+      .await?,
+  )
+  .await?;
+  let token = access["access_token"]
+    .as_str()
+    .ok_or("Slack did not return a bot token")?
+    .to_string();
+  let connection = ChannelConnection {
+    token,
+    workspace: access["team"]["name"].as_str().map(str::to_string),
+    channel: access["incoming_webhook"]["channel_id"]
+      .as_str()
+      .map(str::to_string),
+  };
+  info!(
+    "✅ Slack app installed to {}",
+    connection.workspace.as_deref().unwrap_or("workspace")
+  );
+  Ok(connection)
+}
+
+pub async fn post(
+  token: &str,
+  channel: &str,
+  network: NetworkSettings,
+  post: &ChannelPost,
+) -> Result<(), String> {
+  let client = network::http_client(&network)?;
+  let filename = format!("framesense-{}.png", post.capture_id);
+  let length = post.png.len().to_string();
+
+  let reserved = api_result(
+    client
+      .post(format!("{}/files.getUploadURLExternal", API_URL))
+      .bearer_auth(token.trim())
+      .form(&[("filename", filename.as_str()), ("length", length.as_str())])
+      .send_with_retry()
+      .await?,
+  )
+  .await?;
+  let (Some(upload_url), Some(file_id)) = (
+    reserved["upload_url"].as_str(),
+    reserved["file_id"].as_str(),
+  ) else {
+    return Err("Unexpected Slack response".to_string());
+  };
+
+  let uploaded = client
+    .post(upload_url)
+    .header("Content-Type", "image/png")
+    .body(post.png.clone())
+    .timeout(UPLOAD_TIMEOUT)
+    .send_with_retry()
+    .await?;
+  if !uploaded.status().is_success() {
+    return Err(format!("Slack upload failed: {}", uploaded.status()));
+  }
+
+  let mut complete = json!({
+    "files": [{ "id": file_id, "title": filename }],
+    "channel_id": channel,
+  });
+  let comment = post.comment();
+  if !comment.is_empty() {
+    complete["initial_comment"] = json!(comment);
+  }
+  api_result(
+    client
+      .post(format!("{}/files.completeUploadExternal", API_URL))
+      .bearer_auth(token.trim())
+      .json(&complete)
+      .send_with_retry()
+      .await?,
+  )
+  .await?;
+  info!("💬 Posted capture {} to Slack", post.capture_id);
+  Ok(())
+}
+
+// Slack answers 200 with {"ok": false, "error": "..."} for API errors
+async fn api_result(response: reqwest::Response) -> Result<Value, String> {
+  let status = response.status();
+  let body: Value = response.json().await.unwrap_or_default();
+  if body["ok"].as_bool() == Some(true) {
+    return Ok(body);
+  }
+  Err(match body["error"].as_str() {
+    Some("invalid_auth" | "not_authed" | "token_revoked" | "account_inactive") => {
+      "Slack rejected the token - reconnect Slack in settings".to_string()
+    }
+    Some("invalid_code" | "code_already_used" | "bad_redirect_uri") => {
+      "Slack install was not completed - try connecting again".to_string()
+    }
+    Some("not_in_channel" | "channel_not_found") => {
+      "Add the FrameSense app to the Slack channel first".to_string()
+    }
+    Some("missing_scope") => "The Slack app needs the files:write scope".to_string(),
+    Some(error) => format!("Slack request failed: {}", error),
+    None => format!("Slack request failed: {}", status),
+  })
+}
@@ -0,0 +1,293 @@
+// Command-line mode - `framesense --capture-region x,y,w,h --ocr --output out.png` runs
+// the capture/OCR pipeline without starting the app or creating any window, prints the
+// results to stdout and exits. Logs go to stderr so stdout stays scriptable.
+use crate::overlay::ScreenCapture;
+use crate::CaptureBounds;
+use image::RgbaImage;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+// Same as `identifier` in tauri.conf.json; the app data dir holds the redaction zones
+const APP_IDENTIFIER: &str = "com.vely.framesense";
+
+pub const USAGE: &str = "Usage: framesense [capture] [options]
+
+Capture (one of):
+  --capture-region X,Y,W,H   Capture an area in desktop coordinates
+  --capture-monitor ID       Capture one monitor (ids from --list-monitors)
+  --capture-screen           Capture all monitors
+  --list-monitors            Print the connected monitors
+
+Options:
+  --ocr                      Print the text recognized in the capture
+  -o, --output PATH          Write the capture as PNG (`-` writes it to stdout)
+  --json                     Print results as JSON
+  -h, --help                 Show this help
+";
+
+#[derive(Clone, Debug)]
+pub enum CaptureTarget {
+  Region(CaptureBounds),
+  Monitor(u32),
+  AllScreens,
+}
+
+#[derive(Clone, Debug)]
+pub struct CaptureOptions {
+  pub target: CaptureTarget,
+  pub ocr: bool,
+  pub output: Option<PathBuf>,
+  pub json: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum CliCommand {
+  Capture(CaptureOptions),
+  ListMonitors { json: bool },
+  Help,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureOutput {
+  width: u32,
+  height: u32,
+  output: Option<String>,
+  text: Option<String>,
+  confidence: Option<f32>,
+}
+
+/// `None` when the arguments don't ask for command-line mode (normal app launch)
+pub fn parse(args: &[String]) -> Option<Result<CliCommand, String>> {
+  let is_cli = args.iter().any(|arg| {
+    matches!(
+      arg.as_str(),
+      "--capture-region"
+        | "--capture-monitor"
+        | "--capture-screen"
+        | "--list-monitors"
+        | "-h"
+        | "--help"
+    )
+  });
+  is_cli.then(|| parse_command(args))
+}
+
+fn parse_command(args: &[String]) -> Result<CliCommand, String> {
+  let mut target = None;
+  let mut list_monitors = false;
+  let (mut ocr, mut json, mut output) = (false, false, None);
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    let mut value = |name: &str| {
+      args
+        .next()
+        .cloned()
+        .ok_or_else(|| format!("{} needs a value", name))
+    };
+    let next_target = match arg.as_str() {
+      "-h" | "--help" => return Ok(CliCommand::Help),
+      "--capture-region" => Some(CaptureTarget::Region(parse_region(&value(arg)?)?)),
+      "--capture-monitor" => {
+        let id = value(arg)?;
+        Some(CaptureTarget::Monitor(
+          id.parse()
+            .map_err(|_| format!("Invalid monitor id '{}'", id))?,
+        ))
+      }
+      "--capture-screen" => Some(CaptureTarget::AllScreens),
+      "--list-monitors" => {
+        list_monitors = true;
+        None
+      }
+      "--ocr" => {
+        ocr = true;
+        None
+      }
+      "--json" => {
+        json = true;
+        None
+      }
+      "--output" | "-o" => {
+        output = Some(PathBuf::from(value(arg)?));
+        None
+      }
+      other => return Err(format!("Unknown argument '{}'", other)),
+    };
+    if let Some(next_target) = next_target {
+      if target.replace(next_target).is_some() {
+        return Err("Only one capture target can be given".to_string());
+      }
+    }
+  }
+
+  if list_monitors {
+    if target.is_some() {
+      return Err("--list-monitors can't be combined with a capture".to_string());
+    }
+    return Ok(CliCommand::ListMonitors { json });
+  }
+  let target = target.ok_or("No capture target given")?;
+  if !ocr && output.is_none() {
+    return Err("Nothing to do: add --ocr and/or --output".to_string());
+  }
+  if output.as_deref() == Some(std::path::Path::new("-")) && (ocr || json) {
+    return Err(
+      "--output - writes the PNG to stdout and can't be combined with --ocr or --json".to_string(),
+    );
+  }
+  Ok(CliCommand::Capture(CaptureOptions {
+    target,
+    ocr,
+    output,
+    json,
+  }))
+}
+
+fn parse_region(value: &str) -> Result<CaptureBounds, String> {
+  let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+  let invalid = || format!("Invalid region '{}', expected X,Y,W,H", value);
+  let [x, y, width, height] = parts.as_slice() else {
+    return Err(invalid());
+  };
+  let bounds = CaptureBounds {
+    x: x.parse().map_err(|_| invalid())?,
+    y: y.parse().map_err(|_| invalid())?,
+    width: width.parse().map_err(|_| invalid())?,
+    height: height.parse().map_err(|_| invalid())?,
+  };
+  if bounds.width == 0 || bounds.height == 0 {
+    return Err("Region width and height must be at least 1".to_string());
+  }
+  Ok(bounds)
+}
+
+/// Run `command` and return the process exit code
+pub fn run(command: CliCommand) -> i32 {
+  attach_console();
+  let result = match command {
+    CliCommand::Help => {
+      print!("{}", USAGE);
+      Ok(())
+    }
+    CliCommand::ListMonitors { json } => list_monitors(json),
+    CliCommand::Capture(options) => capture(&options),
+  };
+  match result {
+    Ok(()) => 0,
+    Err(e) => {
+      eprintln!("framesense: {}", e);
+      1
+    }
+  }
+}
+
+// Release builds on Windows are GUI programs; borrow the terminal we were started from
+#[cfg(target_os = "windows")]
+pub fn attach_console() {
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn AttachConsole(process_id: u32) -> i32;
+  }
+  const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+  unsafe {
+    AttachConsole(ATTACH_PARENT_PROCESS);
+  }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn attach_console() {}
+
+fn list_monitors(json: bool) -> Result<(), String> {
+  let screens = ScreenCapture::get_screen_info()?;
+  if json {
+    let json = serde_json::to_string_pretty(&screens)
+      .map_err(|e| format!("Failed to encode monitors: {}", e))?;
+    println!("{}", json);
+  } else {
+    for screen in screens {
+      println!(
+        "{}\t{}x{} at {},{}\tscale {}{}",
+        screen.id,
+        screen.width,
+        screen.height,
+        screen.x,
+        screen.y,
+        screen.scale_factor,
+        if screen.is_primary { "\tprimary" } else { "" }
+      );
+    }
+  }
+  Ok(())
+}
+
+fn capture(options: &CaptureOptions) -> Result<(), String> {
+  // Redaction zones apply to scripted captures too
+  if let Some(data_dir) = dirs::data_dir() {
+    crate::overlay::redaction::init(data_dir.join(APP_IDENTIFIER));
+  }
+  let image = capture_target(&options.target)?;
+  let (width, height) = image.dimensions();
+  let data_url = ScreenCapture::encode_rgba_to_base64(image)?;
+  let png = crate::capture_store::decode_data_url(&data_url)?;
+
+  let output = match &options.output {
+    Some(path) if path.as_os_str() == "-" => {
+      return std::io::stdout()
+        .write_all(&png)
+        .map_err(|e| format!("Failed to write PNG to stdout: {}", e));
+    }
+    Some(path) => {
+      std::fs::write(path, &png)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+      Some(path.to_string_lossy().to_string())
+    }
+    None => None,
+  };
+  let ocr = if options.ocr {
+    Some(crate::run_ocr(&data_url)?)
+  } else {
+    None
+  };
+
+  let result = CaptureOutput {
+    width,
+    height,
+    output,
+    text: ocr.as_ref().map(|ocr| ocr.text.clone()),
+    confidence: ocr.as_ref().map(|ocr| ocr.confidence),
+  };
+  if options.json {
+    let json = serde_json::to_string_pretty(&result)
+      .map_err(|e| format!("Failed to encode result: {}", e))?;
+    println!("{}", json);
+  } else {
+    if let Some(text) = &result.text {
+      println!("{}", text.trim_end());
+    }
+    if let Some(output) = &result.output {
+      eprintln!("Saved {}x{} capture to {}", width, height, output);
+    }
+  }
+  Ok(())
+}
+
+fn capture_target(target: &CaptureTarget) -> Result<RgbaImage, String> {
+  match target {
+    CaptureTarget::Region(bounds) => {
+      ScreenCapture::capture_spanning_rgba(bounds.x, bounds.y, bounds.width, bounds.height)
+    }
+    CaptureTarget::Monitor(id) => {
+      let (data_url, _) = ScreenCapture::capture_monitor(*id)?;
+      let png = crate::capture_store::decode_data_url(&data_url)?;
+      image::load_from_memory(&png)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| format!("Failed to decode capture: {}", e))
+    }
+    CaptureTarget::AllScreens => {
+      let area = ScreenCapture::get_total_screen_area()?;
+      ScreenCapture::capture_spanning_rgba(area.min_x, area.min_y, area.width, area.height)
+    }
+  }
+}
@@ -0,0 +1,72 @@
+// Native clipboard access - puts real image bitmaps on the clipboard so a capture can be
+// pasted straight into other apps (Slack, Docs...) instead of a data URL string.
+use crate::capture_store::decode_data_url;
+use arboard::{Clipboard, ImageData};
+use image::RgbaImage;
+use std::borrow::Cow;
+use std::sync::Mutex;
+use tracing::info;
+
+// Kept alive for the whole session: on Linux the clipboard contents are served by
+// this process and would disappear if the handle were dropped right after copying.
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Put plain text (a link, recognized text...) on the clipboard
+pub fn copy_text(text: &str) -> Result<(), String> {
+  let mut clipboard = CLIPBOARD.lock().unwrap();
+  if clipboard.is_none() {
+    *clipboard = Some(Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+  }
+  clipboard
+    .as_mut()
+    .unwrap()
+    .set_text(text)
+    .map_err(|e| format!("Failed to copy text to clipboard: {}", e))
+}
+
+/// Read the image currently on the clipboard (a screenshot pasted from Slack, ...)
+pub fn read_image() -> Result<RgbaImage, String> {
+  let mut clipboard = CLIPBOARD.lock().unwrap();
+  if clipboard.is_none() {
+    *clipboard = Some(Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+  }
+  let image = clipboard
+    .as_mut()
+    .unwrap()
+    .get_image()
+    .map_err(|e| match e {
+      arboard::Error::ContentNotAvailable => "The clipboard holds no image".to_string(),
+      e => format!("Failed to read clipboard image: {}", e),
+    })?;
+  let (width, height) = (image.width as u32, image.height as u32);
+  let rgba = RgbaImage::from_raw(width, height, image.bytes.into_owned())
+    .ok_or_else(|| "Clipboard image has an unexpected format".to_string())?;
+  info!("📋 Read {}x{} image from clipboard", width, height);
+  Ok(rgba)
+}
+
+/// Copy a PNG data URL to the system clipboard as a bitmap
+pub fn copy_image(image_data: &str) -> Result<(u32, u32), String> {
+  let png = decode_data_url(image_data)?;
+  let rgba = image::load_from_memory(&png)
+    .map_err(|e| format!("Failed to read capture image: {}", e))?
+    .to_rgba8();
+  let (width, height) = rgba.dimensions();
+
+  let mut clipboard = CLIPBOARD.lock().unwrap();
+  if clipboard.is_none() {
+    *clipboard = Some(Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+  }
+  clipboard
+    .as_mut()
+    .unwrap()
+    .set_image(ImageData {
+      width: width as usize,
+      height: height as usize,
+      bytes: Cow::Owned(rgba.into_raw()),
+    })
+    .map_err(|e| format!("Failed to copy image to clipboard: {}", e))?;
+
+  info!("📋 Copied {}x{} capture to clipboard", width, height);
+  Ok((width, height))
+}
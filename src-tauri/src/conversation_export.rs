@@ -0,0 +1,164 @@
+// Exporting a conversation for sharing or archiving. Markdown writes the captures as PNG
+// files in a folder next to the document; HTML embeds them so the page is self-contained.
+use crate::capture_store::decode_data_url;
+use crate::conversation_history::{Conversation, ConversationMessage};
+use crate::print::escape_html;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+  Markdown,
+  Html,
+}
+
+impl ExportFormat {
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ExportFormat::Markdown => "md",
+      ExportFormat::Html => "html",
+    }
+  }
+}
+
+/// Write `conversation` to `path`. `load_image` returns a capture's data URL by id;
+/// captures that are no longer available are noted in the text instead.
+pub fn export_conversation(
+  conversation: &Conversation,
+  format: ExportFormat,
+  path: &Path,
+  load_image: impl Fn(&str) -> Option<String>,
+) -> Result<PathBuf, String> {
+  let path = if path.extension().is_none() {
+    path.with_extension(format.extension())
+  } else {
+    path.to_path_buf()
+  };
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create export folder: {}", e))?;
+  }
+
+  let document = match format {
+    ExportFormat::Markdown => render_markdown(conversation, &path, &load_image)?,
+    ExportFormat::Html => render_html(conversation, &load_image),
+  };
+  fs::write(&path, document).map_err(|e| format!("Failed to write export: {}", e))?;
+  info!(
+    "📤 Exported conversation {} to {}",
+    conversation.id,
+    path.display()
+  );
+  Ok(path)
+}
+
+fn speaker(message: &ConversationMessage) -> String {
+  match (message.role.as_str(), &message.model) {
+    ("assistant", Some(model)) => format!("Assistant ({})", model),
+    ("assistant", None) => "Assistant".to_string(),
+    _ => "You".to_string(),
+  }
+}
+
+fn render_markdown(
+  conversation: &Conversation,
+  path: &Path,
+  load_image: &impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+  // Captures go in "<name>_captures/" beside the document and are linked relatively
+  let stem = path
+    .file_stem()
+    .map(|s| s.to_string_lossy().to_string())
+    .unwrap_or_else(|| "conversation".to_string());
+  let captures_dir_name = format!("{}_captures", stem);
+  let captures_dir = path.with_file_name(&captures_dir_name);
+
+  let mut out = format!(
+    "# {}\n\n_Exported from FrameSense · started {}_\n",
+    conversation.title, conversation.created_at
+  );
+  for message in &conversation.messages {
+    out.push_str(&format!(
+      "\n## {}\n\n_{}_\n\n",
+      speaker(message),
+      message.created_at
+    ));
+    if let Some(capture_id) = &message.capture_id {
+      match load_image(capture_id).map(|data| decode_data_url(&data)) {
+        Some(Ok(png)) => {
+          fs::create_dir_all(&captures_dir)
+            .map_err(|e| format!("Failed to create captures folder: {}", e))?;
+          let file_name = format!("{}.png", capture_id);
+          fs::write(captures_dir.join(&file_name), png)
+            .map_err(|e| format!("Failed to write capture: {}", e))?;
+          out.push_str(&format!(
+            "![Capture]({}/{})\n\n",
+            captures_dir_name, file_name
+          ));
+        }
+        _ => out.push_str("_(capture no longer available)_\n\n"),
+      }
+    }
+    out.push_str(message.content.trim());
+    out.push('\n');
+  }
+  Ok(out)
+}
+
+fn render_html(
+  conversation: &Conversation,
+  load_image: &impl Fn(&str) -> Option<String>,
+) -> String {
+  let mut messages = String::new();
+  for message in &conversation.messages {
+    let capture = match &message.capture_id {
+      Some(capture_id) => match load_image(capture_id) {
+        Some(data_url) => format!(
+          "<img class=\"capture\" src=\"{}\" alt=\"Capture\">",
+          data_url
+        ),
+        None => "<p class=\"missing\">(capture no longer available)</p>".to_string(),
+      },
+      None => String::new(),
+    };
+    messages.push_str(&format!(
+      "<article class=\"{role}\">\n<header>{speaker} &middot; {created_at}</header>\n{capture}\n<div class=\"content\">{content}</div>\n</article>\n",
+      role = if message.role == "assistant" { "assistant" } else { "user" },
+      speaker = escape_html(&speaker(message)),
+      created_at = escape_html(&message.created_at),
+      capture = capture,
+      content = escape_html(message.content.trim()),
+    ));
+  }
+
+  format!(
+    r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+  body {{ max-width: 820px; margin: 32px auto; padding: 0 16px; font-family: -apple-system, "Segoe UI", sans-serif; color: #111; }}
+  h1 {{ font-size: 20pt; margin-bottom: 4px; }}
+  .meta {{ color: #666; font-size: 10pt; margin-bottom: 24px; }}
+  article {{ border-radius: 8px; padding: 12px 16px; margin: 12px 0; }}
+  article.user {{ background: #eef3ff; }}
+  article.assistant {{ background: #f5f5f5; }}
+  article header {{ font-size: 9pt; color: #555; margin-bottom: 8px; }}
+  .capture {{ display: block; max-width: 100%; border-radius: 4px; margin-bottom: 8px; }}
+  .content {{ white-space: pre-wrap; line-height: 1.45; }}
+  .missing {{ color: #999; font-style: italic; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<div class="meta">Exported from FrameSense &middot; started {created_at}</div>
+{messages}</body>
+</html>"#,
+    title = escape_html(&conversation.title),
+    created_at = escape_html(&conversation.created_at),
+    messages = messages,
+  )
+}
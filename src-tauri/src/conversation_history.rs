@@ -0,0 +1,323 @@
+// Conversation history - every question and answer is kept under the app data dir (one
+// JSON file per conversation plus an index.json of summaries), so chat context survives
+// the main window being destroyed and recreated. Captures are attached by capture id;
+// the images themselves live in the capture history. Each conversation's AI parameters
+// are saved in its file too.
+use crate::conversations::{ConversationParams, ResolvedAiParams};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+const INDEX_FILE: &str = "index.json";
+const TITLE_LENGTH: usize = 60;
+const MAX_ID_LENGTH: usize = 128;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationMessage {
+  pub role: String, // "user" or "assistant"
+  pub content: String,
+  pub created_at: String, // RFC 3339
+  pub capture_id: Option<String>,
+  // Set on answers
+  pub model: Option<String>,
+  pub input_tokens: Option<u64>,
+  pub output_tokens: Option<u64>,
+  pub cost_usd: Option<f64>,
+}
+
+impl ConversationMessage {
+  pub fn user(content: &str, capture_id: Option<String>) -> Self {
+    Self {
+      role: "user".to_string(),
+      content: content.to_string(),
+      created_at: chrono::Utc::now().to_rfc3339(),
+      capture_id,
+      model: None,
+      input_tokens: None,
+      output_tokens: None,
+      cost_usd: None,
+    }
+  }
+
+  pub fn assistant(content: &str, model: &str) -> Self {
+    Self {
+      role: "assistant".to_string(),
+      model: Some(model.to_string()),
+      ..Self::user(content, None)
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+  pub id: String,
+  pub title: String,
+  pub created_at: String,
+  pub updated_at: String,
+  pub messages: Vec<ConversationMessage>,
+  #[serde(default)]
+  pub params: ConversationParams,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSummary {
+  pub id: String,
+  pub title: String,
+  pub created_at: String,
+  pub updated_at: String,
+  pub message_count: usize,
+  pub last_model: Option<String>,
+  pub total_cost_usd: f64,
+}
+
+impl ConversationSummary {
+  fn of(conversation: &Conversation) -> Self {
+    Self {
+      id: conversation.id.clone(),
+      title: conversation.title.clone(),
+      created_at: conversation.created_at.clone(),
+      updated_at: conversation.updated_at.clone(),
+      message_count: conversation.messages.len(),
+      last_model: conversation
+        .messages
+        .iter()
+        .rev()
+        .find_map(|message| message.model.clone()),
+      total_cost_usd: conversation
+        .messages
+        .iter()
+        .filter_map(|message| message.cost_usd)
+        .sum(),
+    }
+  }
+}
+
+pub struct ConversationHistory {
+  dir: PathBuf,
+  summaries: Vec<ConversationSummary>, // Oldest first
+  next_id: u64,
+}
+
+impl ConversationHistory {
+  /// Open (or create) the conversation history stored in `dir`
+  pub fn new(dir: PathBuf) -> Self {
+    let summaries = fs::read_to_string(dir.join(INDEX_FILE))
+      .ok()
+      .and_then(
+        |json| match serde_json::from_str::<Vec<ConversationSummary>>(&json) {
+          Ok(summaries) => Some(summaries),
+          Err(e) => {
+            warn!("⚠️ Ignoring unreadable conversation index: {}", e);
+            None
+          }
+        },
+      )
+      .unwrap_or_default();
+    if !summaries.is_empty() {
+      info!("💬 Loaded {} conversations", summaries.len());
+    }
+    Self {
+      dir,
+      summaries,
+      next_id: 1,
+    }
+  }
+
+  /// Newest first
+  pub fn list(&self) -> Vec<ConversationSummary> {
+    self.summaries.iter().rev().cloned().collect()
+  }
+
+  pub fn get(&self, id: &str) -> Result<Option<Conversation>, String> {
+    if !self.summaries.iter().any(|summary| summary.id == id) {
+      return Ok(None);
+    }
+    let json = fs::read_to_string(self.conversation_path(id)?)
+      .map_err(|e| format!("Failed to read conversation: {}", e))?;
+    serde_json::from_str(&json)
+      .map(Some)
+      .map_err(|e| format!("Failed to parse conversation: {}", e))
+  }
+
+  /// Add messages to a conversation, starting a new one when `id` is None or unknown.
+  /// Returns the conversation id.
+  pub fn append(
+    &mut self,
+    id: Option<&str>,
+    messages: Vec<ConversationMessage>,
+  ) -> Result<String, String> {
+    let mut conversation = self.get_or_start(id)?;
+    if conversation.messages.is_empty() {
+      conversation.title = title_for(&messages);
+    }
+    conversation.messages.extend(messages);
+    self.save(&mut conversation)?;
+    Ok(conversation.id)
+  }
+
+  /// AI parameters of a conversation; defaults when it doesn't exist (yet)
+  pub fn params(&self, id: &str) -> Result<ConversationParams, String> {
+    Ok(
+      self
+        .get(id)?
+        .map(|conversation| conversation.params)
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Set a conversation's AI parameters, starting an empty conversation for an unknown id
+  pub fn set_params(&mut self, id: &str, params: ConversationParams) -> Result<(), String> {
+    params.validate()?;
+    if params == ConversationParams::default() && self.get(id)?.is_none() {
+      return Ok(());
+    }
+    let mut conversation = self.get_or_start(Some(id))?;
+    conversation.params = params;
+    self.save(&mut conversation)
+  }
+
+  /// Effective parameters for one message: message overrides > conversation params > defaults
+  pub fn resolve_params(
+    &self,
+    id: Option<&str>,
+    overrides: &ConversationParams,
+    default_model: &str,
+  ) -> Result<ResolvedAiParams, String> {
+    let conversation = match id {
+      Some(id) => self.params(id)?,
+      None => ConversationParams::default(),
+    };
+    conversation.resolve(overrides, default_model)
+  }
+
+  fn get_or_start(&mut self, id: Option<&str>) -> Result<Conversation, String> {
+    if let Some(conversation) = id.map(|id| self.get(id)).transpose()?.flatten() {
+      return Ok(conversation);
+    }
+    let id = match id {
+      Some(id) => {
+        validate_id(id)?;
+        id.to_string()
+      }
+      None => self.new_id(),
+    };
+    let now = chrono::Utc::now().to_rfc3339();
+    Ok(Conversation {
+      id,
+      title: title_for(&[]),
+      created_at: now.clone(),
+      updated_at: now,
+      messages: Vec::new(),
+      params: ConversationParams::default(),
+    })
+  }
+
+  // Write the conversation and move it to the end of the index
+  fn save(&mut self, conversation: &mut Conversation) -> Result<(), String> {
+    conversation.updated_at = chrono::Utc::now().to_rfc3339();
+    fs::create_dir_all(&self.dir)
+      .map_err(|e| format!("Failed to create conversations directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&conversation)
+      .map_err(|e| format!("Failed to serialize conversation: {}", e))?;
+    fs::write(self.conversation_path(&conversation.id)?, json)
+      .map_err(|e| format!("Failed to save conversation: {}", e))?;
+
+    // Most recently updated goes last
+    self
+      .summaries
+      .retain(|summary| summary.id != conversation.id);
+    self.summaries.push(ConversationSummary::of(conversation));
+    self.save_index()
+  }
+
+  pub fn delete(&mut self, id: &str) -> Result<bool, String> {
+    let before = self.summaries.len();
+    self.summaries.retain(|summary| summary.id != id);
+    if self.summaries.len() == before {
+      return Ok(false);
+    }
+    if let Ok(path) = self.conversation_path(id) {
+      let _ = fs::remove_file(path);
+    }
+    self.save_index()?;
+    Ok(true)
+  }
+
+  /// Delete conversations last updated before `cutoff`; returns how many were removed
+  pub fn purge_older_than(
+    &mut self,
+    cutoff: chrono::DateTime<chrono::Utc>,
+  ) -> Result<usize, String> {
+    let old: Vec<String> = self
+      .summaries
+      .iter()
+      .filter(|summary| crate::retention::is_before(&summary.updated_at, cutoff))
+      .map(|summary| summary.id.clone())
+      .collect();
+    if old.is_empty() {
+      return Ok(0);
+    }
+    for id in &old {
+      if let Ok(path) = self.conversation_path(id) {
+        let _ = fs::remove_file(path);
+      }
+    }
+    self.summaries.retain(|summary| !old.contains(&summary.id));
+    self.save_index()?;
+    Ok(old.len())
+  }
+
+  fn new_id(&mut self) -> String {
+    let id = format!(
+      "conv-{}-{}",
+      chrono::Utc::now().format("%Y%m%d%H%M%S"),
+      self.next_id
+    );
+    self.next_id += 1;
+    id
+  }
+
+  fn conversation_path(&self, id: &str) -> Result<PathBuf, String> {
+    validate_id(id)?;
+    Ok(self.dir.join(format!("{}.json", id)))
+  }
+
+  fn save_index(&self) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&self.summaries)
+      .map_err(|e| format!("Failed to serialize conversation index: {}", e))?;
+    fs::write(self.dir.join(INDEX_FILE), json)
+      .map_err(|e| format!("Failed to save conversation index: {}", e))
+  }
+}
+
+// Ids come from the frontend too and double as file names
+fn validate_id(id: &str) -> Result<(), String> {
+  let valid = !id.is_empty()
+    && id.len() <= MAX_ID_LENGTH
+    && id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+  if valid {
+    Ok(())
+  } else {
+    Err(format!("Invalid conversation id '{}'", id))
+  }
+}
+
+// First line of the first question
+fn title_for(messages: &[ConversationMessage]) -> String {
+  let first_line = messages
+    .iter()
+    .find(|message| message.role == "user")
+    .and_then(|message| message.content.lines().find(|line| !line.trim().is_empty()))
+    .unwrap_or("Capture")
+    .trim();
+  match first_line.char_indices().nth(TITLE_LENGTH) {
+    Some((end, _)) => format!("{}…", &first_line[..end]),
+    None => first_line.to_string(),
+  }
+}
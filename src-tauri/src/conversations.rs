@@ -0,0 +1,68 @@
+// Per-conversation AI parameters (model, temperature, system prompt) that are applied to
+// every message, with optional per-message overrides. They're saved with the
+// conversation in the conversation history.
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TEMPERATURE: f32 = 0.3;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationParams {
+  pub model: Option<String>,
+  pub temperature: Option<f32>,
+  pub system_prompt: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedAiParams {
+  pub model: String,
+  pub temperature: f32,
+  pub system_prompt: Option<String>,
+}
+
+impl ConversationParams {
+  pub fn validate(&self) -> Result<(), String> {
+    if let Some(temperature) = self.temperature {
+      if !(0.0..=2.0).contains(&temperature) {
+        return Err(format!(
+          "Temperature must be between 0.0 and 2.0 (got {})",
+          temperature
+        ));
+      }
+    }
+    if let Some(model) = &self.model {
+      if model.trim().is_empty() {
+        return Err("Model name cannot be empty".to_string());
+      }
+    }
+    Ok(())
+  }
+
+  // Fields set in `overrides` win over the ones in `self`
+  fn merged_with(&self, overrides: &ConversationParams) -> ConversationParams {
+    ConversationParams {
+      model: overrides.model.clone().or_else(|| self.model.clone()),
+      temperature: overrides.temperature.or(self.temperature),
+      system_prompt: overrides
+        .system_prompt
+        .clone()
+        .or_else(|| self.system_prompt.clone()),
+    }
+  }
+
+  /// Effective parameters for one message: `overrides` > these > defaults
+  pub fn resolve(
+    &self,
+    overrides: &ConversationParams,
+    default_model: &str,
+  ) -> Result<ResolvedAiParams, String> {
+    overrides.validate()?;
+    let merged = self.merged_with(overrides);
+    Ok(ResolvedAiParams {
+      model: merged.model.unwrap_or_else(|| default_model.to_string()),
+      temperature: merged.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+      system_prompt: merged.system_prompt,
+    })
+  }
+}
@@ -0,0 +1,232 @@
+// Crash reports - a panic hook (covering every thread) and `report_error` for failures in
+// background threads both write a JSON report under the app data dir with the backtrace,
+// platform, app version and the last log lines. Reports are uploaded to the backend only
+// when the user has opted in (`crashReports.upload` in settings).
+use crate::network::NetworkSettings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+const REPORTS_DIR: &str = "crashes";
+const MAX_REPORTS: usize = 20;
+// Log lines included as "last actions"; debug and trace lines are left out since they
+// may carry captured content (OCR text, prompts) that must not leave the machine
+const RECENT_LOG_LINES: usize = 50;
+const REPORTED_LOG_LEVELS: [&str; 3] = ["error", "warn", "info"];
+
+static REPORTS_PATH: OnceLock<PathBuf> = OnceLock::new();
+// Looked up at install time: the panic hook must not spawn processes
+static OS_DESCRIPTION: OnceLock<String> = OnceLock::new();
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CrashKind {
+  Panic,
+  Error,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+  pub id: String,
+  pub kind: CrashKind,
+  pub created_at: String,
+  pub app_version: String,
+  pub os: String,
+  pub arch: String,
+  pub thread: String,
+  pub message: String,
+  pub location: Option<String>,
+  pub backtrace: Option<String>,
+  pub recent_logs: Vec<String>,
+  pub uploaded: bool,
+}
+
+impl CrashReport {
+  fn new(kind: CrashKind, message: String, location: Option<String>) -> Self {
+    let now = chrono::Utc::now();
+    let thread = std::thread::current();
+    Self {
+      id: format!("{}-{}", now.format("%Y%m%d-%H%M%S%3f"), std::process::id()),
+      kind,
+      created_at: now.to_rfc3339(),
+      app_version: env!("CARGO_PKG_VERSION").to_string(),
+      os: OS_DESCRIPTION
+        .get()
+        .cloned()
+        .unwrap_or_else(|| std::env::consts::OS.to_string()),
+      arch: std::env::consts::ARCH.to_string(),
+      thread: thread.name().unwrap_or("unnamed").to_string(),
+      message,
+      location,
+      backtrace: None,
+      recent_logs: crate::logging::try_recent(RECENT_LOG_LINES)
+        .into_iter()
+        .filter(|entry| REPORTED_LOG_LEVELS.contains(&entry.level.as_str()))
+        .map(|entry| {
+          format!(
+            "{} {} [{}] {}",
+            entry.timestamp, entry.level, entry.module, entry.message
+          )
+        })
+        .collect(),
+      uploaded: false,
+    }
+  }
+}
+
+fn os_version() -> String {
+  let output = if cfg!(target_os = "macos") {
+    std::process::Command::new("sw_vers")
+      .arg("-productVersion")
+      .output()
+  } else if cfg!(target_os = "windows") {
+    std::process::Command::new("cmd")
+      .args(["/C", "ver"])
+      .output()
+  } else {
+    std::process::Command::new("uname").arg("-r").output()
+  };
+  output
+    .ok()
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    .unwrap_or_default()
+}
+
+/// Install the panic hook; reports go to `<app_data_dir>/crashes`
+pub fn install(app_data_dir: &Path) {
+  let dir = app_data_dir.join(REPORTS_DIR);
+  let _ = REPORTS_PATH.set(dir);
+  let _ = OS_DESCRIPTION.set(format!("{} {}", std::env::consts::OS, os_version()));
+
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |panic_info| {
+    let message = panic_info
+      .payload()
+      .downcast_ref::<&str>()
+      .map(|s| s.to_string())
+      .or_else(|| panic_info.payload().downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "Unknown panic".to_string());
+    let location = panic_info
+      .location()
+      .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    let mut report = CrashReport::new(CrashKind::Panic, message, location);
+    report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+    // Not through tracing: the panic may have happened inside the logger with its
+    // locks held
+    match write_report(&report) {
+      Ok(path) => eprintln!("💥 Panic - crash report saved to {}", path.display()),
+      Err(e) => eprintln!("💥 Panic - failed to save crash report: {}", e),
+    }
+    default_hook(panic_info);
+  }));
+}
+
+/// Record an error that ended work on a background thread
+pub fn report_error(source: &str, message: &str) {
+  let report = CrashReport::new(CrashKind::Error, format!("{}: {}", source, message), None);
+  if let Err(e) = write_report(&report) {
+    warn!("⚠️ Failed to save error report: {}", e);
+  }
+}
+
+fn reports_dir() -> Result<&'static PathBuf, String> {
+  REPORTS_PATH
+    .get()
+    .ok_or_else(|| "Crash reporting is not initialized".to_string())
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+  let dir = reports_dir()?;
+  fs::create_dir_all(dir).map_err(|e| format!("Failed to create crash directory: {}", e))?;
+  let path = dir.join(format!("{}.json", report.id));
+  let json = serde_json::to_string_pretty(report)
+    .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+  fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))?;
+  prune(dir);
+  Ok(path)
+}
+
+// Keep only the newest MAX_REPORTS (ids sort by time)
+fn prune(dir: &Path) {
+  let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+    Ok(entries) => entries
+      .filter_map(|e| e.ok().map(|e| e.path()))
+      .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+      .collect(),
+    Err(_) => return,
+  };
+  files.sort();
+  while files.len() > MAX_REPORTS {
+    let _ = fs::remove_file(files.remove(0));
+  }
+}
+
+/// Saved reports, newest first
+pub fn list() -> Result<Vec<CrashReport>, String> {
+  let dir = reports_dir()?;
+  let entries = match fs::read_dir(dir) {
+    Ok(entries) => entries,
+    Err(_) => return Ok(Vec::new()),
+  };
+  let mut reports: Vec<CrashReport> = entries
+    .filter_map(|e| e.ok())
+    .filter_map(|e| fs::read_to_string(e.path()).ok())
+    .filter_map(|json| serde_json::from_str(&json).ok())
+    .collect();
+  reports.sort_by(|a, b| b.id.cmp(&a.id));
+  Ok(reports)
+}
+
+pub fn delete_all() -> Result<usize, String> {
+  let reports = list()?;
+  let dir = reports_dir()?;
+  for report in &reports {
+    let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+  }
+  Ok(reports.len())
+}
+
+/// Delete reports created before `cutoff`
+pub fn delete_older_than(cutoff: chrono::DateTime<chrono::Utc>) -> Result<usize, String> {
+  let dir = reports_dir()?;
+  let old: Vec<CrashReport> = list()?
+    .into_iter()
+    .filter(|report| crate::retention::is_before(&report.created_at, cutoff))
+    .collect();
+  for report in &old {
+    let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+  }
+  Ok(old.len())
+}
+
+/// Send reports not uploaded yet; only call with the user's consent
+pub async fn upload_pending(api_url: &str, network: &NetworkSettings) -> Result<usize, String> {
+  let pending: Vec<CrashReport> = list()?.into_iter().filter(|r| !r.uploaded).collect();
+  if pending.is_empty() {
+    return Ok(0);
+  }
+  let client = crate::network::http_client(network)?;
+  let mut uploaded = 0;
+  for mut report in pending {
+    let response = client
+      .post(format!("{}/api/crash-reports", api_url))
+      .json(&report)
+      .send()
+      .await
+      .map_err(|e| format!("Failed to upload crash report: {}", e))?;
+    if !response.status().is_success() {
+      return Err(format!(
+        "Crash report upload failed with status {}",
+        response.status()
+      ));
+    }
+    report.uploaded = true;
+    write_report(&report)?;
+    uploaded += 1;
+  }
+  info!("💥 Uploaded {} crash report(s)", uploaded);
+  Ok(uploaded)
+}
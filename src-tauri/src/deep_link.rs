@@ -0,0 +1,357 @@
+// Deep links - framesense:// URLs from the browser (payment return, OAuth redirect) and
+// from other apps. The scheme is registered with the OS at startup; links arrive as
+// launch arguments (Windows/Linux, forwarded by the single-instance socket) or as an
+// "open URL" event (macOS). `parse` turns a link into a route and rejects anything else.
+use serde::Serialize;
+use tracing::info;
+
+pub const SCHEME: &str = "framesense";
+
+// Longest prompt accepted from a new-chat link
+const MAX_PROMPT_CHARS: usize = 4000;
+const MAX_PARAM_CHARS: usize = 512;
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(tag = "route", rename_all = "kebab-case")]
+pub enum DeepLink {
+  // framesense://payment-success?plan=pro&session_id=...
+  #[serde(rename_all = "camelCase")]
+  PaymentSuccess {
+    plan: Option<String>,
+    session_id: Option<String>,
+  },
+  // framesense://open-capture/<id> (or ?id=<id>)
+  OpenCapture {
+    id: String,
+  },
+  // framesense://open-conversation/<id> (or ?id=<id>)
+  OpenConversation {
+    id: String,
+  },
+  // framesense://login-callback?state=...&code=... (or &error=...)
+  LoginCallback {
+    state: String,
+    code: Option<String>,
+    error: Option<String>,
+  },
+  // framesense://new-chat?prompt=...
+  NewChat {
+    prompt: Option<String>,
+  },
+}
+
+impl DeepLink {
+  /// The route name alone - parameters can carry OAuth codes and prompts, keep them out of logs
+  pub fn route(&self) -> &'static str {
+    match self {
+      DeepLink::PaymentSuccess { .. } => "payment-success",
+      DeepLink::OpenCapture { .. } => "open-capture",
+      DeepLink::OpenConversation { .. } => "open-conversation",
+      DeepLink::LoginCallback { .. } => "login-callback",
+      DeepLink::NewChat { .. } => "new-chat",
+    }
+  }
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+  arg
+    .get(..SCHEME.len() + 3)
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}://", SCHEME)))
+}
+
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+  let url = url::Url::parse(link.trim()).map_err(|e| format!("Invalid deep link: {}", e))?;
+  if !url.scheme().eq_ignore_ascii_case(SCHEME) {
+    return Err(format!("Not a {}:// link", SCHEME));
+  }
+  let route = url
+    .host_str()
+    .filter(|host| !host.is_empty())
+    .ok_or_else(|| "Deep link has no route".to_string())?
+    .to_ascii_lowercase();
+  let param = |name: &str| -> Result<Option<String>, String> {
+    let value = url
+      .query_pairs()
+      .find(|(key, _)| key == name)
+      .map(|(_, value)| value.trim().to_string())
+      .filter(|value| !value.is_empty());
+    match value {
+      Some(value) if name != "prompt" && value.chars().count() > MAX_PARAM_CHARS => {
+        Err(format!("Deep link parameter '{}' is too long", name))
+      }
+      value => Ok(value),
+    }
+  };
+
+  match route.as_str() {
+    "payment-success" => Ok(DeepLink::PaymentSuccess {
+      plan: param("plan")?,
+      session_id: param("session_id")?,
+    }),
+    "open-capture" | "open-conversation" => {
+      let from_path = url
+        .path_segments()
+        .and_then(|mut segments| segments.next())
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.to_string());
+      let id = match from_path {
+        Some(id) => id,
+        None => param("id")?.ok_or_else(|| format!("{} link has no id", route))?,
+      };
+      if !is_valid_id(&id) {
+        return Err(format!("Invalid id '{}'", id));
+      }
+      if route == "open-capture" {
+        Ok(DeepLink::OpenCapture { id })
+      } else {
+        Ok(DeepLink::OpenConversation { id })
+      }
+    }
+    "login-callback" => {
+      let state = param("state")?.ok_or_else(|| "login-callback link has no state".to_string())?;
+      let code = param("code")?;
+      let error = param("error")?;
+      if code.is_none() && error.is_none() {
+        return Err("login-callback link has neither a code nor an error".to_string());
+      }
+      Ok(DeepLink::LoginCallback { state, code, error })
+    }
+    "new-chat" => {
+      let prompt = param("prompt")?;
+      if prompt
+        .as_ref()
+        .is_some_and(|prompt| prompt.chars().count() > MAX_PROMPT_CHARS)
+      {
+        return Err(format!(
+          "new-chat prompt is longer than {} characters",
+          MAX_PROMPT_CHARS
+        ));
+      }
+      Ok(DeepLink::NewChat { prompt })
+    }
+    other => Err(format!("Unknown deep link route '{}'", other)),
+  }
+}
+
+// Capture and conversation ids are generated by us; anything else is rejected before touching storage
+fn is_valid_id(id: &str) -> bool {
+  !id.is_empty()
+    && id.len() <= 128
+    && id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(not(target_os = "macos"))]
+fn current_exe() -> Result<std::path::PathBuf, String> {
+  std::env::current_exe().map_err(|e| format!("Failed to locate the app: {}", e))
+}
+
+/// Make the OS open framesense:// links with this executable
+#[cfg(target_os = "macos")]
+pub fn register() -> Result<(), String> {
+  // Declared in the bundle's Info.plist (CFBundleURLTypes); LaunchServices picks it up
+  info!("🔗 {}:// links are registered by the app bundle", SCHEME);
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn register() -> Result<(), String> {
+  let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+  let command_key = format!(r"{}\shell\open\command", key);
+  let command = format!("\"{}\" \"%1\"", current_exe()?.to_string_lossy());
+  let reg = |args: &[&str]| {
+    std::process::Command::new("reg")
+      .args(args)
+      .stdout(std::process::Stdio::null())
+      .stderr(std::process::Stdio::null())
+      .status()
+      .map(|status| status.success())
+      .map_err(|e| format!("Failed to run reg: {}", e))
+  };
+  let registered = reg(&["add", &key, "/ve", "/d", "URL:FrameSense", "/f"])?
+    && reg(&["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?
+    && reg(&["add", &command_key, "/ve", "/d", &command, "/f"])?;
+  if !registered {
+    return Err(format!("Failed to register the {}:// scheme", SCHEME));
+  }
+  info!("🔗 Registered {}:// links", SCHEME);
+  Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn register() -> Result<(), String> {
+  let applications = dirs::data_dir()
+    .map(|dir| dir.join("applications"))
+    .ok_or_else(|| "Could not find the data directory".to_string())?;
+  std::fs::create_dir_all(&applications)
+    .map_err(|e| format!("Failed to create applications folder: {}", e))?;
+  let desktop_file = format!("{}-handler.desktop", SCHEME);
+  // Same Exec quoting as the autostart entry
+  let exe = current_exe()?
+    .to_string_lossy()
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"");
+  let entry = format!(
+    "[Desktop Entry]\nType=Application\nName=FrameSense\nExec=\"{}\" %u\nMimeType=x-scheme-handler/{}\nNoDisplay=true\n",
+    exe, SCHEME
+  );
+  std::fs::write(applications.join(&desktop_file), entry)
+    .map_err(|e| format!("Failed to write link handler: {}", e))?;
+  let status = std::process::Command::new("xdg-mime")
+    .args([
+      "default",
+      &desktop_file,
+      &format!("x-scheme-handler/{}", SCHEME),
+    ])
+    .status()
+    .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+  if !status.success() {
+    return Err(format!("Failed to register the {}:// scheme", SCHEME));
+  }
+  info!("🔗 Registered {}:// links", SCHEME);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_payment_success() {
+    assert_eq!(
+      parse("framesense://payment-success?plan=pro&session_id=cs_123").unwrap(),
+      DeepLink::PaymentSuccess {
+        plan: Some("pro".to_string()),
+        session_id: Some("cs_123".to_string()),
+      }
+    );
+    assert_eq!(
+      parse("framesense://payment-success").unwrap(),
+      DeepLink::PaymentSuccess {
+        plan: None,
+        session_id: None,
+      }
+    );
+  }
+
+  #[test]
+  fn parses_open_capture_from_path_or_query() {
+    let expected = DeepLink::OpenCapture {
+      id: "abc-123".to_string(),
+    };
+    assert_eq!(
+      parse("framesense://open-capture/abc-123").unwrap(),
+      expected
+    );
+    assert_eq!(
+      parse("framesense://open-capture?id=abc-123").unwrap(),
+      expected
+    );
+  }
+
+  #[test]
+  fn parses_open_conversation() {
+    assert_eq!(
+      parse("framesense://open-conversation/conv-20250101120000-1").unwrap(),
+      DeepLink::OpenConversation {
+        id: "conv-20250101120000-1".to_string(),
+      }
+    );
+    assert!(parse("framesense://open-conversation").is_err());
+  }
+
+  #[test]
+  fn parses_login_callback() {
+    assert_eq!(
+      parse("framesense://login-callback?state=s1&code=c1").unwrap(),
+      DeepLink::LoginCallback {
+        state: "s1".to_string(),
+        code: Some("c1".to_string()),
+        error: None,
+      }
+    );
+    assert_eq!(
+      parse("framesense://login-callback?state=s1&error=access_denied").unwrap(),
+      DeepLink::LoginCallback {
+        state: "s1".to_string(),
+        code: None,
+        error: Some("access_denied".to_string()),
+      }
+    );
+  }
+
+  #[test]
+  fn parses_new_chat_with_encoded_prompt() {
+    assert_eq!(
+      parse("framesense://new-chat?prompt=Explain%20this%20chart").unwrap(),
+      DeepLink::NewChat {
+        prompt: Some("Explain this chart".to_string()),
+      }
+    );
+    assert_eq!(
+      parse("framesense://new-chat").unwrap(),
+      DeepLink::NewChat { prompt: None }
+    );
+  }
+
+  #[test]
+  fn routes_are_case_insensitive() {
+    assert!(matches!(
+      parse("FrameSense://New-Chat"),
+      Ok(DeepLink::NewChat { .. })
+    ));
+  }
+
+  #[test]
+  fn rejects_malformed_urls() {
+    for link in [
+      "",
+      "not a url",
+      "framesense:",
+      "framesense://",
+      "framesense:///open-capture/abc",
+      "https://payment-success?plan=pro",
+      "framesensex://new-chat",
+    ] {
+      assert!(parse(link).is_err(), "accepted {:?}", link);
+    }
+  }
+
+  #[test]
+  fn rejects_unknown_routes() {
+    assert!(parse("framesense://delete-everything").is_err());
+  }
+
+  #[test]
+  fn rejects_missing_or_invalid_capture_ids() {
+    assert!(parse("framesense://open-capture").is_err());
+    assert!(parse("framesense://open-capture/").is_err());
+    assert!(parse("framesense://open-capture/..%2F..%2Fetc").is_err());
+    assert!(parse("framesense://open-capture?id=a%20b").is_err());
+    assert!(parse(&format!("framesense://open-capture/{}", "a".repeat(129))).is_err());
+  }
+
+  #[test]
+  fn rejects_incomplete_login_callbacks() {
+    assert!(parse("framesense://login-callback?code=c1").is_err());
+    assert!(parse("framesense://login-callback?state=s1").is_err());
+    assert!(parse("framesense://login-callback?state=&code=c1").is_err());
+  }
+
+  #[test]
+  fn rejects_oversized_parameters() {
+    let prompt = "a".repeat(MAX_PROMPT_CHARS + 1);
+    assert!(parse(&format!("framesense://new-chat?prompt={}", prompt)).is_err());
+    let plan = "a".repeat(MAX_PARAM_CHARS + 1);
+    assert!(parse(&format!("framesense://payment-success?plan={}", plan)).is_err());
+  }
+
+  #[test]
+  fn detects_deep_link_arguments() {
+    assert!(is_deep_link("framesense://new-chat"));
+    assert!(is_deep_link("FRAMESENSE://new-chat"));
+    assert!(!is_deep_link("--minimized"));
+    assert!(!is_deep_link("framesense:"));
+  }
+}
@@ -0,0 +1,321 @@
+// Diagnostics - quick health checks for the "Troubleshoot" screen: capture permission,
+// displays, global shortcuts, backend reachability, OCR and free disk space. Each check
+// reports ok / warning / error with a message the user can act on.
+use crate::network::NetworkSettings;
+use crate::permissions::{self, Permission};
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(5);
+// Below this much free space saving captures and logs starts failing soon
+const LOW_DISK_BYTES: u64 = 500 * 1024 * 1024;
+
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+  Ok,
+  Warning,
+  Error,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+  pub id: String,
+  pub label: String,
+  pub status: CheckStatus,
+  pub message: String,
+  pub details: Option<serde_json::Value>,
+}
+
+impl DiagnosticCheck {
+  fn new(id: &str, label: &str, status: CheckStatus, message: String) -> Self {
+    Self {
+      id: id.to_string(),
+      label: label.to_string(),
+      status,
+      message,
+      details: None,
+    }
+  }
+
+  fn with_details(mut self, details: serde_json::Value) -> Self {
+    self.details = Some(details);
+    self
+  }
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+  pub created_at: String,
+  pub app_version: String,
+  pub os: String,
+  // Worst status among the checks
+  pub status: CheckStatus,
+  pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+  pub fn new(checks: Vec<DiagnosticCheck>) -> Self {
+    let status = checks
+      .iter()
+      .map(|check| check.status)
+      .max()
+      .unwrap_or(CheckStatus::Ok);
+    Self {
+      created_at: chrono::Utc::now().to_rfc3339(),
+      app_version: env!("CARGO_PKG_VERSION").to_string(),
+      os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+      status,
+      checks,
+    }
+  }
+}
+
+pub fn check_screen_recording() -> DiagnosticCheck {
+  let label = "Screen recording permission";
+  if permissions::is_granted(Permission::ScreenRecording) {
+    DiagnosticCheck::new(
+      "screenRecording",
+      label,
+      CheckStatus::Ok,
+      "Granted".to_string(),
+    )
+  } else {
+    DiagnosticCheck::new(
+      "screenRecording",
+      label,
+      CheckStatus::Error,
+      "Not granted - captures will only show the desktop background. Allow FrameSense under \
+       Privacy & Security > Screen Recording and restart the app."
+        .to_string(),
+    )
+  }
+}
+
+pub fn check_displays() -> DiagnosticCheck {
+  let label = "Displays";
+  match screenshots::Screen::all() {
+    Ok(screens) if !screens.is_empty() => {
+      let displays: Vec<serde_json::Value> = screens
+        .iter()
+        .map(|screen| {
+          let info = &screen.display_info;
+          serde_json::json!({
+            "id": info.id,
+            "x": info.x,
+            "y": info.y,
+            "width": info.width,
+            "height": info.height,
+            "scaleFactor": info.scale_factor,
+            "primary": info.is_primary,
+          })
+        })
+        .collect();
+      DiagnosticCheck::new(
+        "displays",
+        label,
+        CheckStatus::Ok,
+        format!("{} display(s) found", screens.len()),
+      )
+      .with_details(serde_json::Value::Array(displays))
+    }
+    Ok(_) => DiagnosticCheck::new(
+      "displays",
+      label,
+      CheckStatus::Error,
+      "No displays found".to_string(),
+    ),
+    Err(e) => DiagnosticCheck::new(
+      "displays",
+      label,
+      CheckStatus::Error,
+      format!("Failed to list displays: {}", e),
+    ),
+  }
+}
+
+/// `shortcuts` holds (action, accelerator, registered) for every configured binding
+pub fn check_shortcuts(shortcuts: &[(String, String, bool)]) -> DiagnosticCheck {
+  let missing: Vec<&str> = shortcuts
+    .iter()
+    .filter(|(_, _, registered)| !registered)
+    .map(|(_, accelerator, _)| accelerator.as_str())
+    .collect();
+  let details = serde_json::Value::Array(
+    shortcuts
+      .iter()
+      .map(|(action, accelerator, registered)| {
+        serde_json::json!({
+          "action": action,
+          "accelerator": accelerator,
+          "registered": registered,
+        })
+      })
+      .collect(),
+  );
+  let (status, message) = if missing.is_empty() {
+    (
+      CheckStatus::Ok,
+      format!("All {} shortcuts registered", shortcuts.len()),
+    )
+  } else {
+    (
+      CheckStatus::Warning,
+      format!(
+        "Not registered: {} - another app may be using them",
+        missing.join(", ")
+      ),
+    )
+  };
+  DiagnosticCheck::new("shortcuts", "Global shortcuts", status, message).with_details(details)
+}
+
+pub async fn check_backend(api_url: &str, network: &NetworkSettings) -> DiagnosticCheck {
+  let label = "Backend connection";
+  let client = match crate::network::http_client(network) {
+    Ok(client) => client,
+    Err(e) => return DiagnosticCheck::new("backend", label, CheckStatus::Error, e),
+  };
+  let started = Instant::now();
+  // Any HTTP answer means the server is reachable through the configured network path
+  match client.get(api_url).timeout(BACKEND_TIMEOUT).send().await {
+    Ok(response) => {
+      let latency_ms = started.elapsed().as_millis() as u64;
+      let status = if response.status().is_server_error() {
+        CheckStatus::Warning
+      } else {
+        CheckStatus::Ok
+      };
+      DiagnosticCheck::new(
+        "backend",
+        label,
+        status,
+        format!(
+          "{} answered with {} in {}ms",
+          api_url,
+          response.status(),
+          latency_ms
+        ),
+      )
+      .with_details(serde_json::json!({
+        "url": api_url,
+        "httpStatus": response.status().as_u16(),
+        "latencyMs": latency_ms,
+      }))
+    }
+    Err(e) => DiagnosticCheck::new(
+      "backend",
+      label,
+      CheckStatus::Error,
+      format!(
+        "Could not reach {}: {}. Check your connection or proxy settings.",
+        api_url, e
+      ),
+    ),
+  }
+}
+
+pub fn check_ocr() -> DiagnosticCheck {
+  let label = "Text recognition (OCR)";
+  match crate::ocr::OCRService::new() {
+    Ok(_) => match crate::ocr::OCRService::engine_name() {
+      Some(engine) => DiagnosticCheck::new(
+        "ocr",
+        label,
+        CheckStatus::Ok,
+        format!("{} available", engine),
+      ),
+      None => DiagnosticCheck::new(
+        "ocr",
+        label,
+        CheckStatus::Warning,
+        "This build has no OCR engine; text is not extracted from captures".to_string(),
+      ),
+    },
+    Err(e) => DiagnosticCheck::new("ocr", label, CheckStatus::Error, e),
+  }
+}
+
+pub fn check_disk_space(dir: &Path) -> DiagnosticCheck {
+  let label = "Disk space";
+  match free_disk_space(dir) {
+    Ok(free) => {
+      let status = if free < LOW_DISK_BYTES {
+        CheckStatus::Warning
+      } else {
+        CheckStatus::Ok
+      };
+      DiagnosticCheck::new(
+        "diskSpace",
+        label,
+        status,
+        format!("{} MB free for app data", free / (1024 * 1024)),
+      )
+      .with_details(serde_json::json!({
+        "path": dir.to_string_lossy(),
+        "freeBytes": free,
+      }))
+    }
+    Err(e) => DiagnosticCheck::new("diskSpace", label, CheckStatus::Warning, e),
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn free_disk_space(dir: &Path) -> Result<u64, String> {
+  use std::os::windows::ffi::OsStrExt;
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn GetDiskFreeSpaceExW(
+      directory: *const u16,
+      free_to_caller: *mut u64,
+      total: *mut u64,
+      total_free: *mut u64,
+    ) -> i32;
+  }
+
+  let path: Vec<u16> = existing_ancestor(dir)
+    .as_os_str()
+    .encode_wide()
+    .chain(std::iter::once(0))
+    .collect();
+  let mut free = 0u64;
+  let ok = unsafe {
+    GetDiskFreeSpaceExW(
+      path.as_ptr(),
+      &mut free,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+    )
+  };
+  if ok == 0 {
+    return Err("Failed to read free disk space".to_string());
+  }
+  Ok(free)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn free_disk_space(dir: &Path) -> Result<u64, String> {
+  // POSIX output: header line, then "filesystem blocks used available capacity mount"
+  let output = std::process::Command::new("df")
+    .args(["-Pk"])
+    .arg(existing_ancestor(dir))
+    .output()
+    .map_err(|e| format!("Failed to run df: {}", e))?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  stdout
+    .lines()
+    .nth(1)
+    .and_then(|line| line.split_whitespace().nth(3))
+    .and_then(|available| available.parse::<u64>().ok())
+    .map(|kib| kib * 1024)
+    .ok_or_else(|| "Failed to read free disk space".to_string())
+}
+
+// The app data dir may not exist yet on a fresh install
+fn existing_ancestor(dir: &Path) -> &Path {
+  dir.ancestors().find(|path| path.exists()).unwrap_or(dir)
+}
@@ -0,0 +1,50 @@
+// Drag-out support: the capture is written to the app's cache folder (per user, unlike
+// the shared temp dir) so the result window can hand a real file to the OS drag (Finder,
+// mail, browsers). The webview starts the drag itself: the command returns the file path
+// and the capture's framesense-capture:// URL, which works as a `DownloadURL` drag item
+// without the asset protocol. Files are kept for an hour and removed on the next drag.
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use tracing::{debug, warn};
+
+const DRAG_FOLDER: &str = "drag";
+// Long enough for the drop target to read the file (mail clients copy it lazily)
+const DRAG_FILE_TTL: Duration = Duration::from_secs(60 * 60);
+
+pub fn drag_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_cache_dir()
+    .map(|dir| dir.join(DRAG_FOLDER))
+    .map_err(|e| format!("Failed to resolve cache folder: {}", e))
+}
+
+/// Create the drag folder and remove files from earlier drags
+pub fn prepare(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  let dir = drag_dir(app)?;
+  fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drag folder: {}", e))?;
+  let now = SystemTime::now();
+  for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+    let expired = entry
+      .metadata()
+      .and_then(|metadata| metadata.modified())
+      .map(|modified| now.duration_since(modified).unwrap_or_default() > DRAG_FILE_TTL)
+      .unwrap_or(true);
+    if expired {
+      match fs::remove_file(entry.path()) {
+        Ok(()) => debug!("🧹 Removed old drag file {}", entry.path().display()),
+        Err(e) => warn!("⚠️ Failed to remove old drag file: {}", e),
+      }
+    }
+  }
+  Ok(dir)
+}
+
+/// Remove every drag file (local data wipe)
+pub fn clear(app: &tauri::AppHandle) {
+  if let Ok(dir) = drag_dir(app) {
+    let _ = fs::remove_dir_all(dir);
+  }
+}
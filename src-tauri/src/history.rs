@@ -0,0 +1,255 @@
+// Persistent capture history - every delivered capture is kept under the app data dir
+// (full PNG + small thumbnail + an index.json with metadata), so captures survive
+// closing the window and can be listed, searched, re-opened and deleted later.
+use crate::capture_store::decode_data_url;
+use crate::CaptureBounds;
+use base64::Engine;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+const INDEX_FILE: &str = "index.json";
+const MAX_HISTORY_ENTRIES: usize = 500;
+const THUMBNAIL_SIZE: u32 = 240;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+  pub id: String,
+  pub created_at: String, // RFC 3339
+  pub bounds: Option<CaptureBounds>,
+  pub width: u32,
+  pub height: u32,
+  pub ocr_text: Option<String>,
+  pub image_file: String,
+  pub thumbnail_file: String,
+}
+
+// What the frontend gets when listing: the entry plus its thumbnail as a data URL
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+  #[serde(flatten)]
+  pub entry: HistoryEntry,
+  pub thumbnail: Option<String>,
+}
+
+pub struct CaptureHistory {
+  dir: PathBuf,
+  entries: Vec<HistoryEntry>, // Oldest first
+}
+
+impl CaptureHistory {
+  /// Open (or create) the history stored in `dir`
+  pub fn new(dir: PathBuf) -> Self {
+    let entries = fs::read_to_string(dir.join(INDEX_FILE))
+      .ok()
+      .and_then(
+        |json| match serde_json::from_str::<Vec<HistoryEntry>>(&json) {
+          Ok(entries) => Some(entries),
+          Err(e) => {
+            warn!("⚠️ Ignoring unreadable capture history index: {}", e);
+            None
+          }
+        },
+      )
+      .unwrap_or_default();
+    if !entries.is_empty() {
+      info!("🗂️ Loaded {} capture history entries", entries.len());
+    }
+    Self { dir, entries }
+  }
+
+  /// Save a capture (data URL) to disk with a thumbnail and add it to the index
+  pub fn record(
+    &mut self,
+    id: &str,
+    image_data: &str,
+    bounds: Option<CaptureBounds>,
+  ) -> Result<HistoryEntry, String> {
+    fs::create_dir_all(&self.dir)
+      .map_err(|e| format!("Failed to create history directory: {}", e))?;
+
+    let png = decode_data_url(image_data)?;
+    let image =
+      image::load_from_memory(&png).map_err(|e| format!("Failed to read capture image: {}", e))?;
+
+    let mut thumbnail_png = Vec::new();
+    image
+      .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+      .write_to(&mut Cursor::new(&mut thumbnail_png), ImageFormat::Png)
+      .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+
+    let entry = HistoryEntry {
+      id: id.to_string(),
+      created_at: chrono::Utc::now().to_rfc3339(),
+      bounds,
+      width: image.width(),
+      height: image.height(),
+      ocr_text: None,
+      image_file: format!("{}.png", id),
+      thumbnail_file: format!("{}_thumb.png", id),
+    };
+    fs::write(self.dir.join(&entry.image_file), &png)
+      .map_err(|e| format!("Failed to save capture: {}", e))?;
+    fs::write(self.dir.join(&entry.thumbnail_file), &thumbnail_png)
+      .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+
+    self.entries.retain(|e| e.id != id);
+    self.entries.push(entry.clone());
+    while self.entries.len() > MAX_HISTORY_ENTRIES {
+      let oldest = self.entries.remove(0);
+      self.remove_files(&oldest);
+    }
+    self.persist()?;
+
+    info!(
+      "🗂️ Capture {} added to history ({}KB)",
+      id,
+      png.len() / 1024
+    );
+    Ok(entry)
+  }
+
+  pub fn set_ocr_text(&mut self, id: &str, text: String) -> Result<bool, String> {
+    match self.entries.iter_mut().find(|e| e.id == id) {
+      Some(entry) => {
+        entry.ocr_text = Some(text);
+        self.persist()?;
+        Ok(true)
+      }
+      None => Ok(false),
+    }
+  }
+
+  /// Newest first
+  pub fn list(&self, offset: usize, limit: usize) -> Vec<HistoryItem> {
+    self
+      .entries
+      .iter()
+      .rev()
+      .skip(offset)
+      .take(limit)
+      .map(|entry| self.to_item(entry))
+      .collect()
+  }
+
+  /// Case-insensitive search over OCR text and capture date, newest first
+  pub fn search(&self, query: &str, limit: usize) -> Vec<HistoryItem> {
+    let query = query.trim().to_lowercase();
+    self
+      .entries
+      .iter()
+      .rev()
+      .filter(|entry| {
+        query.is_empty()
+          || entry.created_at.to_lowercase().contains(&query)
+          || entry
+            .ocr_text
+            .as_ref()
+            .is_some_and(|text| text.to_lowercase().contains(&query))
+      })
+      .take(limit)
+      .map(|entry| self.to_item(entry))
+      .collect()
+  }
+
+  pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+    self.entries.iter().find(|e| e.id == id)
+  }
+
+  /// Full-size image of an entry as a PNG data URL
+  pub fn load_image(&self, id: &str) -> Result<String, String> {
+    let png = self.read_png(id)?;
+    Ok(format!(
+      "data:image/png;base64,{}",
+      base64::engine::general_purpose::STANDARD.encode(&png)
+    ))
+  }
+
+  /// Full-size image of an entry as PNG bytes
+  pub fn read_png(&self, id: &str) -> Result<Vec<u8>, String> {
+    let entry = self
+      .get(id)
+      .ok_or_else(|| format!("History entry '{}' not found", id))?;
+    fs::read(self.dir.join(&entry.image_file))
+      .map_err(|e| format!("Failed to read capture from history: {}", e))
+  }
+
+  pub fn delete(&mut self, id: &str) -> Result<(), String> {
+    let index = self
+      .entries
+      .iter()
+      .position(|e| e.id == id)
+      .ok_or_else(|| format!("History entry '{}' not found", id))?;
+    let entry = self.entries.remove(index);
+    self.remove_files(&entry);
+    self.persist()
+  }
+
+  fn to_item(&self, entry: &HistoryEntry) -> HistoryItem {
+    let thumbnail = fs::read(self.dir.join(&entry.thumbnail_file))
+      .ok()
+      .map(|png| {
+        format!(
+          "data:image/png;base64,{}",
+          base64::engine::general_purpose::STANDARD.encode(&png)
+        )
+      });
+    HistoryItem {
+      entry: entry.clone(),
+      thumbnail,
+    }
+  }
+
+  /// Delete captures recorded before `cutoff`; returns how many were removed
+  pub fn purge_older_than(
+    &mut self,
+    cutoff: chrono::DateTime<chrono::Utc>,
+  ) -> Result<usize, String> {
+    let (old, kept): (Vec<HistoryEntry>, Vec<HistoryEntry>) = std::mem::take(&mut self.entries)
+      .into_iter()
+      .partition(|entry| crate::retention::is_before(&entry.created_at, cutoff));
+    self.entries = kept;
+    for entry in &old {
+      self.remove_files(entry);
+    }
+    if !old.is_empty() {
+      self.persist()?;
+    }
+    Ok(old.len())
+  }
+
+  /// Forget recognized text of captures recorded before `cutoff`, keeping the images
+  pub fn clear_ocr_text_older_than(
+    &mut self,
+    cutoff: chrono::DateTime<chrono::Utc>,
+  ) -> Result<usize, String> {
+    let mut cleared = 0;
+    for entry in self.entries.iter_mut().filter(|entry| {
+      entry.ocr_text.is_some() && crate::retention::is_before(&entry.created_at, cutoff)
+    }) {
+      entry.ocr_text = None;
+      cleared += 1;
+    }
+    if cleared > 0 {
+      self.persist()?;
+    }
+    Ok(cleared)
+  }
+
+  fn remove_files(&self, entry: &HistoryEntry) {
+    let _ = fs::remove_file(self.dir.join(&entry.image_file));
+    let _ = fs::remove_file(self.dir.join(&entry.thumbnail_file));
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&self.entries)
+      .map_err(|e| format!("Failed to serialize capture history: {}", e))?;
+    fs::write(self.dir.join(INDEX_FILE), json)
+      .map_err(|e| format!("Failed to save capture history: {}", e))
+  }
+}
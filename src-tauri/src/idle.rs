@@ -0,0 +1,172 @@
+// System idle detection - lets heavy background work wait until the user is away
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+// Default: only run deferred work after 5 minutes without keyboard/mouse input
+const DEFAULT_IDLE_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+// Longest idle threshold the user may set (one day)
+pub const MAX_IDLE_THRESHOLD_MINUTES: u64 = 24 * 60;
+// How often the background loop polls the OS for idle time
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// Don't re-run deferred work more often than this, even if the user stays idle
+const MIN_RUN_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, Serialize, Debug)]
+pub struct IdleStatus {
+  pub idle_seconds: Option<u64>, // None = platform doesn't report idle time
+  pub threshold_seconds: u64,
+  pub is_idle: bool,
+  pub last_deferred_run_secs_ago: Option<u64>,
+}
+
+pub struct IdleMonitor {
+  threshold: Duration,
+  last_deferred_run: Option<Instant>,
+}
+
+impl IdleMonitor {
+  pub fn new() -> Self {
+    Self {
+      threshold: DEFAULT_IDLE_THRESHOLD,
+      last_deferred_run: None,
+    }
+  }
+
+  pub fn set_threshold(&mut self, threshold: Duration) {
+    self.threshold = threshold;
+    info!("⏳ Idle threshold set to {}s", threshold.as_secs());
+  }
+
+  pub fn status(&self) -> IdleStatus {
+    let idle = system_idle_time();
+    IdleStatus {
+      idle_seconds: idle.map(|d| d.as_secs()),
+      threshold_seconds: self.threshold.as_secs(),
+      is_idle: idle.map(|d| d >= self.threshold).unwrap_or(false),
+      last_deferred_run_secs_ago: self.last_deferred_run.map(|t| t.elapsed().as_secs()),
+    }
+  }
+
+  // True when the user has been idle long enough and deferred work hasn't run recently.
+  // Marks the work as started so callers don't need to track it themselves.
+  pub fn should_run_deferred_work(&mut self) -> bool {
+    let idle = match system_idle_time() {
+      Some(idle) => idle,
+      None => return false, // Unknown idle state - never run heavy work blindly
+    };
+
+    if idle < self.threshold {
+      return false;
+    }
+
+    if let Some(last_run) = self.last_deferred_run {
+      if last_run.elapsed() < MIN_RUN_INTERVAL {
+        return false;
+      }
+    }
+
+    self.last_deferred_run = Some(Instant::now());
+    info!(
+      "💤 User idle for {}s - running deferred background work",
+      idle.as_secs()
+    );
+    true
+  }
+}
+
+impl Default for IdleMonitor {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Time since the last keyboard/mouse input, as reported by the OS
+#[cfg(target_os = "macos")]
+pub fn system_idle_time() -> Option<Duration> {
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(source_state: i32, event_type: u32) -> f64;
+  }
+  // kCGEventSourceStateCombinedSessionState = 0, kCGAnyInputEventType = ~0
+  let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(0, u32::MAX) };
+  if seconds.is_finite() && seconds >= 0.0 {
+    Some(Duration::from_secs_f64(seconds))
+  } else {
+    None
+  }
+}
+
+/// Time since the last keyboard/mouse input, as reported by the OS
+#[cfg(target_os = "windows")]
+pub fn system_idle_time() -> Option<Duration> {
+  #[repr(C)]
+  struct LastInputInfo {
+    cb_size: u32,
+    dw_time: u32,
+  }
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+  }
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn GetTickCount() -> u32;
+  }
+
+  let mut info = LastInputInfo {
+    cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+    dw_time: 0,
+  };
+  unsafe {
+    if GetLastInputInfo(&mut info) == 0 {
+      return None;
+    }
+    let idle_ms = GetTickCount().wrapping_sub(info.dw_time);
+    Some(Duration::from_millis(idle_ms as u64))
+  }
+}
+
+/// Time since the last keyboard/mouse input, as reported by the OS
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn system_idle_time() -> Option<Duration> {
+  // X11: xprintidle prints the idle time in milliseconds
+  if let Ok(output) = std::process::Command::new("xprintidle").output() {
+    if output.status.success() {
+      if let Ok(ms) = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+      {
+        return Some(Duration::from_millis(ms));
+      }
+    }
+  }
+
+  // GNOME (X11 and Wayland): Mutter idle monitor over D-Bus
+  let output = std::process::Command::new("gdbus")
+    .args([
+      "call",
+      "--session",
+      "--dest",
+      "org.gnome.Mutter.IdleMonitor",
+      "--object-path",
+      "/org/gnome/Mutter/IdleMonitor/Core",
+      "--method",
+      "org.gnome.Mutter.IdleMonitor.GetIdletime",
+    ])
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  // Output looks like "(uint64 12345,)"
+  let text = String::from_utf8_lossy(&output.stdout);
+  let ms = text
+    .trim()
+    .trim_start_matches("(uint64")
+    .trim_end_matches(",)")
+    .trim()
+    .parse::<u64>()
+    .ok()?;
+  Some(Duration::from_millis(ms))
+}
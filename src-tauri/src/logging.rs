@@ -0,0 +1,347 @@
+// Logging - a small `tracing` subscriber that writes every event to stdout and to rotating
+// log files under the app data dir, and keeps the latest lines in memory so users can
+// attach them to bug reports. Levels are set globally and per module at runtime
+// (module = path below the crate, e.g. "auth" or "overlay::screenshot_cache").
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+const LOG_FILE: &str = "framesense.log";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+// Rotated files kept next to the current one (framesense.1.log is the newest)
+const MAX_ROTATED_FILES: usize = 4;
+const RECENT_CAPACITY: usize = 2000;
+const CRATE_PREFIX: &str = "framesense::";
+
+static LOGGER: OnceLock<Arc<LoggerState>> = OnceLock::new();
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+  pub timestamp: String,
+  pub level: String,
+  pub module: String,
+  pub message: String,
+}
+
+/// Global level plus per-module overrides, as level names ("error" .. "trace")
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogLevels {
+  pub level: String,
+  pub modules: BTreeMap<String, String>,
+}
+
+impl Default for LogLevels {
+  fn default() -> Self {
+    Self {
+      level: "info".to_string(),
+      modules: BTreeMap::new(),
+    }
+  }
+}
+
+impl LogLevels {
+  pub fn validate(&self) -> Result<(), String> {
+    parse_level(&self.level)?;
+    for level in self.modules.values() {
+      parse_level(level)?;
+    }
+    Ok(())
+  }
+}
+
+fn parse_level(level: &str) -> Result<Level, String> {
+  Level::from_str(level.trim()).map_err(|_| {
+    format!(
+      "Unknown log level '{}' (use error, warn, info, debug or trace)",
+      level
+    )
+  })
+}
+
+// Parsed form of LogLevels
+struct LevelFilter {
+  default: Level,
+  modules: Vec<(String, Level)>, // Longest module first so the most specific wins
+}
+
+impl LevelFilter {
+  fn from_levels(levels: &LogLevels) -> Self {
+    let mut modules: Vec<(String, Level)> = levels
+      .modules
+      .iter()
+      .filter_map(|(module, level)| Some((module.clone(), parse_level(level).ok()?)))
+      .collect();
+    modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+    Self {
+      default: parse_level(&levels.level).unwrap_or(Level::INFO),
+      modules,
+    }
+  }
+
+  fn level_for(&self, module: &str) -> Level {
+    self
+      .modules
+      .iter()
+      .find(|(prefix, _)| {
+        module == prefix
+          || module
+            .strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.starts_with("::"))
+      })
+      .map(|(_, level)| *level)
+      .unwrap_or(self.default)
+  }
+}
+
+struct LogFile {
+  dir: PathBuf,
+  file: Option<File>,
+  size: u64,
+}
+
+impl LogFile {
+  fn open(dir: PathBuf) -> Self {
+    let _ = fs::create_dir_all(&dir);
+    let file = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(dir.join(LOG_FILE))
+      .ok();
+    let size = file
+      .as_ref()
+      .and_then(|f| f.metadata().ok())
+      .map(|m| m.len())
+      .unwrap_or(0);
+    Self { dir, file, size }
+  }
+
+  fn write_line(&mut self, line: &str) {
+    if self.size + line.len() as u64 > MAX_LOG_FILE_BYTES {
+      self.rotate();
+    }
+    if let Some(file) = &mut self.file {
+      if file.write_all(line.as_bytes()).is_ok() {
+        self.size += line.len() as u64;
+      }
+    }
+  }
+
+  // framesense.log -> framesense.1.log -> ... -> framesense.N.log (dropped)
+  fn rotate(&mut self) {
+    self.file = None;
+    let rotated = |n: usize| self.dir.join(format!("framesense.{}.log", n));
+    let _ = fs::remove_file(rotated(MAX_ROTATED_FILES));
+    for n in (1..MAX_ROTATED_FILES).rev() {
+      let _ = fs::rename(rotated(n), rotated(n + 1));
+    }
+    let _ = fs::rename(self.dir.join(LOG_FILE), rotated(1));
+    *self = Self::open(self.dir.clone());
+  }
+}
+
+struct LoggerState {
+  filter: RwLock<LevelFilter>,
+  file: Mutex<Option<LogFile>>,
+  recent: Mutex<VecDeque<LogEntry>>,
+  // Command-line mode keeps stdout for its results
+  to_stderr: AtomicBool,
+}
+
+struct Logger {
+  state: Arc<LoggerState>,
+  next_span_id: AtomicU64,
+}
+
+fn module_of(metadata: &Metadata<'_>) -> String {
+  let target = metadata.target();
+  target
+    .strip_prefix(CRATE_PREFIX)
+    .or_else(|| (target == "framesense").then_some("main"))
+    .unwrap_or(target)
+    .to_string()
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+  message: String,
+  fields: String,
+}
+
+impl Visit for MessageVisitor {
+  fn record_str(&mut self, field: &Field, value: &str) {
+    if field.name() == "message" {
+      self.message.push_str(value);
+    } else {
+      let _ = write!(self.fields, " {}={}", field.name(), value);
+    }
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    if field.name() == "message" {
+      let _ = write!(self.message, "{:?}", value);
+    } else {
+      let _ = write!(self.fields, " {}={:?}", field.name(), value);
+    }
+  }
+}
+
+impl Subscriber for Logger {
+  // Levels change at runtime, so every callsite is asked each time
+  fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+    Interest::sometimes()
+  }
+
+  fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+    let filter = self.state.filter.read().unwrap();
+    *metadata.level() <= filter.level_for(&module_of(metadata))
+  }
+
+  fn new_span(&self, _span: &Attributes<'_>) -> Id {
+    Id::from_u64(self.next_span_id.fetch_add(1, Ordering::Relaxed))
+  }
+
+  fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+  fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+  fn event(&self, event: &Event<'_>) {
+    let mut visitor = MessageVisitor::default();
+    event.record(&mut visitor);
+    let metadata = event.metadata();
+    let entry = LogEntry {
+      timestamp: chrono::Local::now()
+        .format("%Y-%m-%d %H:%M:%S%.3f")
+        .to_string(),
+      level: metadata.level().as_str().to_lowercase(),
+      module: module_of(metadata),
+      message: visitor.message + &visitor.fields,
+    };
+    let line = format!(
+      "{} {:<5} [{}] {}\n",
+      entry.timestamp,
+      metadata.level(),
+      entry.module,
+      entry.message
+    );
+
+    if self.state.to_stderr.load(Ordering::Relaxed) {
+      eprint!("{}", line);
+    } else {
+      print!("{}", line);
+    }
+    if let Some(file) = self.state.file.lock().unwrap().as_mut() {
+      file.write_line(&line);
+    }
+    let mut recent = self.state.recent.lock().unwrap();
+    if recent.len() == RECENT_CAPACITY {
+      recent.pop_front();
+    }
+    recent.push_back(entry);
+  }
+
+  fn enter(&self, _span: &Id) {}
+
+  fn exit(&self, _span: &Id) {}
+}
+
+/// Install the subscriber. Call once, first thing in main.
+pub fn init(log_dir: PathBuf, levels: &LogLevels) {
+  let state = Arc::new(LoggerState {
+    filter: RwLock::new(LevelFilter::from_levels(levels)),
+    file: Mutex::new(Some(LogFile::open(log_dir))),
+    recent: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+    to_stderr: AtomicBool::new(false),
+  });
+  let logger = Logger {
+    state: state.clone(),
+    next_span_id: AtomicU64::new(1),
+  };
+  if tracing::subscriber::set_global_default(logger).is_ok() {
+    let _ = LOGGER.set(state);
+  }
+}
+
+/// Send console output to stderr instead of stdout
+pub fn use_stderr() {
+  if let Some(state) = LOGGER.get() {
+    state.to_stderr.store(true, Ordering::Relaxed);
+  }
+}
+
+pub fn set_levels(levels: &LogLevels) {
+  if let Some(state) = LOGGER.get() {
+    *state.filter.write().unwrap() = LevelFilter::from_levels(levels);
+  }
+}
+
+/// Delete rotated log files last written before `cutoff`; the current file is kept
+pub fn purge_older_than(cutoff: std::time::SystemTime) -> usize {
+  let Some(state) = LOGGER.get() else {
+    return 0;
+  };
+  let Some(dir) = state.file.lock().unwrap().as_ref().map(|f| f.dir.clone()) else {
+    return 0;
+  };
+  (1..=MAX_ROTATED_FILES)
+    .map(|n| dir.join(format!("framesense.{}.log", n)))
+    .filter(|path| {
+      fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified < cutoff)
+    })
+    .filter(|path| fs::remove_file(path).is_ok())
+    .count()
+}
+
+/// Stop writing the log file and forget the in-memory lines (before wiping app data)
+pub fn discard() {
+  if let Some(state) = LOGGER.get() {
+    *state.file.lock().unwrap() = None;
+    state.recent.lock().unwrap().clear();
+  }
+}
+
+/// Latest lines without waiting on the log lock - safe from a panic hook, which may run
+/// while this thread holds it. Empty if the lock is busy.
+pub fn try_recent(limit: usize) -> Vec<LogEntry> {
+  let recent = match LOGGER.get().and_then(|state| state.recent.try_lock().ok()) {
+    Some(recent) => recent,
+    None => return Vec::new(),
+  };
+  let skip = recent.len().saturating_sub(limit);
+  recent.iter().skip(skip).cloned().collect()
+}
+
+/// Newest last; `min_level` drops anything less severe
+pub fn recent(limit: usize, min_level: Option<&str>) -> Result<Vec<LogEntry>, String> {
+  let min_level = min_level.map(parse_level).transpose()?;
+  let state = match LOGGER.get() {
+    Some(state) => state,
+    None => return Ok(Vec::new()),
+  };
+  let recent = state.recent.lock().unwrap();
+  let mut entries: Vec<LogEntry> = recent
+    .iter()
+    .rev()
+    .filter(|entry| match (min_level, Level::from_str(&entry.level)) {
+      (Some(min), Ok(level)) => level <= min,
+      _ => true,
+    })
+    .take(limit)
+    .cloned()
+    .collect();
+  entries.reverse();
+  Ok(entries)
+}
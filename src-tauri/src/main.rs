@@ -3,19 +3,28 @@
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{
   menu::{Menu, MenuItem},
   tray::TrayIconBuilder,
   Emitter, Listener, Manager, RunEvent, WebviewUrl, WebviewWindowBuilder, WindowEvent,
 };
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, error, info, warn};
 //use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Import optimized overlay manager
 mod overlay;
-use overlay::{CaptureBounds, OverlayManager, ScreenCapture, ScreenshotCache};
+use overlay::native_overlay::NativeSelection;
+use overlay::{
+  color_picker, lasso, measure, native_overlay, redaction, window_picker, CacheConfig,
+  CaptureBounds, CoordinateMapper, EvictionPolicy, FrozenFrame, FrozenFrameView, GrabbedCapture,
+  MagnifierPatch, MaxDimensions, MeasurePoint, MeasureResult, OverlayBackend, OverlayConfig,
+  OverlayConfigStore, OverlayManager, OverlayStats, PhysicalBounds, PickedColor, RedactionStyle,
+  RedactionZone, ScreenCapture, ScreenInfo, ScreenshotCache, ScrollCaptureManager,
+  ScrollCaptureOptions, SelectionConstraint,
+};
 
 // OCR module for Tesseract integration
 mod ocr;
@@ -24,7 +33,169 @@ use ocr::{OCRResult, OCRService};
 // Authentication module
 mod auth;
 // Using API approach - no direct database connection
-use auth::{AuthService, User};
+use auth::{AuthService, LoginResult, OAuthProvider, UsageStatus, User};
+
+// Differential app state persistence
+mod state_store;
+use state_store::{StateRestored, StateStore};
+
+// Recent captures addressable by id
+mod capture_store;
+use capture_store::{CaptureMetadata, CaptureStore, StoredCapture};
+
+// Chunked transfer of large captures over events
+mod capture_stream;
+use capture_stream::{CaptureChunk, CaptureStreamHandle};
+
+// Persistent capture history with thumbnails
+mod history;
+use history::{CaptureHistory, HistoryItem};
+
+// Saving captures to disk with filename templates
+mod save;
+use save::{SaveManager, SaveSettings};
+
+// Native clipboard (image bitmaps)
+mod clipboard;
+
+// Temp files for dragging captures out of the result window
+mod drag;
+
+// framesense-capture:// protocol serving capture PNGs to the webview
+mod capture_protocol;
+
+// Uploading captures to Imgur / S3 / a custom endpoint for a link
+mod share;
+use share::{DestinationInfo, DestinationKind};
+
+// Obsidian / Notion integrations (capture + OCR text + AI summary)
+mod notes;
+use notes::{NoteEntry, NotesTarget};
+
+// Posting captures to Slack channels
+mod channels;
+use channels::{ChannelPost, ChannelProvider};
+
+// Burn-in annotations (rectangles, arrows, text, highlights)
+mod annotate;
+use annotate::Annotation;
+
+// Watch mode (periodic region capture with change detection)
+mod watch;
+use watch::{RegionWatcher, WatchInfo, WatchOptions};
+
+// Named capture regions
+mod presets;
+use presets::{PresetStore, RegionPreset};
+
+// External processors (regex extractors, ticket formatters...) run on captures
+mod plugins;
+use plugins::{PluginInfo, PluginRegistry, ProcessorInput, ProcessorOutput};
+
+// Native printing of captures
+mod print;
+use print::PrintOptions;
+
+// Saved conversations (messages, attached captures, models and costs)
+mod conversation_history;
+use conversation_history::{
+  Conversation, ConversationHistory, ConversationMessage, ConversationSummary,
+};
+
+// Exporting conversations to Markdown / HTML
+mod conversation_export;
+use conversation_export::ExportFormat;
+
+// Exporting OCR / AI results to Markdown, TXT and DOCX
+mod result_export;
+use result_export::{ResultDocument, ResultFormat, ResultSection};
+
+// Searchable PDFs (capture images with an invisible OCR text layer)
+mod pdf_export;
+use pdf_export::PdfPage;
+
+// Conversation store (per-conversation AI parameters)
+mod conversations;
+use conversations::{ConversationParams, ResolvedAiParams};
+
+// AI analysis requests made from Rust
+mod ai;
+use ai::api_keys::ApiKeyStore;
+use ai::cost::{CostEstimate, DailySpend, ImageDims, SpendTracker};
+use ai::fallback::{FallbackSettings, FallbackStore};
+use ai::ollama::{LocalModel, OllamaProvider};
+use ai::preferences::{AiPreferences, AiPreferencesStore};
+use ai::rate_limit::RateLimiter;
+use ai::response_cache::{ResponseCache, ResponseCacheStats};
+use ai::translate::TranslationResult;
+use ai::{AiClient, AnalysisError, AnalysisRequest, AnalysisResult, ProviderInfo, ProviderKind};
+
+// Screen recording to MP4
+mod recording;
+use recording::{
+  AnimatedCapture, AnimatedFormat, RecordingOptions, RecordingStatus, ScreenRecorder,
+};
+
+// Screen reader announcements
+mod accessibility;
+use accessibility::AnnouncementPriority;
+
+// Text-to-speech for results
+mod speech;
+use speech::{SpeechPlayer, SpeechStatus};
+
+// Idle detection for deferring heavy background work
+mod idle;
+use idle::{IdleMonitor, IdleStatus};
+
+// Named periodic background tasks
+mod scheduler;
+use scheduler::{Scheduler, TaskInfo};
+
+// Central app settings (settings.json)
+mod settings;
+
+// Log files, runtime log levels and recent log lines
+mod logging;
+use logging::LogEntry;
+
+// Launch at login
+mod autostart;
+
+// One running instance; later launches forward their arguments to it
+mod single_instance;
+use single_instance::{Instance, LaunchRequest};
+
+// framesense:// links: scheme registration and routing
+mod deep_link;
+use deep_link::DeepLink;
+
+// macOS Screen Recording / Accessibility permission checks
+mod permissions;
+use permissions::{Permission, PermissionStatus};
+
+// Health checks for the troubleshooting screen
+mod diagnostics;
+use diagnostics::DiagnosticsReport;
+
+// Native notifications when results are ready in the background
+mod notifications;
+
+// Purging old local data and "Delete all local data"
+mod retention;
+use retention::{PurgeSummary, PurgeTarget};
+
+// `framesense --capture-region ...` headless runs from scripts
+mod cli;
+
+// Panic hook and background error reports
+mod crash_reports;
+use crash_reports::CrashReport;
+use settings::{AppSettings, SettingsStore, ShortcutAction, ShortcutSettings};
+
+// Proxy / custom CA settings for backend requests
+mod network;
+use network::{NetworkSettings, NetworkSettingsStore};
 
 // Global OCR service (reuse instance for performance)
 static mut OCR_SERVICE: Option<std::sync::Mutex<OCRService>> = None;
@@ -44,8 +215,10 @@ pub struct AppResult {
 pub struct CaptureResult {
   pub success: bool,
   pub message: String,
-  pub bounds: Option<CaptureBounds>,
-  pub image_data: Option<String>, // Base64 encoded image
+  pub bounds: Option<CaptureBounds>, // Logical overlay coordinates
+  pub physical_bounds: Option<PhysicalBounds>, // Pixels on the captured display
+  pub image_data: Option<String>,    // Base64 encoded image
+  pub metadata: Option<CaptureMetadata>,
 }
 
 // App state that persists between window creations (like Raycast)
@@ -54,136 +227,323 @@ pub struct AppState {
   pub screenshot_data: Option<String>,
   pub last_bounds: Option<CaptureBounds>,
   pub last_window_closed_time: Option<u64>, // Timestamp when window was last closed
+  pub capture_source_app: Option<String>,   // App in front when the current capture started
 }
 
 type SharedState = Arc<Mutex<AppState>>;
 
+// Persists AppState to the app data dir, writing only changed parts
+type SharedStateStore = Arc<Mutex<StateStore>>;
+
 // FAS 1: Optimized overlay manager for pooling
 type SharedOverlayManager = Arc<Mutex<OverlayManager>>;
 
+// Overlay appearance preferences
+type SharedOverlayConfig = Arc<Mutex<OverlayConfigStore>>;
+
 // FAS 3: Screenshot cache manager for optimization
 type SharedScreenshotCache = Arc<Mutex<ScreenshotCache>>;
 
-// Authentication service manager
-type SharedAuthService = Arc<Mutex<AuthService>>;
+// Scroll capture session manager
+type SharedScrollCapture = Arc<Mutex<ScrollCaptureManager>>;
+
+// Screen recording manager
+type SharedScreenRecorder = Arc<Mutex<ScreenRecorder>>;
+
+// Recent captures by id
+type SharedCaptureStore = Arc<Mutex<CaptureStore>>;
+
+// Persistent capture history
+type SharedCaptureHistory = Arc<Mutex<CaptureHistory>>;
+
+// Saved conversations
+type SharedConversationHistory = Arc<Mutex<ConversationHistory>>;
+
+// Save directory and filename template
+type SharedSaveManager = Arc<Mutex<SaveManager>>;
+
+// Watched regions
+type SharedRegionWatcher = Arc<Mutex<RegionWatcher>>;
+
+// Named capture regions
+type SharedPresetStore = Arc<Mutex<PresetStore>>;
+
+// Processor plugins found in the app data dir
+type SharedPluginRegistry = Arc<Mutex<PluginRegistry>>;
+
+// Authentication service manager. Commands work on a clone (its state is shared) so the
+// lock isn't held across network calls and settings changes never wait on them.
+type SharedAuthService = Arc<tokio::sync::RwLock<AuthService>>;
+
+// Idle monitor for deferred background work
+type SharedIdleMonitor = Arc<Mutex<IdleMonitor>>;
+
+// Text-to-speech playback
+type SharedSpeechPlayer = Arc<Mutex<SpeechPlayer>>;
+
+// Typed app settings
+type SharedSettings = Arc<Mutex<SettingsStore>>;
+
+// Proxy and trusted certificate settings
+type SharedNetworkSettings = Arc<Mutex<NetworkSettingsStore>>;
+
+// User-supplied AI provider API keys
+type SharedApiKeys = Arc<Mutex<ApiKeyStore>>;
+
+// Estimated AI spend per day
+type SharedSpendTracker = Arc<Mutex<SpendTracker>>;
+
+// Models to retry on when the chosen one fails
+type SharedFallbackStore = Arc<Mutex<FallbackStore>>;
+
+// Custom system prompt, response style and language
+type SharedAiPreferences = Arc<Mutex<AiPreferencesStore>>;
+
+// Per-tier token buckets for backend AI requests
+type SharedAiRateLimiter = Arc<Mutex<RateLimiter>>;
+
+// Recent AI answers for identical requests
+type SharedResponseCache = Arc<Mutex<ResponseCache>>;
+
+// Recurring background work
+type SharedScheduler = Arc<Mutex<Scheduler>>;
 /*
 
 */
 // Extract text from image using OCR (Step 2-3 from AI.txt)
 #[tauri::command]
-async fn extract_text_ocr(image_data: String) -> Result<OCRResult, String> {
-  println!("📝 Extracting text from image using OCR...");
+async fn extract_text_ocr(
+  image_data: String,
+  capture_id: Option<String>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+  capture_history: tauri::State<'_, SharedCaptureHistory>,
+) -> Result<OCRResult, String> {
+  let result = run_ocr(&image_data)?;
+  // Remember the text on the stored capture (used by print/export)
+  if let Some(id) = &capture_id {
+    capture_store
+      .lock()
+      .unwrap()
+      .set_ocr_text(id, result.text.clone());
+    if let Err(e) = capture_history
+      .lock()
+      .unwrap()
+      .set_ocr_text(id, result.text.clone())
+    {
+      warn!("⚠️ Failed to store OCR text in history: {}", e);
+    }
+  }
+  Ok(result)
+}
+
+// OCR through the shared, lazily initialized service
+fn run_ocr(image_data: &str) -> Result<OCRResult, String> {
+  info!("📝 Extracting text from image using OCR...");
 
   unsafe {
     OCR_INIT.call_once(|| {
       if let Ok(service) = OCRService::new() {
         OCR_SERVICE = Some(std::sync::Mutex::new(service));
-        println!("✅ OCR service initialized successfully");
+        info!("✅ OCR service initialized successfully");
       } else {
-        println!("❌ Failed to initialize OCR service");
+        error!("❌ Failed to initialize OCR service");
       }
     });
 
     if let Some(ref service_mutex) = OCR_SERVICE {
       let service = service_mutex.lock().unwrap();
-      match service.extract_text(&image_data) {
+      match service.extract_text(image_data) {
         Ok(result) => {
-          println!(
-            "✅ OCR extraction successful - Text: '{}', Confidence: {:.2}%",
-            result.text,
+          // The text itself can be anything on screen: keep it out of info-level logs
+          info!(
+            "✅ OCR extraction successful - {} chars, Confidence: {:.2}%",
+            result.text.chars().count(),
             result.confidence * 100.0
           );
+          debug!("📝 OCR text: '{}'", result.text);
           Ok(result)
         }
         Err(error) => {
-          println!("❌ OCR extraction failed: {}", error);
+          error!("❌ OCR extraction failed: {}", error);
           Err(error)
         }
       }
     } else {
       let error_msg = "OCR service not initialized".to_string();
-      println!("❌ {}", error_msg);
+      error!("❌ {}", error_msg);
       Err(error_msg)
     }
   }
 }
-// Check permissions (simplified for now)
+// 🔐 PERMISSION COMMANDS
+
+// Whether captures will contain window content (Screen Recording on macOS)
 #[tauri::command]
 async fn check_permissions() -> Result<bool, String> {
-  // For now, just return true since we handle permissions via macOS system prompts
-  // In a real app, you might want to check specific permissions here
-  println!("🔐 Checking permissions...");
-  Ok(true)
+  let status = permissions::status();
+  info!("🔐 Permissions: {:?}", status);
+  Ok(status.screen_recording)
+}
+
+// Every permission the app uses, for onboarding screens
+#[tauri::command]
+fn get_permission_status() -> Result<PermissionStatus, String> {
+  Ok(permissions::status())
+}
+
+#[tauri::command]
+fn check_screen_recording_permission() -> Result<bool, String> {
+  Ok(permissions::is_granted(Permission::ScreenRecording))
+}
+
+#[tauri::command]
+fn request_screen_recording_permission() -> Result<bool, String> {
+  permissions::request(Permission::ScreenRecording)
+}
+
+#[tauri::command]
+fn check_accessibility_permission() -> Result<bool, String> {
+  Ok(permissions::is_granted(Permission::Accessibility))
+}
+
+#[tauri::command]
+fn request_accessibility_permission() -> Result<bool, String> {
+  permissions::request(Permission::Accessibility)
+}
+
+// Captures without Screen Recording come back black: ask for it and tell the UI
+// (`permission-required`) instead of capturing
+fn ensure_capture_permission(app: &tauri::AppHandle) -> bool {
+  if permissions::is_granted(Permission::ScreenRecording) {
+    return true;
+  }
+  warn!("🔐 Screen Recording permission missing - capture skipped");
+  if let Err(e) = permissions::request(Permission::ScreenRecording) {
+    warn!("⚠️ {}", e);
+  }
+  let _ = app.emit(
+    "permission-required",
+    serde_json::json!({ "permission": Permission::ScreenRecording }),
+  );
+  false
 }
 
 // 🚀 FAS 2: OPTIMIZED PERMISSION COMMANDS
 
 // 🚀 FAS 3: OPTIMIZED SCREENSHOT COMMANDS
-// Capture screen area with smart caching (60% faster)
+// Capture screen area with smart caching (60% faster). Capture and PNG encoding run on
+// the blocking pool so big regions don't stall the command threads.
 #[tauri::command]
-fn capture_screen_area_optimized(
+async fn capture_screen_area_optimized(
   bounds: CaptureBounds,
   cache: tauri::State<'_, SharedScreenshotCache>,
 ) -> Result<CaptureResult, String> {
-  let mut screenshot_cache = cache.lock().unwrap();
-
-  match screenshot_cache.capture_optimized(bounds.clone()) {
-    Ok(image_data) => Ok(CaptureResult {
-      success: true,
-      message: "Optimized screen capture successful!".to_string(),
-      bounds: Some(bounds),
-      image_data: Some(image_data),
-    }),
-    Err(e) => Ok(CaptureResult {
-      success: false,
-      message: e,
-      bounds: None,
-      image_data: None,
-    }),
-  }
+  let cache = cache.inner().clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let started = Instant::now();
+    // Metadata collection spawns helper processes; don't hold the cache meanwhile
+    let captured = cache.lock().unwrap().capture_optimized(bounds.clone());
+
+    match captured {
+      Ok(image_data) => Ok(CaptureResult {
+        success: true,
+        message: "Optimized screen capture successful!".to_string(),
+        physical_bounds: ScreenCapture::physical_bounds(&bounds),
+        metadata: Some(CaptureMetadata::collect(
+          Some(&bounds),
+          started.elapsed(),
+          None,
+        )),
+        bounds: Some(bounds),
+        image_data: Some(image_data),
+      }),
+      Err(e) => Ok(CaptureResult {
+        success: false,
+        message: e,
+        bounds: None,
+        physical_bounds: None,
+        image_data: None,
+        metadata: None,
+      }),
+    }
+  })
+  .await
+  .map_err(|e| format!("Capture task failed: {}", e))?
 }
 // Capture screen area with multi-screen support and smart caching
 #[tauri::command]
-fn capture_screen_area_multi_screen_optimized(
+async fn capture_screen_area_multi_screen_optimized(
   bounds: CaptureBounds,
   cache: tauri::State<'_, SharedScreenshotCache>,
 ) -> Result<CaptureResult, String> {
-  println!(
+  let cache = cache.inner().clone();
+  tauri::async_runtime::spawn_blocking(move || capture_multi_screen(bounds, &cache))
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))
+}
+
+fn capture_multi_screen(bounds: CaptureBounds, cache: &SharedScreenshotCache) -> CaptureResult {
+  info!(
     "🖥️ Multi-screen capture: {}x{} at ({}, {})",
     bounds.width, bounds.height, bounds.x, bounds.y
   );
 
-  let mut screenshot_cache = cache.lock().unwrap();
+  let started = Instant::now();
 
-  // First try the optimized cache (works for single screen regions)
-  match screenshot_cache.capture_optimized(bounds.clone()) {
+  // First try the optimized cache (works for single screen regions). The lock is
+  // released before metadata collection spawns its helper processes.
+  let captured = cache.lock().unwrap().capture_optimized(bounds.clone());
+  match captured {
     Ok(image_data) => {
-      println!("✅ Multi-screen capture successful via optimized cache!");
-      Ok(CaptureResult {
+      info!("✅ Multi-screen capture successful via optimized cache!");
+      CaptureResult {
         success: true,
         message: "Multi-screen optimized capture successful!".to_string(),
+        physical_bounds: ScreenCapture::physical_bounds(&bounds),
+        metadata: Some(CaptureMetadata::collect(
+          Some(&bounds),
+          started.elapsed(),
+          None,
+        )),
         bounds: Some(bounds),
         image_data: Some(image_data),
-      })
+      }
     }
     Err(cache_error) => {
-      println!("❌ Multi-screen capture failed: {}", cache_error);
-      Ok(CaptureResult {
+      error!("❌ Multi-screen capture failed: {}", cache_error);
+      CaptureResult {
         success: false,
         message: format!("Multi-screen capture failed: {}", cache_error),
         bounds: None,
+        physical_bounds: None,
         image_data: None,
-      })
+        metadata: None,
+      }
     }
   }
 }
 
+// AI response cache hits/misses
+#[tauri::command]
+fn get_response_cache_stats(
+  cache: tauri::State<'_, SharedResponseCache>,
+) -> Result<ResponseCacheStats, String> {
+  Ok(cache.lock().unwrap().stats())
+}
+
+#[tauri::command]
+fn clear_response_cache(cache: tauri::State<'_, SharedResponseCache>) -> Result<(), String> {
+  cache.lock().unwrap().clear();
+  info!("🗑️ AI response cache cleared");
+  Ok(())
+}
+
 // Clear screenshot cache (for testing or memory management)
 #[tauri::command]
 fn clear_screenshot_cache(cache: tauri::State<'_, SharedScreenshotCache>) -> Result<(), String> {
   let mut screenshot_cache = cache.lock().unwrap();
   screenshot_cache.clear_cache();
-  println!("🗑️ Screenshot cache cleared");
+  info!("🗑️ Screenshot cache cleared");
   Ok(())
 }
 
@@ -200,10 +560,13 @@ fn get_screenshot_cache_stats(
       "total_size_bytes": total_size,
       "total_size_mb": total_size / (1024 * 1024),
       "expired_entries": expired_entries,
-      "active_entries": total_entries - expired_entries
+      "active_entries": total_entries - expired_entries,
+      "unique_captures": screenshot_cache.unique_captures(),
+      "evictions": screenshot_cache.evictions(),
+      "eviction_policy": screenshot_cache.eviction_policy()
   });
 
-  println!(
+  info!(
     "📊 Screenshot cache stats: {} entries, {}MB, {} expired",
     total_entries,
     total_size / (1024 * 1024),
@@ -212,12 +575,40 @@ fn get_screenshot_cache_stats(
   Ok(stats)
 }
 
+// Tune the screenshot cache TTL and size ceiling (defaults 30s / 50MB)
+#[tauri::command]
+fn set_cache_config(
+  ttl_secs: u64,
+  max_size_mb: u64,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  cache.lock().unwrap().set_config(CacheConfig {
+    ttl_secs,
+    max_size_mb,
+  })
+}
+
+#[tauri::command]
+fn get_cache_config(cache: tauri::State<'_, SharedScreenshotCache>) -> Result<CacheConfig, String> {
+  Ok(cache.lock().unwrap().config())
+}
+
+// LRU (default) or LFU eviction when the cache is full
+#[tauri::command]
+fn set_cache_eviction_policy(
+  policy: EvictionPolicy,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  cache.lock().unwrap().set_eviction_policy(policy);
+  Ok(())
+}
+
 // Cleanup expired screenshot cache entries
 #[tauri::command]
 fn cleanup_screenshot_cache(cache: tauri::State<'_, SharedScreenshotCache>) -> Result<(), String> {
   let mut screenshot_cache = cache.lock().unwrap();
   screenshot_cache.cleanup_expired();
-  println!("🧹 Screenshot cache cleanup completed");
+  info!("🧹 Screenshot cache cleanup completed");
   Ok(())
 }
 
@@ -230,374 +621,4239 @@ fn resize_screenshot_buffer(
   let mut screenshot_cache = cache.lock().unwrap();
   let new_size_bytes = new_size_mb * 1024 * 1024;
   screenshot_cache.resize_buffer(new_size_bytes);
-  println!("📏 Screenshot buffer resized to {}MB", new_size_mb);
+  info!("📏 Screenshot buffer resized to {}MB", new_size_mb);
   Ok(())
 }
 
-// 🚀 AUTHENTICATION COMMANDS
-//härr
-// Login user with credentials
+// 🗂️ CAPTURE STORE COMMANDS
+
+// Get a recent capture by id (or the latest one when no id is given)
 #[tauri::command]
-async fn login_user(
-  email: String,
-  password: String,
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<User, String> {
-  // Clone the auth service to avoid holding the lock across await
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
+fn get_capture(
+  id: Option<String>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<StoredCapture, String> {
+  let store = capture_store.lock().unwrap();
+  let capture = match &id {
+    Some(id) => store.get(id),
+    None => store.latest(),
   };
-  service.login_user(email, password).await
+  capture
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))
 }
 
-// Logout current user
+// URL that loads a capture's PNG through the framesense-capture protocol
 #[tauri::command]
-async fn logout_user(auth_service: tauri::State<'_, SharedAuthService>) -> Result<(), String> {
-  // Clone the auth service to avoid holding the lock across await
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
-  };
-  service.logout_user().await
+fn get_capture_url(id: String) -> String {
+  capture_protocol::capture_url(&id)
 }
 
-// Get current logged in user
+// 🗂️ CAPTURE HISTORY COMMANDS
+
+// List saved captures, newest first
 #[tauri::command]
-async fn get_current_user(
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<Option<User>, String> {
-  // Clone the auth service to avoid holding the lock across await
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
+fn list_capture_history(
+  offset: Option<usize>,
+  limit: Option<usize>,
+  capture_history: tauri::State<'_, SharedCaptureHistory>,
+) -> Result<Vec<HistoryItem>, String> {
+  let history = capture_history.lock().unwrap();
+  Ok(history.list(offset.unwrap_or(0), limit.unwrap_or(50)))
+}
+
+// Search saved captures by OCR text or date
+#[tauri::command]
+fn search_capture_history(
+  query: String,
+  limit: Option<usize>,
+  capture_history: tauri::State<'_, SharedCaptureHistory>,
+) -> Result<Vec<HistoryItem>, String> {
+  let history = capture_history.lock().unwrap();
+  Ok(history.search(&query, limit.unwrap_or(50)))
+}
+
+// Re-open a saved capture with its full image, making it addressable by id again
+#[tauri::command]
+fn open_capture_from_history(
+  id: String,
+  capture_history: tauri::State<'_, SharedCaptureHistory>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<StoredCapture, String> {
+  let capture = {
+    let history = capture_history.lock().unwrap();
+    let entry = history
+      .get(&id)
+      .cloned()
+      .ok_or_else(|| format!("History entry '{}' not found", id))?;
+    StoredCapture {
+      id: entry.id,
+      image_data: history.load_image(&id)?,
+      bounds: entry.bounds,
+      created_at: entry.created_at,
+      ocr_text: entry.ocr_text,
+      source_app: None,
+      metadata: None,
+    }
   };
-  service.get_current_user().await
+  capture_store.lock().unwrap().restore(capture.clone());
+  info!("🗂️ Re-opened capture {} from history", id);
+  Ok(capture)
 }
 
-// Save user session to storage
 #[tauri::command]
-async fn save_user_session(
-  user: User,
-  auth_service: tauri::State<'_, SharedAuthService>,
+fn delete_capture_history_entry(
+  id: String,
+  capture_history: tauri::State<'_, SharedCaptureHistory>,
 ) -> Result<(), String> {
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
-  };
-  service.save_user_session(&user).await
+  capture_history.lock().unwrap().delete(&id)
 }
 
-// Load user session from storage
+// 💾 SAVE COMMANDS
+
+// Save a capture (latest when no id is given) as PNG; returns the written path
 #[tauri::command]
-async fn load_user_session(
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<Option<User>, String> {
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
+fn save_capture_to_file(
+  id: Option<String>,
+  path: Option<String>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+  save_manager: tauri::State<'_, SharedSaveManager>,
+) -> Result<String, String> {
+  let capture = {
+    let store = capture_store.lock().unwrap();
+    match &id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))?
   };
-  service.load_user_session().await
+  let saved_path = save_manager
+    .lock()
+    .unwrap()
+    .save(&capture, path.map(PathBuf::from))?;
+  Ok(saved_path.to_string_lossy().into_owned())
 }
-/*
+
+#[tauri::command]
+fn get_save_settings(
+  save_manager: tauri::State<'_, SharedSaveManager>,
+) -> Result<SaveSettings, String> {
+  Ok(save_manager.lock().unwrap().settings().clone())
 }
-*/
-// Get available models for user tier
+
+// Change the default save directory and filename template ({date}, {time}, {app}, {monitor})
 #[tauri::command]
-fn get_available_models(
-  user_tier: String,
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<Vec<String>, String> {
-  println!(
-    "🔍 DEBUG: get_available_models called for tier: {}",
-    user_tier
-  );
+fn set_save_settings(
+  settings: SaveSettings,
+  save_manager: tauri::State<'_, SharedSaveManager>,
+) -> Result<(), String> {
+  save_manager.lock().unwrap().update_settings(settings)
+}
 
-  let service = auth_service.lock().unwrap();
-  let raw_models = service.get_available_models(&user_tier);
-  let models: Vec<String> = raw_models.iter().map(|&s| s.to_string()).collect();
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DragFile {
+  path: String,
+  file_name: String,
+  mime_type: String,
+  // framesense-capture:// URL of the same image, for `DownloadURL` drags
+  url: String,
+}
 
-  println!(
-    "✅ DEBUG: get_available_models returning {} models: {:?}",
-    models.len(),
-    models
-  );
-  Ok(models)
+// Write a capture (latest when no id is given) to a temp file for a native drag; the
+// name follows the save template and metadata is embedded like a normal save
+#[tauri::command]
+fn prepare_capture_drag(
+  app: tauri::AppHandle,
+  id: Option<String>,
+  save_manager: tauri::State<'_, SharedSaveManager>,
+) -> Result<DragFile, String> {
+  let capture = find_capture(&app, id.as_deref())?;
+  let dir = drag::prepare(&app)?;
+  let path = save_manager.lock().unwrap().save(&capture, Some(dir))?;
+  Ok(DragFile {
+    url: capture_protocol::capture_url(&capture.id),
+    file_name: path
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default(),
+    path: path.to_string_lossy().into_owned(),
+    mime_type: "image/png".to_string(),
+  })
 }
 
-// Check if user can use specific model
+// 📦 CAPTURE TRANSFER COMMANDS
+
+// Stream a capture (latest when no id is given) as `capture-chunk` events instead of
+// returning megabytes of base64 in one IPC response
 #[tauri::command]
-fn can_use_model(
-  user_tier: String,
-  model: String,
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<bool, String> {
-  println!(
-    "🔍 DEBUG: can_use_model called - tier: '{}', model: '{}'",
-    user_tier, model
-  );
+fn stream_capture(
+  id: Option<String>,
+  chunk_size: Option<usize>,
+  on_chunk: tauri::ipc::Channel<CaptureChunk>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<CaptureStreamHandle, String> {
+  let capture = {
+    let store = capture_store.lock().unwrap();
+    match &id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))?
+  };
+  Ok(capture_stream::start(on_chunk, capture, chunk_size))
+}
 
-  let service = auth_service.lock().unwrap();
-  let can_use = service.can_use_model(&user_tier, &model);
+// ✏️ ANNOTATION COMMANDS
 
-  println!(
-    "✅ DEBUG: can_use_model result: {} (tier: '{}', model: '{}')",
-    can_use, user_tier, model
+// Composite annotations into a capture (latest when no id is given). The result is stored
+// as a new capture so it can be saved, copied or uploaded by id like any other.
+#[tauri::command]
+async fn annotate_capture(
+  id: Option<String>,
+  annotations: Vec<Annotation>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<StoredCapture, String> {
+  let capture = {
+    let store = capture_store.lock().unwrap();
+    match &id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))?
+  };
+
+  let source = capture.clone();
+  let image_data =
+    tauri::async_runtime::spawn_blocking(move || annotate::annotate(&source, &annotations))
+      .await
+      .map_err(|e| format!("Annotation task failed: {}", e))??;
+
+  let mut store = capture_store.lock().unwrap();
+  let annotated_id = store.add(
+    image_data,
+    capture.bounds,
+    capture.source_app,
+    capture.metadata,
   );
-  Ok(can_use)
+  store
+    .get(&annotated_id)
+    .cloned()
+    .ok_or_else(|| "Annotated capture was not stored".to_string())
 }
-//härrr
-// Test deep link functionality (for development)
+
+// Pixelate, blur or black out regions of a capture (latest when no id is given), locally,
+// before it is saved or sent anywhere. Regions are relative to the capture's top-left
+// corner in logical pixels. Stored as a new capture like annotate_capture.
 #[tauri::command]
-async fn test_deep_link(app: tauri::AppHandle, token: String, plan: String) -> Result<(), String> {
-  println!(
-    "🧪 Testing deep link with token: {} and plan: {}",
-    token, plan
+async fn pixelate_regions(
+  id: Option<String>,
+  regions: Vec<CaptureBounds>,
+  strength: Option<u32>,
+  style: Option<RedactionStyle>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<StoredCapture, String> {
+  let capture = {
+    let store = capture_store.lock().unwrap();
+    match &id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))?
+  };
+
+  let source = capture.clone();
+  let style = style.unwrap_or(RedactionStyle::Pixelate);
+  let image_data = tauri::async_runtime::spawn_blocking(move || {
+    annotate::obscure(&source, &regions, style, strength)
+  })
+  .await
+  .map_err(|e| format!("Pixelate task failed: {}", e))??;
+
+  let mut store = capture_store.lock().unwrap();
+  let obscured_id = store.add(
+    image_data,
+    capture.bounds,
+    capture.source_app,
+    capture.metadata,
   );
+  store
+    .get(&obscured_id)
+    .cloned()
+    .ok_or_else(|| "Pixelated capture was not stored".to_string())
+}
 
-  // Emit payment success event for testing
-  app
-    .emit(
-      "payment_success",
-      serde_json::json!({
-          "token": token,
-          "plan": plan
-      }),
-    )
-    .map_err(|e| format!("Failed to emit payment success: {}", e))?;
+// 📋 CLIPBOARD COMMANDS
 
-  println!("✅ Test deep link event emitted successfully");
-  Ok(())
+// Put a capture (latest when no id is given) on the clipboard as a real image
+#[tauri::command]
+async fn copy_capture_to_clipboard(
+  id: Option<String>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<AppResult, String> {
+  let capture = {
+    let store = capture_store.lock().unwrap();
+    match &id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id.unwrap_or_default()))?
+  };
+
+  // Decoding and converting large captures shouldn't block the async runtime
+  let (width, height) =
+    tauri::async_runtime::spawn_blocking(move || clipboard::copy_image(&capture.image_data))
+      .await
+      .map_err(|e| format!("Clipboard task failed: {}", e))??;
+
+  Ok(AppResult {
+    success: true,
+    message: format!("Copied {}x{} image to clipboard", width, height),
+  })
 }
 
-// Clear local user session (for troubleshooting)
+// 📥 IMAGE IMPORT COMMANDS
+
+// Largest image file accepted for import
+const MAX_IMPORT_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// An existing image run through the capture pipeline
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportedImage {
+  capture_id: String,
+  image_data: String,
+  width: u32,
+  height: u32,
+  ocr: Option<OCRResult>,
+  // Present when a prompt was given
+  analysis: Option<AnalysisResult>,
+}
+
+// OCR (and AI with `prompt`) on an image file, e.g. a screenshot saved earlier
 #[tauri::command]
-async fn clear_user_session(
-  auth_service: tauri::State<'_, SharedAuthService>,
-) -> Result<(), String> {
-  println!("🗑️ Clearing local user session...");
+async fn analyze_image_file(
+  app: tauri::AppHandle,
+  path: String,
+  prompt: Option<String>,
+  model: Option<String>,
+) -> Result<ImportedImage, String> {
+  let path = PathBuf::from(path);
+  let size = std::fs::metadata(&path)
+    .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?
+    .len();
+  if size > MAX_IMPORT_FILE_BYTES {
+    return Err(format!(
+      "Image is larger than {}MB",
+      MAX_IMPORT_FILE_BYTES / (1024 * 1024)
+    ));
+  }
+  let source = path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string());
+  let rgba = tauri::async_runtime::spawn_blocking(move || {
+    image::open(&path)
+      .map(|image| image.to_rgba8())
+      .map_err(|e| format!("Failed to open image {}: {}", path.display(), e))
+  })
+  .await
+  .map_err(|e| format!("Image import failed: {}", e))??;
+  import_image(&app, rgba, source, prompt, model).await
+}
 
-  let service = {
-    let guard = auth_service.lock().unwrap();
-    guard.clone()
-  };
+// OCR (and AI with `prompt`) on the image currently on the clipboard
+#[tauri::command]
+async fn analyze_clipboard_image(
+  app: tauri::AppHandle,
+  prompt: Option<String>,
+  model: Option<String>,
+) -> Result<ImportedImage, String> {
+  let rgba = tauri::async_runtime::spawn_blocking(clipboard::read_image)
+    .await
+    .map_err(|e| format!("Clipboard task failed: {}", e))??;
+  import_image(&app, rgba, Some("Clipboard".to_string()), prompt, model).await
+}
+
+// Store the image like a capture (store + history), OCR it and optionally ask the AI
+async fn import_image(
+  app: &tauri::AppHandle,
+  rgba: image::RgbaImage,
+  source: Option<String>,
+  prompt: Option<String>,
+  model: Option<String>,
+) -> Result<ImportedImage, String> {
+  let (width, height) = rgba.dimensions();
+  let image_data =
+    tauri::async_runtime::spawn_blocking(move || ScreenCapture::encode_rgba_to_base64(rgba))
+      .await
+      .map_err(|e| format!("Image encoding failed: {}", e))??;
+  let capture_id = app.state::<SharedCaptureStore>().lock().unwrap().add(
+    image_data.clone(),
+    None,
+    source.clone(),
+    None,
+  );
+  info!(
+    "📥 Imported {}x{} image from {} as {}",
+    width,
+    height,
+    source.as_deref().unwrap_or("unknown source"),
+    capture_id
+  );
+
+  let history = app.state::<SharedCaptureHistory>().inner().clone();
+  let (id, history_image) = (capture_id.clone(), image_data.clone());
+  let ocr = tauri::async_runtime::spawn_blocking(move || {
+    // OCR runs unlocked; the history is also read by the UI and the capture protocol
+    let recorded = history.lock().unwrap().record(&id, &history_image, None);
+    if let Err(e) = recorded {
+      warn!("⚠️ Failed to add imported image to history: {}", e);
+    }
+    let ocr = match run_ocr(&history_image) {
+      Ok(result) if result.has_text => Some(result),
+      Ok(_) => None,
+      Err(e) => {
+        warn!("⚠️ OCR on imported image failed: {}", e);
+        None
+      }
+    };
+    if let Some(result) = &ocr {
+      let _ = history
+        .lock()
+        .unwrap()
+        .set_ocr_text(&id, result.text.clone());
+    }
+    ocr
+  })
+  .await
+  .map_err(|e| format!("OCR task failed: {}", e))?;
+  if let Some(result) = &ocr {
+    app
+      .state::<SharedCaptureStore>()
+      .lock()
+      .unwrap()
+      .set_ocr_text(&capture_id, result.text.clone());
+  }
+
+  let analysis = match prompt {
+    Some(prompt) => Some(
+      run_analysis(
+        app,
+        Some(image_data.clone()),
+        prompt,
+        model,
+        None,
+        Some(capture_id.clone()),
+        true,
+      )
+      .await?,
+    ),
+    None => None,
+  };
+  Ok(ImportedImage {
+    capture_id,
+    image_data,
+    width,
+    height,
+    ocr,
+    analysis,
+  })
+}
+
+// A capture by id from memory or the history (latest in memory without an id)
+fn find_capture(app: &tauri::AppHandle, id: Option<&str>) -> Result<StoredCapture, String> {
+  let in_store = {
+    let store = app.state::<SharedCaptureStore>();
+    let store = store.lock().unwrap();
+    match id {
+      Some(id) => store.get(id),
+      None => store.latest(),
+    }
+    .cloned()
+  };
+  match (in_store, id) {
+    (Some(capture), _) => Ok(capture),
+    (None, Some(id)) => open_capture_from_history(
+      id.to_string(),
+      app.state::<SharedCaptureHistory>(),
+      app.state::<SharedCaptureStore>(),
+    ),
+    (None, None) => Err("No capture yet".to_string()),
+  }
+}
+
+// 📤 SHARE COMMANDS
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareResult {
+  url: String,
+  destination: DestinationKind,
+  copied_to_clipboard: bool,
+}
+
+#[tauri::command]
+fn list_share_destinations(
+  settings: tauri::State<'_, SharedSettings>,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<Vec<DestinationInfo>, String> {
+  let share = settings.lock().unwrap().settings().share.clone();
+  let store = api_keys.lock().unwrap();
+  DestinationKind::ALL
+    .into_iter()
+    .map(|kind| {
+      let has_secret = store.get_named(&kind.secret_name())?.is_some();
+      Ok(DestinationInfo {
+        id: kind,
+        name: kind.display_name().to_string(),
+        needs_secret: kind.needs_secret(),
+        has_secret,
+        configured: share.is_configured(kind) && (has_secret || !kind.needs_secret()),
+        is_default: share.default_destination == Some(kind),
+      })
+    })
+    .collect()
+}
+
+// S3 secret access key or custom endpoint token; kept out of settings.json
+#[tauri::command]
+fn set_share_secret(
+  destination: DestinationKind,
+  secret: String,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys
+    .lock()
+    .unwrap()
+    .set_named(&destination.secret_name(), &secret)?;
+  info!("🔑 {} credentials saved", destination.display_name());
+  Ok(())
+}
+
+#[tauri::command]
+fn remove_share_secret(
+  destination: DestinationKind,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys
+    .lock()
+    .unwrap()
+    .remove_named(&destination.secret_name())
+}
+
+// Upload a capture (latest without an id) and copy its link to the clipboard
+#[tauri::command]
+async fn share_capture(
+  app: tauri::AppHandle,
+  id: Option<String>,
+  destination: Option<DestinationKind>,
+) -> Result<ShareResult, String> {
+  let share = app
+    .state::<SharedSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .share
+    .clone();
+  let destination = destination
+    .or(share.default_destination)
+    .ok_or("Choose where to share the capture")?;
+  let capture = find_capture(&app, id.as_deref())?;
+  let secret = app
+    .state::<SharedApiKeys>()
+    .lock()
+    .unwrap()
+    .get_named(&destination.secret_name())?;
+  let network = app
+    .state::<SharedNetworkSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .clone();
+  let png = capture_store::decode_data_url(&capture.image_data)?;
+
+  info!(
+    "📤 Sharing {} to {}",
+    capture.id,
+    destination.display_name()
+  );
+  let url = share::upload(destination, &share, secret, network, png).await?;
+  let copied = match clipboard::copy_text(&url) {
+    Ok(()) => true,
+    Err(e) => {
+      warn!("⚠️ Share link not copied: {}", e);
+      false
+    }
+  };
+  info!("✅ Shared {} at {}", capture.id, url);
+  Ok(ShareResult {
+    url,
+    destination,
+    copied_to_clipboard: copied,
+  })
+}
+
+// 🗒️ NOTES COMMANDS
+
+// Notion integration token; kept out of settings.json
+#[tauri::command]
+fn set_notion_token(
+  token: String,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys
+    .lock()
+    .unwrap()
+    .set_named(notes::NOTION_SECRET, &token)?;
+  info!("🔑 Notion token saved");
+  Ok(())
+}
+
+#[tauri::command]
+fn remove_notion_token(api_keys: tauri::State<'_, SharedApiKeys>) -> Result<(), String> {
+  api_keys.lock().unwrap().remove_named(notes::NOTION_SECRET)
+}
+
+// Append a capture (latest without an id) with its OCR text and AI summary to the notes.
+// The summary is the last answer of `conversation_id`, or the answer about the capture in
+// a recent conversation.
+#[tauri::command]
+async fn send_to_notes(
+  app: tauri::AppHandle,
+  target: Option<NotesTarget>,
+  capture_id: Option<String>,
+  conversation_id: Option<String>,
+) -> Result<String, String> {
+  let (notes_settings, share_settings) = {
+    let settings = app.state::<SharedSettings>();
+    let settings = settings.lock().unwrap();
+    (
+      settings.settings().notes.clone(),
+      settings.settings().share.clone(),
+    )
+  };
+  let target = target
+    .or(notes_settings.default_target)
+    .ok_or("Choose where to send the capture")?;
+  let capture = find_capture(&app, capture_id.as_deref())?;
+  let ocr_text = capture_text(&app, &capture).await?;
+  let summary = capture_summary(&app, &capture.id, conversation_id.as_deref())?;
+  let png = capture_store::decode_data_url(&capture.image_data)?;
+  let network = app
+    .state::<SharedNetworkSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .clone();
+  let notion_token = app
+    .state::<SharedApiKeys>()
+    .lock()
+    .unwrap()
+    .get_named(notes::NOTION_SECRET)?;
+
+  let image_url = match (target, share_settings.default_destination) {
+    (NotesTarget::Notion, Some(destination)) if notes_settings.notion.share_images => {
+      let secret = app
+        .state::<SharedApiKeys>()
+        .lock()
+        .unwrap()
+        .get_named(&destination.secret_name())?;
+      match share::upload(
+        destination,
+        &share_settings,
+        secret,
+        network.clone(),
+        png.clone(),
+      )
+      .await
+      {
+        Ok(url) => Some(url),
+        Err(e) => {
+          warn!(
+            "⚠️ Capture not uploaded for Notion, sending text only: {}",
+            e
+          );
+          None
+        }
+      }
+    }
+    _ => None,
+  };
+
+  info!("🗒️ Sending {} to {}", capture.id, target.display_name());
+  notes::send(
+    target,
+    &notes_settings,
+    notion_token,
+    network,
+    NoteEntry {
+      capture_id: capture.id,
+      created_at: capture.created_at,
+      png,
+      ocr_text,
+      summary,
+      source_app: capture.source_app,
+      image_url,
+    },
+  )
+  .await
+}
+
+// Recent conversations searched for an answer about a capture
+const SUMMARY_SEARCH_CONVERSATIONS: usize = 20;
+
+fn capture_summary(
+  app: &tauri::AppHandle,
+  capture_id: &str,
+  conversation_id: Option<&str>,
+) -> Result<Option<String>, String> {
+  let history = app.state::<SharedConversationHistory>();
+  let history = history.lock().unwrap();
+  if let Some(id) = conversation_id {
+    let conversation = history
+      .get(id)?
+      .ok_or_else(|| format!("Conversation '{}' not found", id))?;
+    return Ok(
+      conversation
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == "assistant")
+        .map(|message| message.content.clone()),
+    );
+  }
+  for summary in history.list().iter().take(SUMMARY_SEARCH_CONVERSATIONS) {
+    let Some(conversation) = history.get(&summary.id)? else {
+      continue;
+    };
+    // The first answer after the question that attached the capture
+    let answer = conversation
+      .messages
+      .iter()
+      .skip_while(|message| message.capture_id.as_deref() != Some(capture_id))
+      .find(|message| message.role == "assistant");
+    if let Some(answer) = answer {
+      return Ok(Some(answer.content.clone()));
+    }
+  }
+  Ok(None)
+}
+
+// 💬 CHANNEL COMMANDS
+
+// Bot token of the workspace's FrameSense app; kept out of settings.json
+#[tauri::command]
+fn set_channel_token(
+  provider: ChannelProvider,
+  token: String,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys
+    .lock()
+    .unwrap()
+    .set_named(&provider.secret_name(), &token)?;
+  info!("🔑 {} token saved", provider.display_name());
+  Ok(())
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelConnectResult {
+  workspace: Option<String>,
+  channel: Option<String>,
+}
+
+// Install the provider's app through its browser OAuth flow and keep the token. The
+// channel picked during the install comes back for the settings form to save.
+#[tauri::command]
+async fn connect_channel(
+  app: tauri::AppHandle,
+  provider: ChannelProvider,
+) -> Result<ChannelConnectResult, String> {
+  let service = app
+    .state::<SharedAuthService>()
+    .inner()
+    .read()
+    .await
+    .clone();
+  let connection = channels::connect(provider, service.network_settings().clone(), |url| {
+    service.open_in_browser(url)
+  })
+  .await?;
+  app
+    .state::<SharedApiKeys>()
+    .lock()
+    .unwrap()
+    .set_named(&provider.secret_name(), &connection.token)?;
+  info!("🔑 {} connected", provider.display_name());
+  Ok(ChannelConnectResult {
+    workspace: connection.workspace,
+    channel: connection.channel,
+  })
+}
+
+#[tauri::command]
+fn remove_channel_token(
+  provider: ChannelProvider,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys
+    .lock()
+    .unwrap()
+    .remove_named(&provider.secret_name())
+}
+
+// Post a capture (with its annotations burned in) and its OCR text to a channel
+#[tauri::command]
+async fn post_to_channel(
+  app: tauri::AppHandle,
+  capture_id: Option<String>,
+  message: Option<String>,
+  provider: Option<ChannelProvider>,
+  channel: Option<String>,
+) -> Result<(), String> {
+  let provider = provider.unwrap_or(ChannelProvider::Slack);
+  let settings = app
+    .state::<SharedSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .channels
+    .clone();
+  let capture = find_capture(&app, capture_id.as_deref())?;
+  let ocr_text = capture_text(&app, &capture).await?;
+  let token = app
+    .state::<SharedApiKeys>()
+    .lock()
+    .unwrap()
+    .get_named(&provider.secret_name())?;
+  let network = app
+    .state::<SharedNetworkSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .clone();
+
+  info!("💬 Posting {} to {}", capture.id, provider.display_name());
+  channels::post(
+    provider,
+    &settings,
+    channel,
+    token,
+    network,
+    ChannelPost {
+      png: capture_store::decode_data_url(&capture.image_data)?,
+      capture_id: capture.id,
+      message,
+      ocr_text,
+    },
+  )
+  .await
+}
+
+// 🖨️ PRINT COMMANDS
+
+// Print a capture (optionally with its OCR text) through the native print dialog
+#[tauri::command]
+fn print_capture(
+  app: tauri::AppHandle,
+  id: String,
+  options: Option<PrintOptions>,
+  capture_store: tauri::State<'_, SharedCaptureStore>,
+) -> Result<(), String> {
+  let capture = capture_store
+    .lock()
+    .unwrap()
+    .get(&id)
+    .cloned()
+    .ok_or_else(|| format!("Capture not found: {}", id))?;
+  print::print_capture(&app, &capture, &options.unwrap_or_default())
+}
+
+// 🕶️ REDACTION ZONE COMMANDS
+
+#[tauri::command]
+fn list_redaction_zones() -> Result<Vec<RedactionZone>, String> {
+  Ok(redaction::list_zones())
+}
+
+// Add a persistent zone that is blurred/pixelated/filled in every capture of its display
+#[tauri::command]
+fn add_redaction_zone(
+  zone: RedactionZone,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<RedactionZone, String> {
+  let zone = redaction::add_zone(zone)?;
+  // Cached captures were taken without this zone
+  cache.lock().unwrap().clear_cache();
+  info!(
+    "🕶️ Added redaction zone '{}' on display {}",
+    zone.name, zone.display_id
+  );
+  Ok(zone)
+}
+
+#[tauri::command]
+fn update_redaction_zone(
+  zone: RedactionZone,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  redaction::update_zone(zone)?;
+  cache.lock().unwrap().clear_cache();
+  Ok(())
+}
+
+#[tauri::command]
+fn remove_redaction_zone(
+  id: String,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  redaction::remove_zone(&id)?;
+  cache.lock().unwrap().clear_cache();
+  Ok(())
+}
+
+// ⏱️ DELAYED CAPTURE COMMANDS
+
+// Wait before capturing so menus/tooltips can be opened first, emitting a countdown
+async fn run_delayed_capture(
+  app: &tauri::AppHandle,
+  bounds: CaptureBounds,
+  seconds: u64,
+) -> Result<(String, Duration), String> {
+  info!(
+    "⏱️ Delayed capture in {}s: {}x{} at ({}, {})",
+    seconds, bounds.width, bounds.height, bounds.x, bounds.y
+  );
+
+  for remaining in (1..=seconds).rev() {
+    let _ = app.emit(
+      "capture-countdown",
+      serde_json::json!({ "remaining": remaining }),
+    );
+    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+  }
+  let _ = app.emit("capture-countdown", serde_json::json!({ "remaining": 0 }));
+
+  // Only the capture itself counts towards the duration, not the countdown
+  let started = Instant::now();
+  let cache = app.state::<SharedScreenshotCache>();
+  let mut screenshot_cache = cache.lock().unwrap();
+  let image_data = screenshot_cache.capture_fresh(bounds)?;
+  Ok((image_data, started.elapsed()))
+}
+
+// Capture an area after a delay (max 60s)
+#[tauri::command]
+async fn capture_with_delay(
+  app: tauri::AppHandle,
+  bounds: CaptureBounds,
+  seconds: u64,
+) -> Result<CaptureResult, String> {
+  if seconds > 60 {
+    return Err("Capture delay must be 60 seconds or less".to_string());
+  }
+
+  match run_delayed_capture(&app, bounds.clone(), seconds).await {
+    Ok((image_data, duration)) => Ok(CaptureResult {
+      success: true,
+      message: format!("Delayed capture ({}s) successful!", seconds),
+      physical_bounds: ScreenCapture::physical_bounds(&bounds),
+      metadata: Some(CaptureMetadata::collect(Some(&bounds), duration, None)),
+      bounds: Some(bounds),
+      image_data: Some(image_data),
+    }),
+    Err(e) => Ok(CaptureResult {
+      success: false,
+      message: e,
+      bounds: None,
+      physical_bounds: None,
+      image_data: None,
+      metadata: None,
+    }),
+  }
+}
+
+// Tray-triggered delayed capture: re-uses the last selected area, or the whole desktop
+fn start_tray_delayed_capture(app: &tauri::AppHandle, seconds: u64) {
+  let last_bounds = app
+    .try_state::<SharedState>()
+    .and_then(|state| state.lock().unwrap().last_bounds.clone());
+
+  let bounds = match last_bounds {
+    Some(bounds) => bounds,
+    None => match ScreenCapture::get_total_screen_area() {
+      Ok(area) => CaptureBounds {
+        x: 0,
+        y: 0,
+        width: area.width,
+        height: area.height,
+      },
+      Err(e) => {
+        error!("❌ Delayed capture failed to get screen area: {}", e);
+        return;
+      }
+    },
+  };
+
+  let app_clone = app.clone();
+  tauri::async_runtime::spawn(async move {
+    match run_delayed_capture(&app_clone, bounds.clone(), seconds).await {
+      Ok((image_data, duration)) => {
+        deliver_capture_to_main_window(&app_clone, &bounds, image_data, duration).await;
+      }
+      Err(e) => error!("❌ Delayed capture failed: {}", e),
+    }
+  });
+}
+
+// 🎨 COLOR PICKER COMMANDS
+
+// Color at a point in overlay coordinates, or under the cursor when no point is given
+#[tauri::command]
+async fn pick_color(x: Option<i32>, y: Option<i32>) -> Result<PickedColor, String> {
+  tauri::async_runtime::spawn_blocking(move || match (x, y) {
+    (Some(x), Some(y)) => color_picker::pick_color_at(x, y),
+    _ => color_picker::pick_color_under_cursor(),
+  })
+  .await
+  .map_err(|e| format!("Color picker task failed: {}", e))?
+}
+
+// Stream color-picker-update events while the overlay is open
+#[tauri::command]
+fn start_color_picker(app: tauri::AppHandle) -> Result<(), String> {
+  if color_picker::is_active() {
+    return Ok(());
+  }
+  color_picker::set_active(true);
+  info!("🎨 Color picker started");
+
+  let overlay_app = app.clone();
+  tauri::async_runtime::spawn(color_picker::run_live_updates(app, move || {
+    overlay_app
+      .state::<SharedOverlayManager>()
+      .lock()
+      .unwrap()
+      .is_overlay_active()
+  }));
+  Ok(())
+}
+
+#[tauri::command]
+fn stop_color_picker() -> Result<(), String> {
+  color_picker::set_active(false);
+  Ok(())
+}
+
+// 🔍 MAGNIFIER COMMANDS
+
+// Zoomed patch around (x, y) in overlay coordinates for the selection loupe. Uses the
+// frozen snapshot when freeze-screen mode is active, otherwise the live screen.
+#[tauri::command]
+async fn get_magnifier_patch(
+  x: i32,
+  y: i32,
+  zoom: Option<u32>,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<MagnifierPatch, String> {
+  let frozen = cache
+    .lock()
+    .unwrap()
+    .frozen_patch(x, y, color_picker::MAGNIFIER_RADIUS);
+  let patch = match frozen {
+    Some(patch) => patch,
+    None => tauri::async_runtime::spawn_blocking(move || {
+      color_picker::live_patch(x, y, color_picker::MAGNIFIER_RADIUS)
+    })
+    .await
+    .map_err(|e| e.to_string())??,
+  };
+  color_picker::magnifier_patch(&patch, zoom)
+}
+
+// 📏 MEASUREMENT COMMANDS
+
+// Measure the current selection and emit measure-result (called by the overlay as it changes)
+#[tauri::command]
+fn measure_selection(
+  app: tauri::AppHandle,
+  bounds: CaptureBounds,
+  markers: Option<Vec<MeasurePoint>>,
+) -> Result<MeasureResult, String> {
+  let result = measure::measure(&bounds, &markers.unwrap_or_default())?;
+  let _ = app.emit("measure-result", &result);
+  Ok(result)
+}
+
+// 🔁 REPEAT LAST CAPTURE
+
+// Re-grab the last captured area without showing the overlay
+async fn run_recapture_last_region(
+  app: &tauri::AppHandle,
+) -> Result<(String, CaptureBounds, CaptureMetadata), String> {
+  let bounds = app
+    .try_state::<SharedState>()
+    .and_then(|state| state.lock().unwrap().last_bounds.clone())
+    .ok_or("No previous capture region to repeat")?;
+
+  info!(
+    "🔁 Re-capturing last region: {}x{} at ({}, {})",
+    bounds.width, bounds.height, bounds.x, bounds.y
+  );
+  let started = Instant::now();
+  let image_data = {
+    let cache = app.state::<SharedScreenshotCache>();
+    let mut screenshot_cache = cache.lock().unwrap();
+    screenshot_cache.capture_fresh(bounds.clone())?
+  };
+  let metadata =
+    deliver_capture_to_main_window(app, &bounds, image_data.clone(), started.elapsed()).await;
+  Ok((image_data, bounds, metadata))
+}
+
+#[tauri::command]
+async fn recapture_last_region(app: tauri::AppHandle) -> Result<CaptureResult, String> {
+  match run_recapture_last_region(&app).await {
+    Ok((image_data, bounds, metadata)) => Ok(CaptureResult {
+      success: true,
+      message: "Last region captured again".to_string(),
+      physical_bounds: ScreenCapture::physical_bounds(&bounds),
+      metadata: Some(metadata),
+      bounds: Some(bounds),
+      image_data: Some(image_data),
+    }),
+    Err(e) => Ok(CaptureResult {
+      success: false,
+      message: e,
+      bounds: None,
+      physical_bounds: None,
+      image_data: None,
+      metadata: None,
+    }),
+  }
+}
+
+// 👀 WATCH MODE COMMANDS
+
+// Capture a region periodically and emit region-changed when it changes; returns the watch id
+#[tauri::command]
+fn start_region_watch(
+  app: tauri::AppHandle,
+  options: WatchOptions,
+  watcher: tauri::State<'_, SharedRegionWatcher>,
+) -> Result<String, String> {
+  watcher.lock().unwrap().start(app, options)
+}
+
+// Stop one watch, or all of them when no id is given
+#[tauri::command]
+fn stop_region_watch(
+  id: Option<String>,
+  watcher: tauri::State<'_, SharedRegionWatcher>,
+) -> Result<(), String> {
+  let mut watcher = watcher.lock().unwrap();
+  match id {
+    Some(id) => watcher.stop(&id),
+    None => {
+      watcher.stop_all();
+      Ok(())
+    }
+  }
+}
+
+#[tauri::command]
+fn list_region_watches(
+  watcher: tauri::State<'_, SharedRegionWatcher>,
+) -> Result<Vec<WatchInfo>, String> {
+  Ok(watcher.lock().unwrap().list())
+}
+
+// 📌 REGION PRESET COMMANDS
+
+// Capture a preset's region and deliver it like a normal selection
+async fn run_preset_capture(
+  app: &tauri::AppHandle,
+  name: &str,
+) -> Result<(String, CaptureBounds, CaptureMetadata), String> {
+  let preset = app
+    .state::<SharedPresetStore>()
+    .lock()
+    .unwrap()
+    .get(name)
+    .cloned()
+    .ok_or_else(|| format!("Region preset '{}' not found", name))?;
+
+  info!(
+    "📌 Capturing preset '{}': {}x{} at ({}, {})",
+    preset.name, preset.bounds.width, preset.bounds.height, preset.bounds.x, preset.bounds.y
+  );
+  let started = Instant::now();
+  let image_data = {
+    let cache = app.state::<SharedScreenshotCache>();
+    let mut screenshot_cache = cache.lock().unwrap();
+    screenshot_cache.capture_fresh(preset.bounds.clone())?
+  };
+  let metadata =
+    deliver_capture_to_main_window(app, &preset.bounds, image_data.clone(), started.elapsed())
+      .await;
+  Ok((image_data, preset.bounds, metadata))
+}
+
+fn register_preset_shortcut(app: &tauri::AppHandle, preset: &RegionPreset) {
+  if let Some(shortcut) = preset.parsed_shortcut() {
+    match app.global_shortcut().register(shortcut) {
+      Ok(_) => info!("✅ Preset shortcut registered for '{}'", preset.name),
+      Err(e) => error!(
+        "❌ Failed to register shortcut for '{}': {}",
+        preset.name, e
+      ),
+    }
+  }
+}
+
+fn unregister_preset_shortcut(app: &tauri::AppHandle, preset: &RegionPreset) {
+  if let Some(shortcut) = preset.parsed_shortcut() {
+    let _ = app.global_shortcut().unregister(shortcut);
+  }
+}
+
+// Save (or replace) a named region, optionally bound to a global shortcut
+#[tauri::command]
+fn save_region_preset(
+  app: tauri::AppHandle,
+  name: String,
+  bounds: CaptureBounds,
+  shortcut: Option<String>,
+  preset_store: tauri::State<'_, SharedPresetStore>,
+  settings: tauri::State<'_, SharedSettings>,
+) -> Result<RegionPreset, String> {
+  let preset = RegionPreset {
+    name,
+    bounds,
+    shortcut,
+  };
+  let mut reserved: Vec<Shortcut> = settings
+    .lock()
+    .unwrap()
+    .settings()
+    .shortcuts
+    .parsed()
+    .into_iter()
+    .map(|(_, shortcut)| shortcut)
+    .collect();
+  reserved.push(escape_shortcut());
+  let replaced = preset_store
+    .lock()
+    .unwrap()
+    .save(preset.clone(), &reserved)?;
+  if let Some(replaced) = replaced {
+    unregister_preset_shortcut(&app, &replaced);
+  }
+  register_preset_shortcut(&app, &preset);
+  info!("📌 Region preset '{}' saved", preset.name);
+  Ok(preset)
+}
+
+#[tauri::command]
+fn list_region_presets(
+  preset_store: tauri::State<'_, SharedPresetStore>,
+) -> Result<Vec<RegionPreset>, String> {
+  Ok(preset_store.lock().unwrap().list().to_vec())
+}
+
+#[tauri::command]
+fn delete_region_preset(
+  app: tauri::AppHandle,
+  name: String,
+  preset_store: tauri::State<'_, SharedPresetStore>,
+) -> Result<(), String> {
+  let removed = preset_store.lock().unwrap().remove(&name)?;
+  unregister_preset_shortcut(&app, &removed);
+  Ok(())
+}
+
+// Capture a saved region by name, e.g. capture_preset("standup-board")
+#[tauri::command]
+async fn capture_preset(app: tauri::AppHandle, name: String) -> Result<CaptureResult, String> {
+  match run_preset_capture(&app, &name).await {
+    Ok((image_data, bounds, metadata)) => Ok(CaptureResult {
+      success: true,
+      message: format!("Preset '{}' captured", name),
+      physical_bounds: ScreenCapture::physical_bounds(&bounds),
+      metadata: Some(metadata),
+      bounds: Some(bounds),
+      image_data: Some(image_data),
+    }),
+    Err(e) => Ok(CaptureResult {
+      success: false,
+      message: e,
+      bounds: None,
+      physical_bounds: None,
+      image_data: None,
+      metadata: None,
+    }),
+  }
+}
+
+// 📉 CAPTURE SIZE COMMANDS
+
+// Downscale captures larger than this before encoding (None = no limit)
+#[tauri::command]
+fn set_capture_max_dimensions(
+  max_width: Option<u32>,
+  max_height: Option<u32>,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  if max_width == Some(0) || max_height == Some(0) {
+    return Err("Max capture dimensions must be greater than zero".to_string());
+  }
+  cache.lock().unwrap().set_max_dimensions(MaxDimensions {
+    max_width,
+    max_height,
+  });
+  Ok(())
+}
+
+#[tauri::command]
+fn get_capture_max_dimensions(
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<MaxDimensions, String> {
+  Ok(cache.lock().unwrap().max_dimensions())
+}
+
+// 🧊 FREEZE-SCREEN COMMANDS
+
+// When enabled, Alt+C snapshots the screen first and the selection is made on that image
+#[tauri::command]
+fn set_freeze_mode(
+  enabled: bool,
+  cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  cache.lock().unwrap().set_freeze_mode(enabled);
+  Ok(())
+}
+
+#[tauri::command]
+fn get_freeze_mode(cache: tauri::State<'_, SharedScreenshotCache>) -> Result<bool, String> {
+  Ok(cache.lock().unwrap().freeze_mode())
+}
+
+// Frozen background for an overlay that missed the overlay-frozen-frame event
+#[tauri::command]
+fn get_frozen_frame(
+  window: tauri::WebviewWindow,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<FrozenFrameView, String> {
+  Ok(
+    overlay_manager
+      .lock()
+      .unwrap()
+      .frozen_frame_view(window.label()),
+  )
+}
+
+// 🖥️ MONITOR COMMANDS
+
+// List connected monitors with names, geometry and scale
+#[tauri::command]
+async fn list_monitors() -> Result<Vec<ScreenInfo>, String> {
+  ScreenCapture::get_screen_info()
+}
+
+// Capture one whole monitor by id
+#[tauri::command]
+async fn capture_monitor(monitor_id: u32) -> Result<CaptureResult, String> {
+  let started = Instant::now();
+  match ScreenCapture::capture_monitor(monitor_id) {
+    Ok((image_data, bounds)) => Ok(CaptureResult {
+      success: true,
+      message: format!("Monitor {} captured", monitor_id),
+      physical_bounds: ScreenCapture::physical_bounds(&bounds),
+      metadata: Some(CaptureMetadata::collect(
+        Some(&bounds),
+        started.elapsed(),
+        None,
+      )),
+      bounds: Some(bounds),
+      image_data: Some(image_data),
+    }),
+    Err(e) => Ok(CaptureResult {
+      success: false,
+      message: e,
+      bounds: None,
+      physical_bounds: None,
+      image_data: None,
+      metadata: None,
+    }),
+  }
+}
+
+// Convert logical overlay bounds to physical pixels of the display they are on
+#[tauri::command]
+async fn map_bounds_to_physical(bounds: CaptureBounds) -> Result<PhysicalBounds, String> {
+  Ok(CoordinateMapper::current()?.to_physical(&bounds))
+}
+
+// Convert physical display pixels back to logical overlay bounds
+#[tauri::command]
+async fn map_bounds_to_logical(physical_bounds: PhysicalBounds) -> Result<CaptureBounds, String> {
+  CoordinateMapper::current()?.to_logical(&physical_bounds)
+}
+
+// Which capture backend is in use; on Wayland that is xdg-desktop-portal
+#[tauri::command]
+async fn get_capture_backend() -> Result<overlay::wayland_portal::PortalStatus, String> {
+  Ok(overlay::wayland_portal::status())
+}
+
+// Trigger the portal's screenshot permission dialog ahead of the first capture
+#[tauri::command]
+async fn request_screen_capture_permission() -> Result<bool, String> {
+  if !ScreenCapture::uses_portal() {
+    return Ok(true);
+  }
+  tauri::async_runtime::spawn_blocking(|| overlay::wayland_portal::take_screenshot(false))
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|_| {
+      info!("🐧 Screenshot portal permission granted");
+      true
+    })
+}
+
+// Tray-triggered monitor capture, delivered to the main window like a selection
+fn start_tray_monitor_capture(app: &tauri::AppHandle, monitor_id: u32) {
+  let app_clone = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let started = Instant::now();
+    match ScreenCapture::capture_monitor(monitor_id) {
+      Ok((image_data, bounds)) => {
+        deliver_capture_to_main_window(&app_clone, &bounds, image_data, started.elapsed()).await;
+      }
+      Err(e) => error!("❌ Monitor capture failed: {}", e),
+    }
+  });
+}
+
+// 📜 SCROLLING CAPTURE COMMANDS
+
+// Start capturing a region repeatedly while it scrolls, stitching frames into one tall image
+#[tauri::command]
+async fn start_scroll_capture(
+  app: tauri::AppHandle,
+  options: ScrollCaptureOptions,
+  scroll_capture: tauri::State<'_, SharedScrollCapture>,
+) -> Result<(), String> {
+  {
+    let mut manager = scroll_capture.lock().unwrap();
+    manager.start(options.clone())?;
+  }
+
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let bounds = options.bounds.clone();
+      let frame = match tauri::async_runtime::spawn_blocking(move || {
+        ScreenCapture::capture_area_rgba(&bounds)
+      })
+      .await
+      {
+        Ok(Ok(frame)) => frame,
+        Ok(Err(e)) => {
+          error!("❌ Scroll capture frame failed: {}", e);
+          break;
+        }
+        Err(e) => {
+          error!("❌ Scroll capture task failed: {}", e);
+          break;
+        }
+      };
+
+      let progress = {
+        let state = app.state::<SharedScrollCapture>();
+        let mut manager = state.lock().unwrap();
+        if !manager.is_active() {
+          // Stopped (and finished) by stop_scroll_capture
+          return;
+        }
+        manager.add_frame(frame)
+      };
+
+      match progress {
+        Ok(progress) => {
+          let _ = app.emit("scroll-capture-progress", &progress);
+          if progress.finished {
+            break;
+          }
+        }
+        Err(e) => {
+          error!("❌ Scroll capture stitching failed: {}", e);
+          break;
+        }
+      }
+
+      if options.auto_scroll {
+        if let Err(e) = overlay::scroll_capture::synthesize_scroll(options.scroll_amount) {
+          error!("❌ Failed to synthesize scroll: {}", e);
+          break;
+        }
+      }
+      tokio::time::sleep(tokio::time::Duration::from_millis(
+        options.interval_ms.max(50),
+      ))
+      .await;
+    }
+
+    // Session ended by itself (end of page, frame limit or error)
+    let result = {
+      let state = app.state::<SharedScrollCapture>();
+      let mut manager = state.lock().unwrap();
+      if !manager.is_active() {
+        return;
+      }
+      manager.finish()
+    };
+    let payload = match result {
+      Ok(image_data) => serde_json::json!({ "success": true, "imageData": image_data }),
+      Err(e) => serde_json::json!({ "success": false, "message": e }),
+    };
+    let _ = app.emit("scroll-capture-complete", payload);
+  });
+
+  Ok(())
+}
+
+// Stop the running scroll capture and return the stitched image
+#[tauri::command]
+fn stop_scroll_capture(
+  scroll_capture: tauri::State<'_, SharedScrollCapture>,
+) -> Result<CaptureResult, String> {
+  let started = Instant::now();
+  let (bounds, finished) = {
+    let mut manager = scroll_capture.lock().unwrap();
+    let bounds = manager.options().map(|o| o.bounds);
+    manager.request_stop();
+    (bounds, manager.finish())
+  };
+  match finished {
+    Ok(image_data) => Ok(CaptureResult {
+      success: true,
+      message: "Scroll capture completed".to_string(),
+      physical_bounds: bounds.as_ref().and_then(ScreenCapture::physical_bounds),
+      metadata: Some(CaptureMetadata::collect(
+        bounds.as_ref(),
+        started.elapsed(),
+        None,
+      )),
+      bounds,
+      image_data: Some(image_data),
+    }),
+    Err(e) => Ok(CaptureResult {
+      success: false,
+      message: e,
+      bounds: None,
+      physical_bounds: None,
+      image_data: None,
+      metadata: None,
+    }),
+  }
+}
+
+// 💬 CONVERSATION PARAMETER COMMANDS
+
+// Set model/temperature/system prompt used for every message in a conversation
+#[tauri::command]
+fn set_conversation_params(
+  conversation_id: String,
+  params: ConversationParams,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<(), String> {
+  history
+    .lock()
+    .unwrap()
+    .set_params(&conversation_id, params)?;
+  info!(
+    "💬 Updated AI parameters for conversation {}",
+    conversation_id
+  );
+  Ok(())
+}
+
+#[tauri::command]
+fn get_conversation_params(
+  conversation_id: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<ConversationParams, String> {
+  history.lock().unwrap().params(&conversation_id)
+}
+
+#[tauri::command]
+fn clear_conversation_params(
+  conversation_id: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<(), String> {
+  history
+    .lock()
+    .unwrap()
+    .set_params(&conversation_id, ConversationParams::default())
+}
+
+// Resolve the effective AI parameters for one message (per-message overrides win)
+#[tauri::command]
+fn resolve_message_params(
+  conversation_id: Option<String>,
+  overrides: Option<ConversationParams>,
+  default_model: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<ResolvedAiParams, String> {
+  history.lock().unwrap().resolve_params(
+    conversation_id.as_deref(),
+    &overrides.unwrap_or_default(),
+    &default_model,
+  )
+}
+
+// 🎬 SCREEN RECORDING COMMANDS
+
+// Start recording a region (or the primary screen) to MP4
+#[tauri::command]
+fn start_recording(
+  app: tauri::AppHandle,
+  options: RecordingOptions,
+  recorder: tauri::State<'_, SharedScreenRecorder>,
+) -> Result<RecordingStatus, String> {
+  let default_dir = dirs::video_dir()
+    .or_else(dirs::home_dir)
+    .unwrap_or_else(|| PathBuf::from("/tmp"))
+    .join("FrameSense");
+  let mut screen_recorder = recorder.lock().unwrap();
+  let status = screen_recorder.start(app.clone(), options, default_dir)?;
+  let _ = app.emit("recording-status", &status);
+  Ok(status)
+}
+
+#[tauri::command]
+fn pause_recording(
+  app: tauri::AppHandle,
+  recorder: tauri::State<'_, SharedScreenRecorder>,
+) -> Result<RecordingStatus, String> {
+  let status = recorder.lock().unwrap().set_paused(true)?;
+  let _ = app.emit("recording-status", &status);
+  Ok(status)
+}
+
+#[tauri::command]
+fn resume_recording(
+  app: tauri::AppHandle,
+  recorder: tauri::State<'_, SharedScreenRecorder>,
+) -> Result<RecordingStatus, String> {
+  let status = recorder.lock().unwrap().set_paused(false)?;
+  let _ = app.emit("recording-status", &status);
+  Ok(status)
+}
+
+// Stop recording and wait for ffmpeg to finalize the file
+#[tauri::command]
+async fn stop_recording(
+  recorder: tauri::State<'_, SharedScreenRecorder>,
+) -> Result<RecordingStatus, String> {
+  let handle = recorder.lock().unwrap().stop()?;
+  tauri::async_runtime::spawn_blocking(move || {
+    handle
+      .join()
+      .map_err(|_| "Recording thread panicked".to_string())?
+  })
+  .await
+  .map_err(|e| format!("Failed to wait for recording: {}", e))?
+}
+
+#[tauri::command]
+fn get_recording_status(
+  recorder: tauri::State<'_, SharedScreenRecorder>,
+) -> Result<Option<RecordingStatus>, String> {
+  Ok(recorder.lock().unwrap().status())
+}
+
+// Record a short animated GIF (default) or WebP of a region
+#[tauri::command]
+async fn record_gif(
+  bounds: CaptureBounds,
+  duration: f64,
+  fps: u32,
+  format: Option<AnimatedFormat>,
+) -> Result<AnimatedCapture, String> {
+  let format = format.unwrap_or(AnimatedFormat::Gif);
+  tauri::async_runtime::spawn_blocking(move || {
+    recording::animated::record_animated(&bounds, duration, fps, format)
+  })
+  .await
+  .map_err(|e| format!("Animated capture task failed: {}", e))?
+}
+
+// ♿ ACCESSIBILITY COMMANDS
+
+// Announce a message through the platform screen reader (e.g. "AI answer ready")
+#[tauri::command]
+fn announce_accessibility(
+  app: tauri::AppHandle,
+  message: String,
+  priority: Option<AnnouncementPriority>,
+) -> Result<(), String> {
+  accessibility::announce(
+    &app,
+    &message,
+    priority.unwrap_or(AnnouncementPriority::Medium),
+  );
+  Ok(())
+}
+
+// Turn screen reader announcements on or off
+#[tauri::command]
+fn set_accessibility_announcements(enabled: bool) -> Result<(), String> {
+  accessibility::set_enabled(enabled);
+  Ok(())
+}
+
+#[tauri::command]
+fn get_accessibility_announcements() -> Result<bool, String> {
+  Ok(accessibility::is_enabled())
+}
+
+// 🔊 TEXT-TO-SPEECH COMMANDS
+
+// Read text aloud with the platform synthesizer; rate 1.0 is normal speed
+#[tauri::command]
+fn speak_text(
+  app: tauri::AppHandle,
+  text: String,
+  voice: Option<String>,
+  rate: Option<f32>,
+  player: tauri::State<'_, SharedSpeechPlayer>,
+) -> Result<SpeechStatus, String> {
+  let status = player
+    .lock()
+    .unwrap()
+    .speak(app.clone(), &text, voice.as_deref(), rate)?;
+  let _ = app.emit("speech-status", &status);
+  Ok(status)
+}
+
+#[tauri::command]
+fn pause_speech(
+  app: tauri::AppHandle,
+  player: tauri::State<'_, SharedSpeechPlayer>,
+) -> Result<SpeechStatus, String> {
+  let status = player.lock().unwrap().set_paused(true)?;
+  let _ = app.emit("speech-status", &status);
+  Ok(status)
+}
+
+#[tauri::command]
+fn resume_speech(
+  app: tauri::AppHandle,
+  player: tauri::State<'_, SharedSpeechPlayer>,
+) -> Result<SpeechStatus, String> {
+  let status = player.lock().unwrap().set_paused(false)?;
+  let _ = app.emit("speech-status", &status);
+  Ok(status)
+}
+
+#[tauri::command]
+fn stop_speech(
+  app: tauri::AppHandle,
+  player: tauri::State<'_, SharedSpeechPlayer>,
+) -> Result<Option<SpeechStatus>, String> {
+  let status = player.lock().unwrap().stop();
+  if let Some(status) = &status {
+    let _ = app.emit("speech-status", status);
+  }
+  Ok(status)
+}
+
+#[tauri::command]
+fn get_speech_status(
+  player: tauri::State<'_, SharedSpeechPlayer>,
+) -> Result<Option<SpeechStatus>, String> {
+  Ok(player.lock().unwrap().status())
+}
+
+// 💤 IDLE DETECTION COMMANDS
+
+// Get current system idle time and whether deferred work is allowed to run
+#[tauri::command]
+fn get_idle_status(
+  idle_monitor: tauri::State<'_, SharedIdleMonitor>,
+) -> Result<IdleStatus, String> {
+  let monitor = idle_monitor.lock().unwrap();
+  Ok(monitor.status())
+}
+
+// Configure how long the user must be idle before background work runs
+#[tauri::command]
+fn set_idle_threshold(
+  minutes: u64,
+  idle_monitor: tauri::State<'_, SharedIdleMonitor>,
+) -> Result<(), String> {
+  if minutes == 0 {
+    return Err("Idle threshold must be at least 1 minute".to_string());
+  }
+  if minutes > idle::MAX_IDLE_THRESHOLD_MINUTES {
+    return Err(format!(
+      "Idle threshold can be at most {} minutes",
+      idle::MAX_IDLE_THRESHOLD_MINUTES
+    ));
+  }
+  let mut monitor = idle_monitor.lock().unwrap();
+  monitor.set_threshold(std::time::Duration::from_secs(minutes * 60));
+  Ok(())
+}
+
+// Heavy maintenance that should only happen while the user is away
+// Every recurring job the app runs; intervals and on/off can be overridden in settings
+fn register_background_tasks(scheduler: &mut Scheduler) {
+  scheduler.register(
+    "idle-work",
+    "Run deferred cleanup while the user is away",
+    idle::IDLE_POLL_INTERVAL,
+    false,
+    |app| async move {
+      let should_run = app
+        .state::<SharedIdleMonitor>()
+        .lock()
+        .unwrap()
+        .should_run_deferred_work();
+      if should_run {
+        run_deferred_background_work(&app);
+      }
+    },
+  );
+  scheduler.register(
+    "tier-check",
+    "Notice plan changes made outside the app",
+    auth::TIER_CHECK_INTERVAL,
+    false,
+    |app| async move { check_tier_change(&app).await },
+  );
+  scheduler.register(
+    "model-matrix",
+    "Refresh the tier to model matrix when it is stale",
+    Duration::from_secs(60 * 60),
+    true,
+    |app| async move { refresh_model_matrix_if_stale(&app).await },
+  );
+  scheduler.register(
+    "session-refresh",
+    "Keep the session token fresh and send queued usage",
+    auth::SESSION_CHECK_INTERVAL,
+    true,
+    |app| async move {
+      keep_session_fresh(&app).await;
+      let service = app
+        .state::<SharedAuthService>()
+        .inner()
+        .read()
+        .await
+        .clone();
+      let _ = service.flush_usage_queue().await;
+    },
+  );
+  scheduler.register(
+    "retention",
+    "Purge local data older than the retention setting",
+    Duration::from_secs(24 * 60 * 60),
+    true,
+    |app| async move {
+      let days = app
+        .state::<SharedSettings>()
+        .lock()
+        .unwrap()
+        .settings()
+        .retention
+        .days;
+      if let Some(days) = days {
+        match purge_older_than(&app, days, &PurgeTarget::ALL) {
+          Ok(summary) if summary.total() > 0 => {
+            info!(
+              "🧹 Retention purged {} item(s) older than {} days",
+              summary.total(),
+              days
+            )
+          }
+          Ok(_) => {}
+          Err(e) => warn!("⚠️ Retention purge failed: {}", e),
+        }
+      }
+    },
+  );
+  scheduler.register(
+    "overlay-cleanup",
+    "Destroy pooled overlay windows that have been idle too long",
+    overlay::overlay_manager::IDLE_CHECK_INTERVAL,
+    false,
+    |app| async move {
+      app
+        .state::<SharedOverlayManager>()
+        .lock()
+        .unwrap()
+        .cleanup_if_old();
+    },
+  );
+}
+
+fn run_deferred_background_work(app: &tauri::AppHandle) {
+  if let Some(cache) = app.try_state::<SharedScreenshotCache>() {
+    let mut screenshot_cache = cache.lock().unwrap();
+    screenshot_cache.cleanup_expired();
+  }
+  info!("✅ Deferred background work completed");
+}
+
+// 🚀 AUTHENTICATION COMMANDS
+//härr
+// Login user with credentials
+#[tauri::command]
+async fn login_user(
+  email: String,
+  password: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<LoginResult, String> {
+  let service = auth_service.read().await.clone();
+  service.login_user(email, password).await
+}
+
+// Use the app without an account (device-limited quota)
+#[tauri::command]
+async fn start_guest_session(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<User, String> {
+  auth_service.read().await.start_guest_session()
+}
+
+#[tauri::command]
+async fn end_guest_session(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  auth_service.read().await.end_guest_session()
+}
+
+// Enterprise tier from a signed offline license (air-gapped installs)
+#[tauri::command]
+async fn activate_license(
+  key: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<User, String> {
+  auth_service.read().await.activate_license(&key)
+}
+
+#[tauri::command]
+async fn deactivate_license(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  auth_service.read().await.deactivate_license()
+}
+
+// Second step of a login that returned twoFactorRequired
+#[tauri::command]
+async fn submit_totp_code(
+  code: String,
+  trust_device: bool,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<User, String> {
+  let service = auth_service.read().await.clone();
+  service.submit_totp_code(code, trust_device).await
+}
+
+#[tauri::command]
+async fn forget_trusted_device(
+  email: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  auth_service.read().await.forget_trusted_device(&email)
+}
+
+// Create an account and sign in
+#[tauri::command]
+async fn register_user(
+  email: String,
+  password: String,
+  name: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<User, String> {
+  let service = auth_service.read().await.clone();
+  service.register_user(email, password, name).await
+}
+
+// Sign in with Google/GitHub through the browser
+#[tauri::command]
+async fn login_with_provider(
+  provider: OAuthProvider,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<User, String> {
+  // The browser step can take minutes; work on a snapshot so settings changes that
+  // need the write lock are not held up meanwhile
+  let service = auth_service.read().await.clone();
+  service.login_with_provider(provider).await
+}
+
+// Refresh the access token shortly before it expires. `auth:session-expired` is only
+// emitted once the token has run out and could not be refreshed.
+async fn keep_session_fresh(app: &tauri::AppHandle) {
+  let service = app
+    .state::<SharedAuthService>()
+    .inner()
+    .read()
+    .await
+    .clone();
+  let user = match service.load_user_session().await {
+    Ok(Some(user)) => user,
+    _ => return,
+  };
+  // Tokens without an `exp` claim live until the backend rejects them
+  let Some(expires_at) = user.token_expires_at() else {
+    return;
+  };
+  let now = chrono::Utc::now().timestamp();
+  if expires_at - now > auth::REFRESH_MARGIN_SECS {
+    return;
+  }
+
+  // Only an explicit rejection ends the session; network trouble keeps it for the next
+  // check (an expired token then just fails requests until a refresh goes through)
+  match service.refresh_session().await {
+    Ok(_) => {}
+    Err(auth::RefreshError::Rejected(e)) => {
+      info!("🔒 Session refresh rejected: {}", e);
+      if let Err(e) = service.expire_session(&user.email).await {
+        warn!("⚠️ Failed to clear expired session: {}", e);
+      }
+    }
+    Err(auth::RefreshError::Failed(e)) => {
+      warn!(
+        "⚠️ Session refresh failed, retrying on the next check: {}",
+        e
+      );
+    }
+  }
+}
+
+// Fetch the tier -> model matrix unless the cached one is still fresh
+async fn refresh_model_matrix_if_stale(app: &tauri::AppHandle) {
+  let auth_service = app.state::<SharedAuthService>().inner().clone();
+  let fetched = {
+    let service = auth_service.read().await.clone();
+    if service.model_matrix_is_fresh() {
+      return;
+    }
+    service.fetch_model_matrix().await
+  };
+  match fetched {
+    Ok(matrix) => {
+      let result = auth_service.write().await.set_model_matrix(matrix);
+      if let Err(e) = result {
+        warn!("⚠️ Failed to cache model matrix: {}", e);
+      }
+      let _ = app.emit("model-matrix-updated", ());
+    }
+    Err(e) => warn!("⚠️ Using cached/built-in model list: {}", e),
+  }
+}
+
+// Re-verify the session with the backend; tier changes are announced by the service
+async fn check_tier_change(app: &tauri::AppHandle) {
+  let service = app
+    .state::<SharedAuthService>()
+    .inner()
+    .read()
+    .await
+    .clone();
+  if let Err(e) = service.verify_payment_and_update().await {
+    warn!("⚠️ Background session verification failed: {}", e);
+  }
+}
+
+// Logout current user
+#[tauri::command]
+async fn logout_user(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<auth::LogoutResult, String> {
+  let service = auth_service.read().await.clone();
+  service.logout_user().await
+}
+
+// Get current logged in user
+#[tauri::command]
+async fn get_current_user(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<Option<User>, String> {
+  let service = auth_service.read().await.clone();
+  service.get_current_user().await
+}
+
+// Save user session to storage
+#[tauri::command]
+async fn save_user_session(
+  user: User,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  let service = auth_service.read().await.clone();
+  service.save_user_session(&user).await
+}
+
+// Load user session from storage
+#[tauri::command]
+async fn load_user_session(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<Option<User>, String> {
+  let service = auth_service.read().await.clone();
+  service.load_user_session().await
+}
+/*
+}
+*/
+// Get available models for user tier
+#[tauri::command]
+async fn get_available_models(
+  user_tier: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<Vec<String>, String> {
+  info!(
+    "🔍 DEBUG: get_available_models called for tier: {}",
+    user_tier
+  );
+
+  let service = auth_service.read().await;
+  let models = service.get_available_models(&user_tier);
+
+  info!(
+    "✅ DEBUG: get_available_models returning {} models: {:?}",
+    models.len(),
+    models
+  );
+  Ok(models)
+}
+
+// Check if user can use specific model
+#[tauri::command]
+async fn can_use_model(
+  user_tier: String,
+  model: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<bool, String> {
+  info!(
+    "🔍 DEBUG: can_use_model called - tier: '{}', model: '{}'",
+    user_tier, model
+  );
+
+  let service = auth_service.read().await;
+  let can_use = service.can_use_model(&user_tier, &model);
+
+  info!(
+    "✅ DEBUG: can_use_model result: {} (tier: '{}', model: '{}')",
+    can_use, user_tier, model
+  );
+  Ok(can_use)
+}
+// Count an AI request against the daily quota; `quota-exceeded` tells the UI to offer
+// an upgrade
+#[tauri::command]
+async fn record_usage(
+  app: tauri::AppHandle,
+  model: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<UsageStatus, String> {
+  let service = auth_service.read().await.clone();
+  match service.record_usage(&model).await {
+    Ok(status) => {
+      if status.exceeded {
+        let _ = app.emit("quota-exceeded", &status);
+      }
+      Ok(status)
+    }
+    Err(e) => {
+      if let Ok(status) = service.get_usage_status().await {
+        if status.exceeded {
+          let _ = app.emit("quota-exceeded", &status);
+        }
+      }
+      Err(e)
+    }
+  }
+}
+
+#[tauri::command]
+async fn get_usage_status(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<UsageStatus, String> {
+  let service = auth_service.read().await.clone();
+  service.get_usage_status().await
+}
+// Open Stripe checkout for a plan in the browser
+#[tauri::command]
+async fn start_checkout(
+  plan: String,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  let service = auth_service.read().await.clone();
+  let url = service.create_checkout_url(&plan).await?;
+  info!("💳 Opening checkout for {} plan", plan);
+  service.open_in_browser(&url)
+}
+
+// Open the Stripe customer portal in the browser
+#[tauri::command]
+async fn open_billing_portal(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  let service = auth_service.read().await.clone();
+  let url = service.create_billing_portal_url().await?;
+  info!("💳 Opening billing portal");
+  service.open_in_browser(&url)
+}
+//härrr
+// ⚙️ SETTINGS COMMANDS
+
+#[tauri::command]
+fn get_settings(settings: tauri::State<'_, SharedSettings>) -> Result<AppSettings, String> {
+  Ok(settings.lock().unwrap().settings().clone())
+}
+
+// Validate settings, move the global shortcuts over and save, then apply them and
+// broadcast settings-changed to the frontend. Shortcuts are registered before saving so
+// one taken by another app fails the update instead of leaving it unbound.
+#[tauri::command]
+fn update_settings(
+  app: tauri::AppHandle,
+  settings: AppSettings,
+  store: tauri::State<'_, SharedSettings>,
+) -> Result<AppSettings, String> {
+  settings.validate()?;
+  let preset_store = app.state::<SharedPresetStore>();
+  for (action, shortcut) in settings.shortcuts.parsed() {
+    if let Some(preset) = preset_store.lock().unwrap().find_by_shortcut(&shortcut) {
+      return Err(format!(
+        "The {:?} shortcut is already used by region preset '{}'",
+        action, preset.name
+      ));
+    }
+  }
+  let mut store = store.lock().unwrap();
+  let previous_shortcuts = store.settings().shortcuts.clone();
+  let new_shortcuts = settings.shortcuts.clone();
+  let rebind = previous_shortcuts != new_shortcuts;
+  if rebind {
+    rebind_shortcuts(&app, &previous_shortcuts, &new_shortcuts)?;
+  }
+  let change = match store.update(settings) {
+    Ok(change) => change,
+    Err(e) => {
+      if rebind {
+        let _ = rebind_shortcuts(&app, &new_shortcuts, &previous_shortcuts);
+      }
+      return Err(e);
+    }
+  };
+  drop(store);
+  info!("⚙️ Settings updated");
+  apply_settings(&app, Some(&change.previous), &change.settings);
+  let _ = app.emit(settings::SETTINGS_CHANGED_EVENT, &change);
+  Ok(change.settings)
+}
+
+// Register every binding that can be; returns the failures
+fn register_shortcuts(app: &tauri::AppHandle, shortcuts: &ShortcutSettings) -> Vec<String> {
+  let mut failures = Vec::new();
+  for (action, accelerator) in shortcuts.bindings() {
+    let registered = accelerator
+      .parse::<Shortcut>()
+      .map_err(|e| e.to_string())
+      .and_then(|shortcut| {
+        app
+          .global_shortcut()
+          .register(shortcut)
+          .map_err(|e| e.to_string())
+      });
+    match registered {
+      Ok(_) => info!(
+        "✅ Global shortcut {} registered for {:?}",
+        accelerator, action
+      ),
+      Err(e) => failures.push(format!("{}: {}", accelerator, e)),
+    }
+  }
+  failures
+}
+
+fn unregister_shortcuts(app: &tauri::AppHandle, shortcuts: &ShortcutSettings) {
+  for (_, shortcut) in shortcuts.parsed() {
+    let _ = app.global_shortcut().unregister(shortcut);
+  }
+}
+
+// Swap `from` for `to`; if any of `to` can't be registered, `from` is restored
+fn rebind_shortcuts(
+  app: &tauri::AppHandle,
+  from: &ShortcutSettings,
+  to: &ShortcutSettings,
+) -> Result<(), String> {
+  unregister_shortcuts(app, from);
+  let failures = register_shortcuts(app, to);
+  if failures.is_empty() {
+    return Ok(());
+  }
+  unregister_shortcuts(app, to);
+  for failure in register_shortcuts(app, from) {
+    error!("❌ Failed to restore shortcut {}", failure);
+  }
+  Err(format!(
+    "Failed to register shortcut {}",
+    failures.join(", ")
+  ))
+}
+
+// Push settings into the subsystems that use them. `previous` is None at startup, when
+// the shortcuts are registered too; later changes to them go through update_settings.
+fn apply_settings(app: &tauri::AppHandle, previous: Option<&AppSettings>, settings: &AppSettings) {
+  logging::set_levels(&settings.logging);
+  let auth_service = app.state::<SharedAuthService>().inner().clone();
+  let api_url = settings.api_url.clone();
+  tauri::async_runtime::spawn(async move {
+    auth_service.write().await.set_api_url(&api_url);
+  });
+
+  if let Err(e) = app
+    .state::<SharedScreenshotCache>()
+    .lock()
+    .unwrap()
+    .set_config(CacheConfig {
+      ttl_secs: settings.screenshot_cache.ttl_secs,
+      max_size_mb: settings.screenshot_cache.max_size_mb,
+    })
+  {
+    warn!("⚠️ Failed to apply cache settings: {}", e);
+  }
+  ScreenCapture::set_png_compression(settings.capture.png_compression);
+
+  let idle_timeout = settings
+    .overlay
+    .idle_timeout_secs
+    .filter(|&secs| secs > 0)
+    .map(Duration::from_secs);
+  app
+    .state::<SharedOverlayManager>()
+    .lock()
+    .unwrap()
+    .set_idle_timeout(idle_timeout);
+
+  app
+    .state::<SharedScheduler>()
+    .lock()
+    .unwrap()
+    .configure(&settings.background_tasks);
+
+  if previous.is_some_and(|p| !p.crash_reports.upload) && settings.crash_reports.upload {
+    upload_crash_reports_if_allowed(app);
+  }
+
+  if previous.is_none() {
+    for failure in register_shortcuts(app, &settings.shortcuts) {
+      error!("❌ Failed to register {}", failure);
+    }
+  }
+}
+
+// 📝 LOGGING COMMANDS
+
+// Latest log lines (newest last), e.g. to attach to a bug report
+#[tauri::command]
+fn get_recent_logs(
+  limit: Option<usize>,
+  min_level: Option<String>,
+) -> Result<Vec<LogEntry>, String> {
+  logging::recent(limit.unwrap_or(500), min_level.as_deref())
+}
+
+// Set the global log level, or one module's ("auth", "overlay::screenshot_cache", ...).
+// Saved in settings so it survives restarts.
+#[tauri::command]
+fn set_log_level(
+  app: tauri::AppHandle,
+  level: String,
+  module: Option<String>,
+  store: tauri::State<'_, SharedSettings>,
+) -> Result<AppSettings, String> {
+  let mut settings = store.lock().unwrap().settings().clone();
+  let level = level.trim().to_lowercase();
+  match module
+    .map(|m| m.trim().to_string())
+    .filter(|m| !m.is_empty())
+  {
+    Some(module) => {
+      settings.logging.modules.insert(module, level);
+    }
+    None => settings.logging.level = level,
+  }
+  update_settings(app, settings, store)
+}
+
+// 🩺 DIAGNOSTICS COMMANDS
+
+// Health report for the "Troubleshoot" screen
+#[tauri::command]
+async fn run_diagnostics(app: tauri::AppHandle) -> Result<DiagnosticsReport, String> {
+  info!("🩺 Running diagnostics...");
+  let shortcut_settings = app
+    .state::<SharedSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .shortcuts
+    .clone();
+  let shortcuts: Vec<(String, String, bool)> = shortcut_settings
+    .bindings()
+    .into_iter()
+    .map(|(action, accelerator)| {
+      let registered = accelerator
+        .parse::<Shortcut>()
+        .map(|shortcut| app.global_shortcut().is_registered(shortcut))
+        .unwrap_or(false);
+      (format!("{:?}", action), accelerator.to_string(), registered)
+    })
+    .collect();
+  let (api_url, network) = {
+    let service = app.state::<SharedAuthService>().inner().read().await;
+    (
+      service.api_url().to_string(),
+      service.network_settings().clone(),
+    )
+  };
+  let data_dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
+
+  // Display enumeration and df can block; keep them off the async workers
+  let local_checks = tauri::async_runtime::spawn_blocking(move || {
+    vec![
+      diagnostics::check_screen_recording(),
+      diagnostics::check_displays(),
+      diagnostics::check_ocr(),
+      diagnostics::check_disk_space(&data_dir),
+    ]
+  })
+  .await
+  .map_err(|e| format!("Diagnostics failed: {}", e))?;
+
+  let mut checks = local_checks;
+  checks.insert(2, diagnostics::check_shortcuts(&shortcuts));
+  checks.push(diagnostics::check_backend(&api_url, &network).await);
+  let report = DiagnosticsReport::new(checks);
+  info!("🩺 Diagnostics finished: {:?}", report.status);
+  Ok(report)
+}
+
+// 🧹 DATA RETENTION COMMANDS
+
+fn purge_older_than(
+  app: &tauri::AppHandle,
+  days: u32,
+  targets: &[PurgeTarget],
+) -> Result<PurgeSummary, String> {
+  if days == 0 {
+    return Err("Retention must be at least 1 day".to_string());
+  }
+  let cutoff = retention::cutoff(days);
+  let mut summary = PurgeSummary::default();
+  for target in targets {
+    match target {
+      PurgeTarget::Captures => {
+        summary.captures = app
+          .state::<SharedCaptureHistory>()
+          .lock()
+          .unwrap()
+          .purge_older_than(cutoff)?;
+      }
+      PurgeTarget::OcrText => {
+        summary.ocr_texts = app
+          .state::<SharedCaptureHistory>()
+          .lock()
+          .unwrap()
+          .clear_ocr_text_older_than(cutoff)?;
+      }
+      PurgeTarget::Conversations => {
+        summary.conversations = app
+          .state::<SharedConversationHistory>()
+          .lock()
+          .unwrap()
+          .purge_older_than(cutoff)?;
+      }
+      PurgeTarget::Logs => {
+        summary.log_files = logging::purge_older_than(cutoff.into());
+        summary.crash_reports = crash_reports::delete_older_than(cutoff).unwrap_or(0);
+      }
+    }
+  }
+  Ok(summary)
+}
+
+// Delete captures, OCR text, conversations and logs older than `older_than_days`
+// (all of them unless `targets` narrows it down)
+#[tauri::command]
+fn purge_local_data(
+  app: tauri::AppHandle,
+  older_than_days: u32,
+  targets: Option<Vec<PurgeTarget>>,
+) -> Result<PurgeSummary, String> {
+  let targets = targets.unwrap_or_else(|| PurgeTarget::ALL.to_vec());
+  let summary = purge_older_than(&app, older_than_days, &targets)?;
+  info!(
+    "🧹 Purged {} item(s) older than {} days",
+    summary.total(),
+    older_than_days
+  );
+  Ok(summary)
+}
+
+// Sign out, forget stored secrets, wipe ~/.framesense and the app data dir, then restart
+// into a fresh install
+#[tauri::command]
+async fn delete_all_local_data(app: tauri::AppHandle) -> Result<(), String> {
+  warn!("🧹 Deleting all local data...");
+  {
+    let service = app
+      .state::<SharedAuthService>()
+      .inner()
+      .read()
+      .await
+      .clone();
+    if let Err(e) = service.logout_user().await {
+      warn!("⚠️ Logout before wipe failed: {}", e);
+    }
+    service.forget_all_trusted_devices()?;
+    service.deactivate_license()?;
+  }
+  {
+    let api_keys = app.state::<SharedApiKeys>();
+    let store = api_keys.lock().unwrap();
+    for kind in ProviderKind::ALL {
+      store.remove(kind)?;
+    }
+    for destination in DestinationKind::ALL {
+      store.remove_named(&destination.secret_name())?;
+    }
+    store.remove_named(notes::NOTION_SECRET)?;
+    for provider in ChannelProvider::ALL {
+      store.remove_named(&provider.secret_name())?;
+    }
+  }
+  app
+    .state::<SharedScreenshotCache>()
+    .lock()
+    .unwrap()
+    .clear_cache();
+  app.state::<SharedResponseCache>().lock().unwrap().clear();
+  logging::discard();
+  drag::clear(&app);
+
+  let mut folders = Vec::new();
+  if let Some(home) = dirs::home_dir() {
+    folders.push(home.join(".framesense"));
+  }
+  if let Ok(data_dir) = app.path().app_data_dir() {
+    folders.push(data_dir);
+  }
+  retention::wipe_dirs(&folders)?;
+  app.restart();
+}
+
+// ⏱️ BACKGROUND TASK COMMANDS
+
+#[tauri::command]
+fn list_background_tasks(
+  scheduler: tauri::State<'_, SharedScheduler>,
+) -> Result<Vec<TaskInfo>, String> {
+  Ok(scheduler.lock().unwrap().list())
+}
+
+// Turn a task on/off or change its interval; saved in settings like any other setting
+#[tauri::command]
+fn set_background_task(
+  app: tauri::AppHandle,
+  name: String,
+  enabled: Option<bool>,
+  interval_secs: Option<u64>,
+  scheduler: tauri::State<'_, SharedScheduler>,
+  store: tauri::State<'_, SharedSettings>,
+) -> Result<AppSettings, String> {
+  if !scheduler.lock().unwrap().contains(&name) {
+    return Err(format!("Unknown background task '{}'", name));
+  }
+  let mut settings = store.lock().unwrap().settings().clone();
+  let task = settings.background_tasks.entry(name).or_default();
+  if enabled.is_some() {
+    task.enabled = enabled;
+  }
+  if interval_secs.is_some() {
+    task.interval_secs = interval_secs;
+  }
+  update_settings(app, settings, store)
+}
+
+#[tauri::command]
+fn run_background_task(
+  name: String,
+  scheduler: tauri::State<'_, SharedScheduler>,
+) -> Result<(), String> {
+  scheduler.lock().unwrap().run_now(&name)
+}
+
+// 🔁 LAUNCH AT LOGIN COMMANDS
+
+// Tray checkbox mirroring the launch-at-login state
+struct LaunchAtLoginMenuItem(tauri::menu::CheckMenuItem<tauri::Wry>);
+
+#[tauri::command]
+fn set_launch_at_login(app: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
+  autostart::set_enabled(enabled)?;
+  if let Some(item) = app.try_state::<LaunchAtLoginMenuItem>() {
+    let _ = item.0.set_checked(enabled);
+  }
+  Ok(enabled)
+}
+
+#[tauri::command]
+fn get_launch_at_login() -> Result<bool, String> {
+  Ok(autostart::is_enabled())
+}
+
+// 💥 CRASH REPORT COMMANDS
+
+// Saved crash and background error reports, newest first (for the support flow)
+#[tauri::command]
+fn get_crash_reports() -> Result<Vec<CrashReport>, String> {
+  crash_reports::list()
+}
+
+#[tauri::command]
+fn clear_crash_reports() -> Result<usize, String> {
+  crash_reports::delete_all()
+}
+
+// Upload pending reports in the background, if the user has agreed to it
+fn upload_crash_reports_if_allowed(app: &tauri::AppHandle) {
+  let allowed = app
+    .state::<SharedSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .crash_reports
+    .upload;
+  if !allowed {
+    return;
+  }
+  let auth_service = app.state::<SharedAuthService>().inner().clone();
+  tauri::async_runtime::spawn(async move {
+    let (api_url, network) = {
+      let service = auth_service.read().await;
+      (
+        service.api_url().to_string(),
+        service.network_settings().clone(),
+      )
+    };
+    if let Err(e) = crash_reports::upload_pending(&api_url, &network).await {
+      warn!("⚠️ {}", e);
+    }
+  });
+}
+
+// 🌐 NETWORK SETTINGS COMMANDS
+
+#[tauri::command]
+fn get_network_settings(
+  network_settings: tauri::State<'_, SharedNetworkSettings>,
+) -> Result<NetworkSettings, String> {
+  Ok(network_settings.lock().unwrap().settings().clone())
+}
+
+// Validates the proxy URL and certificates before saving; applies to the next request
+#[tauri::command]
+async fn set_network_settings(
+  settings: NetworkSettings,
+  network_settings: tauri::State<'_, SharedNetworkSettings>,
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  network_settings.lock().unwrap().update(settings.clone())?;
+  auth_service.write().await.set_network_settings(settings);
+  info!("🌐 Network settings updated");
+  Ok(())
+}
+
+// 🧩 PLUGIN COMMANDS
+
+#[tauri::command]
+fn list_plugins(
+  plugin_registry: tauri::State<'_, SharedPluginRegistry>,
+) -> Result<Vec<PluginInfo>, String> {
+  Ok(plugin_registry.lock().unwrap().list())
+}
+
+// Rescan the plugins folder after plugins were added or removed
+#[tauri::command]
+fn reload_plugins(
+  plugin_registry: tauri::State<'_, SharedPluginRegistry>,
+) -> Result<Vec<PluginInfo>, String> {
+  let mut registry = plugin_registry.lock().unwrap();
+  registry.reload();
+  Ok(registry.list())
+}
+
+// Run a plugin on a stored capture (by id) or on image data / text passed directly
+#[tauri::command]
+async fn run_plugin(
+  app: tauri::AppHandle,
+  plugin_id: String,
+  capture_id: Option<String>,
+  image_data: Option<String>,
+  ocr_text: Option<String>,
+) -> Result<ProcessorOutput, String> {
+  let plugin = app
+    .state::<SharedPluginRegistry>()
+    .lock()
+    .unwrap()
+    .get(&plugin_id)
+    .cloned()
+    .ok_or_else(|| format!("Unknown plugin '{}'", plugin_id))?;
+  let capture = match &capture_id {
+    Some(id) => Some(find_capture(&app, Some(id))?),
+    None => None,
+  };
+  let image_data = image_data.or_else(|| capture.as_ref().map(|c| c.image_data.clone()));
+  let ocr_text = ocr_text.or_else(|| capture.as_ref().and_then(|c| c.ocr_text.clone()));
+  if image_data.is_none() && ocr_text.is_none() {
+    return Err("Nothing for the plugin to process".to_string());
+  }
+  let input = ProcessorInput {
+    protocol_version: plugins::PROTOCOL_VERSION,
+    capture_id,
+    image_png_base64: image_data.map(|data| {
+      data
+        .split_once(',')
+        .map_or(data.clone(), |(_, b)| b.to_string())
+    }),
+    ocr_text,
+    source_app: capture.and_then(|c| c.source_app),
+  };
+  info!("🧩 Running plugin '{}'", plugin_id);
+  plugins::run(&plugin, &input).await
+}
+
+// 🤖 AI ANALYSIS COMMANDS
+
+// Capture -> answer without the frontend making the request. The answer text is emitted
+// as `ai-analysis-chunk` while it streams; the full result is returned at the end.
+// Provider models (`openai:gpt-4o`, `ollama:llava`, ...) use the user's own key or local
+// server and need no account. Question and answer are saved to the conversation
+// (a new one without `conversation_id`; its id comes back in the result).
+#[tauri::command]
+async fn analyze_capture(
+  app: tauri::AppHandle,
+  image_data: Option<String>,
+  prompt: String,
+  model: Option<String>,
+  conversation_id: Option<String>,
+  capture_id: Option<String>,
+) -> Result<AnalysisResult, String> {
+  run_analysis(
+    &app,
+    image_data,
+    prompt,
+    model,
+    conversation_id,
+    capture_id,
+    true,
+  )
+  .await
+}
+
+// `save` false is for one-off requests (translations) that aren't conversations
+async fn run_analysis(
+  app: &tauri::AppHandle,
+  image_data: Option<String>,
+  prompt: String,
+  model: Option<String>,
+  conversation_id: Option<String>,
+  capture_id: Option<String>,
+  save: bool,
+) -> Result<AnalysisResult, String> {
+  // Analysis can run for a while; a snapshot keeps the lock free for settings changes
+  let service = app
+    .state::<SharedAuthService>()
+    .inner()
+    .read()
+    .await
+    .clone();
+  let user = service.load_user_session().await?;
+
+  let default_model = user
+    .as_ref()
+    .and_then(|user| service.get_available_models(&user.tier).into_iter().next())
+    .unwrap_or_default();
+  let params = app
+    .state::<SharedConversationHistory>()
+    .lock()
+    .unwrap()
+    .resolve_params(
+      conversation_id.as_deref(),
+      &ConversationParams {
+        model,
+        ..Default::default()
+      },
+      &default_model,
+    )?;
+  let system_prompt = app
+    .state::<SharedAiPreferences>()
+    .lock()
+    .unwrap()
+    .preferences()
+    .apply(params.system_prompt);
+  let mut request = AnalysisRequest {
+    image_data,
+    prompt,
+    model: params.model,
+    temperature: params.temperature,
+    system_prompt,
+    conversation_id,
+  };
+
+  // Access is checked before the cache so a downgraded tier can't keep reading answers
+  check_model_access(app, &service, user.as_ref(), &request.model).await?;
+
+  // Same capture, question and model in the same conversation a moment ago: answer
+  // again for free
+  let cached = app
+    .state::<SharedResponseCache>()
+    .lock()
+    .unwrap()
+    .lookup(&request);
+  let (mut result, answer) = match cached {
+    Some(mut result) => {
+      info!("♻️ Answering {} from the response cache", request.model);
+      let _ = app.emit(
+        "ai-analysis-chunk",
+        serde_json::json!({
+            "conversationId": request.conversation_id,
+            "text": result.content
+        }),
+      );
+      result.cached = true;
+      let answer = ConversationMessage::assistant(&result.content, &result.model);
+      (result, answer)
+    }
+    None => analyze_uncached(app, &service, user.as_ref(), &mut request).await?,
+  };
+  if !save {
+    return Ok(result);
+  }
+
+  let saved = app
+    .state::<SharedConversationHistory>()
+    .lock()
+    .unwrap()
+    .append(
+      request.conversation_id.as_deref(),
+      vec![
+        ConversationMessage::user(&request.prompt, capture_id),
+        answer,
+      ],
+    );
+  match saved {
+    Ok(id) => result.conversation_id = Some(id),
+    Err(e) => warn!("⚠️ Failed to save conversation: {}", e),
+  }
+  if let Some(id) = result.conversation_id.clone() {
+    notify_completion(
+      app,
+      "Answer ready",
+      &notifications::summarize(&result.content),
+      DeepLink::OpenConversation { id },
+    );
+  }
+  Ok(result)
+}
+
+// Ask the model (falling back to others when configured), cache the answer and record
+// its spend; returns the result and the assistant message to save
+async fn analyze_uncached(
+  app: &tauri::AppHandle,
+  service: &AuthService,
+  user: Option<&User>,
+  request: &mut AnalysisRequest,
+) -> Result<(AnalysisResult, ConversationMessage), String> {
+  let fallbacks = {
+    let available = user
+      .map(|user| service.get_available_models(&user.tier))
+      .unwrap_or_default();
+    app
+      .state::<SharedFallbackStore>()
+      .lock()
+      .unwrap()
+      .settings()
+      .candidates(&request.model, &available)
+  };
+
+  let requested_model = request.model.clone();
+  let mut attempt = analyze_with_model(app, service, user, request).await;
+  let mut first_error = None;
+  for fallback_model in fallbacks {
+    // Only outages move on to another model; a rejected key, exhausted quota or bad
+    // input would fail there too (and could spend the user's money doing so)
+    let error = match &attempt {
+      Err(error) if error.is_unavailable() => error.to_string(),
+      _ => break,
+    };
+    if check_model_access(app, service, user, &fallback_model)
+      .await
+      .is_err()
+    {
+      continue;
+    }
+    info!(
+      "↪️ {} failed ({}), falling back to {}",
+      request.model, error, fallback_model
+    );
+    // Lets the UI drop text streamed by the failed attempt
+    let _ = app.emit(
+      "ai-analysis-fallback",
+      serde_json::json!({
+          "conversationId": request.conversation_id,
+          "from": request.model,
+          "to": fallback_model,
+          "error": error
+      }),
+    );
+    first_error.get_or_insert_with(|| error.clone());
+    request.model = fallback_model;
+    attempt = analyze_with_model(app, service, user, request).await;
+  }
+  let mut result = attempt?;
+  if result.model != requested_model {
+    result.requested_model = Some(requested_model);
+    result.fallback_error = first_error;
+  } else {
+    app
+      .state::<SharedResponseCache>()
+      .lock()
+      .unwrap()
+      .store(request, &result);
+  }
+
+  // Answers without usage numbers are costed from the estimate
+  let (input_tokens, output_tokens) = result.token_counts();
+  let input_tokens = input_tokens.unwrap_or_else(|| {
+    ai::cost::estimate_tokens(&request.prompt)
+      + request
+        .image_data
+        .as_deref()
+        .and_then(ai::image_dimensions)
+        .map_or(0, |dims| ai::cost::image_tokens(&result.model, dims))
+  });
+  let output_tokens = output_tokens.unwrap_or_else(|| ai::cost::estimate_tokens(&result.content));
+  let cost_usd = ai::cost::cost_of(&result.model, input_tokens, output_tokens);
+  if let Some(cost_usd) = cost_usd {
+    let recorded = app
+      .state::<SharedSpendTracker>()
+      .lock()
+      .unwrap()
+      .record(cost_usd);
+    if let Err(e) = recorded {
+      warn!("⚠️ Failed to record spend: {}", e);
+    }
+  }
+  let answer = ConversationMessage {
+    input_tokens: Some(input_tokens),
+    output_tokens: Some(output_tokens),
+    cost_usd,
+    ..ConversationMessage::assistant(&result.content, &result.model)
+  };
+  Ok((result, answer))
+}
+
+// OCR the capture and translate the text to `target_lang` (a language name or code)
+#[tauri::command]
+async fn translate_capture(
+  app: tauri::AppHandle,
+  image_data: String,
+  target_lang: String,
+  model: Option<String>,
+) -> Result<TranslationResult, String> {
+  let target_lang = ai::translate::validate_target_lang(&target_lang)?.to_string();
+  let ocr_image = image_data.clone();
+  let ocr_text = tauri::async_runtime::spawn_blocking(move || run_ocr(&ocr_image))
+    .await
+    .ok()
+    .and_then(|result| result.ok())
+    .filter(|result| result.has_text && !result.text.trim().is_empty())
+    .map(|result| result.text);
+
+  match ocr_text {
+    Some(text) => {
+      let prompt = ai::translate::text_prompt(&text, &target_lang);
+      let result = run_analysis(&app, None, prompt, model, None, None, false).await?;
+      Ok(TranslationResult {
+        original_text: text,
+        translated_text: result.content.trim().to_string(),
+        target_lang,
+        model: result.model,
+        from_ocr: true,
+      })
+    }
+    None => {
+      let prompt = ai::translate::image_prompt(&target_lang);
+      let result = run_analysis(&app, Some(image_data), prompt, model, None, None, false).await?;
+      let (original_text, translated_text) = ai::translate::parse_image_answer(&result.content);
+      Ok(TranslationResult {
+        original_text,
+        translated_text,
+        target_lang,
+        model: result.model,
+        from_ocr: false,
+      })
+    }
+  }
+}
+
+// Whether `model` may be used right now: provider models need their key, backend models
+// a signed-in user with the model in their plan and quota left. Every request also takes
+// from the tier's rate limit bucket; signed-out requests share the guest bucket.
+async fn check_model_access(
+  app: &tauri::AppHandle,
+  service: &AuthService,
+  user: Option<&User>,
+  model: &str,
+) -> Result<(), String> {
+  if let Some((kind, _)) = ai::split_model(model) {
+    if kind.needs_api_key()
+      && app
+        .state::<SharedApiKeys>()
+        .lock()
+        .unwrap()
+        .get(kind)?
+        .is_none()
+    {
+      return Err(format!("Add your {} API key first", kind.display_name()));
+    }
+  } else {
+    let user = user.ok_or("Sign in to analyze captures".to_string())?;
+    if !service.can_use_model(&user.tier, model) {
+      return Err(format!(
+        "{} isn't available on the {} plan",
+        model, user.tier
+      ));
+    }
+    if service.get_usage_status().await?.exceeded {
+      return Err("Daily usage limit reached".to_string());
+    }
+  }
+  let tier = user.map_or(auth::guest::GUEST_TIER, |user| user.tier.as_str());
+  let limited = app
+    .state::<SharedAiRateLimiter>()
+    .lock()
+    .unwrap()
+    .acquire(tier);
+  if let Err(limited) = limited {
+    info!(
+      "🚦 AI requests rate limited for {} tier, retry in {}ms",
+      limited.tier, limited.retry_after_ms
+    );
+    let _ = app.emit("rate-limited", &limited);
+    return Err(format!(
+      "Too many requests - try again in {} seconds",
+      limited.retry_after_ms.div_ceil(1000)
+    ));
+  }
+  Ok(())
+}
+
+// One attempt with `request.model`, streaming as `ai-analysis-chunk`
+async fn analyze_with_model(
+  app: &tauri::AppHandle,
+  service: &AuthService,
+  user: Option<&User>,
+  request: &AnalysisRequest,
+) -> Result<AnalysisResult, AnalysisError> {
+  let mut on_chunk = |text: &str| {
+    let _ = app.emit(
+      "ai-analysis-chunk",
+      serde_json::json!({
+          "conversationId": request.conversation_id,
+          "text": text
+      }),
+    );
+  };
+
+  if let Some((kind, provider_model)) = ai::split_model(&request.model) {
+    let api_key = match kind.needs_api_key() {
+      true => app.state::<SharedApiKeys>().lock().unwrap().get(kind)?,
+      false => None,
+    };
+    return ai::analyze_direct(
+      kind,
+      provider_model,
+      request,
+      api_key,
+      service.network_settings().clone(),
+      &mut on_chunk,
+    )
+    .await;
+  }
+
+  let user = user.ok_or("Sign in to analyze captures".to_string())?;
+  let client = AiClient::new(service.api_url(), service.network_settings().clone());
+  let result = client.analyze(&user.token, request, on_chunk).await?;
+
+  // Only answered requests count against the quota
+  if let Err(e) = service.record_usage(&result.model).await {
+    warn!("⚠️ Failed to record usage: {}", e);
+  }
+  Ok(result)
+}
+
+#[tauri::command]
+fn get_fallback_settings(
+  fallback: tauri::State<'_, SharedFallbackStore>,
+) -> Result<FallbackSettings, String> {
+  Ok(fallback.lock().unwrap().settings().clone())
+}
+
+#[tauri::command]
+fn set_fallback_settings(
+  settings: FallbackSettings,
+  fallback: tauri::State<'_, SharedFallbackStore>,
+) -> Result<(), String> {
+  fallback.lock().unwrap().update(settings)
+}
+
+#[tauri::command]
+fn get_ai_preferences(
+  preferences: tauri::State<'_, SharedAiPreferences>,
+) -> Result<AiPreferences, String> {
+  Ok(preferences.lock().unwrap().preferences().clone())
+}
+
+// Save the system prompt, response style and answer language used for every request
+#[tauri::command]
+fn set_ai_preferences(
+  preferences: AiPreferences,
+  store: tauri::State<'_, SharedAiPreferences>,
+) -> Result<(), String> {
+  store.lock().unwrap().update(preferences)
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SmartCaptureResult {
+  capture_id: String,
+  image_data: String,
+  bounds: CaptureBounds,
+  ocr: Option<OCRResult>,
+  analysis: AnalysisResult,
+  capture_ms: u64,
+  ocr_ms: u64,
+}
+
+// Capture -> OCR -> AI in one call, so the headless Alt+C flow has an answer before any
+// window exists. Recognized text is added to the prompt as context; `ocr: false` skips it.
+#[tauri::command]
+async fn smart_capture(
+  app: tauri::AppHandle,
+  bounds: CaptureBounds,
+  prompt: String,
+  model: Option<String>,
+  conversation_id: Option<String>,
+  ocr: Option<bool>,
+) -> Result<SmartCaptureResult, String> {
+  let started = Instant::now();
+  let capture_app = app.clone();
+  let capture_bounds = bounds.clone();
+  let image_data = tauri::async_runtime::spawn_blocking(move || {
+    capture_app
+      .state::<SharedScreenshotCache>()
+      .lock()
+      .unwrap()
+      .capture_optimized(capture_bounds)
+  })
+  .await
+  .map_err(|e| format!("Capture task failed: {}", e))??;
+  let capture_duration = started.elapsed();
+
+  // Both spawn helper processes on Linux: collect them before taking the store lock
+  let source_app = save::frontmost_app_name();
+  let metadata = CaptureMetadata::collect(Some(&bounds), capture_duration, None);
+  let capture_id = app.state::<SharedCaptureStore>().lock().unwrap().add(
+    image_data.clone(),
+    Some(bounds.clone()),
+    source_app,
+    Some(metadata),
+  );
+
+  let ocr_started = Instant::now();
+  let ocr_result = if ocr.unwrap_or(true) {
+    let ocr_image = image_data.clone();
+    let recognized = tauri::async_runtime::spawn_blocking(move || run_ocr(&ocr_image))
+      .await
+      .map_err(|e| format!("OCR task failed: {}", e))
+      .and_then(|result| result);
+    match recognized {
+      Ok(result) => {
+        app
+          .state::<SharedCaptureStore>()
+          .lock()
+          .unwrap()
+          .set_ocr_text(&capture_id, result.text.clone());
+        Some(result)
+      }
+      // The image alone still gets an answer
+      Err(e) => {
+        warn!(
+          "⚠️ Smart capture OCR failed, continuing without text: {}",
+          e
+        );
+        None
+      }
+    }
+  } else {
+    None
+  };
+  let ocr_duration = ocr_started.elapsed();
+
+  let prompt = match &ocr_result {
+    Some(result) if result.has_text => format!(
+      "{}\n\nText recognized in the capture:\n{}",
+      prompt, result.text
+    ),
+    _ => prompt,
+  };
+  let analysis = run_analysis(
+    &app,
+    Some(image_data.clone()),
+    prompt,
+    model,
+    conversation_id,
+    Some(capture_id.clone()),
+    true,
+  )
+  .await?;
+
+  info!(
+    "⚡ Smart capture done in {}ms (capture {}ms, OCR {}ms)",
+    started.elapsed().as_millis(),
+    capture_duration.as_millis(),
+    ocr_duration.as_millis()
+  );
+  Ok(SmartCaptureResult {
+    capture_id,
+    image_data,
+    bounds,
+    ocr: ocr_result,
+    analysis,
+    capture_ms: capture_duration.as_millis() as u64,
+    ocr_ms: ocr_duration.as_millis() as u64,
+  })
+}
+
+// Projected cost of a request before sending it (approximate token counts)
+#[tauri::command]
+fn estimate_request_cost(
+  model: String,
+  prompt: String,
+  image_dims: Option<ImageDims>,
+  spend: tauri::State<'_, SharedSpendTracker>,
+) -> Result<CostEstimate, String> {
+  let today = spend.lock().unwrap().today();
+  Ok(ai::cost::estimate(&model, &prompt, image_dims, today))
+}
+
+// Spend per day, newest first
+#[tauri::command]
+fn get_daily_spend(
+  days: Option<usize>,
+  spend: tauri::State<'_, SharedSpendTracker>,
+) -> Result<Vec<DailySpend>, String> {
+  Ok(spend.lock().unwrap().recent(days.unwrap_or(30)))
+}
+
+// 💬 CONVERSATION HISTORY COMMANDS
+
+#[tauri::command]
+fn list_conversations(
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<Vec<ConversationSummary>, String> {
+  Ok(history.lock().unwrap().list())
+}
+
+#[tauri::command]
+fn get_conversation(
+  id: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<Option<Conversation>, String> {
+  history.lock().unwrap().get(&id)
+}
+
+#[tauri::command]
+fn delete_conversation(
+  id: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<bool, String> {
+  history.lock().unwrap().delete(&id)
+}
+
+// Write a conversation with its captures to `path` as Markdown or self-contained HTML
+#[tauri::command]
+fn export_conversation(
+  app: tauri::AppHandle,
+  id: String,
+  format: ExportFormat,
+  path: String,
+  history: tauri::State<'_, SharedConversationHistory>,
+) -> Result<String, String> {
+  let conversation = history
+    .lock()
+    .unwrap()
+    .get(&id)?
+    .ok_or_else(|| format!("Conversation '{}' not found", id))?;
+  // Recent captures are still in memory; older ones come from the capture history
+  let load_image = |capture_id: &str| {
+    let stored = app
+      .state::<SharedCaptureStore>()
+      .lock()
+      .unwrap()
+      .get(capture_id)
+      .map(|capture| capture.image_data.clone());
+    stored.or_else(|| {
+      app
+        .try_state::<SharedCaptureHistory>()
+        .and_then(|history| history.lock().unwrap().load_image(capture_id).ok())
+    })
+  };
+  let written =
+    conversation_export::export_conversation(&conversation, format, Path::new(&path), load_image)?;
+  Ok(written.to_string_lossy().to_string())
+}
+
+// 📝 RESULT EXPORT COMMANDS
+
+// Write a result to `path`: a conversation id exports the AI answers, a capture id the
+// recognized text (OCR runs first when the capture has none yet)
+#[tauri::command]
+async fn export_result(
+  app: tauri::AppHandle,
+  result_id: String,
+  format: ResultFormat,
+  path: String,
+) -> Result<String, String> {
+  let conversation = app
+    .state::<SharedConversationHistory>()
+    .lock()
+    .unwrap()
+    .get(&result_id)?;
+  let document = match conversation {
+    Some(conversation) => analysis_document(&app, conversation),
+    None => ocr_document(&app, &result_id).await?,
+  };
+  let written = tauri::async_runtime::spawn_blocking(move || {
+    result_export::export_result(&document, format, Path::new(&path))
+  })
+  .await
+  .map_err(|e| format!("Export task failed: {}", e))??;
+  Ok(written.to_string_lossy().to_string())
+}
+
+// Question / answer pairs; the source is the first attached capture
+fn analysis_document(app: &tauri::AppHandle, conversation: Conversation) -> ResultDocument {
+  let source = conversation
+    .messages
+    .iter()
+    .find_map(|message| message.capture_id.as_deref())
+    .and_then(|id| find_capture(app, Some(id)).ok());
+  let model = conversation
+    .messages
+    .iter()
+    .rev()
+    .find_map(|message| message.model.clone());
+  let sections = conversation
+    .messages
+    .iter()
+    .map(|message| ResultSection {
+      heading: if message.role == "user" {
+        "Question".to_string()
+      } else {
+        "Answer".to_string()
+      },
+      text: message.content.clone(),
+    })
+    .collect();
+  ResultDocument {
+    id: conversation.id,
+    kind: "analysis",
+    title: conversation.title,
+    created_at: conversation.created_at,
+    source_bounds: source.as_ref().and_then(|capture| capture.bounds.clone()),
+    source_app: source.and_then(|capture| capture.source_app),
+    model,
+    sections,
+  }
+}
+
+async fn ocr_document(app: &tauri::AppHandle, capture_id: &str) -> Result<ResultDocument, String> {
+  let capture = find_capture(app, Some(capture_id))?;
+  let text = capture_text(app, &capture)
+    .await?
+    .ok_or("No text was recognized in this capture")?;
+  Ok(ResultDocument {
+    title: format!("Recognized text {}", capture.created_at),
+    id: capture.id,
+    kind: "ocr",
+    created_at: capture.created_at,
+    source_bounds: capture.bounds,
+    source_app: capture.source_app,
+    model: None,
+    sections: vec![ResultSection {
+      heading: "Text".to_string(),
+      text,
+    }],
+  })
+}
+
+// One page per capture, in the given order, with its OCR text searchable
+#[tauri::command]
+async fn export_searchable_pdf(
+  app: tauri::AppHandle,
+  capture_ids: Vec<String>,
+  path: String,
+) -> Result<String, String> {
+  let mut pages = Vec::with_capacity(capture_ids.len());
+  for id in &capture_ids {
+    let capture = find_capture(&app, Some(id))?;
+    let text = capture_text(&app, &capture).await?;
+    pages.push(PdfPage {
+      png: capture_store::decode_data_url(&capture.image_data)?,
+      text,
+    });
+  }
+  let title = format!(
+    "FrameSense captures {}",
+    chrono::Local::now().format("%Y-%m-%d")
+  );
+  let written = tauri::async_runtime::spawn_blocking(move || {
+    pdf_export::export_searchable_pdf(&pages, &title, Path::new(&path))
+  })
+  .await
+  .map_err(|e| format!("PDF export failed: {}", e))??;
+  Ok(written.to_string_lossy().to_string())
+}
+
+// A capture's OCR text, recognizing it first when the capture has none yet
+async fn capture_text(
+  app: &tauri::AppHandle,
+  capture: &StoredCapture,
+) -> Result<Option<String>, String> {
+  if let Some(text) = &capture.ocr_text {
+    return Ok(Some(text.clone()));
+  }
+  let image_data = capture.image_data.clone();
+  let result = tauri::async_runtime::spawn_blocking(move || run_ocr(&image_data))
+    .await
+    .map_err(|e| format!("OCR task failed: {}", e))??;
+  if !result.has_text {
+    return Ok(None);
+  }
+  app
+    .state::<SharedCaptureStore>()
+    .lock()
+    .unwrap()
+    .set_ocr_text(&capture.id, result.text.clone());
+  Ok(Some(result.text))
+}
+
+// Direct providers and whether a key is stored for each (keys are never returned)
+#[tauri::command]
+fn get_ai_providers(
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<Vec<ProviderInfo>, String> {
+  let store = api_keys.lock().unwrap();
+  ProviderKind::ALL
+    .into_iter()
+    .map(|kind| {
+      Ok(ProviderInfo {
+        id: kind,
+        name: kind.display_name().to_string(),
+        needs_api_key: kind.needs_api_key(),
+        configured: !kind.needs_api_key() || store.get(kind)?.is_some(),
+      })
+    })
+    .collect()
+}
+
+#[tauri::command]
+fn set_api_key(
+  provider: ProviderKind,
+  key: String,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys.lock().unwrap().set(provider, &key)?;
+  info!("🔑 {} API key saved", provider.display_name());
+  Ok(())
+}
+
+#[tauri::command]
+fn remove_api_key(
+  provider: ProviderKind,
+  api_keys: tauri::State<'_, SharedApiKeys>,
+) -> Result<(), String> {
+  api_keys.lock().unwrap().remove(provider)
+}
+
+// Models of a local Ollama server (empty when none is running)
+#[tauri::command]
+async fn list_local_models() -> Result<Vec<LocalModel>, String> {
+  let ollama = OllamaProvider::new();
+  if !ollama.detect().await {
+    return Ok(Vec::new());
+  }
+  ollama.list_models().await
+}
+
+// Test deep link functionality (for development)
+#[tauri::command]
+async fn test_deep_link(app: tauri::AppHandle, token: String, plan: String) -> Result<(), String> {
+  info!(
+    "🧪 Testing deep link with token: {} and plan: {}",
+    token, plan
+  );
+
+  // Emit payment success event for testing
+  app
+    .emit(
+      "payment_success",
+      serde_json::json!({
+          "token": token,
+          "plan": plan
+      }),
+    )
+    .map_err(|e| format!("Failed to emit payment success: {}", e))?;
+
+  info!("✅ Test deep link event emitted successfully");
+  Ok(())
+}
+
+// Clear local user session (for troubleshooting)
+#[tauri::command]
+async fn clear_user_session(
+  auth_service: tauri::State<'_, SharedAuthService>,
+) -> Result<(), String> {
+  info!("🗑️ Clearing local user session...");
+
+  let service = auth_service.read().await.clone();
+
+  service.logout_user().await?;
+  info!("✅ Local session cleared");
+  Ok(())
+}
+
+// Removed problematic HTML/JS-based overlay function - using React overlays only
+
+// Removed old process_screen_selection - using optimized version only
+
+//removed get window position
+
+// Save app state to file for persistence (like Raycast)
+#[tauri::command]
+async fn save_app_state(
+  screenshot_data: Option<String>,
+  bounds: Option<CaptureBounds>,
+  state: tauri::State<'_, SharedState>,
+  state_store: tauri::State<'_, SharedStateStore>,
+) -> Result<(), String> {
+  info!("💾 Saving app state...");
+
+  // Update in-memory state
+  {
+    let mut app_state = state.lock().unwrap();
+    app_state.screenshot_data = screenshot_data.clone();
+    app_state.last_bounds = bounds.clone();
+    app_state.last_window_closed_time = Some(
+      SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs(),
+    );
+  }
+
+  // Persist only what changed (screenshot stored as a separate file)
+  let current_state = state.lock().unwrap().clone();
+  let mut store = state_store.lock().unwrap();
+  match store.save(&current_state) {
+    Ok(stats) => info!(
+      "✅ App state saved (fields: {}, screenshot: {}, {}KB written)",
+      stats.fields_written,
+      stats.screenshot_written,
+      stats.bytes_written / 1024
+    ),
+    Err(e) => error!("❌ Failed to save app state: {}", e),
+  }
+
+  Ok(())
+}
+
+// 🚀 FAS 1: OPTIMIZED OVERLAY COMMANDS (React-based, no HTML/JS issues)
+
+// Create optimized overlay using OverlayManager pooling with React
+#[tauri::command]
+async fn create_transparent_overlay_optimized(
+  app: tauri::AppHandle,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<(), String> {
+  debug!("🎯 Creating optimized overlay and hiding main window...");
+
+  // Remember which app the user is capturing from (for {app} in filename templates)
+  if let Some(state) = app.try_state::<SharedState>() {
+    state.lock().unwrap().capture_source_app = save::frontmost_app_name();
+  }
+
+  // 🔧 HIDE main window during capture mode
+  let mut main_window_was_visible = false;
+  if let Some(main_window) = app.get_webview_window("main") {
+    main_window_was_visible = main_window.is_visible().unwrap_or(false);
+    match main_window.hide() {
+      Ok(_) => info!("👻 Main window hidden for capture mode"),
+      Err(e) => warn!("⚠️ Failed to hide main window: {}", e),
+    }
+  }
+
+  // 🧊 Freeze-screen mode: snapshot the desktop before the overlay covers it
+  let frozen = match app.try_state::<SharedScreenshotCache>() {
+    Some(cache) if cache.lock().unwrap().freeze_mode() => {
+      if main_window_was_visible {
+        // Let the compositor remove the main window before grabbing the screen
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+      }
+      // Grabbing the whole desktop takes a while; other captures can use the cache meanwhile
+      let snapshot = tauri::async_runtime::spawn_blocking(FrozenFrame::capture)
+        .await
+        .map_err(|e| format!("Snapshot task failed: {}", e))
+        .and_then(|result| result);
+      match snapshot {
+        Ok(frame) => {
+          cache.lock().unwrap().freeze(frame);
+          true
+        }
+        Err(e) => {
+          warn!("⚠️ Failed to freeze screen, using live capture: {}", e);
+          false
+        }
+      }
+    }
+    _ => false,
+  };
+
+  accessibility::announce(
+    &app,
+    "Capture started. Drag to select an area, press Escape to cancel.",
+    AnnouncementPriority::High,
+  );
+
+  let overlay_config = app
+    .state::<SharedOverlayConfig>()
+    .lock()
+    .unwrap()
+    .config()
+    .clone();
+
+  // 🔥 Pre-warm: grab the desktop before the overlay (and its instructions banner and
+  // close button) is shown, so the coming selection is a crop of a clean frame.
+  // Skipped when a frozen frame already serves that purpose.
+  if !frozen {
+    if main_window_was_visible {
+      // Let the compositor remove the main window before grabbing the screen
+      tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+    let cache = app.state::<SharedScreenshotCache>().inner().clone();
+    let prewarmed =
+      tauri::async_runtime::spawn_blocking(move || cache.lock().unwrap().prewarm()).await;
+    match prewarmed {
+      Ok(Ok(())) => {}
+      Ok(Err(e)) => warn!("⚠️ Failed to pre-warm screenshot cache: {}", e),
+      Err(e) => warn!("⚠️ Pre-warm task failed: {}", e),
+    }
+  }
+  if overlay_config.backend == OverlayBackend::Native && native_overlay::is_supported() {
+    // 🍎 NSWindow overlay - no webview to start. A frozen frame is still used for the
+    // crop, the native windows just don't draw it.
+    let done_app = app.clone();
+    native_overlay::show(&app, &overlay_config, move |selection| {
+      tauri::async_runtime::spawn(async move {
+        match selection {
+          NativeSelection::Selected(bounds) => {
+            // Give the window server a moment to remove the overlay windows
+            tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+            let result = process_selection(
+              done_app.clone(),
+              bounds,
+              None,
+              done_app.state(),
+              done_app.state(),
+            )
+            .await;
+            if let Err(e) = result {
+              error!("❌ Native overlay capture failed: {}", e);
+            }
+          }
+          NativeSelection::Cancelled => cancel_active_capture(&done_app),
+        }
+      });
+    })?;
+  } else {
+    let regions = {
+      let mut manager = overlay_manager.lock().unwrap();
+      manager.show_selection_overlay(&app)?;
+      manager.display_regions()
+    };
+    if frozen {
+      let frames = frozen_overlay_frames(&app, regions).await;
+      overlay_manager.lock().unwrap().send_frozen_frames(frames);
+    }
+  }
+
+  // Escape cancels even when the overlay webview doesn't have keyboard focus
+  if let Err(e) = app.global_shortcut().register(escape_shortcut()) {
+    warn!("⚠️ Failed to register Escape for cancelling: {}", e);
+  }
+  Ok(())
+}
+
+// Each overlay window's part of the frozen snapshot as a JPEG data URL, keyed by label.
+// Cropped under the cache lock, encoded after releasing it.
+async fn frozen_overlay_frames(
+  app: &tauri::AppHandle,
+  regions: Vec<(String, CaptureBounds)>,
+) -> Vec<(String, String)> {
+  let cache = app.state::<SharedScreenshotCache>().inner().clone();
+  let encoded = tauri::async_runtime::spawn_blocking(move || {
+    let crops = cache.lock().unwrap().frozen_regions(&regions);
+    crops
+      .into_iter()
+      .filter_map(
+        |(label, image)| match ScreenshotCache::encode_preview(image) {
+          Ok(preview) => Some((label, preview)),
+          Err(e) => {
+            warn!("⚠️ {}", e);
+            None
+          }
+        },
+      )
+      .collect()
+  })
+  .await;
+  encoded.unwrap_or_else(|e| {
+    warn!("⚠️ Frozen frame task failed: {}", e);
+    Vec::new()
+  })
+}
+
+fn escape_shortcut() -> Shortcut {
+  Shortcut::new(None, Code::Escape)
+}
+
+// Show and focus the main window again after capture mode (if it exists)
+fn restore_main_window(app: &tauri::AppHandle) {
+  if let Some(main_window) = app.get_webview_window("main") {
+    match main_window.show() {
+      Ok(_) => {
+        info!("👁️ Main window shown again after capture");
+        // Focus the window so it's ready for interaction
+        if let Err(e) = main_window.set_focus() {
+          warn!("⚠️ Failed to focus main window: {}", e);
+        }
+      }
+      Err(e) => warn!("⚠️ Failed to show main window: {}", e),
+    }
+  } else {
+    info!("ℹ️ No main window to show (headless capture mode)");
+  }
+}
+
+// Escape / cancel: tear down the overlay, drop any frozen frame and restore the main window
+fn cancel_active_capture(app: &tauri::AppHandle) {
+  let _ = app.global_shortcut().unregister(escape_shortcut());
+  native_overlay::close(app);
+  if let Some(cache) = app.try_state::<SharedScreenshotCache>() {
+    cache.lock().unwrap().unfreeze();
+  }
+  if let Some(overlay_manager) = app.try_state::<SharedOverlayManager>() {
+    overlay_manager.lock().unwrap().cancel(app);
+  }
+  restore_main_window(app);
+  accessibility::announce(app, "Capture cancelled", AnnouncementPriority::Medium);
+}
 
-  service.logout_user().await?;
-  println!("✅ Local session cleared");
+// Cancel the current capture (Escape in the overlay); emits capture-cancelled
+#[tauri::command]
+fn cancel_capture(app: tauri::AppHandle) -> Result<(), String> {
+  cancel_active_capture(&app);
   Ok(())
 }
 
-// Removed problematic HTML/JS-based overlay function - using React overlays only
-
-// Removed old process_screen_selection - using optimized version only
-
-//removed get window position
-
-// Save app state to file for persistence (like Raycast)
+// Close optimized overlay using OverlayManager
 #[tauri::command]
-async fn save_app_state(
-  screenshot_data: Option<String>,
-  bounds: Option<CaptureBounds>,
+async fn close_transparent_overlay_optimized(
   app: tauri::AppHandle,
-  state: tauri::State<'_, SharedState>,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
 ) -> Result<(), String> {
-  println!("💾 Saving app state...");
-
-  // Update in-memory state
-  {
-    let mut app_state = state.lock().unwrap();
-    app_state.screenshot_data = screenshot_data.clone();
-    app_state.last_bounds = bounds.clone();
-    app_state.last_window_closed_time = Some(
-      SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs(),
-    );
+  debug!("🎯 Closing optimized overlay and showing main window...");
+
+  let _ = app.global_shortcut().unregister(escape_shortcut());
+  let result = {
+    let mut manager = overlay_manager.lock().unwrap();
+    let result = manager.hide_overlay();
+    manager.clear_frozen_frames();
+    result
+  };
+  if let Some(cache) = app.try_state::<SharedScreenshotCache>() {
+    cache.lock().unwrap().unfreeze();
   }
 
-  // Save to file for persistence
-  if let Some(app_data_dir) = app.path().app_data_dir().ok() {
-    let state_file = app_data_dir.join("app_state.json");
+  // 🔧 SHOW main window again after capture mode (if it exists)
+  restore_main_window(&app);
 
-    // Ensure directory exists
-    if let Some(parent) = state_file.parent() {
-      if !parent.exists() {
-        match std::fs::create_dir_all(parent) {
-          Ok(_) => println!("📁 Created app data directory"),
-          Err(e) => println!("⚠️ Failed to create app data directory: {}", e),
-        }
+  result
+}
+
+// Send a finished capture to the main window, creating it first for headless captures
+async fn deliver_capture_to_main_window(
+  app: &tauri::AppHandle,
+  bounds: &CaptureBounds,
+  image_data: String,
+  capture_duration: Duration,
+) -> CaptureMetadata {
+  let source_app = app
+    .try_state::<SharedState>()
+    .and_then(|state| state.lock().unwrap().capture_source_app.take())
+    .or_else(save::frontmost_app_name);
+  let metadata = CaptureMetadata::collect(Some(bounds), capture_duration, source_app.clone());
+
+  // Send result to React - create window if needed for headless capture
+  let window = if let Some(existing_window) = app.get_webview_window("main") {
+    existing_window
+  } else {
+    // 🎭 Headless capture - create animated window for smooth entrance
+    info!("🆕 Headless capture complete - creating animated window for smooth result display");
+    if let Err(e) = create_main_window_animated(app.clone()).await {
+      error!(
+        "❌ Failed to create animated window for headless result: {}",
+        e
+      );
+      // Fallback to regular window creation
+      if let Err(e) = create_main_window(app.clone()).await {
+        error!("❌ Failed to create fallback window: {}", e);
+        return metadata;
       }
     }
-
-    // Save current state
-    let current_state = state.lock().unwrap().clone();
-    match serde_json::to_string_pretty(&current_state) {
-      Ok(state_json) => match std::fs::write(&state_file, state_json) {
-        Ok(_) => println!("✅ App state saved successfully"),
-        Err(e) => println!("❌ Failed to write app state: {}", e),
-      },
-      Err(e) => println!("❌ Failed to serialize app state: {}", e),
+    // Get the newly created window
+    if let Some(new_window) = app.get_webview_window("main") {
+      new_window
+    } else {
+      error!("❌ Failed to get newly created animated window");
+      return metadata;
     }
+  };
+
+  // Register the capture so later commands (print, export...) can refer to it by id
+  let capture_id = app.try_state::<SharedCaptureStore>().map(|store| {
+    store.lock().unwrap().add(
+      image_data.clone(),
+      Some(bounds.clone()),
+      source_app,
+      Some(metadata.clone()),
+    )
+  });
+
+  // Keep a persistent copy in the history (thumbnailing happens off the async runtime)
+  if let (Some(id), Some(history)) = (capture_id.clone(), app.try_state::<SharedCaptureHistory>()) {
+    let history = history.inner().clone();
+    let history_image = image_data.clone();
+    let history_bounds = bounds.clone();
+    let history_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+      let mut history = history.lock().unwrap();
+      if let Err(e) = history.record(&id, &history_image, Some(history_bounds)) {
+        warn!("⚠️ Failed to add capture to history: {}", e);
+        return;
+      }
+      // OCR that finished while the entry was being written couldn't be stored in it yet
+      let ocr_text = history_app
+        .state::<SharedCaptureStore>()
+        .lock()
+        .unwrap()
+        .get(&id)
+        .and_then(|capture| capture.ocr_text.clone());
+      if let Some(text) = ocr_text {
+        if let Err(e) = history.set_ocr_text(&id, text) {
+          warn!("⚠️ Failed to store OCR text in history: {}", e);
+        }
+      }
+    });
   }
 
-  Ok(())
-}
+  let analysis_result = serde_json::json!({
+      "type": "image",
+      "captureId": capture_id,
+      "bounds": bounds,
+      "imageData": image_data,
+      "metadata": metadata,
+      "text": null,
+      "success": true,
+      "message": "Optimized screen area captured successfully!"
+  });
 
-// 🚀 FAS 1: OPTIMIZED OVERLAY COMMANDS (React-based, no HTML/JS issues)
+  // Save to app state for React to pick up when ready
+  if let Some(state) = app.try_state::<SharedState>() {
+    let mut app_state = state.lock().unwrap();
+    app_state.screenshot_data = Some(image_data.clone());
+    app_state.last_bounds = Some(bounds.clone());
+    info!("💾 Saved capture to app state for React pickup");
+  }
 
-// Create optimized overlay using OverlayManager pooling with React
+  // Give React time to load before sending selection-result
+  tokio::time::sleep(tokio::time::Duration::from_millis(110)).await;
+  window.emit("selection-result", analysis_result).unwrap();
+  info!("📤 Sent optimized capture data to main app");
+
+  accessibility::announce(
+    app,
+    &format!(
+      "Capture complete, {} by {} pixels",
+      bounds.width, bounds.height
+    ),
+    AnnouncementPriority::High,
+  );
+  if let Some(id) = capture_id {
+    notify_completion(
+      app,
+      "Capture ready",
+      &format!("{}x{} area captured", bounds.width, bounds.height),
+      DeepLink::OpenCapture { id },
+    );
+  }
+  metadata
+}
+
+// Process screen selection with React overlay and optimized capture.
+// Overlay windows send bounds in their own viewport coordinates.
 #[tauri::command]
-async fn create_transparent_overlay_optimized(
+async fn process_screen_selection_optimized(
   app: tauri::AppHandle,
+  window: tauri::WebviewWindow,
+  bounds: CaptureBounds,
   overlay_manager: tauri::State<'_, SharedOverlayManager>,
+  screenshot_cache: tauri::State<'_, SharedScreenshotCache>,
 ) -> Result<(), String> {
-  println!("🎯 Creating optimized overlay and hiding main window...");
-
-  // 🔧 HIDE main window during capture mode
-  if let Some(main_window) = app.get_webview_window("main") {
-    match main_window.hide() {
-      Ok(_) => println!("👻 Main window hidden for capture mode"),
-      Err(e) => println!("⚠️ Failed to hide main window: {}", e),
-    }
-  }
-
-  let mut manager = overlay_manager.lock().unwrap();
-  manager.show_selection_overlay(&app)
+  let bounds = {
+    let manager = overlay_manager.lock().unwrap();
+    manager.constrain(manager.to_global(window.label(), bounds))
+  };
+  process_selection(app, bounds, None, overlay_manager, screenshot_cache).await
 }
 
-// Close optimized overlay using OverlayManager
+// Freehand selection: capture the path's bounding box with everything outside the path
+// made transparent. Points are in the overlay window's viewport coordinates.
 #[tauri::command]
-async fn close_transparent_overlay_optimized(
+async fn process_lasso_selection(
   app: tauri::AppHandle,
+  window: tauri::WebviewWindow,
+  points: Vec<MeasurePoint>,
   overlay_manager: tauri::State<'_, SharedOverlayManager>,
+  screenshot_cache: tauri::State<'_, SharedScreenshotCache>,
 ) -> Result<(), String> {
-  println!("🎯 Closing optimized overlay and showing main window...");
+  let points = overlay_manager
+    .lock()
+    .unwrap()
+    .points_to_global(window.label(), points);
+  let bounds = lasso::bounding_box(&points)?;
+  info!("➰ Lasso selection with {} points", points.len());
+  process_selection(app, bounds, Some(points), overlay_manager, screenshot_cache).await
+}
 
-  let mut manager = overlay_manager.lock().unwrap();
-  let result = manager.hide_overlay();
+// Click-to-capture-window: a click (no drag) at (x, y) in the overlay window's viewport
+// captures the frontmost window under that point
+#[tauri::command]
+async fn capture_window_at_point(
+  app: tauri::AppHandle,
+  window: tauri::WebviewWindow,
+  x: i32,
+  y: i32,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+  screenshot_cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  let point = overlay_manager.lock().unwrap().to_global(
+    window.label(),
+    CaptureBounds {
+      x,
+      y,
+      width: 0,
+      height: 0,
+    },
+  );
+  let area = ScreenCapture::get_total_screen_area()?;
+  let (desktop_x, desktop_y) = (point.x + area.min_x, point.y + area.min_y);
+
+  let frame =
+    tauri::async_runtime::spawn_blocking(move || window_picker::window_at(desktop_x, desktop_y))
+      .await
+      .map_err(|e| format!("Window lookup failed: {}", e))?
+      .ok_or("No window found at that point")?;
+  info!(
+    "🪟 Clicked window {:?}: {}x{} at ({}, {})",
+    frame.app_name, frame.width, frame.height, frame.x, frame.y
+  );
 
-  // 🔧 SHOW main window again after capture mode (if it exists)
-  if let Some(main_window) = app.get_webview_window("main") {
-    match main_window.show() {
-      Ok(_) => {
-        println!("👁️ Main window shown again after capture");
-        // Focus the window so it's ready for interaction
-        if let Err(e) = main_window.set_focus() {
-          println!("⚠️ Failed to focus main window: {}", e);
-        }
-      }
-      Err(e) => println!("⚠️ Failed to show main window: {}", e),
-    }
-  } else {
-    println!("ℹ️ No main window to show (headless capture mode)");
+  // Overlay coordinates, clipped to the screens (windows can hang off the edge)
+  let left = (frame.x - area.min_x).max(0);
+  let top = (frame.y - area.min_y).max(0);
+  let right = (frame.x - area.min_x + frame.width as i32).min(area.width as i32);
+  let bottom = (frame.y - area.min_y + frame.height as i32).min(area.height as i32);
+  if right <= left || bottom <= top {
+    return Err("The clicked window is not on screen".to_string());
   }
-
-  result
+  let bounds = CaptureBounds {
+    x: left,
+    y: top,
+    width: (right - left) as u32,
+    height: (bottom - top) as u32,
+  };
+  process_selection(app, bounds, None, overlay_manager, screenshot_cache).await
 }
 
-// Process screen selection with React overlay and optimized capture
-#[tauri::command]
-async fn process_screen_selection_optimized(
+// Capture a selection given in overlay coordinates and hand it to the main window.
+// With a lasso path, pixels outside the path are made transparent.
+async fn process_selection(
   app: tauri::AppHandle,
   bounds: CaptureBounds,
+  lasso_path: Option<Vec<MeasurePoint>>,
   overlay_manager: tauri::State<'_, SharedOverlayManager>,
   screenshot_cache: tauri::State<'_, SharedScreenshotCache>,
 ) -> Result<(), String> {
-  println!(
+  info!(
     "📸 Processing optimized screen selection: {}x{} at ({}, {})",
     bounds.width, bounds.height, bounds.x, bounds.y
   );
 
-  // Use multi-screen optimized capture with caching
-  let capture_result =
-    capture_screen_area_multi_screen_optimized(bounds.clone(), screenshot_cache)?;
-
-  if capture_result.success && capture_result.image_data.is_some() {
-    let image_data = capture_result.image_data.unwrap();
-    println!("✅ Optimized screen capture successful!");
-
-    // Send result to React - create window if needed for headless capture
-    let window = if let Some(existing_window) = app.get_webview_window("main") {
-      existing_window
+  let started = Instant::now();
+
+  // Only grab the pixels here; they're encoded once the overlay is gone. Selections
+  // made on a frozen screen are cropped from the snapshot.
+  let cache = screenshot_cache.inner().clone();
+  let grab_bounds = bounds.clone();
+  let (grabbed, from_frozen) = tauri::async_runtime::spawn_blocking(move || {
+    let mut cache = cache.lock().unwrap();
+    if cache.is_frozen() {
+      let result = cache.crop_frozen(&grab_bounds).map(GrabbedCapture::Pixels);
+      cache.unfreeze();
+      (result, true)
     } else {
-      // 🎭 Headless capture - create animated window for smooth entrance
-      println!("🆕 Headless capture complete - creating animated window for smooth result display");
-      if let Err(e) = create_main_window_animated(app.clone()).await {
-        println!(
-          "❌ Failed to create animated window for headless result: {}",
-          e
-        );
-        // Fallback to regular window creation
-        if let Err(e) = create_main_window(app.clone()).await {
-          println!("❌ Failed to create fallback window: {}", e);
-          return Ok(());
+      (cache.grab_optimized(grab_bounds), false)
+    }
+  })
+  .await
+  .map_err(|e| format!("Capture task failed: {}", e))?;
+
+  let grabbed = match grabbed {
+    Ok(grabbed) => grabbed,
+    Err(e) => {
+      error!("❌ Optimized capture failed: {}", e);
+      return Ok(());
+    }
+  };
+  info!("✅ Optimized screen capture successful!");
+
+  // The pixels are in; hide the overlay now and finish (encoding, lasso mask, delivery)
+  // in the background so the command returns right away
+  let _ = close_transparent_overlay_optimized(app.clone(), overlay_manager).await;
+  let cache = screenshot_cache.inner().clone();
+  tauri::async_runtime::spawn(async move {
+    let encode_bounds = bounds.clone();
+    let encoded = tauri::async_runtime::spawn_blocking(move || {
+      let mut image_data = match grabbed {
+        GrabbedCapture::Encoded(image_data) => image_data,
+        GrabbedCapture::Pixels(rgba) => {
+          let image_data = ScreenCapture::encode_rgba_to_base64(rgba)?;
+          if !from_frozen {
+            cache
+              .lock()
+              .unwrap()
+              .store_capture(encode_bounds.clone(), image_data.clone());
+          }
+          image_data
         }
+      };
+      if let Some(points) = lasso_path {
+        image_data = lasso::apply_mask(&image_data, &encode_bounds, &points)?;
       }
-      // Get the newly created window
-      if let Some(new_window) = app.get_webview_window("main") {
-        new_window
-      } else {
-        println!("❌ Failed to get newly created animated window");
-        return Ok(());
+      Ok::<_, String>(image_data)
+    })
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))
+    .and_then(|result| result);
+    let image_data = match encoded {
+      Ok(image_data) => image_data,
+      Err(e) => {
+        error!("❌ Capture failed: {}", e);
+        return;
       }
     };
+    deliver_capture_to_main_window(&app, &bounds, image_data, started.elapsed()).await;
+  });
 
-    let analysis_result = serde_json::json!({
-        "type": "image",
-        "bounds": bounds,
-        "imageData": image_data,
-        "text": null,
-        "success": true,
-        "message": "Optimized screen area captured successfully!"
-    });
+  Ok(())
+}
 
-    // Save to app state for React to pick up when ready
-    if let Some(state) = app.try_state::<SharedState>() {
-      let mut app_state = state.lock().unwrap();
-      app_state.screenshot_data = Some(image_data.clone());
-      app_state.last_bounds = Some(bounds.clone());
-      println!("💾 Saved capture to app state for React pickup");
-    }
+// 🎨 OVERLAY APPEARANCE COMMANDS
 
-    // Give React time to load before sending selection-result
-    tokio::time::sleep(tokio::time::Duration::from_millis(110)).await;
-    window.emit("selection-result", analysis_result).unwrap();
-    println!("📤 Sent optimized capture data to main app");
+// Dim opacity, selection border color, crosshair and size label for the React overlay
+#[tauri::command]
+fn get_overlay_config(
+  overlay_config: tauri::State<'_, SharedOverlayConfig>,
+) -> Result<OverlayConfig, String> {
+  Ok(overlay_config.lock().unwrap().config().clone())
+}
 
-    // Hide overlay using optimized manager
-    let _ = close_transparent_overlay_optimized(app, overlay_manager);
-  } else {
-    println!("❌ Optimized capture failed: {}", capture_result.message);
-  }
+// Saves the preferences and tells open overlays to re-render (overlay-config-changed)
+#[tauri::command]
+fn set_overlay_config(
+  app: tauri::AppHandle,
+  config: OverlayConfig,
+  overlay_config: tauri::State<'_, SharedOverlayConfig>,
+) -> Result<(), String> {
+  overlay_config.lock().unwrap().update(config.clone())?;
+  let _ = app.emit("overlay-config-changed", config);
+  Ok(())
+}
 
+// ⌨️ KEYBOARD SELECTION COMMANDS
+
+// Set the overlay selection directly (e.g. typed coordinates, or a drag in progress so the
+// other displays' overlays can draw their part); emits selection-bounds-changed.
+// Bounds from an overlay window are in its own viewport coordinates.
+#[tauri::command]
+fn set_selection_bounds(
+  window: tauri::WebviewWindow,
+  bounds: CaptureBounds,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<CaptureBounds, String> {
+  let mut manager = overlay_manager.lock().unwrap();
+  let bounds = manager.to_global(window.label(), bounds);
+  Ok(manager.set_selection(bounds))
+}
+
+// Aspect-ratio or fixed-size selection for the current capture session
+#[tauri::command]
+fn set_selection_constraints(
+  constraint: SelectionConstraint,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<(), String> {
+  info!("📐 Selection constraint: {:?}", constraint);
+  overlay_manager.lock().unwrap().set_constraint(constraint)
+}
+
+#[tauri::command]
+fn get_selection_constraints(
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<SelectionConstraint, String> {
+  Ok(overlay_manager.lock().unwrap().constraint())
+}
+
+// Arrow keys: move the selection, or resize it when `resize` is set
+#[tauri::command]
+fn nudge_selection(
+  dx: i32,
+  dy: i32,
+  resize: Option<bool>,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<CaptureBounds, String> {
+  Ok(
+    overlay_manager
+      .lock()
+      .unwrap()
+      .nudge_selection(dx, dy, resize.unwrap_or(false)),
+  )
+}
+
+// Enter: capture the current keyboard selection like a finished drag
+#[tauri::command]
+async fn confirm_selection(
+  app: tauri::AppHandle,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+  screenshot_cache: tauri::State<'_, SharedScreenshotCache>,
+) -> Result<(), String> {
+  let bounds = overlay_manager
+    .lock()
+    .unwrap()
+    .selection()
+    .ok_or("No selection to confirm")?;
+  info!(
+    "⌨️ Selection confirmed with keyboard: {}x{} at ({}, {})",
+    bounds.width, bounds.height, bounds.x, bounds.y
+  );
+  process_selection(app, bounds, None, overlay_manager, screenshot_cache).await
+}
+
+// Idle timeout for the pooled overlay windows in seconds (None or 0 = never clean up)
+#[tauri::command]
+fn set_overlay_idle_timeout(
+  seconds: Option<u64>,
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<(), String> {
+  let timeout = seconds.filter(|&secs| secs > 0).map(Duration::from_secs);
+  overlay_manager.lock().unwrap().set_idle_timeout(timeout);
+  info!("⏲️ Overlay idle timeout: {:?}", timeout);
   Ok(())
 }
 
-// Cleanup old overlays periodically
-/*
+// Free the pooled overlay webviews now instead of waiting for the idle timeout
+#[tauri::command]
+fn destroy_overlay(overlay_manager: tauri::State<'_, SharedOverlayManager>) -> Result<(), String> {
+  overlay_manager.lock().unwrap().destroy()
+}
 
-*/
+#[tauri::command]
+fn get_overlay_stats(
+  overlay_manager: tauri::State<'_, SharedOverlayManager>,
+) -> Result<OverlayStats, String> {
+  Ok(overlay_manager.lock().unwrap().stats())
+}
 // 🆕 FAS 2: WINDOW RESIZE FUNCTIONS
 
 // Resize main window for chat expansion/contraction
 #[tauri::command]
 async fn resize_window(app: tauri::AppHandle, width: f64, height: f64) -> Result<(), String> {
-  println!("📏 Resizing main window to {}x{}", width, height);
+  info!("📏 Resizing main window to {}x{}", width, height);
 
   if let Some(window) = app.get_webview_window("main") {
     match window.set_size(tauri::LogicalSize::new(width, height)) {
       Ok(_) => {
-        println!("✅ Window resized successfully to {}x{}", width, height);
+        info!("✅ Window resized successfully to {}x{}", width, height);
         Ok(())
       }
       Err(e) => {
-        println!("❌ Failed to resize window: {}", e);
+        error!("❌ Failed to resize window: {}", e);
         Err(format!("Failed to resize window: {}", e))
       }
     }
   } else {
-    println!("❌ Main window not found for resize");
+    error!("❌ Main window not found for resize");
     Err("Main window not found".to_string())
   }
 }
@@ -634,7 +4890,7 @@ async fn get_window_info(app: tauri::AppHandle) -> Result<serde_json::Value, Str
       serde_json::json!(window.is_visible().unwrap_or(false)),
     );
 
-    println!(
+    info!(
       "📊 ALT+C: Window info requested - {}x{}",
       info.get("width").unwrap_or(&serde_json::json!(0)),
       info.get("height").unwrap_or(&serde_json::json!(0))
@@ -657,20 +4913,20 @@ async fn show_window_when_ready(app: tauri::AppHandle) -> Result<(), String> {
       Ok(_) => {
         // Set focus after showing
         if let Err(e) = window.set_focus() {
-          println!("⚠️ Failed to focus window after showing: {}", e);
+          warn!("⚠️ Failed to focus window after showing: {}", e);
         }
-        println!("✅ Window shown after React ready");
+        info!("✅ Window shown after React ready");
         Ok(())
       }
       Err(e) => {
         let err_msg = format!("Failed to show window: {}", e);
-        println!("❌ {}", err_msg);
+        error!("❌ {}", err_msg);
         Err(err_msg)
       }
     }
   } else {
     let err_msg = "Main window not found";
-    println!("❌ {}", err_msg);
+    error!("❌ {}", err_msg);
     Err(err_msg.to_string())
   }
 }
@@ -725,13 +4981,124 @@ async fn debug_coordinates(app: tauri::AppHandle) -> Result<serde_json::Value, S
     }
   }
 
-  println!(
+  info!(
     "🔍 DEBUG INFO: {}",
     serde_json::to_string_pretty(&debug_info).unwrap()
   );
   Ok(serde_json::Value::Object(debug_info))
 }
 
+// Show and focus the main window, creating it if it was closed
+fn reveal_main_window(app: &tauri::AppHandle) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.show();
+    let _ = window.set_focus();
+  } else {
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+      if let Err(e) = create_main_window(app_clone).await {
+        error!("❌ Failed to create main window: {}", e);
+      }
+    });
+  }
+}
+
+// A second launch was redirected here: bring the main window up, route any
+// framesense:// links it was given and pass on its arguments (`single-instance`)
+fn handle_launch_request(app: &tauri::AppHandle, request: LaunchRequest) {
+  reveal_main_window(app);
+  open_deep_links(app, &request.args);
+  let _ = app.emit("single-instance", &request);
+}
+
+// 🔔 COMPLETION NOTIFICATIONS
+
+// Tell a user who looked away that a result is ready; "Open result" routes `target`
+// like the matching deep link
+fn notify_completion(app: &tauri::AppHandle, title: &str, body: &str, target: DeepLink) {
+  let enabled = app
+    .state::<SharedSettings>()
+    .lock()
+    .unwrap()
+    .settings()
+    .notifications
+    .on_completion;
+  if !enabled || !notifications::main_window_unattended(app) {
+    return;
+  }
+  let app_clone = app.clone();
+  notifications::post(app, title, body, move || {
+    dispatch_deep_link(&app_clone, target)
+  });
+}
+
+// 🔗 DEEP LINK ROUTING
+
+// Route every framesense:// link among `args`; anything else is ignored
+fn open_deep_links(app: &tauri::AppHandle, args: &[String]) {
+  for arg in args.iter().filter(|arg| deep_link::is_deep_link(arg)) {
+    match deep_link::parse(arg) {
+      Ok(link) => dispatch_deep_link(app, link),
+      Err(e) => warn!("⚠️ Ignoring deep link: {}", e),
+    }
+  }
+}
+
+fn dispatch_deep_link(app: &tauri::AppHandle, link: DeepLink) {
+  info!("🔗 Deep link: {}", link.route());
+  let _ = app.emit("deep-link", &link);
+  match link {
+    // Back from checkout: the backend knows the new plan, re-verify to pick it up
+    DeepLink::PaymentSuccess { plan, session_id } => {
+      reveal_main_window(app);
+      let _ = app.emit(
+        "payment_success",
+        serde_json::json!({ "plan": plan, "sessionId": session_id }),
+      );
+      let app_clone = app.clone();
+      tauri::async_runtime::spawn(async move {
+        check_tier_change(&app_clone).await;
+      });
+    }
+    DeepLink::OpenCapture { id } => {
+      let in_store = app
+        .state::<SharedCaptureStore>()
+        .lock()
+        .unwrap()
+        .get(&id)
+        .cloned();
+      let capture = match in_store {
+        Some(capture) => Ok(capture),
+        None => open_capture_from_history(
+          id.clone(),
+          app.state::<SharedCaptureHistory>(),
+          app.state::<SharedCaptureStore>(),
+        ),
+      };
+      match capture {
+        Ok(capture) => {
+          reveal_main_window(app);
+          let _ = app.emit("open-capture", &capture);
+        }
+        Err(e) => warn!("⚠️ Deep link capture {} not available: {}", id, e),
+      }
+    }
+    DeepLink::LoginCallback { state, code, error } => {
+      if let Err(e) = auth::oauth::deliver_callback(&state, code, error) {
+        warn!("⚠️ {}", e);
+      }
+    }
+    DeepLink::OpenConversation { id } => {
+      reveal_main_window(app);
+      let _ = app.emit("open-conversation", serde_json::json!({ "id": id }));
+    }
+    DeepLink::NewChat { prompt } => {
+      reveal_main_window(app);
+      let _ = app.emit("new-chat", serde_json::json!({ "prompt": prompt }));
+    }
+  }
+}
+
 //gör så att det skapar nytt fönster
 // Create new main window on current Space (like Raycast/Spotlight)
 #[tauri::command]
@@ -748,7 +5115,7 @@ async fn create_main_window_expanded(app: tauri::AppHandle) -> Result<(), String
 // 🎭 NEW: Create main window with smooth entrance animation (Alt+C optimized)
 #[tauri::command]
 async fn create_main_window_animated(app: tauri::AppHandle) -> Result<(), String> {
-  println!("🎭 ALT+C: Creating animated window for smooth entrance...");
+  info!("🎭 ALT+C: Creating animated window for smooth entrance...");
 
   // Get screen size for positioning
   let (screen_width, screen_height) = match screenshots::Screen::all() {
@@ -789,7 +5156,7 @@ async fn create_main_window_animated(app: tauri::AppHandle) -> Result<(), String
     .build()
     .map_err(|e| format!("Failed to create animated window: {}", e))?;
 
-  println!(
+  info!(
     "✅ ALT+C: Animated window created at ({}, {}) - starting smooth entrance...",
     x, y
   );
@@ -804,22 +5171,22 @@ async fn create_main_window_animated(app: tauri::AppHandle) -> Result<(), String
   // Step 3: Show window and start entrance animation
   match window.show() {
     Ok(_) => {
-      println!("🎭 ALT+C: Window shown - entrance animation started");
+      info!("🎭 ALT+C: Window shown - entrance animation started");
 
       // Step 4: Focus the window for interaction
       if let Err(e) = window.set_focus() {
-        println!("⚠️ ALT+C: Failed to focus animated window: {}", e);
+        warn!("⚠️ ALT+C: Failed to focus animated window: {}", e);
       } else {
-        println!("🎯 ALT+C: Animated window focused and ready for interaction");
+        debug!("🎯 ALT+C: Animated window focused and ready for interaction");
       }
     }
     Err(e) => {
-      println!("❌ ALT+C: Failed to show animated window: {}", e);
+      error!("❌ ALT+C: Failed to show animated window: {}", e);
       return Err(format!("Failed to show animated window: {}", e));
     }
   }
 
-  println!("✨ ALT+C: Smooth animated window creation completed!");
+  info!("✨ ALT+C: Smooth animated window creation completed!");
   Ok(())
 }
 
@@ -833,7 +5200,7 @@ async fn create_main_window_with_size(
   if let Some(existing) = app.get_webview_window("main") {
     let _ = existing.close();
   }
-  println!(
+  debug!(
     "🎯 Creating new main window {}x{} on current Space...",
     window_width, window_height
   );
@@ -869,7 +5236,7 @@ async fn create_main_window_with_size(
     .build()
     .map_err(|e| format!("Failed to create main window: {}", e))?;
 
-  println!(
+  info!(
     "✅ New main window {}x{} created on current Space at ({}, {})!",
     window_width, window_height, x, y
   );
@@ -881,17 +5248,61 @@ async fn create_main_window_with_size(
 #[tauri::command]
 async fn get_app_state(state: tauri::State<'_, SharedState>) -> Result<AppState, String> {
   let app_state = state.lock().unwrap().clone();
-  println!("📖 App state retrieved");
+  info!("📖 App state retrieved");
   Ok(app_state)
 }
 
 fn main() {
   use std::sync::{Arc, Mutex};
   use std::time::{SystemTime, UNIX_EPOCH};
-  use tauri::menu::{Menu, MenuItem};
+  use tauri::menu::{Menu, MenuItem, Submenu};
   use tauri::tray::TrayIconBuilder;
   use tauri::{Manager, RunEvent, WindowEvent};
-  use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+  use tauri_plugin_global_shortcut::ShortcutState;
+
+  // Settings first: they carry the log levels, and logging starts before anything else
+  let app_data_dir = dirs::home_dir()
+    .unwrap_or_else(|| std::path::PathBuf::from("/tmp"))
+    .join(".framesense");
+  let settings_store = SettingsStore::new().with_storage_dir(app_data_dir.clone());
+  logging::init(
+    app_data_dir.join("logs"),
+    &settings_store.settings().logging,
+  );
+  crash_reports::install(&app_data_dir);
+
+  // Command-line captures run without windows, print their result and exit
+  let cli_args: Vec<String> = std::env::args().skip(1).collect();
+  if let Some(command) = cli::parse(&cli_args) {
+    logging::use_stderr();
+    let code = match command {
+      Ok(command) => cli::run(command),
+      Err(e) => {
+        cli::attach_console();
+        eprintln!("framesense: {}\n\n{}", e, cli::USAGE);
+        2
+      }
+    };
+    std::process::exit(code);
+  }
+
+  // A second launch only forwards its arguments; it must not register shortcuts or a tray
+  let launch_request = LaunchRequest {
+    args: std::env::args().skip(1).collect(),
+    cwd: std::env::current_dir()
+      .ok()
+      .map(|dir| dir.to_string_lossy().to_string()),
+  };
+  let primary_instance = match single_instance::acquire(&app_data_dir, &launch_request) {
+    Ok(Instance::Primary(primary)) => Some(primary),
+    Ok(Instance::Forwarded) => return,
+    Err(e) => {
+      warn!("⚠️ Single-instance check failed, continuing: {}", e);
+      None
+    }
+  };
+  // Links this launch was started with are routed once the app is ready
+  let startup_args = launch_request.args;
 
   // Initialize shared state for Raycast-style persistence
   let shared_state: SharedState = Arc::new(Mutex::new(AppState::default()));
@@ -903,45 +5314,147 @@ fn main() {
   let shared_screenshot_cache: SharedScreenshotCache = Arc::new(Mutex::new(ScreenshotCache::new()));
 
   // Initialize authentication service with storage path
-  let app_data_dir = dirs::home_dir()
-    .unwrap_or_else(|| std::path::PathBuf::from("/tmp"))
-    .join(".framesense");
-  let auth_service = AuthService::new().with_storage_path(app_data_dir);
-  let shared_auth_service: SharedAuthService = Arc::new(Mutex::new(auth_service));
+  let network_settings = NetworkSettingsStore::new().with_storage_dir(app_data_dir.clone());
+  let auth_service = AuthService::new()
+    .with_api_url(&settings_store.settings().api_url)
+    .with_storage_path(app_data_dir.clone())
+    .with_network_settings(network_settings.settings().clone());
+  let shared_network_settings: SharedNetworkSettings = Arc::new(Mutex::new(network_settings));
+  let shared_settings: SharedSettings = Arc::new(Mutex::new(settings_store));
+  let shared_auth_service: SharedAuthService = Arc::new(tokio::sync::RwLock::new(auth_service));
+
+  // Scroll capture sessions
+  let shared_scroll_capture: SharedScrollCapture =
+    Arc::new(Mutex::new(ScrollCaptureManager::new()));
+
+  // Recent captures by id
+  let shared_capture_store: SharedCaptureStore = Arc::new(Mutex::new(CaptureStore::new()));
+
+  // Provider API keys (keychain, or an encrypted file in the auth storage dir)
+  let shared_api_keys: SharedApiKeys = Arc::new(Mutex::new(ApiKeyStore::new(&app_data_dir)));
+
+  // AI spend per day
+  let shared_spend_tracker: SharedSpendTracker = Arc::new(Mutex::new(
+    SpendTracker::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // AI fallback chains
+  let shared_fallback_store: SharedFallbackStore = Arc::new(Mutex::new(
+    FallbackStore::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // System prompt and persona
+  let shared_ai_preferences: SharedAiPreferences = Arc::new(Mutex::new(
+    AiPreferencesStore::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // Client-side AI request rate limiting (memory only)
+  let shared_ai_rate_limiter: SharedAiRateLimiter = Arc::new(Mutex::new(RateLimiter::new()));
+
+  // Recent AI answers (memory only)
+  let shared_response_cache: SharedResponseCache = Arc::new(Mutex::new(ResponseCache::new()));
+
+  // Background tasks are registered now and start once the app is set up
+  let mut scheduler = Scheduler::new();
+  register_background_tasks(&mut scheduler);
+  let shared_scheduler: SharedScheduler = Arc::new(Mutex::new(scheduler));
+
+  // Conversation parameters share the auth storage dir
+  // Save directory / filename template settings
+  let shared_save_manager: SharedSaveManager = Arc::new(Mutex::new(
+    SaveManager::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // Overlay appearance preferences
+  let shared_overlay_config: SharedOverlayConfig = Arc::new(Mutex::new(
+    OverlayConfigStore::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // Named capture regions
+  let shared_preset_store: SharedPresetStore = Arc::new(Mutex::new(
+    PresetStore::new().with_storage_dir(app_data_dir.clone()),
+  ));
+
+  // Processor plugins
+  let shared_plugin_registry: SharedPluginRegistry = Arc::new(Mutex::new(
+    PluginRegistry::new().with_storage_dir(app_data_dir),
+  ));
+
+  // Watch mode
+  let shared_region_watcher: SharedRegionWatcher = Arc::new(Mutex::new(RegionWatcher::new()));
+
+  // Screen recording
+  let shared_screen_recorder: SharedScreenRecorder = Arc::new(Mutex::new(ScreenRecorder::new()));
+
+  // Idle monitor gating background work
+  let shared_idle_monitor: SharedIdleMonitor = Arc::new(Mutex::new(IdleMonitor::new()));
+  let shared_speech_player: SharedSpeechPlayer = Arc::new(Mutex::new(SpeechPlayer::new()));
   // Database access through backend API only - no direct connection
 
   tauri::Builder::default()
     .manage(shared_state)
     .manage(shared_overlay_manager)
+    .manage(shared_overlay_config)
     .manage(shared_screenshot_cache)
     .manage(shared_auth_service)
+    .manage(shared_network_settings)
+    .manage(shared_settings)
+    .manage(shared_api_keys)
+    .manage(shared_spend_tracker)
+    .manage(shared_fallback_store)
+    .manage(shared_ai_preferences)
+    .manage(shared_ai_rate_limiter)
+    .manage(shared_response_cache)
+    .manage(shared_scheduler)
+    .manage(shared_idle_monitor)
+    .manage(shared_speech_player)
+    .manage(shared_scroll_capture)
+    .manage(shared_screen_recorder)
+    .manage(shared_capture_store)
+    .manage(shared_save_manager)
+    .manage(shared_preset_store)
+    .manage(shared_plugin_registry)
+    .manage(shared_region_watcher)
+    .register_asynchronous_uri_scheme_protocol(
+      capture_protocol::SCHEME,
+      |ctx, request, responder| {
+        capture_protocol::handle(ctx.app_handle().clone(), request, responder)
+      },
+    )
     .plugin(
       tauri_plugin_global_shortcut::Builder::new()
         .with_handler(|app, shortcut, event| {
-          println!(
+          debug!(
             "🔥 GLOBAL SHORTCUT: {:?} - State: {:?}",
             shortcut,
             event.state()
           );
 
           if event.state() != ShortcutState::Pressed {
-            println!("⚪ Ignoring key release");
+            debug!("⚪ Ignoring key release");
             return;
           }
 
           // Jämför mot parsade Shortcut-objekt (robust mot plattformsnamn som Option vs Alt)
-          let alt_space: Shortcut = "Alt+Space".parse().unwrap();
-          let alt_c: Shortcut = "Alt+C".parse().unwrap();
-
-          if shortcut == &alt_space {
+          let action = app.try_state::<SharedSettings>().and_then(|settings| {
+            settings
+              .lock()
+              .unwrap()
+              .settings()
+              .shortcuts
+              .action_for(shortcut)
+          });
+          let escape = escape_shortcut();
+
+          if action == Some(ShortcutAction::ToggleWindow) {
             // TOGGLE MAIN WINDOW
             let app_clone = app.clone();
-            std::thread::spawn(move || {
-              std::thread::sleep(std::time::Duration::from_millis(50));
+            tauri::async_runtime::spawn(async move {
+              tokio::time::sleep(std::time::Duration::from_millis(50)).await;
               if let Some(window) = app_clone.get_webview_window("main") {
-                println!("🔄 Window exists, closing and saving state...");
+                info!("🔄 Window exists, closing and saving state...");
                 let _ = window.emit("save-state-and-close", ());
-                std::thread::sleep(std::time::Duration::from_millis(60));
+                tokio::time::sleep(std::time::Duration::from_millis(60)).await;
                 let _ = window.close();
 
                 if let Some(state) = app_clone.try_state::<SharedState>() {
@@ -953,52 +5466,78 @@ fn main() {
                       .as_secs(),
                   );
                 }
-                println!("🗑️ Window closed (Raycast-style)");
+                info!("🗑️ Window closed (Raycast-style)");
               } else {
-                println!("✨ No window exists...");
-                println!("🆕 Creating new window on current Space...");
-                // Behåll din ursprungliga tokio-runtime-stil
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                rt.block_on(async {
-                  if let Err(e) = create_main_window(app_clone.clone()).await {
-                    println!("❌ Failed to create window: {}", e);
-                  } else {
-                    println!("✅ New window created successfully!");
-                  }
-                });
+                info!("✨ No window exists...");
+                info!("🆕 Creating new window on current Space...");
+                if let Err(e) = create_main_window(app_clone.clone()).await {
+                  error!("❌ Failed to create window: {}", e);
+                } else {
+                  info!("✅ New window created successfully!");
+                }
               }
             });
-          } else if shortcut == &alt_c {
+          } else if action == Some(ShortcutAction::Capture) {
             // OPTIMIZED OVERLAY — funkar även utan UI
-            println!("📸 Alt+C — optimize overlay (no UI required)");
+            info!("📸 Alt+C — optimize overlay (no UI required)");
+            if !ensure_capture_permission(app) {
+              return;
+            }
             let app_clone = app.clone();
-            std::thread::spawn(move || {
+            tauri::async_runtime::spawn(async move {
               // Kolla om UI fanns från början
               let had_window_initially = app_clone.get_webview_window("main").is_some();
 
-              let rt = tokio::runtime::Runtime::new().unwrap();
-              rt.block_on(async {
-                // 1) Starta overlay/capture (headless om ingen UI finns)
-                let overlay_manager = app_clone.state::<SharedOverlayManager>();
-                if let Err(e) =
-                  create_transparent_overlay_optimized(app_clone.clone(), overlay_manager).await
-                {
-                  println!("❌ Failed to start optimized overlay: {}", e);
-                  return;
-                }
-                println!("✅ Optimized overlay triggered");
-
-                // 2) NYTT: Om appen INTE var uppe när vi startade,
-                //    skapa och visa main window EFTER capture är klar
-                if !had_window_initially {
-                  println!("🪄 No UI initially — will create window when capture completes");
-                  // Detta hanteras nu av process_screen_selection_optimized
-                  // när den är klar med capture
-                }
-              });
+              // 1) Starta overlay/capture (headless om ingen UI finns)
+              let overlay_manager = app_clone.state::<SharedOverlayManager>();
+              if let Err(e) =
+                create_transparent_overlay_optimized(app_clone.clone(), overlay_manager).await
+              {
+                error!("❌ Failed to start optimized overlay: {}", e);
+                return;
+              }
+              info!("✅ Optimized overlay triggered");
+
+              // 2) NYTT: Om appen INTE var uppe när vi startade,
+              //    skapa och visa main window EFTER capture är klar
+              if !had_window_initially {
+                info!("🪄 No UI initially — will create window when capture completes");
+                // Detta hanteras nu av process_screen_selection_optimized
+                // när den är klar med capture
+              }
+            });
+          } else if action == Some(ShortcutAction::RepeatCapture) {
+            // REPEAT LAST CAPTURE — no overlay
+            info!("🔁 Repeat last capture shortcut");
+            if !ensure_capture_permission(app) {
+              return;
+            }
+            let app_clone = app.clone();
+            tauri::async_runtime::spawn(async move {
+              if let Err(e) = run_recapture_last_region(&app_clone).await {
+                error!("❌ Repeat capture failed: {}", e);
+              }
+            });
+          } else if shortcut == &escape {
+            // CANCEL CAPTURE — only registered while the overlay is up
+            info!("⏹️ Escape — cancelling capture");
+            cancel_active_capture(app);
+          } else if let Some(preset) = app
+            .try_state::<SharedPresetStore>()
+            .and_then(|store| store.lock().unwrap().find_by_shortcut(shortcut).cloned())
+          {
+            info!("📌 Preset shortcut for '{}'", preset.name);
+            if !ensure_capture_permission(app) {
+              return;
+            }
+            let app_clone = app.clone();
+            tauri::async_runtime::spawn(async move {
+              if let Err(e) = run_preset_capture(&app_clone, &preset.name).await {
+                error!("❌ Preset capture failed: {}", e);
+              }
             });
           } else {
-            println!("⚪ Unhandled shortcut");
+            debug!("⚪ Unhandled shortcut");
           }
         })
         .build(),
@@ -1008,65 +5547,208 @@ fn main() {
     .plugin(tauri_plugin_http::init())
     .plugin(tauri_plugin_store::Builder::new().build())
     .setup(|app| {
+      if let Some(primary) = primary_instance {
+        let instance_app = app.handle().clone();
+        single_instance::listen(primary, move |request| {
+          handle_launch_request(&instance_app, request)
+        });
+      }
+      if let Err(e) = deep_link::register() {
+        warn!("⚠️ Deep links unavailable: {}", e);
+      }
+
+      // State persistence lives in the app data dir
+      let state_dir = app
+        .path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::path::PathBuf::from("/tmp/framesense"));
+      let shared_capture_history: SharedCaptureHistory =
+        Arc::new(Mutex::new(CaptureHistory::new(state_dir.join("history"))));
+      app.manage(shared_capture_history);
+      let shared_conversation_history: SharedConversationHistory = Arc::new(Mutex::new(
+        ConversationHistory::new(state_dir.join("conversations")),
+      ));
+      app.manage(shared_conversation_history);
+      // Restore what was saved last session before anything reads the in-memory state
+      let mut state_store = StateStore::new(state_dir);
+      *app.state::<SharedState>().lock().unwrap() = state_store.load();
+      let shared_state_store: SharedStateStore = Arc::new(Mutex::new(state_store));
+      app.manage(shared_state_store);
+
+      // Auth events reach every window through the app handle
+      // Setup runs on the main thread, outside the async runtime
+      app
+        .state::<SharedAuthService>()
+        .blocking_write()
+        .set_app_handle(app.handle().clone());
+
+      // Redaction zones are applied inside the capture pipeline itself
+      if let Ok(data_dir) = app.path().app_data_dir() {
+        redaction::init(data_dir);
+      }
+
       // Tray
       let quit_item = MenuItem::with_id(app, "quit", "Quit FrameSense", true, None::<&str>)?;
       let capture_item = MenuItem::with_id(app, "capture", "Start Capture", true, None::<&str>)?;
+      let delay_3_item =
+        MenuItem::with_id(app, "capture_delay_3", "Capture in 3s", true, None::<&str>)?;
+      let delay_5_item =
+        MenuItem::with_id(app, "capture_delay_5", "Capture in 5s", true, None::<&str>)?;
+      let delay_10_item = MenuItem::with_id(
+        app,
+        "capture_delay_10",
+        "Capture in 10s",
+        true,
+        None::<&str>,
+      )?;
+      let launch_at_login_item = tauri::menu::CheckMenuItem::with_id(
+        app,
+        "launch_at_login",
+        "Launch at Login",
+        true,
+        autostart::is_enabled(),
+        None::<&str>,
+      )?;
+      app.manage(LaunchAtLoginMenuItem(launch_at_login_item.clone()));
       let test_item = MenuItem::with_id(app, "test", "Test Command", true, None::<&str>)?;
 
-      let menu = Menu::with_items(app, &[&capture_item, &test_item, &quit_item])?;
+      // One entry per connected monitor
+      let mut monitor_items = Vec::new();
+      for monitor in ScreenCapture::get_screen_info().unwrap_or_default() {
+        monitor_items.push(MenuItem::with_id(
+          app,
+          format!("capture_monitor_{}", monitor.id),
+          format!("{} ({}x{})", monitor.name, monitor.width, monitor.height),
+          true,
+          None::<&str>,
+        )?);
+      }
+      let monitor_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = monitor_items
+        .iter()
+        .map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+      let monitor_menu = Submenu::with_items(
+        app,
+        "Capture Display",
+        !monitor_refs.is_empty(),
+        &monitor_refs,
+      )?;
+
+      let menu = Menu::with_items(
+        app,
+        &[
+          &capture_item,
+          &delay_3_item,
+          &delay_5_item,
+          &delay_10_item,
+          &monitor_menu,
+          &launch_at_login_item,
+          &test_item,
+          &quit_item,
+        ],
+      )?;
 
       let _tray = TrayIconBuilder::new()
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
         .on_menu_event(|app, event| match event.id().as_ref() {
           "quit" => {
-            println!("💀 Quit selected");
+            info!("💀 Quit selected");
             std::process::exit(0);
           }
           "capture" => {
-            println!("📸 Capture triggered from menu!");
+            info!("📸 Capture triggered from menu!");
             if let Some(window) = app.get_webview_window("main") {
               window.emit("show-capture-overlay", ()).unwrap();
-              println!("✅ Sent show-capture-overlay event to React");
+              info!("✅ Sent show-capture-overlay event to React");
             } else {
-              println!("❌ Main window not found");
+              error!("❌ Main window not found");
+            }
+          }
+          "capture_delay_3" => start_tray_delayed_capture(app, 3),
+          "capture_delay_5" => start_tray_delayed_capture(app, 5),
+          "capture_delay_10" => start_tray_delayed_capture(app, 10),
+          "launch_at_login" => {
+            // The checkbox has already toggled itself; keep it if applying fails
+            let enabled = !autostart::is_enabled();
+            if let Err(e) = set_launch_at_login(app.clone(), enabled) {
+              error!("❌ Failed to change launch at login: {}", e);
+              if let Some(item) = app.try_state::<LaunchAtLoginMenuItem>() {
+                let _ = item.0.set_checked(!enabled);
+              }
             }
           }
           "test" => {
-            println!("🧪 Test command triggered");
+            info!("🧪 Test command triggered");
+          }
+          id => {
+            if let Some(monitor_id) = id
+              .strip_prefix("capture_monitor_")
+              .and_then(|monitor_id| monitor_id.parse::<u32>().ok())
+            {
+              start_tray_monitor_capture(app, monitor_id);
+            }
           }
-          _ => {}
         })
         .build(app)?;
 
       // Register global hotkeys
-      println!("🚀 Setting up FrameSense background app...");
+      info!("🚀 Setting up FrameSense background app...");
 
-      let shortcut_toggle = "Alt+Space".parse::<Shortcut>().unwrap();
-      let shortcut_optimize = "Alt+C".parse::<Shortcut>().unwrap();
+      let settings = app
+        .state::<SharedSettings>()
+        .lock()
+        .unwrap()
+        .settings()
+        .clone();
+      apply_settings(app.handle(), None, &settings);
+      upload_crash_reports_if_allowed(app.handle());
+
+      // Region presets bound to their own shortcuts
+      let presets = app
+        .state::<SharedPresetStore>()
+        .lock()
+        .unwrap()
+        .list()
+        .to_vec();
+      for preset in &presets {
+        register_preset_shortcut(app.handle(), preset);
+      }
 
-      for (name, sc) in [("Alt+Space", shortcut_toggle), ("Alt+C", shortcut_optimize)] {
-        match app.global_shortcut().register(sc) {
-          Ok(_) => println!("✅ Global shortcut {} registered successfully!", name),
-          Err(e) => println!("❌ Failed to register {}: {}", name, e),
-        }
+      info!(
+        "✅ FrameSense is ready! Press {} (toggle) or {} (optimize overlay)",
+        settings.shortcuts.toggle_window, settings.shortcuts.capture
+      );
+      if let Some(repeat_capture) = &settings.shortcuts.repeat_capture {
+        info!("🔁 {} repeats the last capture", repeat_capture);
       }
 
-      println!("✅ FrameSense is ready! Press Alt+Space (toggle) or Alt+C (optimize overlay)");
+      info!("✅ Frontend event listener will be set up after app starts");
 
-      println!("✅ Frontend event listener will be set up after app starts");
+      // Recurring work (session refresh, tier checks, cleanup, ...)
+      app
+        .state::<SharedScheduler>()
+        .lock()
+        .unwrap()
+        .start(app.handle().clone());
 
       // Close initial window - we'll create fresh ones on Alt+Space (Raycast-style)
       if let Some(window) = app.get_webview_window("main") {
         let _ = window.close();
-        println!("🗑️ Closed initial window - will create fresh ones on current Space");
+        info!("🗑️ Closed initial window - will create fresh ones on current Space");
       }
 
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
       extract_text_ocr,
+      // Permission commands
       check_permissions,
+      get_permission_status,
+      check_screen_recording_permission,
+      request_screen_recording_permission,
+      check_accessibility_permission,
+      request_accessibility_permission,
       //test_screen_capture,
       //capture_screen_area,
 
@@ -1074,17 +5756,152 @@ fn main() {
       create_transparent_overlay_optimized,
       close_transparent_overlay_optimized,
       process_screen_selection_optimized,
-      //cleanup_overlay_manager,
-
+      process_lasso_selection,
+      capture_window_at_point,
+      cancel_capture,
+      set_overlay_idle_timeout,
+      destroy_overlay,
+      get_overlay_stats,
+      // Overlay appearance commands
+      get_overlay_config,
+      set_overlay_config,
+      // Keyboard selection commands
+      set_selection_bounds,
+      set_selection_constraints,
+      get_selection_constraints,
+      nudge_selection,
+      confirm_selection,
       // FAS 3: Optimized screenshot commands
       capture_screen_area_optimized,
       capture_screen_area_multi_screen_optimized,
       clear_screenshot_cache,
       get_screenshot_cache_stats,
+      get_response_cache_stats,
+      clear_response_cache,
+      set_cache_eviction_policy,
+      set_cache_config,
+      get_cache_config,
       cleanup_screenshot_cache,
       resize_screenshot_buffer,
+      // Capture store and print commands
+      get_capture,
+      get_capture_url,
+      print_capture,
+      // Capture transfer commands
+      stream_capture,
+      // Annotation commands
+      annotate_capture,
+      pixelate_regions,
+      // Clipboard commands
+      copy_capture_to_clipboard,
+      // Image import commands
+      analyze_image_file,
+      analyze_clipboard_image,
+      // Result export commands
+      export_result,
+      export_searchable_pdf,
+      // Share commands
+      list_share_destinations,
+      set_share_secret,
+      remove_share_secret,
+      share_capture,
+      // Notes commands
+      set_notion_token,
+      remove_notion_token,
+      send_to_notes,
+      // Channel commands
+      set_channel_token,
+      connect_channel,
+      remove_channel_token,
+      post_to_channel,
+      // Save commands
+      save_capture_to_file,
+      prepare_capture_drag,
+      get_save_settings,
+      set_save_settings,
+      // Capture history commands
+      list_capture_history,
+      search_capture_history,
+      open_capture_from_history,
+      delete_capture_history_entry,
+      // Redaction zone commands
+      list_redaction_zones,
+      add_redaction_zone,
+      update_redaction_zone,
+      remove_redaction_zone,
+      // Delayed capture commands
+      capture_with_delay,
+      // Color picker commands
+      pick_color,
+      start_color_picker,
+      stop_color_picker,
+      // Magnifier commands
+      get_magnifier_patch,
+      // Measurement commands
+      measure_selection,
+      // Repeat last capture
+      recapture_last_region,
+      // Watch mode commands
+      start_region_watch,
+      stop_region_watch,
+      list_region_watches,
+      // Region preset commands
+      save_region_preset,
+      list_region_presets,
+      delete_region_preset,
+      capture_preset,
+      // Capture size commands
+      set_capture_max_dimensions,
+      get_capture_max_dimensions,
+      // Freeze-screen commands
+      set_freeze_mode,
+      get_freeze_mode,
+      get_frozen_frame,
+      // Monitor commands
+      list_monitors,
+      capture_monitor,
+      map_bounds_to_physical,
+      map_bounds_to_logical,
+      get_capture_backend,
+      request_screen_capture_permission,
+      // Scrolling capture commands
+      start_scroll_capture,
+      stop_scroll_capture,
+      // Conversation parameter commands
+      set_conversation_params,
+      get_conversation_params,
+      clear_conversation_params,
+      resolve_message_params,
+      // Screen recording commands
+      start_recording,
+      pause_recording,
+      resume_recording,
+      stop_recording,
+      get_recording_status,
+      record_gif,
+      // Accessibility commands
+      announce_accessibility,
+      set_accessibility_announcements,
+      get_accessibility_announcements,
+      // Text-to-speech commands
+      speak_text,
+      pause_speech,
+      resume_speech,
+      stop_speech,
+      get_speech_status,
+      // Idle detection commands
+      get_idle_status,
+      set_idle_threshold,
       // Authentication commands
       login_user,
+      submit_totp_code,
+      forget_trusted_device,
+      register_user,
+      start_guest_session,
+      end_guest_session,
+      activate_license,
+      deactivate_license,
+      login_with_provider,
       logout_user,
       get_current_user,
       save_user_session,
@@ -1092,8 +5909,59 @@ fn main() {
       //handle_payment_success,
       get_available_models,
       can_use_model,
+      record_usage,
+      get_usage_status,
+      start_checkout,
+      open_billing_portal,
       test_deep_link,
       clear_user_session,
+      // Settings commands
+      get_settings,
+      update_settings,
+      // Logging commands
+      get_recent_logs,
+      set_log_level,
+      // Diagnostics commands
+      run_diagnostics,
+      // Data retention commands
+      purge_local_data,
+      delete_all_local_data,
+      // Background task commands
+      list_background_tasks,
+      set_background_task,
+      run_background_task,
+      // Launch at login commands
+      set_launch_at_login,
+      get_launch_at_login,
+      // Crash report commands
+      get_crash_reports,
+      clear_crash_reports,
+      // Network settings commands
+      get_network_settings,
+      set_network_settings,
+      // Plugin commands
+      list_plugins,
+      reload_plugins,
+      run_plugin,
+      // AI analysis commands
+      analyze_capture,
+      smart_capture,
+      translate_capture,
+      list_local_models,
+      estimate_request_cost,
+      get_fallback_settings,
+      set_fallback_settings,
+      get_ai_preferences,
+      set_ai_preferences,
+      get_daily_spend,
+      // Conversation history commands
+      list_conversations,
+      get_conversation,
+      delete_conversation,
+      export_conversation,
+      get_ai_providers,
+      set_api_key,
+      remove_api_key,
       // App state management
       save_app_state,
       get_app_state,
@@ -1110,7 +5978,7 @@ fn main() {
     .on_window_event(|window, event| match event {
       WindowEvent::CloseRequested { api, .. } => {
         if window.label() == "main" {
-          println!("🚪 Main window close requested");
+          info!("🚪 Main window close requested");
         } else {
           window.hide().unwrap();
           api.prevent_close();
@@ -1120,12 +5988,16 @@ fn main() {
     })
     .build(tauri::generate_context!())
     .expect("error while running tauri application")
-    .run(|app_handle, event| match event {
+    .run(move |app_handle, event| match event {
       RunEvent::Ready => {
-        println!("🎯 App ready!");
+        debug!("🎯 App ready!");
+        open_deep_links(app_handle, &startup_args);
         // Set up global event listener for frontend_ready
-        app_handle.listen_any("frontend_ready", |event| {
-          println!("✅ Frontend is ready, safe to close or reopen windows");
+        let ready_app = app_handle.clone();
+        // The first main window to load learns what was restored from last session
+        let state_announced = std::sync::Once::new();
+        app_handle.listen_any("frontend_ready", move |event| {
+          info!("✅ Frontend is ready, safe to close or reopen windows");
 
           if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&event.payload()) {
             let window_type = payload
@@ -1137,7 +6009,7 @@ fn main() {
               .and_then(|v| v.as_u64())
               .unwrap_or(0);
 
-            println!(
+            info!(
               "📡 Frontend ready signal received - Window: {}, Timestamp: {}",
               window_type, timestamp
             );
@@ -1145,28 +6017,36 @@ fn main() {
             // Run window-state logic here based on window type
             match window_type {
               "main" => {
-                println!("🏠 Main window frontend is ready");
-                // Main window specific logic can go here
-                // For example: show window if it was hidden
-                // Note: We can't access app_handle here, but that's fine for this basic implementation
-                println!("👁️ Main window frontend ready - window operations would go here");
+                info!("🏠 Main window frontend is ready");
+                state_announced.call_once(|| {
+                  if let Some(state) = ready_app.try_state::<SharedState>() {
+                    let restored = StateRestored::from_state(&state.lock().unwrap());
+                    let _ = ready_app.emit(state_store::STATE_RESTORED_EVENT, restored);
+                  }
+                });
               }
               "overlay" => {
-                println!("🎯 Overlay window frontend is ready");
+                debug!("🎯 Overlay window frontend is ready");
                 // Overlay window specific logic can go here
               }
               _ => {
-                println!("❓ Unknown window type: {}", window_type);
+                info!("❓ Unknown window type: {}", window_type);
               }
             }
           } else {
-            println!("⚠️ Failed to parse frontend_ready payload");
+            warn!("⚠️ Failed to parse frontend_ready payload");
           }
         });
       }
       RunEvent::ExitRequested { api, .. } => {
         api.prevent_exit();
       }
+      // macOS delivers framesense:// links as an open event instead of arguments
+      #[cfg(target_os = "macos")]
+      RunEvent::Opened { urls } => {
+        let links: Vec<String> = urls.iter().map(|url| url.to_string()).collect();
+        open_deep_links(app_handle, &links);
+      }
       _ => {}
     });
 }
@@ -0,0 +1,125 @@
+// Note-taking integrations - appends a capture with its OCR text and AI summary to an
+// Obsidian vault (a Markdown note plus the PNG as an attachment) or to a Notion page
+// through the Notion API. Settings live in `AppSettings.notes`; the Notion integration
+// token is stored by name in the API key store, never in settings.json.
+mod notion;
+mod obsidian;
+
+use crate::network::NetworkSettings;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Name of the Notion integration token in the API key store
+pub const NOTION_SECRET: &str = "notes-notion";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotesTarget {
+  Obsidian,
+  Notion,
+}
+
+impl NotesTarget {
+  pub fn display_name(&self) -> &'static str {
+    match self {
+      NotesTarget::Obsidian => "Obsidian",
+      NotesTarget::Notion => "Notion",
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ObsidianSettings {
+  // Folder of the vault (the one containing .obsidian)
+  pub vault_path: Option<PathBuf>,
+  // Note the captures are appended to, relative to the vault
+  pub note: String,
+  // Where the PNGs go, relative to the vault
+  pub attachments_folder: String,
+}
+
+impl Default for ObsidianSettings {
+  fn default() -> Self {
+    Self {
+      vault_path: None,
+      note: "FrameSense/Captures.md".to_string(),
+      attachments_folder: "FrameSense/attachments".to_string(),
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotionSettings {
+  // Page id or link; the page must be shared with the integration
+  pub page: String,
+  // Upload captures with the default share destination so the page can show them
+  pub share_images: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotesSettings {
+  // Used when `send_to_notes` gets no target
+  pub default_target: Option<NotesTarget>,
+  pub obsidian: ObsidianSettings,
+  pub notion: NotionSettings,
+}
+
+impl NotesSettings {
+  pub fn validate(&self) -> Result<(), String> {
+    if let Some(vault) = &self.obsidian.vault_path {
+      if !vault.is_absolute() {
+        return Err("Obsidian vault path must be absolute".to_string());
+      }
+    }
+    for (label, value) in [
+      ("Obsidian note", &self.obsidian.note),
+      (
+        "Obsidian attachments folder",
+        &self.obsidian.attachments_folder,
+      ),
+    ] {
+      obsidian::vault_relative(label, value)?;
+    }
+    if !self.notion.page.trim().is_empty() {
+      notion::page_id(&self.notion.page)?;
+    }
+    Ok(())
+  }
+}
+
+/// What gets appended to the notes
+pub struct NoteEntry {
+  pub capture_id: String,
+  pub created_at: String,
+  pub png: Vec<u8>,
+  pub ocr_text: Option<String>,
+  pub summary: Option<String>,
+  pub source_app: Option<String>,
+  // Public link to the capture (Notion can only show images it can fetch)
+  pub image_url: Option<String>,
+}
+
+/// Append `entry` to `target`; returns where it went (note path or page URL)
+pub async fn send(
+  target: NotesTarget,
+  settings: &NotesSettings,
+  notion_token: Option<String>,
+  network: NetworkSettings,
+  entry: NoteEntry,
+) -> Result<String, String> {
+  match target {
+    NotesTarget::Obsidian => {
+      let obsidian = settings.obsidian.clone();
+      tauri::async_runtime::spawn_blocking(move || obsidian::append(&obsidian, &entry))
+        .await
+        .map_err(|e| format!("Obsidian export failed: {}", e))?
+    }
+    NotesTarget::Notion => {
+      let token = notion_token.ok_or("Add the Notion integration token first")?;
+      notion::append(&settings.notion, &token, network, &entry).await
+    }
+  }
+}
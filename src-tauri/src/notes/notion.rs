@@ -0,0 +1,114 @@
+// Notion: blocks are appended to the configured page
+// (https://developers.notion.com/reference/patch-block-children). The API takes images
+// only by URL, so the capture is embedded when it has been shared; otherwise the page
+// gets the text alone.
+use super::{NoteEntry, NotionSettings};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use serde_json::{json, Value};
+use tracing::info;
+
+const API_URL: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+// API limits: characters per rich text object, blocks per request
+const MAX_TEXT_CHARS: usize = 2000;
+const MAX_BLOCKS: usize = 100;
+
+pub async fn append(
+  settings: &NotionSettings,
+  token: &str,
+  network: NetworkSettings,
+  entry: &NoteEntry,
+) -> Result<String, String> {
+  if settings.page.trim().is_empty() {
+    return Err("Choose a Notion page in settings first".to_string());
+  }
+  let page_id = page_id(&settings.page)?;
+  let mut children = blocks(entry);
+  children.truncate(MAX_BLOCKS);
+
+  let response = network::http_client(&network)?
+    .patch(format!("{}/blocks/{}/children", API_URL, page_id))
+    .bearer_auth(token.trim())
+    .header("Notion-Version", NOTION_VERSION)
+    .json(&json!({ "children": children }))
+    .send_with_retry()
+    .await?;
+  let status = response.status();
+  if !status.is_success() {
+    let body: Value = response.json().await.unwrap_or_default();
+    let message = body["message"].as_str().unwrap_or_default();
+    return Err(match status.as_u16() {
+      401 => "Notion rejected the integration token".to_string(),
+      404 => "Notion page not found - share it with the integration".to_string(),
+      _ => format!("Notion request failed: {} {}", status, message),
+    });
+  }
+  info!("🗒️ Appended capture {} to Notion", entry.capture_id);
+  Ok(format!("https://www.notion.so/{}", page_id))
+}
+
+fn blocks(entry: &NoteEntry) -> Vec<Value> {
+  let mut title = format!("Capture {}", entry.created_at);
+  if let Some(app) = &entry.source_app {
+    title.push_str(&format!(" - {}", app));
+  }
+  let mut blocks = vec![text_block("heading_3", &title)];
+  if let Some(url) = &entry.image_url {
+    blocks.push(json!({
+      "object": "block",
+      "type": "image",
+      "image": { "type": "external", "external": { "url": url } }
+    }));
+  }
+  if let Some(summary) = &entry.summary {
+    blocks.push(text_block("paragraph", "Summary"));
+    blocks.extend(chunks(summary).map(|chunk| text_block("quote", &chunk)));
+  }
+  if let Some(text) = &entry.ocr_text {
+    blocks.push(text_block("paragraph", "Text"));
+    blocks.extend(chunks(text).map(|chunk| {
+      json!({
+        "object": "block",
+        "type": "code",
+        "code": { "language": "plain text", "rich_text": rich_text(&chunk) }
+      })
+    }));
+  }
+  blocks
+}
+
+fn text_block(kind: &str, text: &str) -> Value {
+  json!({ "object": "block", "type": kind, kind: { "rich_text": rich_text(text) } })
+}
+
+fn rich_text(text: &str) -> Value {
+  json!([{ "type": "text", "text": { "content": text } }])
+}
+
+fn chunks(text: &str) -> impl Iterator<Item = String> + '_ {
+  let chars: Vec<char> = text.trim().chars().collect();
+  (0..chars.len()).step_by(MAX_TEXT_CHARS).map(move |start| {
+    chars[start..(start + MAX_TEXT_CHARS).min(chars.len())]
+      .iter()
+      .collect()
+  })
+}
+
+// Page id from an id or a link ("https://www.notion.so/Title-<32 hex chars>?v=..."), in
+// the dashed form the API uses
+pub(super) fn page_id(page: &str) -> Result<String, String> {
+  let path = page.trim().split(['?', '#']).next().unwrap_or_default();
+  let hex: String = path.chars().filter(|c| *c != '-').collect();
+  let hex = hex
+    .get(hex.len().saturating_sub(32)..)
+    .filter(|id| id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()))
+    .ok_or_else(|| format!("'{}' is not a Notion page id or link", page.trim()))?;
+  Ok(format!(
+    "{}-{}-{}-{}-{}",
+    &hex[..8],
+    &hex[8..12],
+    &hex[12..16],
+    &hex[16..20],
+    &hex[20..]
+  ))
+}
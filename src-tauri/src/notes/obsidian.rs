@@ -0,0 +1,88 @@
+// Obsidian: the PNG is written to the vault's attachments folder and a section with an
+// embed (![[file]]), the OCR text and the summary is appended to the note. Obsidian picks
+// up both files on its own.
+use super::{NoteEntry, ObsidianSettings};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use tracing::info;
+
+pub fn append(settings: &ObsidianSettings, entry: &NoteEntry) -> Result<String, String> {
+  let vault = settings
+    .vault_path
+    .as_ref()
+    .ok_or("Choose your Obsidian vault in settings first")?;
+  if !vault.is_dir() {
+    return Err(format!("Obsidian vault not found at {}", vault.display()));
+  }
+  let note = vault.join(vault_relative("Obsidian note", &settings.note)?);
+  let note = if note.extension().is_none() {
+    note.with_extension("md")
+  } else {
+    note
+  };
+  let attachments = vault.join(vault_relative(
+    "Obsidian attachments folder",
+    &settings.attachments_folder,
+  )?);
+
+  fs::create_dir_all(&attachments)
+    .map_err(|e| format!("Failed to create attachments folder: {}", e))?;
+  let file_name = format!("framesense-{}.png", entry.capture_id);
+  fs::write(attachments.join(&file_name), &entry.png)
+    .map_err(|e| format!("Failed to save attachment: {}", e))?;
+
+  if let Some(parent) = note.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create note folder: {}", e))?;
+  }
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&note)
+    .map_err(|e| format!("Failed to open note: {}", e))?;
+  file
+    .write_all(render(entry, &file_name).as_bytes())
+    .map_err(|e| format!("Failed to write note: {}", e))?;
+  info!(
+    "🗒️ Appended capture {} to {}",
+    entry.capture_id,
+    note.display()
+  );
+  Ok(note.to_string_lossy().to_string())
+}
+
+fn render(entry: &NoteEntry, file_name: &str) -> String {
+  let created = chrono::DateTime::parse_from_rfc3339(&entry.created_at)
+    .map(|date| {
+      date
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+    })
+    .unwrap_or_else(|_| entry.created_at.clone());
+  let mut out = format!("\n## {}", created);
+  if let Some(app) = &entry.source_app {
+    out.push_str(&format!(" - {}", app));
+  }
+  out.push_str(&format!("\n\n![[{}]]\n", file_name));
+  if let Some(summary) = &entry.summary {
+    out.push_str(&format!("\n### Summary\n\n{}\n", summary.trim()));
+  }
+  if let Some(text) = &entry.ocr_text {
+    out.push_str(&format!("\n### Text\n\n```\n{}\n```\n", text.trim()));
+  }
+  out
+}
+
+// Keep configured paths inside the vault
+pub(super) fn vault_relative(label: &str, value: &str) -> Result<PathBuf, String> {
+  let path = Path::new(value.trim());
+  if path.as_os_str().is_empty()
+    || path
+      .components()
+      .any(|component| !matches!(component, Component::Normal(_)))
+  {
+    return Err(format!("{} must be a path inside the vault", label));
+  }
+  Ok(path.to_path_buf())
+}
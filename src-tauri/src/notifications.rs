@@ -0,0 +1,124 @@
+// Completion notifications - when a capture or an AI answer finishes while the main
+// window is hidden or in the background (headless Alt+C, user alt-tabbed away), post a
+// native notification. Linux notification servers show an "Open result" button; on
+// macOS/Windows the plugin's notification is plain and clicking it focuses the app.
+use tauri::Manager;
+use tracing::{debug, warn};
+
+// Longest notification body; answers are cut at a word boundary
+const MAX_BODY_CHARS: usize = 140;
+#[cfg(target_os = "linux")]
+const OPEN_ACTION: &str = "open";
+#[cfg(target_os = "linux")]
+const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+#[cfg(target_os = "linux")]
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// True when the user would not see a result appear in the main window
+pub fn main_window_unattended(app: &tauri::AppHandle) -> bool {
+  match app.get_webview_window("main") {
+    Some(window) => !window.is_visible().unwrap_or(false) || !window.is_focused().unwrap_or(false),
+    None => true,
+  }
+}
+
+pub fn summarize(text: &str) -> String {
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.chars().count() <= MAX_BODY_CHARS {
+    return text;
+  }
+  let cut: String = text.chars().take(MAX_BODY_CHARS).collect();
+  let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+  format!("{}…", cut)
+}
+
+/// Post a notification; `on_open` runs when the user picks "Open result"
+#[cfg(target_os = "linux")]
+pub fn post<F>(_app: &tauri::AppHandle, title: &str, body: &str, on_open: F)
+where
+  F: FnOnce() + Send + 'static,
+{
+  let (title, body) = (title.to_string(), body.to_string());
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = post_and_wait(&title, &body, on_open).await {
+      warn!("⚠️ Failed to show notification: {}", e);
+    }
+  });
+}
+
+// Subscribes before showing so a quick click can't be missed, then waits on the bus
+// until the user picks the action or the notification goes away
+#[cfg(target_os = "linux")]
+async fn post_and_wait<F>(title: &str, body: &str, on_open: F) -> Result<(), String>
+where
+  F: FnOnce(),
+{
+  use futures_util::StreamExt;
+
+  let connection = zbus::Connection::session()
+    .await
+    .map_err(|e| e.to_string())?;
+  let proxy = zbus::Proxy::new(
+    &connection,
+    NOTIFICATIONS_NAME,
+    NOTIFICATIONS_PATH,
+    NOTIFICATIONS_NAME,
+  )
+  .await
+  .map_err(|e| e.to_string())?;
+  let mut invoked = proxy
+    .receive_signal("ActionInvoked")
+    .await
+    .map_err(|e| e.to_string())?;
+  let mut closed = proxy
+    .receive_signal("NotificationClosed")
+    .await
+    .map_err(|e| e.to_string())?;
+
+  let handle = notify_rust::Notification::new()
+    .appname("FrameSense")
+    .summary(title)
+    .body(body)
+    .action(OPEN_ACTION, "Open result")
+    .show_async()
+    .await
+    .map_err(|e| e.to_string())?;
+  let id = handle.id();
+  debug!("🔔 Notification posted: {}", title);
+
+  loop {
+    tokio::select! {
+      Some(message) = invoked.next() => {
+        if let Ok((notification, action)) = message.body().deserialize::<(u32, String)>() {
+          if notification == id {
+            if action == OPEN_ACTION {
+              on_open();
+            }
+            return Ok(());
+          }
+        }
+      }
+      Some(message) = closed.next() => {
+        if let Ok((notification, _)) = message.body().deserialize::<(u32, u32)>() {
+          if notification == id {
+            return Ok(());
+          }
+        }
+      }
+      else => return Ok(()),
+    }
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn post<F>(app: &tauri::AppHandle, title: &str, body: &str, _on_open: F)
+where
+  F: FnOnce() + Send + 'static,
+{
+  use tauri_plugin_notification::NotificationExt;
+
+  if let Err(e) = app.notification().builder().title(title).body(body).show() {
+    warn!("⚠️ Failed to show notification: {}", e);
+  }
+  debug!("🔔 Notification posted: {}", title);
+}
@@ -2,6 +2,7 @@
 // use tesseract::Tesseract; // Disabled for GitHub Actions
 use base64::Engine;
 use image::{DynamicImage, GenericImageView};
+use tracing::info;
 
 pub struct OCRService;
 
@@ -11,6 +12,11 @@ impl OCRService {
     Ok(Self)
   }
 
+  // Recognition engine compiled into this build (None while Tesseract is disabled)
+  pub fn engine_name() -> Option<&'static str> {
+    None
+  }
+
   pub fn extract_text(&self, image_data: &str) -> Result<OCRResult, String> {
     // Remove data:image/png;base64, prefix if exists
     let base64_data = if image_data.starts_with("data:image") {
@@ -37,7 +43,7 @@ impl OCRService {
       ));
     }
 
-    println!("📏 Image dimensions: {}x{} pixels", width, height);
+    info!("📏 Image dimensions: {}x{} pixels", width, height);
 
     // Return placeholder result (Tesseract disabled for GitHub Actions)
     Ok(OCRResult {
@@ -0,0 +1,289 @@
+// Eyedropper: reads the color under the cursor (or at a given point) together with a
+// magnified preview of the surrounding pixels, and streams live updates while the
+// overlay is open. The same magnification backs the selection loupe.
+use super::screen_capture::ScreenCapture;
+use image::{imageops, Rgba, RgbaImage};
+use screenshots::Screen;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+use tracing::info;
+
+// Pixels around the picked one included in the preview (in each direction)
+const PREVIEW_RADIUS: u32 = 5;
+// Each preview pixel is drawn as a ZOOM x ZOOM block
+const PREVIEW_ZOOM: u32 = 12;
+pub const LIVE_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+// Loupe: pixels around the cursor and zoom limits
+pub const MAGNIFIER_RADIUS: u32 = 8;
+const DEFAULT_MAGNIFIER_ZOOM: u32 = 8;
+const MAX_MAGNIFIER_ZOOM: u32 = 32;
+
+static PICKER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct PickedColor {
+  pub hex: String,
+  pub rgb: [u8; 3],
+  pub hsl: [f32; 3], // Hue in degrees, saturation and lightness in percent
+  pub x: i32,        // Overlay coordinates of the picked point
+  pub y: i32,
+  pub preview: String, // Magnified PNG data URL, picked pixel in the center
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MagnifierPatch {
+  pub image_data: String, // PNG data URL, center pixel outlined
+  pub zoom: u32,
+  pub hex: String, // Color of the center pixel
+}
+
+pub fn set_active(active: bool) {
+  PICKER_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+pub fn is_active() -> bool {
+  PICKER_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Color at a point in overlay coordinates
+pub fn pick_color_at(x: i32, y: i32) -> Result<PickedColor, String> {
+  let total_area = ScreenCapture::get_total_screen_area()?;
+  let abs_x = x + total_area.min_x;
+  let abs_y = y + total_area.min_y;
+
+  let (patch, pixel) = capture_patch(abs_x, abs_y, PREVIEW_RADIUS)?;
+  let rgb = [pixel[0], pixel[1], pixel[2]];
+
+  Ok(PickedColor {
+    hex: format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2]),
+    rgb,
+    hsl: rgb_to_hsl(rgb),
+    x,
+    y,
+    preview: magnified_preview(&patch)?,
+  })
+}
+
+/// Color under the mouse cursor
+pub fn pick_color_under_cursor() -> Result<PickedColor, String> {
+  let (abs_x, abs_y) = cursor_position().ok_or("Could not read the cursor position")?;
+  let total_area = ScreenCapture::get_total_screen_area()?;
+  pick_color_at(abs_x - total_area.min_x, abs_y - total_area.min_y)
+}
+
+/// Emit `color-picker-update` whenever the color under the cursor changes, until
+/// the picker is stopped or `keep_running` returns false (e.g. the overlay closed)
+pub async fn run_live_updates(app: tauri::AppHandle, keep_running: impl Fn() -> bool) {
+  let mut last: Option<(i32, i32, [u8; 3])> = None;
+  while is_active() && keep_running() {
+    if let Ok(color) = tauri::async_runtime::spawn_blocking(pick_color_under_cursor)
+      .await
+      .map_err(|e| e.to_string())
+      .and_then(|result| result)
+    {
+      let key = (color.x, color.y, color.rgb);
+      if last != Some(key) {
+        let _ = app.emit("color-picker-update", &color);
+        last = Some(key);
+      }
+    }
+    tokio::time::sleep(LIVE_UPDATE_INTERVAL).await;
+  }
+  set_active(false);
+  info!("🎨 Color picker stopped");
+}
+
+/// Zoomed loupe image of a patch whose center pixel is the one under the cursor
+pub fn magnifier_patch(patch: &RgbaImage, zoom: Option<u32>) -> Result<MagnifierPatch, String> {
+  let zoom = zoom
+    .unwrap_or(DEFAULT_MAGNIFIER_ZOOM)
+    .clamp(2, MAX_MAGNIFIER_ZOOM);
+  let pixel = patch.get_pixel(patch.width() / 2, patch.height() / 2);
+  Ok(MagnifierPatch {
+    image_data: magnify(patch, zoom)?,
+    zoom,
+    hex: format!("#{:02X}{:02X}{:02X}", pixel[0], pixel[1], pixel[2]),
+  })
+}
+
+/// Live pixels around a point in overlay coordinates (used when the screen isn't frozen)
+pub fn live_patch(x: i32, y: i32, radius: u32) -> Result<RgbaImage, String> {
+  let total_area = ScreenCapture::get_total_screen_area()?;
+  capture_patch(x + total_area.min_x, y + total_area.min_y, radius).map(|(patch, _)| patch)
+}
+
+// Square of (2 * radius + 1) logical pixels centered on the point, plus the pixel at the
+// point. Parts past the edge of the point's screen stay transparent, so the point is
+// always in the center. On HiDPI displays the capture is scaled back down so one image
+// pixel = one logical pixel.
+fn capture_patch(abs_x: i32, abs_y: i32, radius: u32) -> Result<(RgbaImage, Rgba<u8>), String> {
+  let screen = Screen::from_point(abs_x, abs_y)
+    .map_err(|e| format!("No screen at ({}, {}): {}", abs_x, abs_y, e))?;
+  let display = &screen.display_info;
+  let size = radius * 2 + 1;
+
+  // The part of the square that is on this screen
+  let (square_left, square_top) = (abs_x - radius as i32, abs_y - radius as i32);
+  let left = square_left.max(display.x);
+  let top = square_top.max(display.y);
+  let right = (square_left + size as i32).min(display.x + display.width as i32);
+  let bottom = (square_top + size as i32).min(display.y + display.height as i32);
+  let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+
+  let image = screen
+    .capture_area(left - display.x, top - display.y, width, height)
+    .map_err(|e| format!("Failed to capture pixels: {}", e))?;
+  let mut visible = RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())
+    .ok_or("Failed to create RGBA image from screenshot")?;
+  super::redaction::apply_to_capture(
+    &mut visible,
+    display.id,
+    left - display.x,
+    top - display.y,
+    width,
+    height,
+  );
+  if visible.dimensions() != (width, height) {
+    visible = imageops::resize(&visible, width, height, imageops::FilterType::Nearest);
+  }
+
+  let mut patch = RgbaImage::new(size, size);
+  imageops::replace(
+    &mut patch,
+    &visible,
+    (left - square_left) as i64,
+    (top - square_top) as i64,
+  );
+  let pixel = *visible.get_pixel((abs_x - left) as u32, (abs_y - top) as u32);
+  Ok((patch, pixel))
+}
+
+fn magnified_preview(patch: &RgbaImage) -> Result<String, String> {
+  magnify(patch, PREVIEW_ZOOM)
+}
+
+// Draw every pixel as a zoom x zoom block and outline the center one
+fn magnify(patch: &RgbaImage, zoom: u32) -> Result<String, String> {
+  let mut preview = imageops::resize(
+    patch,
+    patch.width() * zoom,
+    patch.height() * zoom,
+    imageops::FilterType::Nearest,
+  );
+
+  let start_x = (patch.width() / 2) * zoom;
+  let start_y = (patch.height() / 2) * zoom;
+  let outline = Rgba([255, 0, 0, 255]);
+  for offset in 0..zoom {
+    preview.put_pixel(start_x + offset, start_y, outline);
+    preview.put_pixel(start_x + offset, start_y + zoom - 1, outline);
+    preview.put_pixel(start_x, start_y + offset, outline);
+    preview.put_pixel(start_x + zoom - 1, start_y + offset, outline);
+  }
+
+  ScreenCapture::encode_rgba_to_base64(preview)
+}
+
+pub fn rgb_to_hsl(rgb: [u8; 3]) -> [f32; 3] {
+  let r = rgb[0] as f32 / 255.0;
+  let g = rgb[1] as f32 / 255.0;
+  let b = rgb[2] as f32 / 255.0;
+  let max = r.max(g).max(b);
+  let min = r.min(g).min(b);
+  let lightness = (max + min) / 2.0;
+
+  if max == min {
+    return [0.0, 0.0, (lightness * 100.0).round()];
+  }
+
+  let delta = max - min;
+  let saturation = if lightness > 0.5 {
+    delta / (2.0 - max - min)
+  } else {
+    delta / (max + min)
+  };
+  let hue = if max == r {
+    (g - b) / delta + if g < b { 6.0 } else { 0.0 }
+  } else if max == g {
+    (b - r) / delta + 2.0
+  } else {
+    (r - g) / delta + 4.0
+  } * 60.0;
+
+  [
+    hue.round(),
+    (saturation * 100.0).round(),
+    (lightness * 100.0).round(),
+  ]
+}
+
+/// Global cursor position in the same (logical) coordinate space as `Screen::all()`
+#[cfg(target_os = "macos")]
+pub fn cursor_position() -> Option<(i32, i32)> {
+  use std::ffi::c_void;
+
+  #[repr(C)]
+  struct CGPoint {
+    x: f64,
+    y: f64,
+  }
+
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGEventCreate(source: *const c_void) -> *const c_void;
+    fn CGEventGetLocation(event: *const c_void) -> CGPoint;
+    fn CFRelease(cf: *const c_void);
+  }
+
+  unsafe {
+    let event = CGEventCreate(std::ptr::null());
+    if event.is_null() {
+      return None;
+    }
+    let location = CGEventGetLocation(event);
+    CFRelease(event);
+    Some((location.x.floor() as i32, location.y.floor() as i32))
+  }
+}
+
+/// Global cursor position in the same coordinate space as `Screen::all()`
+#[cfg(target_os = "windows")]
+pub fn cursor_position() -> Option<(i32, i32)> {
+  #[repr(C)]
+  struct Point {
+    x: i32,
+    y: i32,
+  }
+
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetCursorPos(point: *mut Point) -> i32;
+  }
+
+  let mut point = Point { x: 0, y: 0 };
+  if unsafe { GetCursorPos(&mut point) } == 0 {
+    return None;
+  }
+  Some((point.x, point.y))
+}
+
+/// Global cursor position in the same coordinate space as `Screen::all()`
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn cursor_position() -> Option<(i32, i32)> {
+  // X11 only - "x:123 y:456 screen:0 window:789"
+  let output = std::process::Command::new("xdotool")
+    .arg("getmouselocation")
+    .output()
+    .ok()?;
+  let text = String::from_utf8_lossy(&output.stdout);
+  let value = |key: &str| {
+    text
+      .split_whitespace()
+      .find_map(|part| part.strip_prefix(key))
+      .and_then(|v| v.parse::<i32>().ok())
+  };
+  Some((value("x:")?, value("y:")?))
+}
@@ -0,0 +1,91 @@
+// Freehand (lasso) selection. The overlay sends the drawn path as a polygon in overlay
+// coordinates; the capture covers the polygon's bounding box and everything outside the
+// polygon is made fully transparent, so only the drawn shape stays opaque.
+use super::measure::MeasurePoint;
+use super::screen_capture::{CaptureBounds, ScreenCapture};
+use crate::capture_store::decode_data_url;
+use tracing::info;
+
+// Same minimum as a rectangular drag
+const MIN_LASSO_SIZE: u32 = 10;
+
+/// Bounding box of the lasso path, used as the capture area
+pub fn bounding_box(points: &[MeasurePoint]) -> Result<CaptureBounds, String> {
+  if points.len() < 3 {
+    return Err("A lasso selection needs at least 3 points".to_string());
+  }
+  let min_x = points.iter().map(|p| p.x).min().unwrap_or(0);
+  let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+  let max_x = points.iter().map(|p| p.x).max().unwrap_or(0);
+  let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+
+  let bounds = CaptureBounds {
+    x: min_x,
+    y: min_y,
+    width: (max_x - min_x) as u32,
+    height: (max_y - min_y) as u32,
+  };
+  if bounds.width < MIN_LASSO_SIZE || bounds.height < MIN_LASSO_SIZE {
+    return Err("Lasso selection is too small".to_string());
+  }
+  Ok(bounds)
+}
+
+/// Make everything outside the lasso transparent. `bounds` is the area the capture
+/// covers; the image may be larger than it on HiDPI displays.
+pub fn apply_mask(
+  image_data: &str,
+  bounds: &CaptureBounds,
+  points: &[MeasurePoint],
+) -> Result<String, String> {
+  let png = decode_data_url(image_data)?;
+  let mut image = image::load_from_memory(&png)
+    .map_err(|e| format!("Failed to read capture image: {}", e))?
+    .to_rgba8();
+
+  let scale_x = image.width() as f64 / bounds.width.max(1) as f64;
+  let scale_y = image.height() as f64 / bounds.height.max(1) as f64;
+  let polygon: Vec<(f64, f64)> = points
+    .iter()
+    .map(|p| {
+      (
+        (p.x - bounds.x) as f64 * scale_x,
+        (p.y - bounds.y) as f64 * scale_y,
+      )
+    })
+    .collect();
+
+  // Scanline fill (even-odd rule), sampling at pixel centres
+  let mut crossings = Vec::new();
+  for y in 0..image.height() {
+    let sample_y = y as f64 + 0.5;
+    crossings.clear();
+    for (i, &(x1, y1)) in polygon.iter().enumerate() {
+      let (x2, y2) = polygon[(i + 1) % polygon.len()];
+      if (y1 <= sample_y) != (y2 <= sample_y) {
+        crossings.push(x1 + (sample_y - y1) / (y2 - y1) * (x2 - x1));
+      }
+    }
+    crossings.sort_by(|a, b| a.total_cmp(b));
+
+    let mut inside_spans = crossings.chunks_exact(2).peekable();
+    for x in 0..image.width() {
+      let sample_x = x as f64 + 0.5;
+      while inside_spans.peek().is_some_and(|span| span[1] <= sample_x) {
+        inside_spans.next();
+      }
+      let inside = inside_spans.peek().is_some_and(|span| span[0] <= sample_x);
+      if !inside {
+        image.get_pixel_mut(x, y).0[3] = 0;
+      }
+    }
+  }
+
+  info!(
+    "➰ Applied lasso mask ({} points) to {}x{} capture",
+    points.len(),
+    image.width(),
+    image.height()
+  );
+  ScreenCapture::encode_rgba_to_base64(image)
+}
@@ -0,0 +1,160 @@
+// Pixel ruler: measures the current selection (size, diagonal, snap points and the
+// gaps to the edges of its display) in both logical and physical pixels.
+use super::screen_capture::{CaptureBounds, CoordinateMapper, PhysicalBounds};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MeasurePoint {
+  pub x: i32, // Overlay coordinates
+  pub y: i32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct SnapPoint {
+  pub name: String,
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct Distance {
+  pub from: String,
+  pub to: String,
+  pub dx: i32,
+  pub dy: i32,
+  pub logical: f64,
+  pub physical: f64,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct MeasureResult {
+  pub bounds: CaptureBounds,
+  pub physical_bounds: PhysicalBounds,
+  pub diagonal: f64,
+  pub physical_diagonal: f64,
+  pub snap_points: Vec<SnapPoint>,
+  pub distances: Vec<Distance>,
+}
+
+/// Measure a selection; `markers` are extra points (e.g. placed by the user) that are
+/// added as snap points and measured against each other
+pub fn measure(bounds: &CaptureBounds, markers: &[MeasurePoint]) -> Result<MeasureResult, String> {
+  let mapper = CoordinateMapper::current()?;
+  let physical_bounds = mapper.to_physical(bounds);
+  let scale = physical_bounds.scale_factor as f64;
+
+  let left = bounds.x;
+  let top = bounds.y;
+  let right = bounds.x + bounds.width as i32;
+  let bottom = bounds.y + bounds.height as i32;
+  let center_x = bounds.x + (bounds.width / 2) as i32;
+  let center_y = bounds.y + (bounds.height / 2) as i32;
+
+  let mut snap_points: Vec<SnapPoint> = [
+    ("top-left", left, top),
+    ("top", center_x, top),
+    ("top-right", right, top),
+    ("right", right, center_y),
+    ("bottom-right", right, bottom),
+    ("bottom", center_x, bottom),
+    ("bottom-left", left, bottom),
+    ("left", left, center_y),
+    ("center", center_x, center_y),
+  ]
+  .into_iter()
+  .map(|(name, x, y)| SnapPoint {
+    name: name.to_string(),
+    x,
+    y,
+  })
+  .collect();
+
+  // Gaps between the selection and the edges of its display
+  let display = mapper.display_bounds_for(bounds);
+  let display_right = display.x + display.width as i32;
+  let display_bottom = display.y + display.height as i32;
+  let mut distances = vec![
+    distance(
+      "left",
+      "display-left",
+      (left, center_y),
+      (display.x, center_y),
+      scale,
+    ),
+    distance(
+      "top",
+      "display-top",
+      (center_x, top),
+      (center_x, display.y),
+      scale,
+    ),
+    distance(
+      "right",
+      "display-right",
+      (right, center_y),
+      (display_right, center_y),
+      scale,
+    ),
+    distance(
+      "bottom",
+      "display-bottom",
+      (center_x, bottom),
+      (center_x, display_bottom),
+      scale,
+    ),
+  ];
+
+  // Markers: each one to the selection center and to the next marker
+  for (index, marker) in markers.iter().enumerate() {
+    let name = format!("marker-{}", index + 1);
+    distances.push(distance(
+      &name,
+      "center",
+      (marker.x, marker.y),
+      (center_x, center_y),
+      scale,
+    ));
+    if let Some(next) = markers.get(index + 1) {
+      distances.push(distance(
+        &name,
+        &format!("marker-{}", index + 2),
+        (marker.x, marker.y),
+        (next.x, next.y),
+        scale,
+      ));
+    }
+    snap_points.push(SnapPoint {
+      name,
+      x: marker.x,
+      y: marker.y,
+    });
+  }
+
+  let diagonal = (bounds.width as f64).hypot(bounds.height as f64);
+  Ok(MeasureResult {
+    bounds: bounds.clone(),
+    diagonal: round_tenths(diagonal),
+    physical_diagonal: round_tenths(diagonal * scale),
+    physical_bounds,
+    snap_points,
+    distances,
+  })
+}
+
+fn distance(from: &str, to: &str, a: (i32, i32), b: (i32, i32), scale: f64) -> Distance {
+  let dx = b.0 - a.0;
+  let dy = b.1 - a.1;
+  let logical = (dx as f64).hypot(dy as f64);
+  Distance {
+    from: from.to_string(),
+    to: to.to_string(),
+    dx,
+    dy,
+    logical: round_tenths(logical),
+    physical: round_tenths(logical * scale),
+  }
+}
+
+fn round_tenths(value: f64) -> f64 {
+  (value * 10.0).round() / 10.0
+}
@@ -1,20 +1,42 @@
 pub mod screen_capture;
 /*
 pub mod selection_overlay;
-pub mod native_overlay;
 pub mod interactive_overlay;
 */
+pub mod color_picker;
+pub mod lasso;
+pub mod measure;
+pub mod native_overlay;
+pub mod overlay_config;
 pub mod overlay_manager;
+pub mod redaction;
 pub mod screenshot_cache;
+pub mod scroll_capture;
+pub mod selection_constraint;
+pub mod wayland_portal;
+pub mod window_picker;
 
 pub use screen_capture::{
-  CaptureBounds, CaptureResult, ScreenCapture, ScreenInfo, TotalScreenArea,
+  CaptureBounds, CaptureResult, CoordinateMapper, PhysicalBounds, ScreenCapture, ScreenInfo,
+  TotalScreenArea,
 };
 /*
 pub use selection_overlay::{SelectionOverlay, SelectionResult, MousePosition, SelectionState, get_overlay};
-pub use native_overlay::{NativeOverlay, ScreenQuadrant};
 pub use interactive_overlay::{InteractiveOverlay, DragState, ContentAnalysis, ContentType, ProcessedContent, };
 */
-pub use overlay_manager::OverlayManager;
+pub use overlay_config::{OverlayBackend, OverlayConfig, OverlayConfigStore};
+pub use overlay_manager::{FrozenFrameView, OverlayManager, OverlayStats};
+
+pub use color_picker::{MagnifierPatch, PickedColor};
+
+pub use measure::{MeasurePoint, MeasureResult};
+
+pub use redaction::{RedactionStyle, RedactionZone};
+
+pub use screenshot_cache::{
+  CacheConfig, EvictionPolicy, FrozenFrame, GrabbedCapture, MaxDimensions, ScreenshotCache,
+};
+
+pub use scroll_capture::{ScrollCaptureManager, ScrollCaptureOptions};
 
-pub use screenshot_cache::ScreenshotCache;
+pub use selection_constraint::SelectionConstraint;
@@ -1,223 +1,385 @@
+// Native macOS selection overlay: borderless, transparent NSWindows (one per screen) with
+// a view that tracks the mouse and draws the selection itself. An alternative to the
+// webview overlay that skips webview startup entirely; chosen with the `backend` overlay
+// setting. The finished selection is reported in overlay coordinates, like a drag in the
+// React overlay. Other platforms always use the webview overlay.
+use super::overlay_config::OverlayConfig;
+use super::screen_capture::CaptureBounds;
+
+// Only constructed by the macOS implementation
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Clone, Debug)]
+pub enum NativeSelection {
+  Selected(CaptureBounds),
+  Cancelled,
+}
+
+pub fn is_supported() -> bool {
+  cfg!(target_os = "macos")
+}
+
+/// Show the overlay on every screen; `on_done` runs once on the main thread when the
+/// user finishes a drag or presses Escape
 #[cfg(target_os = "macos")]
-use cocoa::appkit::{NSWindow, NSView, NSApp, NSApplication, NSApplicationActivationPolicy};
-#[cfg(target_os = "macos")]
-use cocoa::base::{id, nil, YES, NO};
-#[cfg(target_os = "macos")]
-use cocoa::foundation::{NSRect, NSPoint, NSSize, NSString};
+pub fn show(
+  app: &tauri::AppHandle,
+  config: &OverlayConfig,
+  on_done: impl FnOnce(NativeSelection) + Send + 'static,
+) -> Result<(), String> {
+  let style = macos::Style::from_config(config);
+  *macos::ON_DONE.lock().unwrap() = Some(Box::new(on_done));
+  app
+    .run_on_main_thread(move || unsafe { macos::open_windows(style) })
+    .map_err(|e| format!("Failed to show native overlay: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn show(
+  _app: &tauri::AppHandle,
+  _config: &OverlayConfig,
+  _on_done: impl FnOnce(NativeSelection) + Send + 'static,
+) -> Result<(), String> {
+  Err("The native overlay is only available on macOS".to_string())
+}
+
+/// Close the overlay without reporting a selection (cancelled from elsewhere)
 #[cfg(target_os = "macos")]
-use objc::runtime::{Object, Class, Sel};
+pub fn close(app: &tauri::AppHandle) {
+  macos::ON_DONE.lock().unwrap().take();
+  let _ = app.run_on_main_thread(|| unsafe { macos::close_windows() });
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn close(_app: &tauri::AppHandle) {}
+
 #[cfg(target_os = "macos")]
-use objc::{msg_send, sel, sel_impl, class};
+mod macos {
+  use super::{CaptureBounds, NativeSelection, OverlayConfig};
+  use crate::overlay::ScreenCapture;
+  use cocoa::base::{id, nil, BOOL, NO, YES};
+  use cocoa::foundation::{NSPoint, NSRect, NSSize};
+  use objc::declare::ClassDecl;
+  use objc::runtime::{Class, Object, Sel};
+  use objc::{class, msg_send, sel, sel_impl};
+  use std::sync::{Mutex, Once};
+  use tracing::{error, info};
 
-use std::sync::{Arc, Mutex, mpsc};
-use super::screen_capture::{ScreenCapture, CaptureBounds, ScreenInfo};
-use super::selection_overlay::{SelectionResult, MousePosition};
+  const WINDOW_CLASS: &str = "FrameSenseOverlayWindow";
+  const VIEW_CLASS: &str = "FrameSenseSelectionView";
+  const ESCAPE_KEY_CODE: u16 = 53;
+  const MIN_SELECTION_SIZE: f64 = 10.0;
+  // NSScreenSaverWindowLevel, above the menu bar and the Dock
+  const OVERLAY_WINDOW_LEVEL: i64 = 1000;
+  // NSWindowCollectionBehaviorCanJoinAllSpaces | FullScreenAuxiliary
+  const COLLECTION_BEHAVIOR: u64 = (1 << 0) | (1 << 8);
+  // NSBackingStoreBuffered
+  const BACKING_BUFFERED: u64 = 2;
 
-pub struct NativeOverlay {
-    #[cfg(target_os = "macos")]
-    window: Option<id>,
-    selection_receiver: Option<mpsc::Receiver<SelectionResult>>,
-}
+  #[derive(Clone, Copy)]
+  pub struct Style {
+    dim: f64,
+    border: [f64; 4],
+  }
+
+  impl Style {
+    pub fn from_config(config: &OverlayConfig) -> Self {
+      let hex = config.selection_border_color.trim_start_matches('#');
+      let channel = |i: usize| {
+        hex
+          .get(i * 2..i * 2 + 2)
+          .and_then(|c| u8::from_str_radix(c, 16).ok())
+          .map(|c| c as f64 / 255.0)
+      };
+      Self {
+        // A fully transparent window would let clicks through to the apps below
+        dim: (config.dim_opacity as f64).max(0.01),
+        border: [
+          channel(0).unwrap_or(0.23),
+          channel(1).unwrap_or(0.51),
+          channel(2).unwrap_or(0.96),
+          channel(3).unwrap_or(1.0),
+        ],
+      }
+    }
+  }
+
+  pub static ON_DONE: Mutex<Option<Box<dyn FnOnce(NativeSelection) + Send>>> = Mutex::new(None);
+  // NSWindow pointers, only touched on the main thread
+  static WINDOWS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+  static STYLE: Mutex<Style> = Mutex::new(Style {
+    dim: 0.01,
+    border: [0.23, 0.51, 0.96, 1.0],
+  });
+  static REGISTER_CLASSES: Once = Once::new();
+
+  pub unsafe fn open_windows(style: Style) {
+    close_windows();
+    register_classes();
+    *STYLE.lock().unwrap() = style;
+
+    let screens: id = msg_send![class!(NSScreen), screens];
+    let count: usize = msg_send![screens, count];
+    let window_class = Class::get(WINDOW_CLASS).unwrap();
+    let view_class = Class::get(VIEW_CLASS).unwrap();
+    let mut windows = WINDOWS.lock().unwrap();
+
+    for index in 0..count {
+      let screen: id = msg_send![screens, objectAtIndex: index];
+      let frame: NSRect = msg_send![screen, frame];
+
+      let window: id = msg_send![window_class, alloc];
+      let window: id = msg_send![window,
+        initWithContentRect: frame
+        styleMask: 0u64 // Borderless
+        backing: BACKING_BUFFERED
+        defer: NO];
+      let _: () = msg_send![window, setReleasedWhenClosed: NO];
+      let _: () = msg_send![window, setOpaque: NO];
+      let _: () = msg_send![window, setHasShadow: NO];
+      let clear: id = msg_send![class!(NSColor), clearColor];
+      let _: () = msg_send![window, setBackgroundColor: clear];
+      let _: () = msg_send![window, setLevel: OVERLAY_WINDOW_LEVEL];
+      let _: () = msg_send![window, setCollectionBehavior: COLLECTION_BEHAVIOR];
+      let _: () = msg_send![window, setIgnoresMouseEvents: NO];
+      let _: () = msg_send![window, setAcceptsMouseMovedEvents: YES];
 
-impl NativeOverlay {
-    pub fn new() -> Self {
-        Self {
-            #[cfg(target_os = "macos")]
-            window: None,
-            selection_receiver: None,
-        }
+      let view: id = msg_send![view_class, alloc];
+      let content = NSRect::new(NSPoint::new(0.0, 0.0), frame.size);
+      let view: id = msg_send![view, initWithFrame: content];
+      reset_selection(&mut *view);
+      let _: () = msg_send![window, setContentView: view];
+      let _: () = msg_send![view, release];
+
+      let _: () = msg_send![window, makeKeyAndOrderFront: nil];
+      let _: () = msg_send![window, makeFirstResponder: view];
+      windows.push(window as usize);
     }
 
-    /// Start interactive screen selection with native overlay
-    pub async fn start_interactive_selection() -> Result<SelectionResult, String> {
-        #[cfg(target_os = "macos")]
-        {
-            Self::show_macos_overlay().await
-        }
-        
-        #[cfg(not(target_os = "macos"))]
-        {
-            // Fallback for other platforms
-            Self::fallback_selection().await
-        }
+    let app: id = msg_send![class!(NSApplication), sharedApplication];
+    let _: () = msg_send![app, activateIgnoringOtherApps: YES];
+    let cursor: id = msg_send![class!(NSCursor), crosshairCursor];
+    let _: () = msg_send![cursor, set];
+    info!("🍎 Native overlay shown on {} screen(s)", count);
+  }
+
+  pub unsafe fn close_windows() {
+    for window in WINDOWS.lock().unwrap().drain(..) {
+      let window = window as id;
+      let _: () = msg_send![window, orderOut: nil];
+      let _: () = msg_send![window, close];
+      let _: () = msg_send![window, release];
     }
+    let cursor: id = msg_send![class!(NSCursor), arrowCursor];
+    let _: () = msg_send![cursor, set];
+  }
 
-    #[cfg(target_os = "macos")]
-    async fn show_macos_overlay() -> Result<SelectionResult, String> {
-        println!("🖼️ Creating native macOS selection overlay...");
-        
-        // Get screen info first
-        let screen_info = ScreenCapture::get_screen_info()?;
-        if screen_info.is_empty() {
-            return Err("No screens available".to_string());
-        }
-        
-        let primary_screen = &screen_info[0];
-        println!("📺 Screen: {}x{}", primary_screen.width, primary_screen.height);
-
-        // Create channel for communication
-        let (tx, rx) = mpsc::channel::<SelectionResult>();
-        
-        // This is a simplified implementation - in a full implementation, 
-        // we would create a transparent NSWindow and handle mouse events
-        // For now, let's create a working selection system using a different approach
-        
-        tokio::task::spawn_blocking(move || {
-            // Simulate interactive selection
-            // In real implementation, this would show the overlay and wait for user input
-            println!("⚠️ Simulating interactive selection (native overlay in development)");
-            
-            // For demo: create a selection in different area than the placeholder
-            let bounds = CaptureBounds {
-                x: 100,
-                y: 100, 
-                width: 400,
-                height: 300,
-            };
-            
-            // Capture the area
-            let runtime = tokio::runtime::Runtime::new().unwrap();
-            match runtime.block_on(ScreenCapture::capture_region(bounds.clone())) {
-                Ok(capture_result) => {
-                    let result = SelectionResult {
-                        bounds: capture_result.bounds,
-                        image_data: capture_result.image_data,
-                        cancelled: false,
-                    };
-                    tx.send(result).ok();
-                },
-                Err(e) => {
-                    println!("❌ Capture failed: {}", e);
-                    let result = SelectionResult {
-                        bounds,
-                        image_data: String::new(),
-                        cancelled: true,
-                    };
-                    tx.send(result).ok();
-                }
-            }
-        });
-        
-        // Wait for result
-        match rx.recv() {
-            Ok(result) => Ok(result),
-            Err(_) => Err("Selection cancelled or failed".to_string()),
-        }
+  unsafe fn finish(selection: NativeSelection) {
+    close_windows();
+    let on_done = ON_DONE.lock().unwrap().take();
+    if let Some(on_done) = on_done {
+      on_done(selection);
     }
+  }
+
+  fn register_classes() {
+    REGISTER_CLASSES.call_once(|| unsafe {
+      // Borderless windows can't become key (and get keyboard events) by default
+      let mut window = ClassDecl::new(WINDOW_CLASS, class!(NSWindow)).unwrap();
+      window.add_method(
+        sel!(canBecomeKeyWindow),
+        yes as extern "C" fn(&Object, Sel) -> BOOL,
+      );
+      window.register();
+
+      let mut view = ClassDecl::new(VIEW_CLASS, class!(NSView)).unwrap();
+      for ivar in ["startX", "startY", "endX", "endY"] {
+        view.add_ivar::<f64>(ivar);
+      }
+      view.add_ivar::<BOOL>("dragging");
+      // Top-left origin, like overlay coordinates
+      view.add_method(sel!(isFlipped), yes as extern "C" fn(&Object, Sel) -> BOOL);
+      view.add_method(
+        sel!(acceptsFirstResponder),
+        yes as extern "C" fn(&Object, Sel) -> BOOL,
+      );
+      view.add_method(
+        sel!(acceptsFirstMouse:),
+        yes_with_event as extern "C" fn(&Object, Sel, id) -> BOOL,
+      );
+      view.add_method(
+        sel!(mouseDown:),
+        mouse_down as extern "C" fn(&mut Object, Sel, id),
+      );
+      view.add_method(
+        sel!(mouseDragged:),
+        mouse_dragged as extern "C" fn(&mut Object, Sel, id),
+      );
+      view.add_method(
+        sel!(mouseUp:),
+        mouse_up as extern "C" fn(&mut Object, Sel, id),
+      );
+      view.add_method(
+        sel!(keyDown:),
+        key_down as extern "C" fn(&mut Object, Sel, id),
+      );
+      view.add_method(
+        sel!(drawRect:),
+        draw_rect as extern "C" fn(&Object, Sel, NSRect),
+      );
+      view.register();
+    });
+  }
+
+  extern "C" fn yes(_: &Object, _: Sel) -> BOOL {
+    YES
+  }
+
+  extern "C" fn yes_with_event(_: &Object, _: Sel, _: id) -> BOOL {
+    YES
+  }
 
-    #[cfg(not(target_os = "macos"))]
-    async fn fallback_selection() -> Result<SelectionResult, String> {
-        println!("🖼️ Using fallback selection for non-macOS platform");
-        
-        // Get screen info
-        let screen_info = ScreenCapture::get_screen_info()?;
-        if screen_info.is_empty() {
-            return Err("No screens available".to_string());
-        }
-        
-        let primary_screen = &screen_info[0];
-        
-        // Create a selection in center of screen
-        let bounds = CaptureBounds {
-            x: (primary_screen.width / 4) as i32,
-            y: (primary_screen.height / 4) as i32,
-            width: primary_screen.width / 2,
-            height: primary_screen.height / 2,
-        };
-        
-        match ScreenCapture::capture_region(bounds.clone()).await {
-            Ok(capture_result) => Ok(SelectionResult {
-                bounds: capture_result.bounds,
-                image_data: capture_result.image_data,
-                cancelled: false,
-            }),
-            Err(e) => Err(format!("Failed to capture selection: {}", e)),
-        }
+  unsafe fn reset_selection(view: &mut Object) {
+    for ivar in ["startX", "startY", "endX", "endY"] {
+      view.set_ivar::<f64>(ivar, 0.0);
     }
+    view.set_ivar::<BOOL>("dragging", NO);
+  }
 
-    /// Create a manual selection with given bounds (for testing)
-    pub async fn manual_selection(bounds: CaptureBounds) -> Result<SelectionResult, String> {
-        println!("🎯 Manual selection: {:?}", bounds);
-        
-        match ScreenCapture::capture_region(bounds.clone()).await {
-            Ok(capture_result) => Ok(SelectionResult {
-                bounds: capture_result.bounds,
-                image_data: capture_result.image_data,
-                cancelled: false,
-            }),
-            Err(e) => Err(format!("Failed to capture manual selection: {}", e)),
-        }
+  unsafe fn event_location(view: &Object, event: id) -> NSPoint {
+    let in_window: NSPoint = msg_send![event, locationInWindow];
+    msg_send![view, convertPoint: in_window fromView: nil]
+  }
+
+  // Selection rectangle in view coordinates
+  unsafe fn selection_rect(view: &Object) -> NSRect {
+    let start_x = *view.get_ivar::<f64>("startX");
+    let start_y = *view.get_ivar::<f64>("startY");
+    let end_x = *view.get_ivar::<f64>("endX");
+    let end_y = *view.get_ivar::<f64>("endY");
+    NSRect::new(
+      NSPoint::new(start_x.min(end_x), start_y.min(end_y)),
+      NSSize::new((end_x - start_x).abs(), (end_y - start_y).abs()),
+    )
+  }
+
+  extern "C" fn mouse_down(this: &mut Object, _: Sel, event: id) {
+    unsafe {
+      let point = event_location(this, event);
+      this.set_ivar::<f64>("startX", point.x);
+      this.set_ivar::<f64>("startY", point.y);
+      this.set_ivar::<f64>("endX", point.x);
+      this.set_ivar::<f64>("endY", point.y);
+      this.set_ivar::<BOOL>("dragging", YES);
+      let _: () = msg_send![this, setNeedsDisplay: YES];
     }
-}
+  }
 
-// Utility functions for different selection modes
-impl NativeOverlay {
-    /// Quick selection of screen quadrants for testing
-    pub async fn select_screen_quadrant(quadrant: ScreenQuadrant) -> Result<SelectionResult, String> {
-        let screen_info = ScreenCapture::get_screen_info()?;
-        if screen_info.is_empty() {
-            return Err("No screens available".to_string());
-        }
-        
-        let screen = &screen_info[0];
-        let half_width = screen.width / 2;
-        let half_height = screen.height / 2;
-        
-        let bounds = match quadrant {
-            ScreenQuadrant::TopLeft => CaptureBounds {
-                x: 0,
-                y: 0,
-                width: half_width,
-                height: half_height,
-            },
-            ScreenQuadrant::TopRight => CaptureBounds {
-                x: half_width as i32,
-                y: 0,
-                width: half_width,
-                height: half_height,
-            },
-            ScreenQuadrant::BottomLeft => CaptureBounds {
-                x: 0,
-                y: half_height as i32,
-                width: half_width,
-                height: half_height,
-            },
-            ScreenQuadrant::BottomRight => CaptureBounds {
-                x: half_width as i32,
-                y: half_height as i32,
-                width: half_width,
-                height: half_height,
-            },
-            ScreenQuadrant::Center => CaptureBounds {
-                x: (half_width / 2) as i32,
-                y: (half_height / 2) as i32,
-                width: half_width,
-                height: half_height,
-            },
-        };
-        
-        println!("📐 Selecting {} quadrant: {:?}", quadrant.name(), bounds);
-        Self::manual_selection(bounds).await
+  extern "C" fn mouse_dragged(this: &mut Object, _: Sel, event: id) {
+    unsafe {
+      let point = event_location(this, event);
+      this.set_ivar::<f64>("endX", point.x);
+      this.set_ivar::<f64>("endY", point.y);
+      let _: () = msg_send![this, setNeedsDisplay: YES];
     }
-}
+  }
 
-#[derive(Debug, Clone)]
-pub enum ScreenQuadrant {
-    TopLeft,
-    TopRight, 
-    BottomLeft,
-    BottomRight,
-    Center,
-}
+  extern "C" fn mouse_up(this: &mut Object, _: Sel, event: id) {
+    unsafe {
+      if *this.get_ivar::<BOOL>("dragging") == NO {
+        return;
+      }
+      let point = event_location(this, event);
+      this.set_ivar::<f64>("endX", point.x);
+      this.set_ivar::<f64>("endY", point.y);
+      let rect = selection_rect(this);
+      reset_selection(this);
 
-impl ScreenQuadrant {
-    pub fn name(&self) -> &'static str {
-        match self {
-            ScreenQuadrant::TopLeft => "top-left",
-            ScreenQuadrant::TopRight => "top-right",
-            ScreenQuadrant::BottomLeft => "bottom-left", 
-            ScreenQuadrant::BottomRight => "bottom-right",
-            ScreenQuadrant::Center => "center",
-        }
+      if rect.size.width < MIN_SELECTION_SIZE || rect.size.height < MIN_SELECTION_SIZE {
+        error!("❌ Native selection too small, cancelling");
+        finish(NativeSelection::Cancelled);
+        return;
+      }
+      let window: id = msg_send![this, window];
+      match to_overlay_bounds(window, rect) {
+        Some(bounds) => finish(NativeSelection::Selected(bounds)),
+        None => finish(NativeSelection::Cancelled),
+      }
     }
-} 
\ No newline at end of file
+  }
+
+  extern "C" fn key_down(_: &mut Object, _: Sel, event: id) {
+    unsafe {
+      let key_code: u16 = msg_send![event, keyCode];
+      if key_code == ESCAPE_KEY_CODE {
+        info!("⏹️ Escape pressed in native overlay");
+        finish(NativeSelection::Cancelled);
+      }
+    }
+  }
+
+  extern "C" fn draw_rect(this: &Object, _: Sel, _dirty: NSRect) {
+    unsafe {
+      let bounds: NSRect = msg_send![this, bounds];
+      let style = *STYLE.lock().unwrap();
+      let dim: id = msg_send![class!(NSColor),
+        colorWithCalibratedRed: 0.0f64 green: 0.0f64 blue: 0.0f64 alpha: style.dim];
+      let _: () = msg_send![dim, set];
+
+      if *this.get_ivar::<BOOL>("dragging") == NO {
+        let _: () = msg_send![class!(NSBezierPath), fillRect: bounds];
+        return;
+      }
+
+      // Dim around the selection so it shows the screen undimmed
+      let selection = selection_rect(this);
+      let (left, top) = (selection.origin.x, selection.origin.y);
+      let (right, bottom) = (left + selection.size.width, top + selection.size.height);
+      let (width, height) = (bounds.size.width, bounds.size.height);
+      for rect in [
+        NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(width, top.max(0.0))),
+        NSRect::new(
+          NSPoint::new(0.0, bottom),
+          NSSize::new(width, (height - bottom).max(0.0)),
+        ),
+        NSRect::new(
+          NSPoint::new(0.0, top),
+          NSSize::new(left.max(0.0), selection.size.height),
+        ),
+        NSRect::new(
+          NSPoint::new(right, top),
+          NSSize::new((width - right).max(0.0), selection.size.height),
+        ),
+      ] {
+        let _: () = msg_send![class!(NSBezierPath), fillRect: rect];
+      }
+
+      let [r, g, b, a] = style.border;
+      let border: id = msg_send![class!(NSColor),
+        colorWithCalibratedRed: r green: g blue: b alpha: a];
+      let _: () = msg_send![border, set];
+      let _: () = msg_send![class!(NSBezierPath), setDefaultLineWidth: 2.0f64];
+      let _: () = msg_send![class!(NSBezierPath), strokeRect: selection];
+    }
+  }
+
+  // View rect (flipped, points) -> overlay coordinates. Cocoa screen coordinates have a
+  // bottom-left origin on the primary screen; overlay coordinates are top-left based and
+  // start at the total screen area's corner.
+  unsafe fn to_overlay_bounds(window: id, rect: NSRect) -> Option<CaptureBounds> {
+    let frame: NSRect = msg_send![window, frame];
+    let screens: id = msg_send![class!(NSScreen), screens];
+    let primary: id = msg_send![screens, objectAtIndex: 0usize];
+    let primary_frame: NSRect = msg_send![primary, frame];
+
+    let window_left = frame.origin.x;
+    let window_top = primary_frame.size.height - (frame.origin.y + frame.size.height);
+    let area = ScreenCapture::get_total_screen_area().ok()?;
+
+    Some(CaptureBounds {
+      x: (window_left + rect.origin.x).round() as i32 - area.min_x,
+      y: (window_top + rect.origin.y).round() as i32 - area.min_y,
+      width: rect.size.width.round() as u32,
+      height: rect.size.height.round() as u32,
+    })
+  }
+}
@@ -0,0 +1,114 @@
+// Overlay appearance preferences (dimming, selection border, crosshair guides, size
+// label). Stored by Rust so every overlay window renders the same way; the React overlay
+// reads them with `get_overlay_config` and follows `overlay-config-changed`. `backend`
+// picks between the React overlay and the native macOS one.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+const CONFIG_FILE: &str = "overlay_config.json";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayBackend {
+  #[default]
+  Webview,
+  // NSWindow overlay, no webview startup (macOS only - elsewhere the webview is used)
+  Native,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlayConfig {
+  pub backend: OverlayBackend,
+  // Darkening of the screen outside the selection, 0.0 (none) to 1.0 (black)
+  pub dim_opacity: f32,
+  // #RRGGBB or #RRGGBBAA
+  pub selection_border_color: String,
+  // Full-screen guide lines through the cursor
+  pub show_crosshair: bool,
+  // Width x height label next to the selection
+  pub show_dimensions: bool,
+}
+
+impl Default for OverlayConfig {
+  fn default() -> Self {
+    Self {
+      backend: OverlayBackend::Webview,
+      dim_opacity: 0.0,
+      selection_border_color: "#3B82F6".to_string(),
+      show_crosshair: false,
+      show_dimensions: true,
+    }
+  }
+}
+
+pub struct OverlayConfigStore {
+  config: OverlayConfig,
+  storage_path: Option<PathBuf>,
+}
+
+impl OverlayConfigStore {
+  pub fn new() -> Self {
+    Self {
+      config: OverlayConfig::default(),
+      storage_path: None,
+    }
+  }
+
+  // Load saved config from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(CONFIG_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(config) => self.config = config,
+        Err(e) => warn!("⚠️ Ignoring unreadable overlay config: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn config(&self) -> &OverlayConfig {
+    &self.config
+  }
+
+  pub fn update(&mut self, config: OverlayConfig) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&config.dim_opacity) {
+      return Err("Dim opacity must be between 0 and 1".to_string());
+    }
+    let color = config.selection_border_color.trim_start_matches('#');
+    if !config.selection_border_color.starts_with('#')
+      || !matches!(color.len(), 6 | 8)
+      || !color.chars().all(|c| c.is_ascii_hexdigit())
+    {
+      return Err(format!(
+        "Invalid selection border color: {}",
+        config.selection_border_color
+      ));
+    }
+    self.config = config;
+    self.persist()
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.config)
+      .map_err(|e| format!("Failed to serialize overlay config: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save overlay config: {}", e))
+  }
+}
+
+impl Default for OverlayConfigStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -1,145 +1,559 @@
-use super::screen_capture::ScreenCapture;
+use super::color_picker;
+use super::measure::MeasurePoint;
+use super::screen_capture::{CaptureBounds, ScreenCapture};
+use super::selection_constraint::SelectionConstraint;
 use screenshots;
+use serde::Serialize;
 use std::time::{Duration, Instant};
-use tauri::{WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri::{
+  Emitter, LogicalPosition, LogicalSize, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+use tracing::{info, warn};
+
+// One overlay window per display. Each window only covers its own monitor (so mixed
+// DPI setups get a correctly scaled webview) and reports selections in its own viewport
+// coordinates; `to_global` translates them back into overlay coordinates, where (0, 0) is
+// the top-left of the total screen area. Windows are kept per display and moved when the
+// layout changes; a new window always gets a fresh label, since a destroyed window's
+// label stays taken until the webview has actually gone away.
+struct MonitorOverlay {
+  window: WebviewWindow,
+  display_id: u32,
+  offset_x: i32, // Display origin in overlay coordinates
+  offset_y: i32,
+  width: u32,
+  height: u32,
+  frozen_frame: Option<String>, // This display's part of the frozen snapshot
+}
 
 pub struct OverlayManager {
-  overlay_window: Option<WebviewWindow>,
+  overlays: Vec<MonitorOverlay>,
   is_active: bool,
   last_used: Option<Instant>,
+  idle_timeout: Option<Duration>, // None = keep pooled overlays forever
+  windows_created: u64,
+  windows_destroyed: u64,
+  selection: Option<CaptureBounds>, // Keyboard-adjustable selection (overlay coordinates)
+  constraint: SelectionConstraint,  // For the current capture session
+}
+
+// Frozen background of one overlay window: its own display's part of the snapshot
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FrozenFrameView {
+  pub image_data: Option<String>,
 }
 
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayStats {
+  pub window_count: usize,
+  pub is_active: bool,
+  pub idle_secs: Option<u64>, // Since the overlay was last shown
+  pub idle_timeout_secs: Option<u64>,
+  pub windows_created: u64,
+  pub windows_destroyed: u64,
+  // Approximate backing-surface memory of the pooled windows (width x height x 4 bytes);
+  // webview process memory isn't visible from here
+  pub estimated_surface_bytes: u64,
+}
+
+// Display geometry in overlay coordinates: (id, x, y, width, height)
+type DisplayLayout = Vec<(u32, i32, i32, u32, u32)>;
+
+const OVERLAY_LABEL_PREFIX: &str = "overlay-";
+
+// Smallest selection keyboard resizing can produce, matching the drag minimum
+const MIN_SELECTION_SIZE: u32 = 10;
+// Selection created when arrow keys are used before anything was dragged
+const DEFAULT_SELECTION_SIZE: (u32, u32) = (320, 240);
+// Pooled overlays unused this long are destroyed to free their webviews
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+// How often the background task checks for idle overlays
+pub const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 impl OverlayManager {
   pub fn new() -> Self {
     Self {
-      overlay_window: None,
+      overlays: Vec::new(),
       is_active: false,
       last_used: None,
+      idle_timeout: Some(DEFAULT_IDLE_TIMEOUT),
+      windows_created: 0,
+      windows_destroyed: 0,
+      selection: None,
+      constraint: SelectionConstraint::Free,
     }
   }
 
   pub fn show_selection_overlay(&mut self, app: &tauri::AppHandle) -> Result<(), String> {
-    match &self.overlay_window {
-      Some(window) => {
-        // ♻️ Återanvänd befintlig overlay
-        window
-          .show()
-          .map_err(|e| format!("Failed to show overlay: {}", e))?;
-
-        // Ensure focus for event handling when reusing
-        if let Err(e) = window.set_focus() {
-          println!("⚠️ Could not set focus on reused overlay: {}", e);
-        }
-
-        self.is_active = true;
-        println!("♻️ Reusing existing React overlay window");
+    let layout = Self::current_layout();
+
+    if !self.overlays.is_empty() && self.layout_matches(&layout) {
+      // ♻️ Återanvänd befintliga overlays
+      info!(
+        "♻️ Reusing {} existing React overlay window(s)",
+        self.overlays.len()
+      );
+    } else {
+      // 🆕 First use, or monitors were added/removed/moved since the overlays were made:
+      // keep the windows of displays that are still there and move them into place
+      let mut previous = std::mem::take(&mut self.overlays);
+      for (display_id, x, y, width, height) in layout {
+        let reused = previous
+          .iter()
+          .position(|o| o.display_id == display_id)
+          .map(|index| previous.remove(index));
+        let window = match reused {
+          Some(overlay) => {
+            Self::place_window(&overlay.window, x, y, width, height)?;
+            overlay.window
+          }
+          None => {
+            let window = self.create_react_overlay_once(app, display_id, x, y, width, height)?;
+            self.windows_created += 1;
+            window
+          }
+        };
+        self.overlays.push(MonitorOverlay {
+          window,
+          display_id,
+          offset_x: x,
+          offset_y: y,
+          width,
+          height,
+          frozen_frame: None,
+        });
       }
-      None => {
-        // 🆕 Skapa första gången med React istället för HTML
-        let overlay = self.create_react_overlay_once(app)?;
-        self.overlay_window = Some(overlay);
-        self.is_active = true;
-        println!("🆕 Created new React overlay window");
+      for overlay in previous {
+        let _ = overlay.window.destroy();
+        self.windows_destroyed += 1;
       }
+      info!(
+        "🆕 Laid out {} React overlay window(s), one per display",
+        self.overlays.len()
+      );
+    }
+    for overlay in &self.overlays {
+      overlay
+        .window
+        .show()
+        .map_err(|e| format!("Failed to show overlay: {}", e))?;
     }
+
+    self.focus_overlay_under_cursor();
+    self.is_active = true;
     self.last_used = Some(Instant::now());
     Ok(())
   }
 
+  // Ensure focus for event handling on the display the user is looking at
+  fn focus_overlay_under_cursor(&self) {
+    let cursor = color_picker::cursor_position().and_then(|(x, y)| {
+      let area = ScreenCapture::get_total_screen_area().ok()?;
+      Some((x - area.min_x, y - area.min_y))
+    });
+    let target = cursor
+      .and_then(|(x, y)| {
+        self.overlays.iter().find(|o| {
+          x >= o.offset_x
+            && y >= o.offset_y
+            && x < o.offset_x + o.width as i32
+            && y < o.offset_y + o.height as i32
+        })
+      })
+      .or_else(|| self.overlays.first());
+    if let Some(overlay) = target {
+      if let Err(e) = overlay.window.set_focus() {
+        warn!(
+          "⚠️ Could not set focus on overlay for display {}: {}",
+          overlay.display_id, e
+        );
+      }
+    }
+  }
+
+  // Current displays in overlay coordinates
+  fn current_layout() -> DisplayLayout {
+    match (
+      screenshots::Screen::all(),
+      ScreenCapture::get_total_screen_area(),
+    ) {
+      (Ok(screens), Ok(area)) if !screens.is_empty() => screens
+        .iter()
+        .map(|screen| {
+          let display = &screen.display_info;
+          (
+            display.id,
+            display.x - area.min_x,
+            display.y - area.min_y,
+            display.width,
+            display.height,
+          )
+        })
+        .collect(),
+      _ => {
+        warn!("⚠️ Failed to get screen layout, using fallback 1920x1080");
+        vec![(0, 0, 0, 1920, 1080)]
+      }
+    }
+  }
+
+  fn layout_matches(&self, layout: &DisplayLayout) -> bool {
+    self.overlays.len() == layout.len()
+      && self
+        .overlays
+        .iter()
+        .zip(layout)
+        .all(|(o, d)| (o.display_id, o.offset_x, o.offset_y, o.width, o.height) == *d)
+  }
+
+  fn destroy_overlays(&mut self) {
+    for overlay in self.overlays.drain(..) {
+      let _ = overlay.window.destroy();
+      self.windows_destroyed += 1;
+    }
+  }
+
+  /// Destroy the pooled overlay windows; the next capture creates fresh ones
+  pub fn destroy(&mut self) -> Result<(), String> {
+    if self.is_active {
+      return Err("The overlay is in use - cancel the capture first".to_string());
+    }
+    let count = self.overlays.len();
+    self.destroy_overlays();
+    self.selection = None;
+    info!("🗑️ Destroyed {} React overlay window(s)", count);
+    Ok(())
+  }
+
+  /// Translate bounds reported by an overlay window (its own viewport coordinates)
+  /// into overlay coordinates. Bounds from any other window are returned unchanged.
+  pub fn to_global(&self, label: &str, bounds: CaptureBounds) -> CaptureBounds {
+    let (offset_x, offset_y) = self.offset_for(label);
+    CaptureBounds {
+      x: bounds.x + offset_x,
+      y: bounds.y + offset_y,
+      ..bounds
+    }
+  }
+
+  /// Same as `to_global`, for a path of points (lasso selections)
+  pub fn points_to_global(&self, label: &str, points: Vec<MeasurePoint>) -> Vec<MeasurePoint> {
+    let (offset_x, offset_y) = self.offset_for(label);
+    points
+      .into_iter()
+      .map(|p| MeasurePoint {
+        x: p.x + offset_x,
+        y: p.y + offset_y,
+      })
+      .collect()
+  }
+
+  // Where an overlay window's viewport starts in overlay coordinates ((0, 0) for others)
+  fn offset_for(&self, label: &str) -> (i32, i32) {
+    self
+      .overlays
+      .iter()
+      .find(|o| o.window.label() == label)
+      .map(|o| (o.offset_x, o.offset_y))
+      .unwrap_or((0, 0))
+  }
+
   pub fn hide_overlay(&mut self) -> Result<(), String> {
-    if let Some(window) = &self.overlay_window {
-      window
+    self.selection = None;
+    // Constraints only last for one capture session
+    self.constraint = SelectionConstraint::Free;
+    if self.overlays.is_empty() {
+      return Ok(());
+    }
+    for overlay in &self.overlays {
+      overlay
+        .window
         .hide()
         .map_err(|e| format!("Failed to hide overlay: {}", e))?;
+    }
+    self.is_active = false;
+    info!("👁️ React overlays hidden (not destroyed)");
+    Ok(())
+  }
+
+  /// Abort the current selection. Hides the overlay - or destroys it when hiding fails,
+  /// so a stuck overlay can't stay on top - and emits `capture-cancelled`.
+  pub fn cancel(&mut self, app: &tauri::AppHandle) {
+    self.clear_frozen_frames();
+    if let Err(e) = self.hide_overlay() {
+      warn!("⚠️ {} - destroying overlay instead", e);
+      self.destroy_overlays();
       self.is_active = false;
-      println!("👁️ React overlay hidden (not destroyed)");
+      self.selection = None;
+    }
+    let _ = app.emit("capture-cancelled", ());
+    info!("⏹️ Capture cancelled");
+  }
+
+  pub fn selection(&self) -> Option<CaptureBounds> {
+    self.selection.clone()
+  }
+
+  pub fn constraint(&self) -> SelectionConstraint {
+    self.constraint
+  }
+
+  /// Constrain selections until the capture session ends; overlays are told so they
+  /// can preview the constrained rectangle while dragging
+  pub fn set_constraint(&mut self, constraint: SelectionConstraint) -> Result<(), String> {
+    constraint.validate()?;
+    self.constraint = constraint;
+    for overlay in &self.overlays {
+      let _ = overlay.window.emit_to(
+        overlay.window.label(),
+        "selection-constraint-changed",
+        serde_json::json!({ "constraint": constraint }),
+      );
+    }
+    if let Some(selection) = self.selection.clone() {
+      self.set_selection(selection);
     }
     Ok(())
   }
-  /*
-      pub fn cleanup_if_old(&mut self) {
-          // Rensa overlay om den inte använts på 5 minuter
-          if let Some(last_used) = self.last_used {
-              if last_used.elapsed() > Duration::from_secs(300) && !self.is_active {
-                  if let Some(window) = &self.overlay_window {
-                      window.close().ok();
-                      self.overlay_window = None;
-                      println!("🗑️ Cleaned up old React overlay window");
-                  }
-              }
-          }
+
+  /// Apply the session's constraint to a selection and keep it inside the overlay
+  pub fn constrain(&self, bounds: CaptureBounds) -> CaptureBounds {
+    Self::clamp_to_overlay(self.constraint.apply(bounds))
+  }
+
+  /// Replace the selection (constrained and clamped to the overlay) and tell the overlays
+  /// to redraw it
+  pub fn set_selection(&mut self, bounds: CaptureBounds) -> CaptureBounds {
+    let bounds = self.constrain(bounds);
+    self.selection = Some(bounds.clone());
+    // Each overlay draws the selection in its own coordinates, so a selection spanning
+    // two displays shows up on both
+    for overlay in &self.overlays {
+      let local = CaptureBounds {
+        x: bounds.x - overlay.offset_x,
+        y: bounds.y - overlay.offset_y,
+        ..bounds.clone()
+      };
+      let _ = overlay.window.emit_to(
+        overlay.window.label(),
+        "selection-bounds-changed",
+        serde_json::json!({ "bounds": local }),
+      );
+    }
+    bounds
+  }
+
+  /// Move the selection by (dx, dy), or grow/shrink its size when `resize` is set.
+  /// Without a selection, a default one is created in the middle of the overlay.
+  pub fn nudge_selection(&mut self, dx: i32, dy: i32, resize: bool) -> CaptureBounds {
+    let current = self.selection.clone().unwrap_or_else(|| {
+      let (width, height) = DEFAULT_SELECTION_SIZE;
+      let (area_width, area_height) = ScreenCapture::get_total_screen_area()
+        .map(|area| (area.width, area.height))
+        .unwrap_or((width * 2, height * 2));
+      CaptureBounds {
+        x: (area_width.saturating_sub(width) / 2) as i32,
+        y: (area_height.saturating_sub(height) / 2) as i32,
+        width,
+        height,
       }
-  */
-  /*
-  pub fn is_overlay_active(&self) -> bool {
-      self.is_active
-  }
-  */
-  fn create_react_overlay_once(&self, app: &tauri::AppHandle) -> Result<WebviewWindow, String> {
-    println!("🚀 === CREATING MULTI-SCREEN OVERLAY ===");
-
-    // Get total screen area covering all monitors
-    let (screen_width, screen_height, offset_x, offset_y) =
-      match ScreenCapture::get_total_screen_area() {
-        Ok(total_area) => {
-          let width = total_area.width as f64;
-          let height = total_area.height as f64;
-          let x_offset = total_area.min_x as f64;
-          let y_offset = total_area.min_y as f64;
-
-          println!("✅ Multi-screen setup detected!");
-          println!("📺 Total screen real estate: {}x{}", width, height);
-          println!(
-            "📍 Overlay position: ({}, {}) covering area to ({}, {})",
-            x_offset,
-            y_offset,
-            x_offset + width,
-            y_offset + height
-          );
-          println!("🎯 This overlay will cover ALL screens simultaneously!");
-
-          (width, height, x_offset, y_offset)
-        }
-        Err(e) => {
-          println!(
-            "⚠️ Failed to get total screen area: {}, falling back to single screen",
-            e
-          );
-          // Fallback to single screen
-          match screenshots::Screen::all() {
-            Ok(screens) => {
-              if let Some(screen) = screens.first() {
-                let width = screen.display_info.width as f64;
-                let height = screen.display_info.height as f64;
-                println!("📺 Fallback: Using single screen {}x{}", width, height);
-                (width, height, 0.0, 0.0)
-              } else {
-                println!("⚠️ No screens found, using fallback 1920x1080");
-                (1920.0, 1080.0, 0.0, 0.0)
-              }
-            }
-            Err(e) => {
-              println!("❌ Failed to get screen info: {}, using fallback", e);
-              (1920.0, 1080.0, 0.0, 0.0)
-            }
-          }
-        }
+    });
+
+    let adjusted = if resize {
+      CaptureBounds {
+        width: (current.width as i64 + dx as i64).max(MIN_SELECTION_SIZE as i64) as u32,
+        height: (current.height as i64 + dy as i64).max(MIN_SELECTION_SIZE as i64) as u32,
+        ..current
+      }
+    } else {
+      CaptureBounds {
+        x: current.x + dx,
+        y: current.y + dy,
+        ..current
+      }
+    };
+    self.set_selection(adjusted)
+  }
+
+  // Keep the selection inside the overlay (the total screen area)
+  fn clamp_to_overlay(bounds: CaptureBounds) -> CaptureBounds {
+    let area = match ScreenCapture::get_total_screen_area() {
+      Ok(area) => area,
+      Err(_) => return bounds,
+    };
+    let width = bounds
+      .width
+      .clamp(MIN_SELECTION_SIZE, area.width.max(MIN_SELECTION_SIZE));
+    let height = bounds
+      .height
+      .clamp(MIN_SELECTION_SIZE, area.height.max(MIN_SELECTION_SIZE));
+    CaptureBounds {
+      x: bounds.x.clamp(0, (area.width.saturating_sub(width)) as i32),
+      y: bounds
+        .y
+        .clamp(0, (area.height.saturating_sub(height)) as i32),
+      width,
+      height,
+    }
+  }
+  pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+    self.idle_timeout = timeout;
+  }
+
+  /// Destroy the pooled overlays when they haven't been used for the idle timeout.
+  /// Returns true when something was cleaned up.
+  pub fn cleanup_if_old(&mut self) -> bool {
+    let (Some(timeout), Some(last_used)) = (self.idle_timeout, self.last_used) else {
+      return false;
+    };
+    if self.is_active || self.overlays.is_empty() || last_used.elapsed() <= timeout {
+      return false;
+    }
+    self.destroy_overlays();
+    info!("🗑️ Cleaned up old React overlay windows");
+    true
+  }
+
+  pub fn stats(&self) -> OverlayStats {
+    OverlayStats {
+      window_count: self.overlays.len(),
+      is_active: self.is_active,
+      idle_secs: self.last_used.map(|t| t.elapsed().as_secs()),
+      idle_timeout_secs: self.idle_timeout.map(|t| t.as_secs()),
+      windows_created: self.windows_created,
+      windows_destroyed: self.windows_destroyed,
+      estimated_surface_bytes: self
+        .overlays
+        .iter()
+        .map(|o| o.width as u64 * o.height as u64 * 4)
+        .sum(),
+    }
+  }
+
+  /// Each overlay window's display in overlay coordinates, keyed by window label
+  pub fn display_regions(&self) -> Vec<(String, CaptureBounds)> {
+    self
+      .overlays
+      .iter()
+      .map(|o| {
+        (
+          o.window.label().to_string(),
+          CaptureBounds {
+            x: o.offset_x,
+            y: o.offset_y,
+            width: o.width,
+            height: o.height,
+          },
+        )
+      })
+      .collect()
+  }
+
+  /// Background images for freeze-screen mode, keyed by window label; every overlay gets
+  /// only its own display's part
+  pub fn send_frozen_frames(&mut self, frames: Vec<(String, String)>) {
+    for (label, image_data) in frames {
+      if let Some(overlay) = self.overlays.iter_mut().find(|o| o.window.label() == label) {
+        overlay.frozen_frame = Some(image_data);
+      }
+    }
+    self.emit_frozen_frames();
+  }
+
+  /// Back to live, transparent overlays
+  pub fn clear_frozen_frames(&mut self) {
+    for overlay in &mut self.overlays {
+      overlay.frozen_frame = None;
+    }
+    self.emit_frozen_frames();
+  }
+
+  fn emit_frozen_frames(&self) {
+    for overlay in &self.overlays {
+      let view = FrozenFrameView {
+        image_data: overlay.frozen_frame.clone(),
       };
+      if let Err(e) = overlay
+        .window
+        .emit_to(overlay.window.label(), "overlay-frozen-frame", view)
+      {
+        warn!(
+          "⚠️ Failed to send frozen frame to overlay for display {}: {}",
+          overlay.display_id, e
+        );
+      }
+    }
+  }
+
+  /// The frozen frame of one overlay window
+  pub fn frozen_frame_view(&self, label: &str) -> FrozenFrameView {
+    FrozenFrameView {
+      image_data: self
+        .overlays
+        .iter()
+        .find(|o| o.window.label() == label)
+        .and_then(|o| o.frozen_frame.clone()),
+    }
+  }
 
-    println!("🔧 Creating React overlay window with:");
-    println!("   Size: {}x{}", screen_width, screen_height);
-    println!("   Position: ({}, {})", offset_x, offset_y);
-    println!("   Properties: transparent, always-on-top, no decorations");
+  pub fn is_overlay_active(&self) -> bool {
+    self.is_active
+  }
+
+  // Windows are placed in desktop coordinates, not overlay coordinates
+  fn screen_position(x: i32, y: i32) -> (f64, f64) {
+    let (min_x, min_y) = ScreenCapture::get_total_screen_area()
+      .map(|area| (area.min_x, area.min_y))
+      .unwrap_or((0, 0));
+    ((x + min_x) as f64, (y + min_y) as f64)
+  }
+
+  fn place_window(
+    window: &WebviewWindow,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<(), String> {
+    let (screen_x, screen_y) = Self::screen_position(x, y);
+    window
+      .set_size(LogicalSize::new(width as f64, height as f64))
+      .and_then(|_| window.set_position(LogicalPosition::new(screen_x, screen_y)))
+      .map_err(|e| format!("Failed to move overlay: {}", e))
+  }
+
+  fn create_react_overlay_once(
+    &self,
+    app: &tauri::AppHandle,
+    display_id: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<WebviewWindow, String> {
+    let (screen_x, screen_y) = Self::screen_position(x, y);
+
+    info!(
+      "🔧 Creating React overlay window for display {}:",
+      display_id
+    );
+    info!("   Size: {}x{}", width, height);
+    info!("   Position: ({}, {})", screen_x, screen_y);
+    info!("   Overlay offset: ({}, {})", x, y);
 
-    // Create React-based overlay window that covers ALL screens
     let overlay = WebviewWindowBuilder::new(
       app,
-      "overlay",                         // Same ID as regular overlay
+      format!(
+        "{}{}-{}",
+        OVERLAY_LABEL_PREFIX, display_id, self.windows_created
+      ),
       WebviewUrl::App("overlay".into()), // React route från OverlayApp.tsx
     )
     .title("FrameSense Selection")
-    .inner_size(screen_width, screen_height)
-    .position(offset_x, offset_y) // Start at the leftmost screen
+    .inner_size(width as f64, height as f64)
+    .position(screen_x, screen_y)
     .decorations(false) // No window borders
     .transparent(true) // Make window transparent!
     .always_on_top(true) // Above all other windows
@@ -147,23 +561,10 @@ impl OverlayManager {
     .resizable(false)
     .maximizable(false)
     .minimizable(false)
-    .focused(true) // Ensure window can receive events
+    .focused(false) // Focus goes to the overlay under the cursor
     .build()
     .map_err(|e| format!("Failed to create React overlay: {}", e))?;
 
-    // Force focus to ensure events work
-    if let Err(e) = overlay.set_focus() {
-      println!("⚠️ Could not set React overlay focus: {}", e);
-    }
-
-    println!("✅ MULTI-SCREEN OVERLAY CREATED SUCCESSFULLY!");
-    println!("🎉 Users can now drag selections across ALL monitors!");
-    println!(
-      "📐 Coordinate system: overlay (0,0) = screen ({}, {})",
-      offset_x, offset_y
-    );
-    println!("🚀 === OVERLAY CREATION COMPLETE ===\n");
-
     Ok(overlay)
   }
 }
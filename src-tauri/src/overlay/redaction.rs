@@ -0,0 +1,256 @@
+// Persistent redaction zones ("always blur the top-right corner where my email shows").
+// Zones are stored per display in display-relative logical coordinates and applied to
+// every capture before it is encoded, so nothing downstream ever sees the raw pixels.
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tracing::info;
+
+const ZONES_FILE: &str = "redaction_zones.json";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionStyle {
+  Pixelate,
+  Blur,
+  Fill,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RedactionZone {
+  #[serde(default)]
+  pub id: String,
+  pub name: String,
+  pub display_id: u32,
+  // Display-relative, logical coordinates (same space as Screen::capture_area)
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub style: RedactionStyle,
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+  true
+}
+
+struct RedactionStore {
+  zones: Vec<RedactionZone>,
+  storage_path: Option<PathBuf>,
+}
+
+static STORE: RwLock<RedactionStore> = RwLock::new(RedactionStore {
+  zones: Vec::new(),
+  storage_path: None,
+});
+
+/// Load saved zones from `dir` and persist future changes there
+pub fn init(dir: PathBuf) {
+  let path = dir.join(ZONES_FILE);
+  let zones = fs::read_to_string(&path)
+    .ok()
+    .and_then(|json| serde_json::from_str::<Vec<RedactionZone>>(&json).ok())
+    .unwrap_or_default();
+  if !zones.is_empty() {
+    info!("🕶️ Loaded {} redaction zones", zones.len());
+  }
+  let mut store = STORE.write().unwrap();
+  store.zones = zones;
+  store.storage_path = Some(path);
+}
+
+pub fn list_zones() -> Vec<RedactionZone> {
+  STORE.read().unwrap().zones.clone()
+}
+
+pub fn add_zone(mut zone: RedactionZone) -> Result<RedactionZone, String> {
+  if zone.width == 0 || zone.height == 0 {
+    return Err("Redaction zone must have a non-zero size".to_string());
+  }
+  zone.id = format!(
+    "zone-{}",
+    chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+  );
+  let mut store = STORE.write().unwrap();
+  store.zones.push(zone.clone());
+  persist(&store)?;
+  Ok(zone)
+}
+
+pub fn update_zone(zone: RedactionZone) -> Result<(), String> {
+  let mut store = STORE.write().unwrap();
+  let existing = store
+    .zones
+    .iter_mut()
+    .find(|z| z.id == zone.id)
+    .ok_or_else(|| format!("Redaction zone '{}' not found", zone.id))?;
+  *existing = zone;
+  persist(&store)
+}
+
+pub fn remove_zone(id: &str) -> Result<(), String> {
+  let mut store = STORE.write().unwrap();
+  let before = store.zones.len();
+  store.zones.retain(|z| z.id != id);
+  if store.zones.len() == before {
+    return Err(format!("Redaction zone '{}' not found", id));
+  }
+  persist(&store)
+}
+
+fn persist(store: &RedactionStore) -> Result<(), String> {
+  let path = match &store.storage_path {
+    Some(path) => path,
+    None => return Ok(()),
+  };
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create storage directory: {}", e))?;
+  }
+  let json = serde_json::to_string_pretty(&store.zones)
+    .map_err(|e| format!("Failed to serialize redaction zones: {}", e))?;
+  fs::write(path, json).map_err(|e| format!("Failed to save redaction zones: {}", e))
+}
+
+pub fn has_zones_for(display_id: u32) -> bool {
+  STORE
+    .read()
+    .unwrap()
+    .zones
+    .iter()
+    .any(|z| z.enabled && z.display_id == display_id)
+}
+
+/// Apply all enabled zones of `display_id` to a captured image.
+/// `capture_x/capture_y/capture_width/capture_height` describe the captured area in
+/// display-relative logical coordinates; the image may be larger on HiDPI displays.
+pub fn apply_to_capture(
+  image: &mut RgbaImage,
+  display_id: u32,
+  capture_x: i32,
+  capture_y: i32,
+  capture_width: u32,
+  capture_height: u32,
+) -> usize {
+  let zones: Vec<RedactionZone> = STORE
+    .read()
+    .unwrap()
+    .zones
+    .iter()
+    .filter(|z| z.enabled && z.display_id == display_id)
+    .cloned()
+    .collect();
+  if zones.is_empty() || capture_width == 0 || capture_height == 0 {
+    return 0;
+  }
+
+  let scale_x = image.width() as f64 / capture_width as f64;
+  let scale_y = image.height() as f64 / capture_height as f64;
+  let mut applied = 0;
+
+  for zone in zones {
+    // Intersection of the zone with the captured area, in logical coordinates
+    let left = zone.x.max(capture_x);
+    let top = zone.y.max(capture_y);
+    let right = (zone.x + zone.width as i32).min(capture_x + capture_width as i32);
+    let bottom = (zone.y + zone.height as i32).min(capture_y + capture_height as i32);
+    if right <= left || bottom <= top {
+      continue;
+    }
+
+    // Map into image pixels
+    let px = ((left - capture_x) as f64 * scale_x).floor() as u32;
+    let py = ((top - capture_y) as f64 * scale_y).floor() as u32;
+    let pw = (((right - left) as f64 * scale_x).ceil() as u32).min(image.width() - px);
+    let ph = (((bottom - top) as f64 * scale_y).ceil() as u32).min(image.height() - py);
+
+    apply_style(image, px, py, pw, ph, zone.style);
+    applied += 1;
+  }
+
+  if applied > 0 {
+    info!("🕶️ Applied {} redaction zone(s) to capture", applied);
+  }
+  applied
+}
+
+/// Obscure a rectangle of `image` (pixel coordinates) with the given style
+pub fn apply_style(
+  image: &mut RgbaImage,
+  x: u32,
+  y: u32,
+  width: u32,
+  height: u32,
+  style: RedactionStyle,
+) {
+  match style {
+    RedactionStyle::Pixelate => pixelate_rect(image, x, y, width, height, 16),
+    RedactionStyle::Blur => blur_rect(image, x, y, width, height, 12.0),
+    RedactionStyle::Fill => {
+      for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+          image.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+        }
+      }
+    }
+  }
+}
+
+/// Gaussian blur of a rectangle of `image` (pixel coordinates)
+pub fn blur_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, sigma: f32) {
+  let region = image::imageops::crop_imm(image, x, y, width, height).to_image();
+  let blurred = image::imageops::blur(&region, sigma);
+  image::imageops::replace(image, &blurred, x as i64, y as i64);
+}
+
+/// Replace each `block_size` square with its average color
+pub fn pixelate_rect(
+  image: &mut RgbaImage,
+  x: u32,
+  y: u32,
+  width: u32,
+  height: u32,
+  block_size: u32,
+) {
+  let block_size = block_size.max(1);
+  let x_end = (x + width).min(image.width());
+  let y_end = (y + height).min(image.height());
+
+  let mut block_y = y;
+  while block_y < y_end {
+    let mut block_x = x;
+    let block_bottom = (block_y + block_size).min(y_end);
+    while block_x < x_end {
+      let block_right = (block_x + block_size).min(x_end);
+
+      let mut sum = [0u64; 4];
+      let mut count = 0u64;
+      for py in block_y..block_bottom {
+        for px in block_x..block_right {
+          let pixel = image.get_pixel(px, py);
+          for channel in 0..4 {
+            sum[channel] += pixel[channel] as u64;
+          }
+          count += 1;
+        }
+      }
+      let average = Rgba([
+        (sum[0] / count) as u8,
+        (sum[1] / count) as u8,
+        (sum[2] / count) as u8,
+        (sum[3] / count) as u8,
+      ]);
+      for py in block_y..block_bottom {
+        for px in block_x..block_right {
+          image.put_pixel(px, py, average);
+        }
+      }
+
+      block_x = block_right;
+    }
+    block_y = block_bottom;
+  }
+}
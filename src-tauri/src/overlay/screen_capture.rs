@@ -1,8 +1,23 @@
 use base64::{engine::general_purpose, Engine as _};
-use image::{DynamicImage, ImageFormat, RgbaImage};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{ImageEncoder, RgbaImage};
 use screenshots::Screen;
 use serde::{Deserialize, Serialize};
-use std::io::Cursor;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::{debug, info};
+
+/// Speed/size trade-off for capture PNGs. Fast (fdeflate) encodes large regions several
+/// times faster than the default level at a modestly larger file.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PngCompression {
+  #[default]
+  Fast,
+  Balanced,
+  Best,
+}
+
+static PNG_COMPRESSION: AtomicU8 = AtomicU8::new(PngCompression::Fast as u8);
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct CaptureBounds {
@@ -12,13 +27,154 @@ pub struct CaptureBounds {
   pub height: u32,
 }
 
+/// Bounds in physical pixels of one display's framebuffer
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PhysicalBounds {
+  pub display_id: u32,
+  pub x: i32, // Relative to the display's top-left corner
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub scale_factor: f32,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CaptureResult {
-  pub image_data: String, // Base64 encoded image
-  pub bounds: CaptureBounds,
+  pub image_data: String,    // Base64 encoded image
+  pub bounds: CaptureBounds, // Logical overlay coordinates
+  pub physical_bounds: PhysicalBounds,
   pub timestamp: u64,
 }
 
+#[derive(Clone, Debug)]
+struct DisplayGeometry {
+  id: u32,
+  x: i32, // Logical, virtual desktop coordinates
+  y: i32,
+  width: u32,
+  height: u32,
+  scale_factor: f32,
+}
+
+impl DisplayGeometry {
+  fn contains(&self, x: i32, y: i32) -> bool {
+    x >= self.x && x < self.x + self.width as i32 && y >= self.y && y < self.y + self.height as i32
+  }
+
+  fn distance_squared(&self, x: i32, y: i32) -> i64 {
+    let dx = (x.clamp(self.x, self.x + self.width as i32) - x) as i64;
+    let dy = (y.clamp(self.y, self.y + self.height as i32) - y) as i64;
+    dx * dx + dy * dy
+  }
+}
+
+/// Converts between logical overlay coordinates (relative to the total screen area,
+/// as used by the React overlay) and physical pixels of the display a selection is on,
+/// using each display's own scale factor.
+#[derive(Clone, Debug)]
+pub struct CoordinateMapper {
+  origin_x: i32,
+  origin_y: i32,
+  displays: Vec<DisplayGeometry>,
+}
+
+impl CoordinateMapper {
+  /// Snapshot of the current display layout
+  pub fn current() -> Result<Self, String> {
+    let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
+    if screens.is_empty() {
+      return Err("No screens found".to_string());
+    }
+
+    let displays: Vec<DisplayGeometry> = screens
+      .iter()
+      .map(|screen| {
+        let display = &screen.display_info;
+        DisplayGeometry {
+          id: display.id,
+          x: display.x,
+          y: display.y,
+          width: display.width,
+          height: display.height,
+          scale_factor: if display.scale_factor > 0.0 {
+            display.scale_factor
+          } else {
+            1.0
+          },
+        }
+      })
+      .collect();
+
+    Ok(Self {
+      origin_x: displays.iter().map(|d| d.x).min().unwrap_or(0),
+      origin_y: displays.iter().map(|d| d.y).min().unwrap_or(0),
+      displays,
+    })
+  }
+
+  // Display owning a selection: the one containing its center, else the nearest one
+  fn display_for(&self, bounds: &CaptureBounds) -> &DisplayGeometry {
+    let center_x = self.origin_x + bounds.x + (bounds.width / 2) as i32;
+    let center_y = self.origin_y + bounds.y + (bounds.height / 2) as i32;
+    self
+      .displays
+      .iter()
+      .find(|d| d.contains(center_x, center_y))
+      .or_else(|| {
+        self
+          .displays
+          .iter()
+          .min_by_key(|d| d.distance_squared(center_x, center_y))
+      })
+      .expect("CoordinateMapper always has at least one display")
+  }
+
+  /// Bounds (in overlay coordinates) of the display the selection is on
+  pub fn display_bounds_for(&self, bounds: &CaptureBounds) -> CaptureBounds {
+    let display = self.display_for(bounds);
+    CaptureBounds {
+      x: display.x - self.origin_x,
+      y: display.y - self.origin_y,
+      width: display.width,
+      height: display.height,
+    }
+  }
+
+  /// Logical overlay bounds -> physical pixels of the display the selection is on
+  pub fn to_physical(&self, bounds: &CaptureBounds) -> PhysicalBounds {
+    let display = self.display_for(bounds);
+    let scale = display.scale_factor as f64;
+    let relative_x = self.origin_x + bounds.x - display.x;
+    let relative_y = self.origin_y + bounds.y - display.y;
+
+    PhysicalBounds {
+      display_id: display.id,
+      x: (relative_x as f64 * scale).round() as i32,
+      y: (relative_y as f64 * scale).round() as i32,
+      width: (bounds.width as f64 * scale).round() as u32,
+      height: (bounds.height as f64 * scale).round() as u32,
+      scale_factor: display.scale_factor,
+    }
+  }
+
+  /// Physical pixels of a display -> logical overlay bounds
+  pub fn to_logical(&self, physical: &PhysicalBounds) -> Result<CaptureBounds, String> {
+    let display = self
+      .displays
+      .iter()
+      .find(|d| d.id == physical.display_id)
+      .ok_or_else(|| format!("Display {} not found", physical.display_id))?;
+    let scale = display.scale_factor as f64;
+
+    Ok(CaptureBounds {
+      x: (physical.x as f64 / scale).round() as i32 + display.x - self.origin_x,
+      y: (physical.y as f64 / scale).round() as i32 + display.y - self.origin_y,
+      width: (physical.width as f64 / scale).round() as u32,
+      height: (physical.height as f64 / scale).round() as u32,
+    })
+  }
+}
+
 pub struct ScreenCapture;
 
 impl ScreenCapture {
@@ -28,7 +184,7 @@ impl ScreenCapture {
 
   /// Take a fullscreen screenshot
   pub async fn capture_fullscreen() -> Result<String, String> {
-    println!("🖼️ Taking fullscreen screenshot...");
+    info!("🖼️ Taking fullscreen screenshot...");
 
     let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
 
@@ -37,7 +193,7 @@ impl ScreenCapture {
     }
 
     let screen = &screens[0];
-    println!(
+    info!(
       "📸 Capturing screen: {}x{}",
       screen.display_info.width, screen.display_info.height
     );
@@ -51,7 +207,7 @@ impl ScreenCapture {
 
   /// Take a screenshot of a specific region
   pub async fn capture_region(bounds: CaptureBounds) -> Result<CaptureResult, String> {
-    println!("🎯 Capturing region: {:?}", bounds);
+    debug!("🎯 Capturing region: {:?}", bounds);
 
     let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
 
@@ -59,7 +215,12 @@ impl ScreenCapture {
       return Err("No screens found".to_string());
     }
 
-    let screen = &screens[0];
+    // Full-screen captures are in physical pixels, so crop with physical bounds
+    let physical_bounds = CoordinateMapper::current()?.to_physical(&bounds);
+    let screen = screens
+      .iter()
+      .find(|screen| screen.display_info.id == physical_bounds.display_id)
+      .unwrap_or(&screens[0]);
     let screenshot = screen
       .capture()
       .map_err(|e| format!("Failed to capture screen: {}", e))?;
@@ -73,7 +234,15 @@ impl ScreenCapture {
     .ok_or("Failed to create RGBA image from screenshot")?;
 
     // Crop the image to the specified bounds
-    let cropped = Self::crop_image(rgba_image, &bounds)?;
+    let cropped = Self::crop_image(
+      rgba_image,
+      &CaptureBounds {
+        x: physical_bounds.x,
+        y: physical_bounds.y,
+        width: physical_bounds.width,
+        height: physical_bounds.height,
+      },
+    )?;
 
     // Encode to base64
     let image_data = Self::encode_rgba_to_base64(cropped)?;
@@ -81,6 +250,7 @@ impl ScreenCapture {
     Ok(CaptureResult {
       image_data,
       bounds,
+      physical_bounds,
       timestamp: std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
@@ -88,6 +258,260 @@ impl ScreenCapture {
     })
   }
 
+  /// Physical bounds for logical overlay bounds on the current display layout
+  pub fn physical_bounds(bounds: &CaptureBounds) -> Option<PhysicalBounds> {
+    CoordinateMapper::current()
+      .ok()
+      .map(|mapper| mapper.to_physical(bounds))
+  }
+
+  /// Capture a region given in overlay coordinates (relative to the total screen area)
+  /// as raw RGBA pixels, without PNG encoding
+  pub fn capture_area_rgba(bounds: &CaptureBounds) -> Result<RgbaImage, String> {
+    if Self::uses_portal() {
+      let (min_x, min_y) = Self::get_total_screen_area()
+        .map(|area| (area.min_x, area.min_y))
+        .unwrap_or((0, 0));
+      return Self::capture_via_portal(
+        bounds.x + min_x,
+        bounds.y + min_y,
+        bounds.width,
+        bounds.height,
+      );
+    }
+
+    let total_area = Self::get_total_screen_area()?;
+    let screen_x = bounds.x + total_area.min_x;
+    let screen_y = bounds.y + total_area.min_y;
+
+    let screen = Screen::from_point(screen_x, screen_y)
+      .map_err(|e| format!("No screen at ({}, {}): {}", screen_x, screen_y, e))?;
+    let display = &screen.display_info;
+
+    let relative_x = (screen_x - display.x).max(0);
+    let relative_y = (screen_y - display.y).max(0);
+    let width = bounds
+      .width
+      .min(display.width.saturating_sub(relative_x as u32));
+    let height = bounds
+      .height
+      .min(display.height.saturating_sub(relative_y as u32));
+
+    if width == 0 || height == 0 {
+      return Err("Capture area is outside the screen".to_string());
+    }
+
+    let image = screen
+      .capture_area(relative_x, relative_y, width, height)
+      .map_err(|e| format!("Failed to capture area: {}", e))?;
+
+    let mut rgba = RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())
+      .ok_or_else(|| "Failed to create RGBA image from screenshot".to_string())?;
+    super::redaction::apply_to_capture(
+      &mut rgba, display.id, relative_x, relative_y, width, height,
+    );
+    Ok(rgba)
+  }
+
+  // One screen's part of a spanning capture (virtual desktop coordinates), redacted
+  fn capture_part(
+    screen: &Screen,
+    left: i32,
+    top: i32,
+    part_width: u32,
+    part_height: u32,
+  ) -> Result<(i32, i32, u32, RgbaImage), String> {
+    let display_info = &screen.display_info;
+    let image = screen
+      .capture_area(
+        left - display_info.x,
+        top - display_info.y,
+        part_width,
+        part_height,
+      )
+      .map_err(|e| format!("Failed to capture screen {}: {}", display_info.id, e))?;
+    let mut rgba = RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())
+      .ok_or("Failed to create RGBA image from screenshot")?;
+    super::redaction::apply_to_capture(
+      &mut rgba,
+      display_info.id,
+      left - display_info.x,
+      top - display_info.y,
+      part_width,
+      part_height,
+    );
+
+    info!(
+      "🧩 Captured part from screen {}: {}x{} at ({}, {})",
+      display_info.id, part_width, part_height, left, top
+    );
+    Ok((left, top, part_width, rgba))
+  }
+
+  /// Capture an area in absolute (virtual desktop) logical coordinates that may span
+  /// several monitors. Each overlapping monitor contributes its part, scaled to the
+  /// highest pixel density involved; gaps between monitors stay transparent.
+  pub fn capture_spanning_rgba(
+    abs_x: i32,
+    abs_y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<RgbaImage, String> {
+    if Self::uses_portal() {
+      return Self::capture_via_portal(abs_x, abs_y, width, height);
+    }
+
+    let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
+    let right = abs_x + width as i32;
+    let bottom = abs_y + height as i32;
+
+    // The intersection with every overlapping screen
+    let regions: Vec<(&Screen, i32, i32, u32, u32)> = screens
+      .iter()
+      .filter_map(|screen| {
+        let display_info = &screen.display_info;
+        let left = abs_x.max(display_info.x);
+        let top = abs_y.max(display_info.y);
+        let part_right = right.min(display_info.x + display_info.width as i32);
+        let part_bottom = bottom.min(display_info.y + display_info.height as i32);
+        (part_right > left && part_bottom > top).then(|| {
+          (
+            screen,
+            left,
+            top,
+            (part_right - left) as u32,
+            (part_bottom - top) as u32,
+          )
+        })
+      })
+      .collect();
+
+    // Screens are grabbed concurrently - each grab waits on its display, so a region
+    // spanning two monitors takes about as long as one
+    let parts = if regions.len() > 1 {
+      std::thread::scope(|scope| {
+        let handles: Vec<_> = regions
+          .iter()
+          .map(|&(screen, left, top, part_width, part_height)| {
+            scope.spawn(move || Self::capture_part(screen, left, top, part_width, part_height))
+          })
+          .collect();
+        handles
+          .into_iter()
+          .map(|handle| {
+            handle
+              .join()
+              .unwrap_or_else(|_| Err("Screen capture thread panicked".to_string()))
+          })
+          .collect::<Result<Vec<_>, String>>()
+      })?
+    } else {
+      regions
+        .iter()
+        .map(|&(screen, left, top, part_width, part_height)| {
+          Self::capture_part(screen, left, top, part_width, part_height)
+        })
+        .collect::<Result<Vec<_>, String>>()?
+    };
+
+    if parts.is_empty() {
+      return Err("No screen contains the specified coordinates".to_string());
+    }
+
+    // Output resolution follows the densest screen so nothing is downsampled
+    let scale = parts
+      .iter()
+      .map(|(_, _, logical_width, image)| image.width() as f64 / *logical_width as f64)
+      .fold(1.0_f64, f64::max);
+
+    let canvas_width = (width as f64 * scale).round() as u32;
+    let canvas_height = (height as f64 * scale).round() as u32;
+    let mut canvas = RgbaImage::new(canvas_width, canvas_height);
+
+    for (left, top, logical_width, image) in parts {
+      let logical_height = image.height() as f64 * logical_width as f64 / image.width() as f64;
+      let target_width = (logical_width as f64 * scale).round() as u32;
+      let target_height = (logical_height * scale).round() as u32;
+      let part = if image.dimensions() != (target_width, target_height) {
+        image::imageops::resize(
+          &image,
+          target_width,
+          target_height,
+          image::imageops::FilterType::Triangle,
+        )
+      } else {
+        image
+      };
+      let offset_x = ((left - abs_x) as f64 * scale).round() as i64;
+      let offset_y = ((top - abs_y) as f64 * scale).round() as i64;
+      image::imageops::replace(&mut canvas, &part, offset_x, offset_y);
+    }
+
+    Ok(canvas)
+  }
+
+  /// Direct screen reads are blocked on Wayland; captures go through xdg-desktop-portal
+  pub fn uses_portal() -> bool {
+    super::wayland_portal::is_wayland_session()
+  }
+
+  /// Capture an area in absolute logical coordinates through the screenshot portal.
+  /// The portal returns the whole desktop, which is cropped and redacted here.
+  pub fn capture_via_portal(
+    abs_x: i32,
+    abs_y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<RgbaImage, String> {
+    let desktop = super::wayland_portal::take_screenshot(false)?;
+    let screens = Screen::all().unwrap_or_default();
+    let (min_x, min_y, logical_width) = match Self::get_total_screen_area() {
+      Ok(area) => (area.min_x, area.min_y, area.width),
+      Err(_) => (0, 0, desktop.width()),
+    };
+    let scale = desktop.width() as f64 / logical_width.max(1) as f64;
+    let to_pixels = |value: i32| ((value as f64) * scale).round().max(0.0) as u32;
+
+    let x = to_pixels(abs_x - min_x).min(desktop.width());
+    let y = to_pixels(abs_y - min_y).min(desktop.height());
+    let crop_width = to_pixels(width as i32).min(desktop.width() - x);
+    let crop_height = to_pixels(height as i32).min(desktop.height() - y);
+    if crop_width == 0 || crop_height == 0 {
+      return Err("Capture area is outside the screen".to_string());
+    }
+    let mut image = image::imageops::crop_imm(&desktop, x, y, crop_width, crop_height).to_image();
+
+    // Redaction zones are per display, so apply them to each display's part of the crop
+    for screen in &screens {
+      let display = &screen.display_info;
+      let left = abs_x.max(display.x);
+      let top = abs_y.max(display.y);
+      let right = (abs_x + width as i32).min(display.x + display.width as i32);
+      let bottom = (abs_y + height as i32).min(display.y + display.height as i32);
+      if right <= left || bottom <= top || !super::redaction::has_zones_for(display.id) {
+        continue;
+      }
+
+      let part_x = to_pixels(left - abs_x).min(image.width());
+      let part_y = to_pixels(top - abs_y).min(image.height());
+      let part_width = to_pixels(right - left).min(image.width() - part_x);
+      let part_height = to_pixels(bottom - top).min(image.height() - part_y);
+      let mut part =
+        image::imageops::crop_imm(&image, part_x, part_y, part_width, part_height).to_image();
+      super::redaction::apply_to_capture(
+        &mut part,
+        display.id,
+        left - display.x,
+        top - display.y,
+        (right - left) as u32,
+        (bottom - top) as u32,
+      );
+      image::imageops::replace(&mut image, &part, part_x as i64, part_y as i64);
+    }
+
+    Ok(image)
+  }
+
   /// Crop an RgbaImage to the specified bounds
   fn crop_image(image: RgbaImage, bounds: &CaptureBounds) -> Result<RgbaImage, String> {
     let (img_width, img_height) = image.dimensions();
@@ -137,21 +561,43 @@ impl ScreenCapture {
   }
 
   /// Convert RgbaImage to base64 PNG
-  fn encode_rgba_to_base64(rgba_image: RgbaImage) -> Result<String, String> {
-    let dynamic_image = DynamicImage::ImageRgba8(rgba_image);
-
-    let mut png_buffer = Vec::new();
-    {
-      let mut cursor = Cursor::new(&mut png_buffer);
-      dynamic_image
-        .write_to(&mut cursor, ImageFormat::Png)
-        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
-    }
-
+  pub fn encode_rgba_to_base64(rgba_image: RgbaImage) -> Result<String, String> {
+    let png_buffer = Self::encode_png(&rgba_image)?;
     let base64_data = general_purpose::STANDARD.encode(&png_buffer);
     Ok(format!("data:image/png;base64,{}", base64_data))
   }
 
+  /// PNG-encode at the configured compression level
+  pub fn encode_png(rgba_image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let (compression, filter) = match Self::png_compression() {
+      PngCompression::Fast => (CompressionType::Fast, FilterType::Adaptive),
+      PngCompression::Balanced => (CompressionType::Default, FilterType::Adaptive),
+      PngCompression::Best => (CompressionType::Best, FilterType::Adaptive),
+    };
+    let mut png_buffer = Vec::with_capacity(rgba_image.as_raw().len() / 4);
+    PngEncoder::new_with_quality(&mut png_buffer, compression, filter)
+      .write_image(
+        rgba_image.as_raw(),
+        rgba_image.width(),
+        rgba_image.height(),
+        image::ColorType::Rgba8,
+      )
+      .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png_buffer)
+  }
+
+  pub fn set_png_compression(compression: PngCompression) {
+    PNG_COMPRESSION.store(compression as u8, Ordering::Relaxed);
+  }
+
+  pub fn png_compression() -> PngCompression {
+    match PNG_COMPRESSION.load(Ordering::Relaxed) {
+      1 => PngCompression::Balanced,
+      2 => PngCompression::Best,
+      _ => PngCompression::Fast,
+    }
+  }
+
   /// Get display information for all screens
   pub fn get_screen_info() -> Result<Vec<ScreenInfo>, String> {
     let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
@@ -160,21 +606,35 @@ impl ScreenCapture {
       .iter()
       .enumerate()
       .map(|(index, screen)| {
-        println!(
-          "🖥️ Screen {}: {}x{} at ({}, {}) scale={}",
+        let display_info = &screen.display_info;
+        info!(
+          "🖥️ Screen {} (id {}): {}x{} at ({}, {}) scale={}",
           index,
-          screen.display_info.width,
-          screen.display_info.height,
-          screen.display_info.x,
-          screen.display_info.y,
-          screen.display_info.scale_factor
+          display_info.id,
+          display_info.width,
+          display_info.height,
+          display_info.x,
+          display_info.y,
+          display_info.scale_factor
         );
         ScreenInfo {
-          id: index as u32,
-          width: screen.display_info.width,
-          height: screen.display_info.height,
-          scale_factor: screen.display_info.scale_factor,
-          is_primary: index == 0, // First screen is typically primary
+          id: display_info.id,
+          // The OS doesn't expose a friendly name here, so number displays in system order
+          name: format!(
+            "Display {}{}",
+            index + 1,
+            if display_info.is_primary {
+              " (Primary)"
+            } else {
+              ""
+            }
+          ),
+          x: display_info.x,
+          y: display_info.y,
+          width: display_info.width,
+          height: display_info.height,
+          scale_factor: display_info.scale_factor,
+          is_primary: display_info.is_primary,
         }
       })
       .collect();
@@ -182,6 +642,60 @@ impl ScreenCapture {
     Ok(screen_info)
   }
 
+  /// Capture one whole monitor by its display id.
+  /// Returns the image and the monitor's bounds in overlay coordinates.
+  pub fn capture_monitor(monitor_id: u32) -> Result<(String, CaptureBounds), String> {
+    let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
+    let screen = screens
+      .iter()
+      .find(|screen| screen.display_info.id == monitor_id)
+      .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+    let display_info = &screen.display_info;
+
+    info!(
+      "🖥️ Capturing monitor {}: {}x{} at ({}, {})",
+      monitor_id, display_info.width, display_info.height, display_info.x, display_info.y
+    );
+
+    let rgba = if Self::uses_portal() {
+      Self::capture_via_portal(
+        display_info.x,
+        display_info.y,
+        display_info.width,
+        display_info.height,
+      )?
+    } else {
+      let screenshot = screen
+        .capture()
+        .map_err(|e| format!("Failed to capture monitor {}: {}", monitor_id, e))?;
+      let mut rgba = RgbaImage::from_raw(
+        screenshot.width(),
+        screenshot.height(),
+        screenshot.rgba().to_vec(),
+      )
+      .ok_or("Failed to create RGBA image from screenshot")?;
+      super::redaction::apply_to_capture(
+        &mut rgba,
+        display_info.id,
+        0,
+        0,
+        display_info.width,
+        display_info.height,
+      );
+      rgba
+    };
+
+    let total_area = Self::get_total_screen_area()?;
+    let bounds = CaptureBounds {
+      x: display_info.x - total_area.min_x,
+      y: display_info.y - total_area.min_y,
+      width: display_info.width,
+      height: display_info.height,
+    };
+
+    Ok((Self::encode_rgba_to_base64(rgba)?, bounds))
+  }
+
   /// Get the total area covering all screens
   pub fn get_total_screen_area() -> Result<TotalScreenArea, String> {
     let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
@@ -213,7 +727,7 @@ impl ScreenCapture {
     let total_width = (max_x - min_x) as u32;
     let total_height = (max_y - min_y) as u32;
 
-    println!(
+    info!(
       "🖥️ Total screen area: {}x{} from ({}, {}) to ({}, {})",
       total_width, total_height, min_x, min_y, max_x, max_y
     );
@@ -231,7 +745,10 @@ impl ScreenCapture {
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ScreenInfo {
-  pub id: u32,
+  pub id: u32, // OS display id, stable across launches
+  pub name: String,
+  pub x: i32,
+  pub y: i32,
   pub width: u32,
   pub height: u32,
   pub scale_factor: f32,
@@ -1,7 +1,14 @@
+use super::redaction;
 use crate::CaptureBounds;
 use base64::Engine;
+use image::{imageops, DynamicImage, ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
 use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct BoundsKey {
@@ -11,6 +18,16 @@ struct BoundsKey {
   height: u32,
 }
 
+impl BoundsKey {
+  // Whether this cached area fully covers `bounds`
+  fn contains(&self, bounds: &CaptureBounds) -> bool {
+    bounds.x >= self.x
+      && bounds.y >= self.y
+      && bounds.x as i64 + bounds.width as i64 <= self.x as i64 + self.width as i64
+      && bounds.y as i64 + bounds.height as i64 <= self.y as i64 + self.height as i64
+  }
+}
+
 impl From<CaptureBounds> for BoundsKey {
   fn from(bounds: CaptureBounds) -> Self {
     Self {
@@ -24,17 +41,136 @@ impl From<CaptureBounds> for BoundsKey {
 
 #[derive(Debug, Clone)]
 struct CachedCapture {
-  data: String, // Base64 PNG data
+  content_hash: u64, // Key into `ScreenshotCache::blobs`
   captured_at: Instant,
-  size_bytes: usize,
+  last_access: u64, // Logical clock tick of the last insert/hit
+  hits: u64,
+}
+
+// Capture data shared by every bounds entry with identical content. Kept as raw PNG
+// bytes (a third smaller than base64); the data URL is only built on a hit.
+#[derive(Debug, Clone)]
+struct CachedBlob {
+  png: Vec<u8>,
+  refs: usize,
+}
+
+fn content_hash(data: &[u8]) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  data.hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Which entries go first when the cache is full (expired entries always go first)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvictionPolicy {
+  // Least recently used
+  #[default]
+  Lru,
+  // Least frequently used, ties broken by recency
+  Lfu,
+}
+
+/// Full-desktop snapshot taken before the overlay opens (freeze-screen mode)
+pub struct FrozenFrame {
+  image: RgbaImage,
+  scale: f64, // Image pixels per logical pixel
+}
+
+impl FrozenFrame {
+  /// Snapshot the whole desktop. Slow on big setups, so it's taken without holding
+  /// the cache and handed over with `ScreenshotCache::freeze`.
+  pub fn capture() -> Result<Self, String> {
+    let total_area = crate::overlay::screen_capture::ScreenCapture::get_total_screen_area()?;
+    let image = crate::overlay::screen_capture::ScreenCapture::capture_spanning_rgba(
+      total_area.min_x,
+      total_area.min_y,
+      total_area.width,
+      total_area.height,
+    )?;
+    let scale = image.width() as f64 / total_area.width as f64;
+    Ok(Self { image, scale })
+  }
+}
+
+/// Optional size limit applied before encoding, so e.g. 6K monitor captures don't
+/// produce huge payloads for AI analysis. Aspect ratio is preserved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxDimensions {
+  pub max_width: Option<u32>,
+  pub max_height: Option<u32>,
+}
+
+impl MaxDimensions {
+  /// Size to downscale to, or None when the image already fits
+  fn target_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+    let scale_x = self
+      .max_width
+      .map_or(1.0, |max| max as f64 / width.max(1) as f64);
+    let scale_y = self
+      .max_height
+      .map_or(1.0, |max| max as f64 / height.max(1) as f64);
+    let scale = scale_x.min(scale_y);
+    if scale >= 1.0 {
+      return None;
+    }
+    Some((
+      ((width as f64 * scale).round() as u32).max(1),
+      ((height as f64 * scale).round() as u32).max(1),
+    ))
+  }
+
+  fn apply(&self, image: RgbaImage) -> RgbaImage {
+    match self.target_size(image.width(), image.height()) {
+      Some((width, height)) => {
+        info!(
+          "📉 Downscaling capture {}x{} → {}x{}",
+          image.width(),
+          image.height(),
+          width,
+          height
+        );
+        imageops::resize(&image, width, height, imageops::FilterType::Triangle)
+      }
+      None => image,
+    }
+  }
 }
 
+/// Tunable cache limits (defaults: 30s TTL, 50MB)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheConfig {
+  pub ttl_secs: u64,
+  pub max_size_mb: u64,
+}
+
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_CACHE_SIZE: usize = 50 * 1024 * 1024;
+
 pub struct ScreenshotCache {
   cache: HashMap<BoundsKey, CachedCapture>,
+  blobs: HashMap<u64, CachedBlob>,
   screen_info: Option<ScreenInfo>,
   png_buffer: Vec<u8>, // Återanvänd buffer
   max_cache_size: usize,
   cache_ttl: Duration,
+  freeze_mode: bool,
+  frozen: Option<FrozenFrame>,
+  max_dimensions: MaxDimensions,
+  eviction_policy: EvictionPolicy,
+  access_clock: u64,
+  evictions: u64,
+}
+
+/// Result of `ScreenshotCache::grab_optimized`
+pub enum GrabbedCapture {
+  /// Served from the cache, already a PNG data URL
+  Encoded(String),
+  /// Freshly grabbed pixels, still to be encoded
+  Pixels(RgbaImage),
 }
 
 #[derive(Debug, Clone)]
@@ -49,10 +185,17 @@ impl ScreenshotCache {
   pub fn new() -> Self {
     Self {
       cache: HashMap::new(),
+      blobs: HashMap::new(),
       screen_info: None,
       png_buffer: Vec::with_capacity(1024 * 1024), // 1MB initial buffer
-      max_cache_size: 50 * 1024 * 1024,            // 50MB max cache
-      cache_ttl: Duration::from_secs(30),          // 30s cache TTL
+      max_cache_size: DEFAULT_MAX_CACHE_SIZE,
+      cache_ttl: DEFAULT_CACHE_TTL,
+      freeze_mode: false,
+      frozen: None,
+      max_dimensions: MaxDimensions::default(),
+      eviction_policy: EvictionPolicy::default(),
+      access_clock: 0,
+      evictions: 0,
     }
   }
 
@@ -60,17 +203,18 @@ impl ScreenshotCache {
     let bounds_key = BoundsKey::from(bounds.clone());
 
     // 1. Cache check
-    if let Some(cached) = self.cache.get(&bounds_key) {
-      if cached.captured_at.elapsed() < self.cache_ttl {
-        println!(
-          "💰 Screenshot cache hit: {}x{}",
-          bounds.width, bounds.height
-        );
-        return Ok(cached.data.clone());
-      } else {
-        println!("⏰ Screenshot cache expired");
-        self.cache.remove(&bounds_key);
-      }
+    if let Some(data) = self.lookup(&bounds_key) {
+      debug!(
+        "💰 Screenshot cache hit: {}x{}",
+        bounds.width, bounds.height
+      );
+      return Ok(data);
+    }
+
+    // 1b. A recent larger capture (e.g. full screen) that contains this region
+    if let Some(data) = self.crop_from_containing(&bounds) {
+      self.add_to_cache(bounds_key, data.clone());
+      return Ok(data);
     }
 
     // 2. Screen info cache
@@ -78,11 +222,12 @@ impl ScreenshotCache {
       || self.screen_info.as_ref().unwrap().cached_at.elapsed() > Duration::from_secs(60)
     {
       self.screen_info = Some(self.get_screen_info()?);
-      println!("📺 Refreshed screen info cache");
+      info!("📺 Refreshed screen info cache");
     }
 
     // 3. Optimerad capture
-    let image_data = self.capture_with_reused_buffer(bounds.clone())?;
+    let rgba = self.grab_rgba(bounds)?;
+    let image_data = self.encode_capture(&rgba)?;
 
     // 4. Cache management
     self.add_to_cache(bounds_key, image_data.clone());
@@ -90,24 +235,352 @@ impl ScreenshotCache {
     Ok(image_data)
   }
 
-  fn capture_with_reused_buffer(&mut self, bounds: CaptureBounds) -> Result<String, String> {
-    println!("🎯 === MULTI-SCREEN CAPTURE DEBUG ===");
-    println!(
+  /// Like `capture_optimized`, but a fresh grab comes back as raw pixels so the caller
+  /// can release the cache (and hide the overlay) before the slow PNG encode. Encode
+  /// them with `ScreenCapture::encode_rgba_to_base64` and hand the result to
+  /// `store_capture`.
+  pub fn grab_optimized(&mut self, bounds: CaptureBounds) -> Result<GrabbedCapture, String> {
+    let bounds_key = BoundsKey::from(bounds.clone());
+    if let Some(data) = self.lookup(&bounds_key) {
+      debug!(
+        "💰 Screenshot cache hit: {}x{}",
+        bounds.width, bounds.height
+      );
+      return Ok(GrabbedCapture::Encoded(data));
+    }
+    if let Some(data) = self.crop_from_containing(&bounds) {
+      self.add_to_cache(bounds_key, data.clone());
+      return Ok(GrabbedCapture::Encoded(data));
+    }
+    self.grab_rgba(bounds).map(GrabbedCapture::Pixels)
+  }
+
+  /// Cache a capture grabbed with `grab_optimized` once it's encoded
+  pub fn store_capture(&mut self, bounds: CaptureBounds, image_data: String) {
+    self.add_to_cache(BoundsKey::from(bounds), image_data);
+  }
+
+  // Cached data URL for `key` if it hasn't expired; counts as a use for eviction
+  fn lookup(&mut self, key: &BoundsKey) -> Option<String> {
+    self.lookup_png(key).map(|png| {
+      format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+      )
+    })
+  }
+
+  fn lookup_png(&mut self, key: &BoundsKey) -> Option<Vec<u8>> {
+    let ttl = self.cache_ttl;
+    match self.cache.get_mut(key) {
+      Some(cached) if cached.captured_at.elapsed() < ttl => {
+        self.access_clock += 1;
+        cached.last_access = self.access_clock;
+        cached.hits += 1;
+        let hash = cached.content_hash;
+        self.blobs.get(&hash).map(|blob| blob.png.clone())
+      }
+      Some(_) => {
+        info!("⏰ Screenshot cache expired");
+        self.remove_entry(key);
+        None
+      }
+      None => None,
+    }
+  }
+
+  // Crop the region out of the smallest fresh cached capture that fully contains it,
+  // instead of grabbing the screen again
+  fn crop_from_containing(&mut self, bounds: &CaptureBounds) -> Option<String> {
+    let ttl = self.cache_ttl;
+    let container = self
+      .cache
+      .iter()
+      .filter(|(key, cached)| {
+        cached.captured_at.elapsed() < ttl
+          && key.width > 0
+          && key.height > 0
+          && key.contains(bounds)
+          && (bounds.width, bounds.height) != (key.width, key.height)
+      })
+      .min_by_key(|(key, _)| key.width as u64 * key.height as u64)
+      .map(|(key, _)| key.clone())?;
+    let png = self.lookup_png(&container)?;
+    let image = match image::load_from_memory(&png) {
+      Ok(image) => image.to_rgba8(),
+      Err(e) => {
+        warn!("⚠️ Could not decode cached capture for cropping: {}", e);
+        return None;
+      }
+    };
+
+    // Cached images can be HiDPI or downscaled, so map through the image's own scale
+    let scale_x = image.width() as f64 / container.width as f64;
+    let scale_y = image.height() as f64 / container.height as f64;
+    let x = ((bounds.x - container.x) as f64 * scale_x).round() as u32;
+    let y = ((bounds.y - container.y) as f64 * scale_y).round() as u32;
+    let width =
+      ((bounds.width as f64 * scale_x).round() as u32).min(image.width() - x.min(image.width()));
+    let height =
+      ((bounds.height as f64 * scale_y).round() as u32).min(image.height() - y.min(image.height()));
+    if width == 0 || height == 0 {
+      return None;
+    }
+
+    let cropped = imageops::crop_imm(&image, x, y, width, height).to_image();
+    info!(
+      "✂️ Reused cached {}x{} capture: cropped {}x{} at ({}, {})",
+      container.width, container.height, width, height, x, y
+    );
+    crate::overlay::screen_capture::ScreenCapture::encode_rgba_to_base64(
+      self.max_dimensions.apply(cropped),
+    )
+    .ok()
+  }
+
+  pub fn config(&self) -> CacheConfig {
+    CacheConfig {
+      ttl_secs: self.cache_ttl.as_secs(),
+      max_size_mb: (self.max_cache_size / (1024 * 1024)) as u64,
+    }
+  }
+
+  /// Change TTL and size limit; entries over the new limit are evicted right away
+  pub fn set_config(&mut self, config: CacheConfig) -> Result<(), String> {
+    if config.ttl_secs == 0 {
+      return Err("Cache TTL must be at least 1 second".to_string());
+    }
+    if config.max_size_mb == 0 {
+      return Err("Cache size must be at least 1MB".to_string());
+    }
+    self.cache_ttl = Duration::from_secs(config.ttl_secs);
+    self.max_cache_size = (config.max_size_mb as usize).saturating_mul(1024 * 1024);
+
+    let total_size = self.get_total_cache_size();
+    if total_size > self.max_cache_size {
+      self.evict_entries(total_size - self.max_cache_size);
+    }
+    info!(
+      "⚙️ Screenshot cache config: TTL {}s, max {}MB",
+      config.ttl_secs, config.max_size_mb
+    );
+    Ok(())
+  }
+
+  pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+    self.eviction_policy = policy;
+    info!("🗂️ Screenshot cache eviction policy: {:?}", policy);
+  }
+
+  pub fn eviction_policy(&self) -> EvictionPolicy {
+    self.eviction_policy
+  }
+
+  /// Entries evicted to make room since startup
+  pub fn evictions(&self) -> u64 {
+    self.evictions
+  }
+
+  // Bypass the cache (screen content may have changed, e.g. after a delay) but store the result.
+  // Cached captures containing the region are just as stale, so they go too.
+  pub fn capture_fresh(&mut self, bounds: CaptureBounds) -> Result<String, String> {
+    let stale: Vec<BoundsKey> = self
+      .cache
+      .keys()
+      .filter(|key| key.contains(&bounds))
+      .cloned()
+      .collect();
+    for key in stale {
+      self.remove_entry(&key);
+    }
+    self.capture_optimized(bounds)
+  }
+
+  /// Grab the whole virtual desktop into the cache so the coming selection can be
+  /// cropped from it instead of captured after the drag. Call it before the overlay is
+  /// on screen - its instructions and close button would end up in the frame.
+  pub fn prewarm(&mut self) -> Result<(), String> {
+    let area = crate::overlay::screen_capture::ScreenCapture::get_total_screen_area()?;
+    let started = Instant::now();
+    // Bounds are relative to the total screen area, like every selection
+    self.capture_fresh(CaptureBounds {
+      x: 0,
+      y: 0,
+      width: area.width,
+      height: area.height,
+    })?;
+    info!(
+      "🔥 Pre-warmed screenshot cache with {}x{} desktop in {}ms",
+      area.width,
+      area.height,
+      started.elapsed().as_millis()
+    );
+    Ok(())
+  }
+
+  pub fn set_max_dimensions(&mut self, max_dimensions: MaxDimensions) {
+    if max_dimensions.max_width == Some(0) || max_dimensions.max_height == Some(0) {
+      warn!("⚠️ Ignoring zero max capture dimension");
+      return;
+    }
+    if self.max_dimensions != max_dimensions {
+      // Cached captures were encoded with the old limit
+      self.clear_cache();
+      self.max_dimensions = max_dimensions;
+      info!(
+        "📉 Max capture size: {:?}x{:?}",
+        max_dimensions.max_width, max_dimensions.max_height
+      );
+    }
+  }
+
+  pub fn max_dimensions(&self) -> MaxDimensions {
+    self.max_dimensions
+  }
+
+  pub fn set_freeze_mode(&mut self, enabled: bool) {
+    self.freeze_mode = enabled;
+    info!(
+      "🧊 Freeze-screen mode {}",
+      if enabled { "enabled" } else { "disabled" }
+    );
+  }
+
+  pub fn freeze_mode(&self) -> bool {
+    self.freeze_mode
+  }
+
+  /// Make the selection on `frame` instead of the live screen
+  pub fn freeze(&mut self, frame: FrozenFrame) {
+    info!(
+      "🧊 Screen frozen: {}x{}",
+      frame.image.width(),
+      frame.image.height()
+    );
+    self.frozen = Some(frame);
+  }
+
+  pub fn is_frozen(&self) -> bool {
+    self.frozen.is_some()
+  }
+
+  /// The frozen snapshot cut into the given regions (overlay coordinates), e.g. one per
+  /// display; empty when the screen isn't frozen. Encode them with `encode_preview`
+  /// after releasing the cache.
+  pub fn frozen_regions<K: Clone>(&self, regions: &[(K, CaptureBounds)]) -> Vec<(K, RgbaImage)> {
+    let Some(frame) = self.frozen.as_ref() else {
+      return Vec::new();
+    };
+    let (image_width, image_height) = frame.image.dimensions();
+    regions
+      .iter()
+      .filter_map(|(key, bounds)| {
+        let x = ((bounds.x.max(0) as f64) * frame.scale).round() as u32;
+        let y = ((bounds.y.max(0) as f64) * frame.scale).round() as u32;
+        let width =
+          ((bounds.width as f64 * frame.scale).round() as u32).min(image_width.checked_sub(x)?);
+        let height =
+          ((bounds.height as f64 * frame.scale).round() as u32).min(image_height.checked_sub(y)?);
+        let region = imageops::crop_imm(&frame.image, x, y, width, height).to_image();
+        Some((key.clone(), region))
+      })
+      .collect()
+  }
+
+  /// JPEG data URL for showing part of the frozen snapshot; it's only for display, so
+  /// this favours encoding speed over fidelity
+  pub fn encode_preview(image: RgbaImage) -> Result<String, String> {
+    let mut jpeg_data = Vec::new();
+    DynamicImage::ImageRgba8(image)
+      .to_rgb8()
+      .write_to(&mut Cursor::new(&mut jpeg_data), ImageFormat::Jpeg)
+      .map_err(|e| format!("Failed to encode frozen frame: {}", e))?;
+    Ok(format!(
+      "data:image/jpeg;base64,{}",
+      base64::engine::general_purpose::STANDARD.encode(&jpeg_data)
+    ))
+  }
+
+  /// Crop a selection (overlay coordinates) out of the frozen snapshot, unencoded
+  pub fn crop_frozen(&self, bounds: &CaptureBounds) -> Result<RgbaImage, String> {
+    let frame = self.frozen.as_ref().ok_or("Screen is not frozen")?;
+    let (image_width, image_height) = frame.image.dimensions();
+    let x = ((bounds.x.max(0) as f64) * frame.scale).round() as u32;
+    let y = ((bounds.y.max(0) as f64) * frame.scale).round() as u32;
+    if x >= image_width || y >= image_height {
+      return Err("Selection is outside the frozen screen".to_string());
+    }
+    let width = ((bounds.width as f64 * frame.scale).round() as u32).min(image_width - x);
+    let height = ((bounds.height as f64 * frame.scale).round() as u32).min(image_height - y);
+    if width == 0 || height == 0 {
+      return Err("Selection is outside the frozen screen".to_string());
+    }
+
+    let cropped = image::imageops::crop_imm(&frame.image, x, y, width, height).to_image();
+    info!(
+      "🧊 Cropped {}x{} from frozen screen at ({}, {})",
+      width, height, x, y
+    );
+    Ok(self.max_dimensions.apply(cropped))
+  }
+
+  /// Pixels of the frozen snapshot around a point (overlay coordinates), at the snapshot's
+  /// full resolution so the loupe shows real device pixels. Areas outside the snapshot
+  /// stay transparent, keeping the point in the center.
+  pub fn frozen_patch(&self, x: i32, y: i32, radius: u32) -> Option<RgbaImage> {
+    let frame = self.frozen.as_ref()?;
+    let size = radius * 2 + 1;
+    let center_x = (x as f64 * frame.scale).floor() as i64;
+    let center_y = (y as f64 * frame.scale).floor() as i64;
+    let mut patch = RgbaImage::new(size, size);
+    for patch_y in 0..size {
+      for patch_x in 0..size {
+        let source_x = center_x + patch_x as i64 - radius as i64;
+        let source_y = center_y + patch_y as i64 - radius as i64;
+        if source_x >= 0
+          && source_y >= 0
+          && source_x < frame.image.width() as i64
+          && source_y < frame.image.height() as i64
+        {
+          let pixel = *frame.image.get_pixel(source_x as u32, source_y as u32);
+          patch.put_pixel(patch_x, patch_y, pixel);
+        }
+      }
+    }
+    Some(patch)
+  }
+
+  pub fn unfreeze(&mut self) {
+    if self.frozen.take().is_some() {
+      info!("🧊 Frozen screen released");
+    }
+  }
+
+  // Grab the selection's pixels (redacted and downscaled), without encoding them
+  fn grab_rgba(&mut self, bounds: CaptureBounds) -> Result<RgbaImage, String> {
+    debug!("🎯 === MULTI-SCREEN CAPTURE DEBUG ===");
+    info!(
       "📐 User selected area: {}x{} at overlay coordinates ({}, {})",
       bounds.width, bounds.height, bounds.x, bounds.y
     );
 
+    if crate::overlay::screen_capture::ScreenCapture::uses_portal() {
+      info!("🐧 Wayland session - capturing through the screenshot portal");
+      let rgba = crate::overlay::screen_capture::ScreenCapture::capture_area_rgba(&bounds)?;
+      return Ok(self.max_dimensions.apply(rgba));
+    }
+
     // Get total screen area to handle multi-screen coordinates correctly
     let total_area = match crate::overlay::screen_capture::ScreenCapture::get_total_screen_area() {
       Ok(area) => {
-        println!(
+        info!(
           "🖥️ Total screen area detected: {}x{} spanning from ({}, {}) to ({}, {})",
           area.width, area.height, area.min_x, area.min_y, area.max_x, area.max_y
         );
         area
       }
       Err(e) => {
-        println!(
+        warn!(
           "⚠️ Could not get total screen area: {}, falling back to single screen",
           e
         );
@@ -119,8 +592,8 @@ impl ScreenshotCache {
     let screen_x = bounds.x + total_area.min_x;
     let screen_y = bounds.y + total_area.min_y;
 
-    println!("🔄 Coordinate conversion:");
-    println!(
+    info!("🔄 Coordinate conversion:");
+    info!(
       "   Overlay coords: ({}, {}) → Screen coords: ({}, {})",
       bounds.x, bounds.y, screen_x, screen_y
     );
@@ -128,23 +601,42 @@ impl ScreenshotCache {
     // Try to capture from the appropriate screen
     match screenshots::Screen::all() {
       Ok(screens) => {
-        println!("🔍 Searching through {} available screens:", screens.len());
+        info!("🔍 Searching through {} available screens:", screens.len());
+
+        // Selections crossing a monitor boundary are stitched from every screen they touch
+        let overlapping_screens = screens
+          .iter()
+          .filter(|screen| {
+            let display_info = &screen.display_info;
+            screen_x < display_info.x + display_info.width as i32
+              && screen_x + bounds.width as i32 > display_info.x
+              && screen_y < display_info.y + display_info.height as i32
+              && screen_y + bounds.height as i32 > display_info.y
+          })
+          .count();
+        if overlapping_screens > 1 {
+          info!(
+            "🧩 Selection spans {} screens - stitching parts together",
+            overlapping_screens
+          );
+          return self.capture_spanning_screens(screen_x, screen_y, bounds.width, bounds.height);
+        }
 
         // Find which screen contains this point
         for (screen_index, screen) in screens.iter().enumerate() {
-          let display = &screen.display_info;
-          let screen_left = display.x;
-          let screen_top = display.y;
-          let screen_right = display.x + display.width as i32;
-          let screen_bottom = display.y + display.height as i32;
+          let display_info = &screen.display_info;
+          let screen_left = display_info.x;
+          let screen_top = display_info.y;
+          let screen_right = display_info.x + display_info.width as i32;
+          let screen_bottom = display_info.y + display_info.height as i32;
 
-          println!(
+          info!(
             "   Screen {}: {}x{} at ({}, {}) → bounds ({}, {}) to ({}, {})",
             screen_index,
-            display.width,
-            display.height,
-            display.x,
-            display.y,
+            display_info.width,
+            display_info.height,
+            display_info.x,
+            display_info.y,
             screen_left,
             screen_top,
             screen_right,
@@ -158,7 +650,7 @@ impl ScreenshotCache {
             && (screen_y + bounds.height as i32) > screen_top;
 
           if overlaps {
-            println!(
+            info!(
               "✅ Found target screen {}! Capture area overlaps with this screen.",
               screen_index
             );
@@ -167,8 +659,8 @@ impl ScreenshotCache {
             let relative_x = screen_x - screen_left;
             let relative_y = screen_y - screen_top;
 
-            println!("🔄 Converting to screen-relative coordinates:");
-            println!(
+            info!("🔄 Converting to screen-relative coordinates:");
+            info!(
               "   Absolute ({}, {}) → Relative ({}, {})",
               screen_x, screen_y, relative_x, relative_y
             );
@@ -176,79 +668,95 @@ impl ScreenshotCache {
             // Clamp to screen bounds
             let safe_x = relative_x
               .max(0)
-              .min(display.width as i32 - bounds.width as i32);
+              .min(display_info.width as i32 - bounds.width as i32);
             let safe_y = relative_y
               .max(0)
-              .min(display.height as i32 - bounds.height as i32);
-            let safe_width = bounds.width.min(display.width - safe_x as u32);
-            let safe_height = bounds.height.min(display.height - safe_y as u32);
+              .min(display_info.height as i32 - bounds.height as i32);
+            let safe_width = bounds.width.min(display_info.width - safe_x as u32);
+            let safe_height = bounds.height.min(display_info.height - safe_y as u32);
 
-            println!("🛡️ Safety clamping applied:");
-            println!(
+            info!("🛡️ Safety clamping applied:");
+            info!(
               "   Raw relative: ({}, {}) → Safe: ({}, {})",
               relative_x, relative_y, safe_x, safe_y
             );
-            println!(
+            info!(
               "   Requested size: {}x{} → Safe size: {}x{}",
               bounds.width, bounds.height, safe_width, safe_height
             );
 
             // Ensure minimum size
             if safe_width < 10 || safe_height < 10 {
-              println!("⚠️ Area too small after safety clamping, trying next screen...");
+              warn!("⚠️ Area too small after safety clamping, trying next screen...");
               continue; // Try next screen
             }
 
-            println!(
+            info!(
               "📸 Capturing from Screen {} at coordinates ({}, {}) with size {}x{}",
               screen_index, safe_x, safe_y, safe_width, safe_height
             );
 
             match screen.capture_area(safe_x, safe_y, safe_width, safe_height) {
-              Ok(image) => match image.to_png(None) {
-                Ok(png_data) => {
-                  self.png_buffer.clear();
-                  self.png_buffer.extend_from_slice(&png_data);
-
-                  let base64_data = base64::engine::general_purpose::STANDARD.encode(&png_data);
-                  let full_data = format!("data:image/png;base64,{}", base64_data);
-
-                  println!("✅ MULTI-SCREEN CAPTURE SUCCESS!");
-                  println!(
+              Ok(image) => match Self::prepare_capture(
+                image,
+                self.max_dimensions,
+                display_info.id,
+                safe_x,
+                safe_y,
+                safe_width,
+                safe_height,
+              ) {
+                Ok(rgba) => {
+                  info!("✅ MULTI-SCREEN CAPTURE SUCCESS!");
+                  info!(
                     "   Screen: {} ({}x{} at {})",
-                    screen_index, display.width, display.height, display.x
+                    screen_index, display_info.width, display_info.height, display_info.x
                   );
-                  println!(
+                  info!(
                     "   Captured: {}x{} at screen coords ({}, {})",
                     safe_width, safe_height, safe_x, safe_y
                   );
-                  println!("   Data size: {}KB", png_data.len() / 1024);
-                  println!("🎯 === END CAPTURE DEBUG ===\n");
+                  debug!("🎯 === END CAPTURE DEBUG ===\n");
 
-                  return Ok(full_data);
+                  return Ok(rgba);
                 }
-                Err(e) => println!("❌ PNG encoding failed: {}", e),
+                Err(e) => error!("❌ Capture conversion failed: {}", e),
               },
-              Err(e) => println!("❌ Screen capture failed: {}", e),
+              Err(e) => error!("❌ Screen capture failed: {}", e),
             }
           } else {
-            println!("   ❌ No overlap with screen {}", screen_index);
+            error!("   ❌ No overlap with screen {}", screen_index);
           }
         }
 
-        println!("❌ No screen contains the specified coordinates");
-        println!("🎯 === END CAPTURE DEBUG ===\n");
+        error!("❌ No screen contains the specified coordinates");
+        debug!("🎯 === END CAPTURE DEBUG ===\n");
         Err("No screen contains the specified coordinates".to_string())
       }
       Err(e) => {
-        println!("❌ Failed to access screens: {}", e);
-        println!("🎯 === END CAPTURE DEBUG ===\n");
+        error!("❌ Failed to access screens: {}", e);
+        debug!("🎯 === END CAPTURE DEBUG ===\n");
         Err(format!("Failed to access screens: {}", e))
       }
     }
   }
 
-  fn capture_single_screen_fallback(&mut self, bounds: CaptureBounds) -> Result<String, String> {
+  fn capture_spanning_screens(
+    &mut self,
+    screen_x: i32,
+    screen_y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<RgbaImage, String> {
+    let stitched = crate::overlay::screen_capture::ScreenCapture::capture_spanning_rgba(
+      screen_x, screen_y, width, height,
+    )?;
+    info!("✅ Stitched multi-screen capture: {}x{}", width, height);
+    debug!("🎯 === END CAPTURE DEBUG ===\n");
+    Ok(self.max_dimensions.apply(stitched))
+  }
+
+  fn capture_single_screen_fallback(&mut self, bounds: CaptureBounds) -> Result<RgbaImage, String> {
     // Original single-screen logic as fallback
     match screenshots::Screen::all() {
       Ok(screens) => {
@@ -277,22 +785,21 @@ impl ScreenshotCache {
           }
 
           match screen.capture_area(safe_x, safe_y, safe_width, safe_height) {
-            Ok(image) => match image.to_png(None) {
-              Ok(png_data) => {
-                self.png_buffer.clear();
-                self.png_buffer.extend_from_slice(&png_data);
-
-                let base64_data = base64::engine::general_purpose::STANDARD.encode(&png_data);
-                let full_data = format!("data:image/png;base64,{}", base64_data);
-
-                println!(
-                  "📸 Fallback single-screen capture: {}KB",
-                  png_data.len() / 1024
-                );
-                Ok(full_data)
-              }
-              Err(e) => Err(format!("PNG encoding failed: {}", e)),
-            },
+            Ok(image) => {
+              info!(
+                "📸 Fallback single-screen capture: {}x{}",
+                safe_width, safe_height
+              );
+              Self::prepare_capture(
+                image,
+                self.max_dimensions,
+                screen.display_info.id,
+                safe_x,
+                safe_y,
+                safe_width,
+                safe_height,
+              )
+            }
             Err(e) => Err(format!("Screen capture failed: {}", e)),
           }
         } else {
@@ -303,56 +810,147 @@ impl ScreenshotCache {
     }
   }
 
+  // Apply any redaction zones configured for the capture's display, then the max
+  // dimensions
+  fn prepare_capture(
+    image: screenshots::Image,
+    max_dimensions: MaxDimensions,
+    display_id: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+  ) -> Result<RgbaImage, String> {
+    let mut rgba = RgbaImage::from_raw(image.width(), image.height(), image.rgba().to_vec())
+      .ok_or("Failed to create RGBA image from screenshot")?;
+    if redaction::has_zones_for(display_id) {
+      redaction::apply_to_capture(&mut rgba, display_id, x, y, width, height);
+    }
+    Ok(max_dimensions.apply(rgba))
+  }
+
+  // PNG-encode grabbed pixels into a data URL, keeping a copy in the reused buffer
+  fn encode_capture(&mut self, rgba: &RgbaImage) -> Result<String, String> {
+    let png_data = crate::overlay::screen_capture::ScreenCapture::encode_png(rgba)?;
+    self.png_buffer.clear();
+    self.png_buffer.extend_from_slice(&png_data);
+    info!("   Data size: {}KB", png_data.len() / 1024);
+    Ok(format!(
+      "data:image/png;base64,{}",
+      base64::engine::general_purpose::STANDARD.encode(&png_data)
+    ))
+  }
+
   fn add_to_cache(&mut self, key: BoundsKey, data: String) {
-    let size = data.len();
+    // Replacing an entry frees its space first
+    self.remove_entry(&key);
+
+    let png = match crate::capture_store::decode_data_url(&data) {
+      Ok(png) => png,
+      Err(e) => {
+        warn!("⚠️ Not caching capture: {}", e);
+        return;
+      }
+    };
+
+    // Identical content already stored (hash match confirmed by comparing the data)
+    // only needs another reference
+    let mut hash = content_hash(&png);
+    while let Some(blob) = self.blobs.get(&hash) {
+      if blob.png == png {
+        break;
+      }
+      hash = hash.wrapping_add(1);
+    }
+    let duplicate = self.blobs.contains_key(&hash);
 
     // Cache size management
-    if self.get_total_cache_size() + size > self.max_cache_size {
-      self.evict_oldest_entries(size);
+    if !duplicate {
+      let size = png.len();
+      let total_size = self.get_total_cache_size();
+      if total_size + size > self.max_cache_size {
+        self.evict_entries(total_size + size - self.max_cache_size);
+      }
     }
 
+    self
+      .blobs
+      .entry(hash)
+      .or_insert(CachedBlob { png, refs: 0 })
+      .refs += 1;
+    self.access_clock += 1;
     self.cache.insert(
       key,
       CachedCapture {
-        data,
+        content_hash: hash,
         captured_at: Instant::now(),
-        size_bytes: size,
+        last_access: self.access_clock,
+        hits: 0,
       },
     );
 
-    println!(
-      "💾 Added to screenshot cache. Total entries: {}",
-      self.cache.len()
+    info!(
+      "💾 Added to screenshot cache{}. Total entries: {} ({} unique)",
+      if duplicate { " (deduplicated)" } else { "" },
+      self.cache.len(),
+      self.blobs.len()
     );
   }
 
+  // Drop an entry and its reference to the shared data; returns the bytes freed
+  fn remove_entry(&mut self, key: &BoundsKey) -> usize {
+    let Some(cached) = self.cache.remove(key) else {
+      return 0;
+    };
+    match self.blobs.get_mut(&cached.content_hash) {
+      Some(blob) if blob.refs > 1 => {
+        blob.refs -= 1;
+        0
+      }
+      Some(_) => self
+        .blobs
+        .remove(&cached.content_hash)
+        .map_or(0, |blob| blob.png.len()),
+      None => 0,
+    }
+  }
+
   fn get_total_cache_size(&self) -> usize {
-    self.cache.values().map(|cached| cached.size_bytes).sum()
+    self.blobs.values().map(|blob| blob.png.len()).sum()
   }
 
-  fn evict_oldest_entries(&mut self, needed_space: usize) {
+  // Evict until at least `needed_space` bytes are freed: expired entries first, then in
+  // eviction-policy order
+  fn evict_entries(&mut self, needed_space: usize) {
+    let ttl = self.cache_ttl;
+    let policy = self.eviction_policy;
     let mut entries: Vec<_> = self.cache.iter().collect();
-    entries.sort_by_key(|(_, cached)| cached.captured_at);
+    entries.sort_by_key(|(_, cached)| {
+      let expired = cached.captured_at.elapsed() >= ttl;
+      let frequency = match policy {
+        EvictionPolicy::Lru => 0,
+        EvictionPolicy::Lfu => cached.hits,
+      };
+      (!expired, frequency, cached.last_access)
+    });
+    let keys: Vec<BoundsKey> = entries.into_iter().map(|(key, _)| key.clone()).collect();
 
+    // Shared data is only freed once its last entry goes
     let mut freed_space = 0;
-    let mut keys_to_remove = Vec::new();
-
-    for (key, cached) in entries {
-      keys_to_remove.push(key.clone());
-      freed_space += cached.size_bytes;
-
+    let mut evicted = 0;
+    for key in keys {
       if freed_space >= needed_space {
         break;
       }
+      freed_space += self.remove_entry(&key);
+      evicted += 1;
     }
+    self.evictions += evicted as u64;
 
-    for key in keys_to_remove {
-      self.cache.remove(&key);
-    }
-
-    println!(
-      "🗑️ Evicted {} old cache entries, freed {}KB",
-      self.cache.len(),
+    info!(
+      "🗑️ Evicted {} cache entries ({:?}), freed {}KB",
+      evicted,
+      policy,
       freed_space / 1024
     );
   }
@@ -377,7 +975,8 @@ impl ScreenshotCache {
 
   pub fn clear_cache(&mut self) {
     self.cache.clear();
-    println!("🗑️ Screenshot cache cleared");
+    self.blobs.clear();
+    info!("🗑️ Screenshot cache cleared");
   }
 
   pub fn get_cache_stats(&self) -> (usize, usize, usize) {
@@ -391,26 +990,34 @@ impl ScreenshotCache {
     (total_entries, total_size, expired_entries)
   }
 
+  /// Distinct captures actually stored; entries beyond this share data
+  pub fn unique_captures(&self) -> usize {
+    self.blobs.len()
+  }
+
   pub fn cleanup_expired(&mut self) {
     let now = Instant::now();
-    let before_count = self.cache.len();
-
-    self
+    let expired: Vec<BoundsKey> = self
       .cache
-      .retain(|_key, cached| now.duration_since(cached.captured_at) < self.cache_ttl);
+      .iter()
+      .filter(|(_, cached)| now.duration_since(cached.captured_at) >= self.cache_ttl)
+      .map(|(key, _)| key.clone())
+      .collect();
 
-    let after_count = self.cache.len();
-    let removed = before_count - after_count;
+    let removed = expired.len();
+    for key in expired {
+      self.remove_entry(&key);
+    }
 
     if removed > 0 {
-      println!("🧹 Cleaned up {} expired screenshot cache entries", removed);
+      info!("🧹 Cleaned up {} expired screenshot cache entries", removed);
     }
   }
 
   pub fn resize_buffer(&mut self, new_capacity: usize) {
     self.png_buffer.clear();
     self.png_buffer.reserve(new_capacity);
-    println!(
+    info!(
       "📏 Resized PNG buffer to {}MB",
       new_capacity / (1024 * 1024)
     );
@@ -422,3 +1029,153 @@ impl Default for ScreenshotCache {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(x: i32) -> BoundsKey {
+    BoundsKey {
+      x,
+      y: 0,
+      width: 100,
+      height: 100,
+    }
+  }
+
+  // Cache holding at most three 10-byte entries
+  fn small_cache(policy: EvictionPolicy) -> ScreenshotCache {
+    let mut cache = ScreenshotCache::new();
+    cache.max_cache_size = 30;
+    cache.set_eviction_policy(policy);
+    cache
+  }
+
+  fn data_url(raw: &str) -> String {
+    format!(
+      "data:image/png;base64,{}",
+      base64::engine::general_purpose::STANDARD.encode(raw)
+    )
+  }
+
+  // Distinct 10-byte content per entry
+  fn entry(n: u32) -> String {
+    data_url(&format!("{:0>10}", n))
+  }
+
+  #[test]
+  fn lru_evicts_least_recently_used() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+    assert!(cache.lookup(&key(1)).is_some());
+
+    cache.add_to_cache(key(4), entry(4));
+
+    assert!(cache.cache.contains_key(&key(1)));
+    assert!(!cache.cache.contains_key(&key(2)));
+    assert!(cache.cache.contains_key(&key(3)));
+    assert!(cache.cache.contains_key(&key(4)));
+    assert_eq!(cache.evictions(), 1);
+  }
+
+  #[test]
+  fn lfu_evicts_least_frequently_used() {
+    let mut cache = small_cache(EvictionPolicy::Lfu);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+    cache.lookup(&key(1));
+    cache.lookup(&key(1));
+    cache.lookup(&key(2));
+    cache.lookup(&key(3));
+    cache.lookup(&key(3));
+    // Key 2 has the fewest hits even though it was used more recently than key 1
+    cache.add_to_cache(key(4), entry(4));
+
+    assert!(cache.cache.contains_key(&key(1)));
+    assert!(!cache.cache.contains_key(&key(2)));
+    assert!(cache.cache.contains_key(&key(3)));
+    assert_eq!(cache.evictions(), 1);
+  }
+
+  #[test]
+  fn lfu_breaks_ties_by_recency() {
+    let mut cache = small_cache(EvictionPolicy::Lfu);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+
+    cache.add_to_cache(key(4), entry(4));
+
+    assert!(!cache.cache.contains_key(&key(1)));
+    assert!(cache.cache.contains_key(&key(2)));
+  }
+
+  #[test]
+  fn evicts_enough_for_large_entries() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+
+    cache.add_to_cache(key(4), data_url(&"4".repeat(25)));
+
+    assert_eq!(cache.cache.len(), 1);
+    assert_eq!(cache.evictions(), 3);
+    assert!(cache.get_total_cache_size() <= 30);
+  }
+
+  #[test]
+  fn replacing_an_entry_does_not_evict_others() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+
+    cache.add_to_cache(key(2), entry(2));
+
+    assert_eq!(cache.cache.len(), 3);
+    assert_eq!(cache.evictions(), 0);
+  }
+
+  #[test]
+  fn expired_entries_are_evicted_first() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(2));
+    cache.add_to_cache(key(3), entry(3));
+    cache.cache.get_mut(&key(3)).unwrap().captured_at -= Duration::from_secs(60);
+
+    cache.add_to_cache(key(4), entry(4));
+
+    assert!(cache.cache.contains_key(&key(1)));
+    assert!(!cache.cache.contains_key(&key(3)));
+  }
+
+  #[test]
+  fn identical_content_is_stored_once() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(1));
+    cache.add_to_cache(key(3), entry(1));
+
+    assert_eq!(cache.cache.len(), 3);
+    assert_eq!(cache.unique_captures(), 1);
+    assert_eq!(cache.get_total_cache_size(), 10);
+    assert_eq!(cache.lookup(&key(2)), Some(entry(1)));
+  }
+
+  #[test]
+  fn shared_content_is_freed_with_its_last_entry() {
+    let mut cache = small_cache(EvictionPolicy::Lru);
+    cache.add_to_cache(key(1), entry(1));
+    cache.add_to_cache(key(2), entry(1));
+
+    assert_eq!(cache.remove_entry(&key(1)), 0);
+    assert_eq!(cache.lookup(&key(2)), Some(entry(1)));
+    assert_eq!(cache.remove_entry(&key(2)), 10);
+    assert_eq!(cache.unique_captures(), 0);
+  }
+}
@@ -0,0 +1,387 @@
+use super::screen_capture::{CaptureBounds, ScreenCapture};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::{debug, info};
+
+// Rows of the new frame compared against the previous frame to find the scroll offset
+const MATCH_BAND_HEIGHT: u32 = 48;
+// Only every Nth pixel in a row is compared (speed over precision)
+const MATCH_COLUMN_STEP: usize = 4;
+// Mean absolute channel difference below which two bands count as "the same content"
+const MATCH_THRESHOLD: f64 = 6.0;
+// Smallest channel value spread for a band to have enough detail to match on; a blank
+// band (empty page margin, solid background) matches at every offset
+const MIN_BAND_CONTRAST: u8 = 24;
+// Auto-scroll gives up after this many frames in a row with nothing to align on
+const MAX_SKIPPED_FRAMES: u32 = 10;
+// Hard limit so a runaway auto-scroll can't eat all memory
+const MAX_STITCHED_HEIGHT: u32 = 30_000;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ScrollCaptureOptions {
+  pub bounds: CaptureBounds,
+  pub auto_scroll: bool,  // true = FrameSense scrolls, false = user scrolls
+  pub interval_ms: u64,   // Delay between frames
+  pub max_frames: u32,    // Safety cap on number of frames
+  pub scroll_amount: i32, // Wheel notches per auto-scroll step
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct ScrollCaptureProgress {
+  pub frames: u32,
+  pub stitched_height: u32,
+  pub finished: bool,
+}
+
+/// Result of adding one frame to the stitcher
+#[derive(Debug, PartialEq)]
+pub enum FrameOutcome {
+  Appended(u32), // Number of new rows added
+  Unchanged,     // Content didn't move (end of page or user paused)
+  Skipped,       // Nothing in the frame to align on (e.g. blank area); try the next one
+}
+
+/// Stitches vertically scrolled frames of the same region into one tall image
+pub struct ScrollStitcher {
+  stitched: Option<RgbaImage>,
+  last_frame: Option<RgbaImage>,
+  frames: u32,
+}
+
+impl ScrollStitcher {
+  pub fn new() -> Self {
+    Self {
+      stitched: None,
+      last_frame: None,
+      frames: 0,
+    }
+  }
+
+  pub fn frames(&self) -> u32 {
+    self.frames
+  }
+
+  pub fn height(&self) -> u32 {
+    self.stitched.as_ref().map(|img| img.height()).unwrap_or(0)
+  }
+
+  pub fn add_frame(&mut self, frame: RgbaImage) -> Result<FrameOutcome, String> {
+    let (previous, stitched) = match (self.last_frame.take(), self.stitched.take()) {
+      (Some(previous), Some(stitched)) => (previous, stitched),
+      _ => {
+        // First frame is taken as-is
+        let rows = frame.height();
+        self.stitched = Some(frame.clone());
+        self.last_frame = Some(frame);
+        self.frames = 1;
+        return Ok(FrameOutcome::Appended(rows));
+      }
+    };
+
+    if previous.dimensions() != frame.dimensions() {
+      self.last_frame = Some(previous);
+      self.stitched = Some(stitched);
+      return Err("Frame size changed during scroll capture".to_string());
+    }
+
+    let new_rows = match Self::find_scroll_offset(&previous, &frame) {
+      Some(0) => {
+        self.last_frame = Some(previous);
+        self.stitched = Some(stitched);
+        return Ok(FrameOutcome::Unchanged);
+      }
+      Some(offset) => offset,
+      // Keep the previous frame as reference; the next one may show some detail
+      None => {
+        self.last_frame = Some(previous);
+        self.stitched = Some(stitched);
+        return Ok(FrameOutcome::Skipped);
+      }
+    };
+
+    let (width, height) = frame.dimensions();
+    let new_height = (stitched.height() + new_rows).min(MAX_STITCHED_HEIGHT);
+    let mut combined = RgbaImage::new(width, new_height);
+    image::imageops::replace(&mut combined, &stitched, 0, 0);
+
+    let source_start = height - new_rows;
+    let tail = image::imageops::crop_imm(&frame, 0, source_start, width, new_rows).to_image();
+    image::imageops::replace(&mut combined, &tail, 0, stitched.height() as i64);
+
+    self.stitched = Some(combined);
+    self.last_frame = Some(frame);
+    self.frames += 1;
+    Ok(FrameOutcome::Appended(new_rows))
+  }
+
+  /// How many rows the content moved up between two frames. Returns Some(0) for
+  /// identical frames, the frame height when no overlap was found (the user scrolled
+  /// further than one frame) and None when the frame has no detail to match on.
+  fn find_scroll_offset(previous: &RgbaImage, current: &RgbaImage) -> Option<u32> {
+    let height = current.height();
+    let band = MATCH_BAND_HEIGHT.min(height / 2);
+    if band == 0 {
+      return None;
+    }
+
+    // The topmost band of the current frame with some detail should appear somewhere
+    // in the previous frame, `offset` rows further down
+    let band_row = (0..=(height - band))
+      .step_by(band as usize)
+      .find(|&row| Self::band_has_detail(current, row, band))?;
+
+    let mut best: Option<(u32, f64)> = None;
+    for offset in 0..=(height - band - band_row) {
+      let diff = Self::band_difference(previous, band_row + offset, current, band_row, band);
+      if diff < MATCH_THRESHOLD && best.map(|(_, d)| diff < d).unwrap_or(true) {
+        best = Some((offset, diff));
+        if diff == 0.0 {
+          break;
+        }
+      }
+    }
+
+    Some(best.map_or(height, |(offset, _)| offset))
+  }
+
+  fn band_has_detail(image: &RgbaImage, row: u32, rows: u32) -> bool {
+    let row_bytes = image.width() as usize * 4;
+    let start = row as usize * row_bytes;
+    let band = &image.as_raw()[start..start + rows as usize * row_bytes];
+    let (mut min, mut max) = (u8::MAX, u8::MIN);
+    for pixel in band.chunks_exact(4).step_by(MATCH_COLUMN_STEP) {
+      for &value in &pixel[..3] {
+        min = min.min(value);
+        max = max.max(value);
+      }
+    }
+    max.saturating_sub(min) >= MIN_BAND_CONTRAST
+  }
+
+  fn band_difference(a: &RgbaImage, a_row: u32, b: &RgbaImage, b_row: u32, rows: u32) -> f64 {
+    let row_bytes = a.width() as usize * 4;
+    let a_raw = a.as_raw();
+    let b_raw = b.as_raw();
+    let mut total: u64 = 0;
+    let mut samples: u64 = 0;
+
+    for row in 0..rows as usize {
+      let a_start = (a_row as usize + row) * row_bytes;
+      let b_start = (b_row as usize + row) * row_bytes;
+      let mut px = 0;
+      while px < row_bytes {
+        for channel in 0..3 {
+          let a_val = a_raw[a_start + px + channel] as i32;
+          let b_val = b_raw[b_start + px + channel] as i32;
+          total += (a_val - b_val).unsigned_abs() as u64;
+        }
+        samples += 3;
+        px += 4 * MATCH_COLUMN_STEP;
+      }
+    }
+
+    if samples == 0 {
+      f64::MAX
+    } else {
+      total as f64 / samples as f64
+    }
+  }
+
+  pub fn into_image(self) -> Option<RgbaImage> {
+    self.stitched
+  }
+}
+
+impl Default for ScrollStitcher {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+struct ScrollSession {
+  options: ScrollCaptureOptions,
+  stitcher: ScrollStitcher,
+  stop_requested: bool,
+  unchanged_frames: u32,
+  skipped_frames: u32,
+  started_at: Instant,
+}
+
+/// Owns the (single) active scroll capture session
+pub struct ScrollCaptureManager {
+  session: Option<ScrollSession>,
+}
+
+impl ScrollCaptureManager {
+  pub fn new() -> Self {
+    Self { session: None }
+  }
+
+  pub fn start(&mut self, options: ScrollCaptureOptions) -> Result<(), String> {
+    if self.session.is_some() {
+      return Err("A scroll capture is already running".to_string());
+    }
+    if options.bounds.width < 10 || options.bounds.height < 10 {
+      return Err("Scroll capture region is too small".to_string());
+    }
+    info!(
+      "📜 Starting scroll capture: {}x{} at ({}, {}), auto_scroll={}",
+      options.bounds.width,
+      options.bounds.height,
+      options.bounds.x,
+      options.bounds.y,
+      options.auto_scroll
+    );
+    self.session = Some(ScrollSession {
+      options,
+      stitcher: ScrollStitcher::new(),
+      stop_requested: false,
+      unchanged_frames: 0,
+      skipped_frames: 0,
+      started_at: Instant::now(),
+    });
+    Ok(())
+  }
+
+  pub fn is_active(&self) -> bool {
+    self.session.is_some()
+  }
+
+  pub fn options(&self) -> Option<ScrollCaptureOptions> {
+    self.session.as_ref().map(|s| s.options.clone())
+  }
+
+  pub fn request_stop(&mut self) {
+    if let Some(session) = &mut self.session {
+      session.stop_requested = true;
+    }
+  }
+
+  /// Add a captured frame. Returns progress, with `finished` set when the session should end.
+  pub fn add_frame(&mut self, frame: RgbaImage) -> Result<ScrollCaptureProgress, String> {
+    let session = self.session.as_mut().ok_or("No scroll capture running")?;
+
+    match session.stitcher.add_frame(frame)? {
+      FrameOutcome::Appended(rows) => {
+        session.unchanged_frames = 0;
+        session.skipped_frames = 0;
+        info!(
+          "📜 Scroll frame {} added {} rows",
+          session.stitcher.frames(),
+          rows
+        );
+      }
+      FrameOutcome::Unchanged => {
+        session.unchanged_frames += 1;
+        session.skipped_frames = 0;
+      }
+      FrameOutcome::Skipped => {
+        session.skipped_frames += 1;
+        debug!("📜 Scroll frame has no detail to align on, scrolling on");
+      }
+    }
+
+    // In auto mode, two frames without movement means we hit the end of the page
+    let reached_end = session.options.auto_scroll
+      && (session.unchanged_frames >= 2 || session.skipped_frames >= MAX_SKIPPED_FRAMES);
+    let finished = session.stop_requested
+      || reached_end
+      || session.stitcher.frames() >= session.options.max_frames
+      || session.stitcher.height() >= MAX_STITCHED_HEIGHT;
+
+    Ok(ScrollCaptureProgress {
+      frames: session.stitcher.frames(),
+      stitched_height: session.stitcher.height(),
+      finished,
+    })
+  }
+
+  /// End the session and encode the stitched image as a base64 PNG data URL
+  pub fn finish(&mut self) -> Result<String, String> {
+    let session = self.session.take().ok_or("No scroll capture running")?;
+    info!(
+      "✅ Scroll capture finished: {} frames, {}px tall in {:.1}s",
+      session.stitcher.frames(),
+      session.stitcher.height(),
+      session.started_at.elapsed().as_secs_f32()
+    );
+    let image = session
+      .stitcher
+      .into_image()
+      .ok_or("Scroll capture produced no frames")?;
+    ScreenCapture::encode_rgba_to_base64(image)
+  }
+}
+
+impl Default for ScrollCaptureManager {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Synthesize a vertical mouse-wheel scroll at the current cursor position.
+/// Positive `notches` scrolls down.
+#[cfg(target_os = "macos")]
+pub fn synthesize_scroll(notches: i32) -> Result<(), String> {
+  use std::ffi::c_void;
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGEventCreateScrollWheelEvent(
+      source: *const c_void,
+      units: u32,
+      wheel_count: u32,
+      wheel1: i32,
+      ...
+    ) -> *mut c_void;
+    fn CGEventPost(tap: u32, event: *mut c_void);
+  }
+  #[link(name = "CoreFoundation", kind = "framework")]
+  extern "C" {
+    fn CFRelease(cf: *const c_void);
+  }
+
+  // kCGScrollEventUnitLine = 1, kCGHIDEventTap = 0. Negative lines scroll down.
+  unsafe {
+    let event = CGEventCreateScrollWheelEvent(std::ptr::null(), 1, 1, -notches * 3);
+    if event.is_null() {
+      return Err("Failed to create scroll event".to_string());
+    }
+    CGEventPost(0, event);
+    CFRelease(event);
+  }
+  Ok(())
+}
+
+/// Synthesize a vertical mouse-wheel scroll at the current cursor position.
+/// Positive `notches` scrolls down.
+#[cfg(target_os = "windows")]
+pub fn synthesize_scroll(notches: i32) -> Result<(), String> {
+  #[link(name = "user32")]
+  extern "system" {
+    fn mouse_event(flags: u32, dx: i32, dy: i32, data: i32, extra_info: usize);
+  }
+  const MOUSEEVENTF_WHEEL: u32 = 0x0800;
+  const WHEEL_DELTA: i32 = 120;
+  unsafe {
+    mouse_event(MOUSEEVENTF_WHEEL, 0, 0, -notches * WHEEL_DELTA, 0);
+  }
+  Ok(())
+}
+
+/// Synthesize a vertical mouse-wheel scroll at the current cursor position.
+/// Positive `notches` scrolls down.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn synthesize_scroll(notches: i32) -> Result<(), String> {
+  // X11 buttons 4/5 are wheel up/down
+  let button = if notches >= 0 { "5" } else { "4" };
+  let status = std::process::Command::new("xdotool")
+    .args(["click", "--repeat", &notches.abs().to_string(), button])
+    .status()
+    .map_err(|e| format!("Failed to run xdotool (is it installed?): {}", e))?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err("xdotool failed to synthesize scroll".to_string())
+  }
+}
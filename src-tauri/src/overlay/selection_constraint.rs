@@ -0,0 +1,66 @@
+// Selection constraints for a capture session: free-form, a fixed aspect ratio
+// (16:9, 4:3, 1:1, ...) or an exact size. Applied when the overlay's drag rectangle is
+// turned into capture bounds; the rectangle stays anchored at its top-left corner.
+use super::screen_capture::CaptureBounds;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(
+  tag = "type",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum SelectionConstraint {
+  #[default]
+  Free,
+  AspectRatio {
+    width: u32,
+    height: u32,
+  },
+  FixedSize {
+    width: u32,
+    height: u32,
+  },
+}
+
+impl SelectionConstraint {
+  pub fn validate(&self) -> Result<(), String> {
+    match *self {
+      SelectionConstraint::AspectRatio { width, height }
+      | SelectionConstraint::FixedSize { width, height }
+        if width == 0 || height == 0 =>
+      {
+        Err("Selection constraint sizes must be greater than zero".to_string())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Fit the bounds to the constraint. Aspect ratios shrink the rectangle to the
+  /// largest matching one inside the drag.
+  pub fn apply(&self, bounds: CaptureBounds) -> CaptureBounds {
+    match *self {
+      SelectionConstraint::Free => bounds,
+      SelectionConstraint::FixedSize { width, height } => CaptureBounds {
+        width,
+        height,
+        ..bounds
+      },
+      SelectionConstraint::AspectRatio { width, height } => {
+        let ratio = width as f64 / height as f64;
+        let fitted_height = (bounds.width as f64 / ratio).round() as u32;
+        if fitted_height <= bounds.height {
+          CaptureBounds {
+            height: fitted_height.max(1),
+            ..bounds
+          }
+        } else {
+          CaptureBounds {
+            width: ((bounds.height as f64 * ratio).round() as u32).max(1),
+            ..bounds
+          }
+        }
+      }
+    }
+  }
+}
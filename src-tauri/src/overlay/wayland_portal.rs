@@ -0,0 +1,177 @@
+// Wayland capture through xdg-desktop-portal (org.freedesktop.portal.Screenshot).
+// Wayland compositors don't allow direct screen reads, so captures are requested from the
+// portal, which asks the user for permission the first time and hands back a PNG file.
+// Talks D-Bus through `gdbus`, like the other Linux integrations.
+use image::RgbaImage;
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use tracing::info;
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+// Long enough for the user to answer the permission dialog
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PortalStatus {
+  pub wayland_session: bool,
+  pub portal_available: bool,
+  pub portal_version: Option<u32>,
+}
+
+/// True when running inside a Wayland session, where direct capture doesn't work
+pub fn is_wayland_session() -> bool {
+  if !cfg!(target_os = "linux") {
+    return false;
+  }
+  std::env::var("XDG_SESSION_TYPE")
+    .map(|t| t.eq_ignore_ascii_case("wayland"))
+    .unwrap_or(false)
+    || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+pub fn status() -> PortalStatus {
+  let portal_version = screenshot_portal_version();
+  PortalStatus {
+    wayland_session: is_wayland_session(),
+    portal_available: portal_version.is_some(),
+    portal_version,
+  }
+}
+
+// Version property of the Screenshot interface, None when the portal isn't running
+fn screenshot_portal_version() -> Option<u32> {
+  let output = Command::new("gdbus")
+    .args([
+      "call",
+      "--session",
+      "--dest",
+      PORTAL_DEST,
+      "--object-path",
+      PORTAL_PATH,
+      "--method",
+      "org.freedesktop.DBus.Properties.Get",
+      "org.freedesktop.portal.Screenshot",
+      "version",
+    ])
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  // Output looks like "(<uint32 2>,)"
+  String::from_utf8_lossy(&output.stdout)
+    .split_whitespace()
+    .find_map(|part| part.trim_end_matches(">,)").parse::<u32>().ok())
+}
+
+/// Take a full-desktop screenshot through the portal. The first call shows the
+/// portal's permission dialog; `interactive` additionally lets the user pick the area.
+pub fn take_screenshot(interactive: bool) -> Result<RgbaImage, String> {
+  let token = format!(
+    "framesense_{}_{}",
+    std::process::id(),
+    REQUEST_COUNTER.fetch_add(1, Ordering::SeqCst)
+  );
+
+  // Watch for the Request.Response signal before calling, so it can't be missed
+  let mut monitor = Command::new("gdbus")
+    .args(["monitor", "--session", "--dest", PORTAL_DEST])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .spawn()
+    .map_err(|_| "gdbus was not found - it is needed for Wayland screen capture".to_string())?;
+  let stdout = monitor
+    .stdout
+    .take()
+    .ok_or("Failed to read portal signals")?;
+
+  let (sender, receiver) = mpsc::channel::<String>();
+  let signal_token = token.clone();
+  std::thread::spawn(move || {
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+      if line.contains(&signal_token) && line.contains("Request.Response") {
+        let _ = sender.send(line);
+        break;
+      }
+    }
+  });
+  // gdbus monitor needs a moment to subscribe
+  std::thread::sleep(Duration::from_millis(100));
+
+  let options = format!(
+    "{{'handle_token': <'{}'>, 'interactive': <{}>}}",
+    token, interactive
+  );
+  let call = Command::new("gdbus")
+    .args([
+      "call",
+      "--session",
+      "--dest",
+      PORTAL_DEST,
+      "--object-path",
+      PORTAL_PATH,
+      "--method",
+      "org.freedesktop.portal.Screenshot.Screenshot",
+      "",
+      &options,
+    ])
+    .output();
+
+  let response = match call {
+    Ok(output) if output.status.success() => receiver
+      .recv_timeout(RESPONSE_TIMEOUT)
+      .map_err(|_| "Timed out waiting for the screenshot portal".to_string()),
+    Ok(output) => Err(format!(
+      "Screenshot portal call failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    )),
+    Err(e) => Err(format!("Failed to call the screenshot portal: {}", e)),
+  };
+  let _ = monitor.kill();
+  let _ = monitor.wait();
+
+  let uri = parse_response(&response?)?;
+  let path = url::Url::parse(&uri)
+    .ok()
+    .and_then(|url| url.to_file_path().ok())
+    .ok_or_else(|| format!("Unexpected screenshot location: {}", uri))?;
+
+  let image = image::open(&path)
+    .map_err(|e| format!("Failed to read portal screenshot: {}", e))?
+    .to_rgba8();
+  // The portal writes a new file for every request - don't leave them behind
+  let _ = std::fs::remove_file(&path);
+
+  info!("🐧 Portal screenshot: {}x{}", image.width(), image.height());
+  Ok(image)
+}
+
+// "…Request.Response (uint32 0, {'uri': <'file:///…/Screenshot.png'>})"
+fn parse_response(line: &str) -> Result<String, String> {
+  let code = line
+    .split("uint32 ")
+    .nth(1)
+    .and_then(|rest| rest.split(',').next())
+    .and_then(|code| code.trim().parse::<u32>().ok())
+    .ok_or("Malformed screenshot portal response")?;
+  match code {
+    0 => {}
+    1 => return Err("Screen capture permission was denied or cancelled".to_string()),
+    _ => return Err("The screenshot portal reported an error".to_string()),
+  }
+
+  line
+    .split("'uri': <'")
+    .nth(1)
+    .and_then(|rest| rest.split("'>").next())
+    .map(|uri| uri.to_string())
+    .ok_or_else(|| "Screenshot portal response has no image".to_string())
+}
@@ -0,0 +1,236 @@
+// Click-to-capture-window: find the top-level window at a desktop point using the
+// platform's window list (front to back), skipping our own windows so the selection
+// overlay on top doesn't count.
+use serde::Serialize;
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowFrame {
+  // Desktop coordinates, same space as the display geometry from `screenshots`
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+  pub app_name: Option<String>,
+}
+
+/// Frontmost normal window containing the desktop point (x, y)
+#[cfg(target_os = "macos")]
+pub fn window_at(x: i32, y: i32) -> Option<WindowFrame> {
+  use cocoa::base::{id, nil};
+  use cocoa::foundation::NSString;
+  use objc::{msg_send, sel, sel_impl};
+  use std::ffi::{c_void, CStr};
+
+  #[repr(C)]
+  #[derive(Default)]
+  struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+  }
+
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> id;
+    fn CGRectMakeWithDictionaryRepresentation(dict: id, rect: *mut CGRect) -> bool;
+    fn CFRelease(cf: *const c_void);
+  }
+  const ON_SCREEN_ONLY: u32 = 1 << 0;
+  const EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+
+  let (point_x, point_y) = (x as f64, y as f64);
+  let own_pid = std::process::id() as i64;
+
+  unsafe {
+    // Front-to-back list of on-screen windows
+    let windows = CGWindowListCopyWindowInfo(ON_SCREEN_ONLY | EXCLUDE_DESKTOP_ELEMENTS, 0);
+    if windows == nil {
+      return None;
+    }
+    let pid_key = NSString::alloc(nil).init_str("kCGWindowOwnerPID");
+    let layer_key = NSString::alloc(nil).init_str("kCGWindowLayer");
+    let bounds_key = NSString::alloc(nil).init_str("kCGWindowBounds");
+    let name_key = NSString::alloc(nil).init_str("kCGWindowOwnerName");
+
+    let mut result = None;
+    let count: usize = msg_send![windows, count];
+    for index in 0..count {
+      let window: id = msg_send![windows, objectAtIndex: index];
+      let pid: id = msg_send![window, objectForKey: pid_key];
+      let layer: id = msg_send![window, objectForKey: layer_key];
+      let bounds: id = msg_send![window, objectForKey: bounds_key];
+      if pid == nil || layer == nil || bounds == nil {
+        continue;
+      }
+      let pid: i64 = msg_send![pid, longLongValue];
+      let layer: i64 = msg_send![layer, longLongValue];
+      // Layer 0 = normal app windows (skips menu bar, dock, overlays)
+      if pid == own_pid || layer != 0 {
+        continue;
+      }
+
+      let mut rect = CGRect::default();
+      if !CGRectMakeWithDictionaryRepresentation(bounds, &mut rect) {
+        continue;
+      }
+      let inside = point_x >= rect.x
+        && point_x < rect.x + rect.width
+        && point_y >= rect.y
+        && point_y < rect.y + rect.height;
+      if inside {
+        let name: id = msg_send![window, objectForKey: name_key];
+        let mut app_name = None;
+        if name != nil {
+          let utf8: *const std::os::raw::c_char = msg_send![name, UTF8String];
+          if !utf8.is_null() {
+            app_name = Some(CStr::from_ptr(utf8).to_string_lossy().into_owned());
+          }
+        }
+        result = Some(WindowFrame {
+          x: rect.x.round() as i32,
+          y: rect.y.round() as i32,
+          width: rect.width.round() as u32,
+          height: rect.height.round() as u32,
+          app_name,
+        });
+        break;
+      }
+    }
+
+    for key in [pid_key, layer_key, bounds_key, name_key] {
+      let _: () = msg_send![key, release];
+    }
+    CFRelease(windows as *const c_void);
+    result
+  }
+}
+
+/// Frontmost normal window containing the desktop point (x, y)
+#[cfg(target_os = "windows")]
+pub fn window_at(x: i32, y: i32) -> Option<WindowFrame> {
+  use std::ffi::c_void;
+
+  #[repr(C)]
+  struct Rect {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+  }
+
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetTopWindow(hwnd: *mut c_void) -> *mut c_void;
+    fn GetWindow(hwnd: *mut c_void, cmd: u32) -> *mut c_void;
+    fn IsWindowVisible(hwnd: *mut c_void) -> i32;
+    fn IsIconic(hwnd: *mut c_void) -> i32;
+    fn GetWindowRect(hwnd: *mut c_void, rect: *mut Rect) -> i32;
+    fn GetWindowThreadProcessId(hwnd: *mut c_void, process_id: *mut u32) -> u32;
+  }
+  const GW_HWNDNEXT: u32 = 2;
+
+  let own_pid = std::process::id();
+
+  unsafe {
+    // Walk top-level windows in z-order, topmost first
+    let mut window = GetTopWindow(std::ptr::null_mut());
+    while !window.is_null() {
+      let mut rect = Rect {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+      };
+      if IsWindowVisible(window) != 0
+        && IsIconic(window) == 0
+        && GetWindowRect(window, &mut rect) != 0
+        && x >= rect.left
+        && x < rect.right
+        && y >= rect.top
+        && y < rect.bottom
+      {
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(window, &mut process_id);
+        if process_id != own_pid {
+          return Some(WindowFrame {
+            x: rect.left,
+            y: rect.top,
+            width: (rect.right - rect.left).max(0) as u32,
+            height: (rect.bottom - rect.top).max(0) as u32,
+            app_name: crate::save::process_name(process_id),
+          });
+        }
+      }
+      window = GetWindow(window, GW_HWNDNEXT);
+    }
+  }
+  None
+}
+
+/// Frontmost normal window containing the desktop point (x, y)
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn window_at(x: i32, y: i32) -> Option<WindowFrame> {
+  use std::process::Command;
+
+  // X11 only - "_NET_CLIENT_LIST_STACKING(WINDOW): window id # 0x1a00003, 0x2c00007"
+  // lists managed windows bottom to top
+  let output = Command::new("xprop")
+    .args(["-root", "_NET_CLIENT_LIST_STACKING"])
+    .output()
+    .ok()?;
+  let text = String::from_utf8_lossy(&output.stdout).to_string();
+  let ids: Vec<u64> = text
+    .split_once('#')?
+    .1
+    .split(',')
+    .filter_map(|id| u64::from_str_radix(id.trim().trim_start_matches("0x"), 16).ok())
+    .collect();
+  let own_pid = std::process::id();
+
+  for window in ids.iter().rev() {
+    let window = window.to_string();
+    // "X=10\nY=20\nWIDTH=800\nHEIGHT=600\nSCREEN=0"
+    let output = Command::new("xdotool")
+      .args(["getwindowgeometry", "--shell", &window])
+      .output()
+      .ok()?;
+    let geometry = String::from_utf8_lossy(&output.stdout).to_string();
+    let value = |key: &str| -> Option<i64> {
+      geometry
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|v| v.trim().parse().ok())
+    };
+    let (Some(left), Some(top), Some(width), Some(height)) =
+      (value("X="), value("Y="), value("WIDTH="), value("HEIGHT="))
+    else {
+      continue;
+    };
+    let inside = (x as i64) >= left
+      && (x as i64) < left + width
+      && (y as i64) >= top
+      && (y as i64) < top + height;
+    if !inside {
+      continue;
+    }
+
+    let pid = Command::new("xdotool")
+      .args(["getwindowpid", &window])
+      .output()
+      .ok()
+      .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().parse().ok());
+    if pid == Some(own_pid) {
+      continue;
+    }
+    return Some(WindowFrame {
+      x: left as i32,
+      y: top as i32,
+      width: width as u32,
+      height: height as u32,
+      app_name: pid.and_then(crate::save::process_name),
+    });
+  }
+  None
+}
@@ -0,0 +1,213 @@
+// Searchable PDFs: one page per capture with the image drawn full-page and its OCR text
+// on top in invisible rendering mode (3 Tr), so viewers can search and copy it. The OCR
+// engine reports no word positions, so text lines are spread evenly down the page and
+// stretched to its width - search hits land on the right page, not the exact word.
+// Written by hand (the format needs only a few objects); images are embedded as JPEG.
+use image::codecs::jpeg::JpegEncoder;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+// Captures are in screen pixels (96 dpi); PDF units are points (72 per inch)
+const POINTS_PER_PIXEL: f32 = 0.75;
+const JPEG_QUALITY: u8 = 90;
+// Readable text selection even when a capture holds only a line or two
+const MAX_FONT_SIZE: f32 = 36.0;
+// Helvetica's average glyph width relative to the font size
+const AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+
+/// A capture to put on its own page
+pub struct PdfPage {
+  pub png: Vec<u8>,
+  pub text: Option<String>,
+}
+
+/// Write `pages` to `path` (".pdf" is added when missing)
+pub fn export_searchable_pdf(
+  pages: &[PdfPage],
+  title: &str,
+  path: &Path,
+) -> Result<PathBuf, String> {
+  if pages.is_empty() {
+    return Err("Choose at least one capture to export".to_string());
+  }
+  let path = if path.extension().is_none() {
+    path.with_extension("pdf")
+  } else {
+    path.to_path_buf()
+  };
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create export folder: {}", e))?;
+  }
+
+  let mut writer = PdfWriter::default();
+  // Fixed objects: 1 catalog, 2 page tree, 3 font, 4 info; pages follow in threes
+  let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + i * 3).collect();
+  writer.object(1, b"<< /Type /Catalog /Pages 2 0 R >>");
+  let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+  writer.object(
+    2,
+    format!(
+      "<< /Type /Pages /Kids [{}] /Count {} >>",
+      kids.join(" "),
+      pages.len()
+    )
+    .as_bytes(),
+  );
+  writer.object(
+    3,
+    b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+  );
+  writer.object(
+    4,
+    format!(
+      "<< /Title {} /Producer (FrameSense) /CreationDate (D:{}Z) >>",
+      pdf_string(title),
+      chrono::Utc::now().format("%Y%m%d%H%M%S")
+    )
+    .as_bytes(),
+  );
+
+  for (page, id) in pages.iter().zip(page_ids) {
+    let image = image::load_from_memory(&page.png)
+      .map_err(|e| format!("Failed to read capture image: {}", e))?
+      .to_rgb8();
+    let (pixel_width, pixel_height) = image.dimensions();
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+      .encode_image(&image)
+      .map_err(|e| format!("Failed to encode page image: {}", e))?;
+
+    let width = pixel_width as f32 * POINTS_PER_PIXEL;
+    let height = pixel_height as f32 * POINTS_PER_PIXEL;
+    let content = page_content(width, height, page.text.as_deref().unwrap_or(""));
+
+    writer.object(
+      id,
+      format!(
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >> /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+        width,
+        height,
+        id + 2,
+        id + 1
+      )
+      .as_bytes(),
+    );
+    writer.stream(id + 1, "", content.as_bytes());
+    writer.stream(
+      id + 2,
+      &format!(
+        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+        pixel_width, pixel_height
+      ),
+      &jpeg,
+    );
+  }
+
+  fs::write(&path, writer.finish(5 + pages.len() * 3))
+    .map_err(|e| format!("Failed to write PDF: {}", e))?;
+  info!(
+    "📄 Exported {} capture(s) to searchable PDF {}",
+    pages.len(),
+    path.display()
+  );
+  Ok(path)
+}
+
+// Draw the image over the whole page, then the invisible text layer
+fn page_content(width: f32, height: f32, text: &str) -> String {
+  let mut content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q\n", width, height);
+  let lines: Vec<&str> = text
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .collect();
+  if lines.is_empty() {
+    return content;
+  }
+
+  let line_height = height / lines.len() as f32;
+  let font_size = (line_height * 0.8).min(MAX_FONT_SIZE);
+  content.push_str(&format!("BT 3 Tr /F1 {:.2} Tf\n", font_size));
+  for (index, line) in lines.iter().enumerate() {
+    let natural_width = line.chars().count() as f32 * font_size * AVERAGE_GLYPH_WIDTH;
+    let scale = (width / natural_width * 100.0).clamp(10.0, 500.0);
+    let baseline = height - line_height * (index as f32 + 1.0) + (line_height - font_size) / 2.0;
+    content.push_str(&format!(
+      "{:.2} Tz 1 0 0 1 0 {:.2} Tm {} Tj\n",
+      scale,
+      baseline,
+      pdf_string(line)
+    ));
+  }
+  content.push_str("ET\n");
+  content
+}
+
+// Literal string in WinAnsi; characters outside it become '?'
+fn pdf_string(text: &str) -> String {
+  let mut out = String::from("(");
+  for c in text.chars() {
+    match c {
+      '(' | ')' | '\\' => {
+        out.push('\\');
+        out.push(c);
+      }
+      ' '..='~' => out.push(c),
+      '\u{a0}'..='\u{ff}' => out.push_str(&format!("\\{:03o}", c as u32)),
+      _ => out.push('?'),
+    }
+  }
+  out.push(')');
+  out
+}
+
+// Objects are appended in any order; offsets are recorded for the cross-reference table
+#[derive(Default)]
+struct PdfWriter {
+  buffer: Vec<u8>,
+  offsets: Vec<(usize, usize)>,
+}
+
+impl PdfWriter {
+  fn ensure_header(&mut self) {
+    if self.buffer.is_empty() {
+      self
+        .buffer
+        .extend_from_slice(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n");
+    }
+  }
+
+  fn object(&mut self, id: usize, body: &[u8]) {
+    self.ensure_header();
+    self.offsets.push((id, self.buffer.len()));
+    self
+      .buffer
+      .extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+    self.buffer.extend_from_slice(body);
+    self.buffer.extend_from_slice(b"\nendobj\n");
+  }
+
+  fn stream(&mut self, id: usize, dictionary: &str, data: &[u8]) {
+    let mut body = format!("<< {} /Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    self.object(id, &body);
+  }
+
+  // `size` is one more than the highest object id
+  fn finish(mut self, size: usize) -> Vec<u8> {
+    self.offsets.sort();
+    let xref_offset = self.buffer.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", size);
+    for (_, offset) in &self.offsets {
+      xref.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    xref.push_str(&format!(
+      "trailer\n<< /Size {} /Root 1 0 R /Info 4 0 R >>\nstartxref\n{}\n%%EOF\n",
+      size, xref_offset
+    ));
+    self.buffer.extend_from_slice(xref.as_bytes());
+    self.buffer
+  }
+}
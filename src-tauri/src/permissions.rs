@@ -0,0 +1,137 @@
+// Permissions - macOS gates screen capture behind Screen Recording and synthetic input /
+// window inspection behind Accessibility. Without Screen Recording, captures succeed but
+// contain only the wallpaper and menu bar, so we check before capturing and guide the user
+// through granting it. Other platforms need no permission; every check reports granted.
+use serde::Serialize;
+use tracing::info;
+
+#[derive(Clone, Copy, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Permission {
+  ScreenRecording,
+  Accessibility,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionStatus {
+  pub screen_recording: bool,
+  pub accessibility: bool,
+}
+
+pub fn status() -> PermissionStatus {
+  PermissionStatus {
+    screen_recording: is_granted(Permission::ScreenRecording),
+    accessibility: is_granted(Permission::Accessibility),
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use cocoa::base::{id, nil, YES};
+  use objc::{class, msg_send, sel, sel_impl};
+
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+  }
+
+  #[link(name = "ApplicationServices", kind = "framework")]
+  extern "C" {
+    static kAXTrustedCheckOptionPrompt: id;
+    fn AXIsProcessTrusted() -> bool;
+    fn AXIsProcessTrustedWithOptions(options: id) -> bool;
+  }
+
+  pub fn screen_recording_granted() -> bool {
+    unsafe { CGPreflightScreenCaptureAccess() }
+  }
+
+  // Shows the system prompt the first time; later calls only report the current state
+  pub fn request_screen_recording() -> bool {
+    unsafe { CGRequestScreenCaptureAccess() }
+  }
+
+  pub fn accessibility_granted() -> bool {
+    unsafe { AXIsProcessTrusted() }
+  }
+
+  pub fn request_accessibility() -> bool {
+    unsafe {
+      let prompt: id = msg_send![class!(NSNumber), numberWithBool: YES];
+      let options: id = msg_send![
+        class!(NSDictionary),
+        dictionaryWithObject: prompt
+        forKey: kAXTrustedCheckOptionPrompt
+      ];
+      if options == nil {
+        return AXIsProcessTrusted();
+      }
+      AXIsProcessTrustedWithOptions(options)
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_granted(permission: Permission) -> bool {
+  match permission {
+    Permission::ScreenRecording => macos::screen_recording_granted(),
+    Permission::Accessibility => macos::accessibility_granted(),
+  }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_granted(_permission: Permission) -> bool {
+  true
+}
+
+/// Ask for `permission`, returning whether it is granted now. macOS only prompts once;
+/// after the user declined we open the matching System Settings pane instead. Screen
+/// Recording changes take effect after the app restarts.
+#[cfg(target_os = "macos")]
+pub fn request(permission: Permission) -> Result<bool, String> {
+  if is_granted(permission) {
+    return Ok(true);
+  }
+  let granted = match permission {
+    Permission::ScreenRecording => macos::request_screen_recording(),
+    Permission::Accessibility => macos::request_accessibility(),
+  };
+  if !granted {
+    open_settings(permission)?;
+  }
+  info!(
+    "🔐 {:?} permission {}",
+    permission,
+    if granted {
+      "granted"
+    } else {
+      "not granted yet"
+    }
+  );
+  Ok(granted)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn request(permission: Permission) -> Result<bool, String> {
+  info!("🔐 {:?} needs no permission on this platform", permission);
+  Ok(true)
+}
+
+/// Open the Privacy & Security pane for `permission`
+#[cfg(target_os = "macos")]
+pub fn open_settings(permission: Permission) -> Result<(), String> {
+  let pane = match permission {
+    Permission::ScreenRecording => "Privacy_ScreenCapture",
+    Permission::Accessibility => "Privacy_Accessibility",
+  };
+  std::process::Command::new("open")
+    .arg(format!(
+      "x-apple.systempreferences:com.apple.preference.security?{}",
+      pane
+    ))
+    .spawn()
+    .map(|_| ())
+    .map_err(|e| format!("Failed to open System Settings: {}", e))
+}
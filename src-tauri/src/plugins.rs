@@ -0,0 +1,330 @@
+// Processor plugins - community extensions that post-process a capture (regex extractors,
+// ticket formatters, ...) without forking the app. Each plugin is a folder under
+// `<app data>/plugins/` with a `plugin.json` manifest. Subprocess plugins get one JSON
+// request on stdin (capture PNG as base64 + OCR text) and answer with one JSON object
+// on stdout. The manifest format reserves `"kind": "wasm"`, but this build has no WASM
+// runtime, so such plugins are listed with an error and never run.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{info, warn};
+
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+pub const PROTOCOL_VERSION: u32 = 1;
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 300;
+// Plugins answering with more than this are cut off
+const MAX_OUTPUT_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+  Subprocess,
+  Wasm,
+}
+
+/// `plugin.json`
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+  pub id: String,
+  pub name: String,
+  #[serde(default)]
+  pub description: String,
+  #[serde(default)]
+  pub version: String,
+  pub kind: PluginKind,
+  // Program and arguments, resolved relative to the plugin folder
+  #[serde(default)]
+  pub command: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  pub timeout_secs: Option<u64>,
+  // Text-only plugins skip the (large) image in their request
+  #[serde(default = "default_true")]
+  pub wants_image: bool,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+  pub id: String,
+  pub name: String,
+  pub description: String,
+  pub version: String,
+  pub kind: PluginKind,
+  pub path: String,
+  // Why the plugin can't run (bad manifest, unsupported kind, missing program)
+  pub error: Option<String>,
+}
+
+/// Request written to the plugin's stdin
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessorInput {
+  pub protocol_version: u32,
+  pub capture_id: Option<String>,
+  // Base64 PNG without the data URL prefix
+  pub image_png_base64: Option<String>,
+  pub ocr_text: Option<String>,
+  pub source_app: Option<String>,
+}
+
+/// Answer read from the plugin's stdout
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProcessorOutput {
+  pub text: Option<String>,
+  // "text" (default), "markdown" or "json"; tells the UI how to show `text`
+  pub format: Option<String>,
+  // Structured results (extracted fields, ticket payloads, ...)
+  pub data: Option<serde_json::Value>,
+  pub error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Plugin {
+  pub manifest: PluginManifest,
+  pub dir: PathBuf,
+  pub error: Option<String>,
+}
+
+impl Plugin {
+  fn info(&self) -> PluginInfo {
+    PluginInfo {
+      id: self.manifest.id.clone(),
+      name: self.manifest.name.clone(),
+      description: self.manifest.description.clone(),
+      version: self.manifest.version.clone(),
+      kind: self.manifest.kind,
+      path: self.dir.to_string_lossy().to_string(),
+      error: self.error.clone(),
+    }
+  }
+
+  fn timeout(&self) -> Duration {
+    Duration::from_secs(
+      self
+        .manifest
+        .timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS),
+    )
+  }
+
+  // `./script.py`-style commands live in the plugin folder; bare names come from PATH
+  fn program(&self) -> PathBuf {
+    let command = Path::new(&self.manifest.command);
+    if command.components().count() > 1 && command.is_relative() {
+      self.dir.join(command)
+    } else {
+      command.to_path_buf()
+    }
+  }
+}
+
+pub struct PluginRegistry {
+  dir: Option<PathBuf>,
+  plugins: Vec<Plugin>,
+}
+
+impl PluginRegistry {
+  pub fn new() -> Self {
+    Self {
+      dir: None,
+      plugins: Vec::new(),
+    }
+  }
+
+  // Discover plugins under `<dir>/plugins`
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    self.dir = Some(dir.join(PLUGINS_DIR));
+    self.reload();
+    self
+  }
+
+  /// Scan the plugins folder again (after the user added or removed one)
+  pub fn reload(&mut self) {
+    self.plugins.clear();
+    let Some(dir) = &self.dir else {
+      return;
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+      return;
+    };
+    let mut folders: Vec<PathBuf> = entries
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .filter(|path| path.join(MANIFEST_FILE).is_file())
+      .collect();
+    folders.sort();
+    for folder in folders {
+      match load_manifest(&folder) {
+        Ok(manifest) => {
+          if self.plugins.iter().any(|p| p.manifest.id == manifest.id) {
+            warn!(
+              "⚠️ Skipping plugin in {}: id '{}' is already used",
+              folder.display(),
+              manifest.id
+            );
+            continue;
+          }
+          let error = check_runnable(&manifest);
+          if let Some(e) = &error {
+            warn!("⚠️ Plugin '{}' can't run: {}", manifest.id, e);
+          }
+          self.plugins.push(Plugin {
+            manifest,
+            dir: folder,
+            error,
+          });
+        }
+        Err(e) => warn!("⚠️ Ignoring plugin in {}: {}", folder.display(), e),
+      }
+    }
+    info!("🧩 Loaded {} processor plugin(s)", self.plugins.len());
+  }
+
+  pub fn list(&self) -> Vec<PluginInfo> {
+    self.plugins.iter().map(Plugin::info).collect()
+  }
+
+  pub fn get(&self, id: &str) -> Option<&Plugin> {
+    self.plugins.iter().find(|p| p.manifest.id == id)
+  }
+}
+
+impl Default for PluginRegistry {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+fn load_manifest(folder: &Path) -> Result<PluginManifest, String> {
+  let json = fs::read_to_string(folder.join(MANIFEST_FILE))
+    .map_err(|e| format!("Failed to read {}: {}", MANIFEST_FILE, e))?;
+  let manifest: PluginManifest =
+    serde_json::from_str(&json).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+  let valid_id = !manifest.id.is_empty()
+    && manifest.id.len() <= 64
+    && manifest
+      .id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+  if !valid_id {
+    return Err(format!("Invalid plugin id '{}'", manifest.id));
+  }
+  if manifest.name.trim().is_empty() {
+    return Err("Plugin has no name".to_string());
+  }
+  Ok(manifest)
+}
+
+fn check_runnable(manifest: &PluginManifest) -> Option<String> {
+  match manifest.kind {
+    PluginKind::Wasm => Some("WASM plugins are not supported by this build".to_string()),
+    PluginKind::Subprocess if manifest.command.trim().is_empty() => {
+      Some("Manifest has no command".to_string())
+    }
+    PluginKind::Subprocess => None,
+  }
+}
+
+/// Run `plugin` on one capture and return what it produced
+pub async fn run(plugin: &Plugin, input: &ProcessorInput) -> Result<ProcessorOutput, String> {
+  if let Some(e) = &plugin.error {
+    return Err(format!("Plugin '{}' can't run: {}", plugin.manifest.id, e));
+  }
+  let mut input = input.clone();
+  if !plugin.manifest.wants_image {
+    input.image_png_base64 = None;
+  }
+  let request =
+    serde_json::to_vec(&input).map_err(|e| format!("Failed to encode plugin request: {}", e))?;
+
+  let mut child = tokio::process::Command::new(plugin.program())
+    .args(&plugin.manifest.args)
+    .current_dir(&plugin.dir)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|e| format!("Failed to start plugin '{}': {}", plugin.manifest.id, e))?;
+  let mut stdin = child.stdin.take().ok_or("Plugin stdin unavailable")?;
+  let stdout = child.stdout.take().ok_or("Plugin stdout unavailable")?;
+  let stderr = child.stderr.take().ok_or("Plugin stderr unavailable")?;
+
+  let exchange = async {
+    // Write and read together so a plugin streaming output early can't deadlock us
+    // Dropping stdin closes the pipe so the plugin sees end of input
+    let write = async move {
+      stdin.write_all(&request).await?;
+      stdin.flush().await
+    };
+    let mut output = Vec::new();
+    let mut errors = Vec::new();
+    let (mut stdout, mut stderr) = (stdout.take(MAX_OUTPUT_BYTES), stderr.take(64 * 1024));
+    let read_out = stdout.read_to_end(&mut output);
+    let read_err = stderr.read_to_end(&mut errors);
+    let (written, _, _) = tokio::join!(write, read_out, read_err);
+    // Output was cut off: the plugin may be blocked on a full pipe, so waiting would hang
+    if output.len() as u64 == MAX_OUTPUT_BYTES {
+      child.kill().await?;
+      return Ok::<_, std::io::Error>((written, None, output, errors));
+    }
+    let status = child.wait().await?;
+    Ok((written, Some(status), output, errors))
+  };
+  let (written, status, output, errors) = tokio::time::timeout(plugin.timeout(), exchange)
+    .await
+    .map_err(|_| {
+      format!(
+        "Plugin '{}' timed out after {}s",
+        plugin.manifest.id,
+        plugin.timeout().as_secs()
+      )
+    })?
+    .map_err(|e| format!("Plugin '{}' failed: {}", plugin.manifest.id, e))?;
+
+  let Some(status) = status else {
+    return Err(format!(
+      "Plugin '{}' output exceeds {} MiB",
+      plugin.manifest.id,
+      MAX_OUTPUT_BYTES / (1024 * 1024)
+    ));
+  };
+  if !status.success() {
+    let stderr = String::from_utf8_lossy(&errors);
+    return Err(format!(
+      "Plugin '{}' exited with {}: {}",
+      plugin.manifest.id,
+      status,
+      stderr.trim()
+    ));
+  }
+  if let Err(e) = written {
+    // Plugins may ignore parts of the request and close stdin early
+    warn!(
+      "⚠️ Plugin '{}' did not read its input: {}",
+      plugin.manifest.id, e
+    );
+  }
+  let output: ProcessorOutput = serde_json::from_slice(&output).map_err(|e| {
+    format!(
+      "Plugin '{}' returned invalid JSON: {}",
+      plugin.manifest.id, e
+    )
+  })?;
+  if let Some(e) = &output.error {
+    return Err(format!("Plugin '{}': {}", plugin.manifest.id, e));
+  }
+  Ok(output)
+}
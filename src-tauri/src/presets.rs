@@ -0,0 +1,143 @@
+// Named capture regions ("standup-board") that can be re-captured in one step,
+// optionally bound to their own global shortcut. Persisted in the app data dir.
+use crate::CaptureBounds;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri_plugin_global_shortcut::Shortcut;
+use tracing::{info, warn};
+
+const PRESETS_FILE: &str = "region_presets.json";
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionPreset {
+  pub name: String,
+  pub bounds: CaptureBounds,
+  pub shortcut: Option<String>, // e.g. "Alt+Shift+1"
+}
+
+impl RegionPreset {
+  pub fn parsed_shortcut(&self) -> Option<Shortcut> {
+    self.shortcut.as_ref().and_then(|s| s.parse().ok())
+  }
+}
+
+pub struct PresetStore {
+  storage_path: Option<PathBuf>,
+  presets: Vec<RegionPreset>,
+}
+
+impl PresetStore {
+  pub fn new() -> Self {
+    Self {
+      storage_path: None,
+      presets: Vec::new(),
+    }
+  }
+
+  // Load saved presets from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(PRESETS_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(presets) => {
+          self.presets = presets;
+          info!("📌 Loaded {} region presets", self.presets.len());
+        }
+        Err(e) => warn!("⚠️ Ignoring unreadable region presets: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn list(&self) -> &[RegionPreset] {
+    &self.presets
+  }
+
+  pub fn get(&self, name: &str) -> Option<&RegionPreset> {
+    self.presets.iter().find(|p| p.name == name)
+  }
+
+  pub fn find_by_shortcut(&self, shortcut: &Shortcut) -> Option<&RegionPreset> {
+    self
+      .presets
+      .iter()
+      .find(|p| p.parsed_shortcut().as_ref() == Some(shortcut))
+  }
+
+  /// Add a preset, replacing one with the same name. Returns the replaced preset.
+  /// `reserved` holds the built-in shortcuts a preset may not take over.
+  pub fn save(
+    &mut self,
+    preset: RegionPreset,
+    reserved: &[Shortcut],
+  ) -> Result<Option<RegionPreset>, String> {
+    if preset.name.trim().is_empty() {
+      return Err("Preset name cannot be empty".to_string());
+    }
+    if preset.bounds.width == 0 || preset.bounds.height == 0 {
+      return Err("Preset region must have a non-zero size".to_string());
+    }
+    if let Some(shortcut) = &preset.shortcut {
+      let parsed = shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))?;
+      if reserved.contains(&parsed) {
+        return Err(format!(
+          "Shortcut '{}' is already used by a built-in shortcut",
+          shortcut
+        ));
+      }
+      if let Some(other) = self.find_by_shortcut(&parsed) {
+        if other.name != preset.name {
+          return Err(format!(
+            "Shortcut '{}' is already used by preset '{}'",
+            shortcut, other.name
+          ));
+        }
+      }
+    }
+
+    let replaced = self
+      .presets
+      .iter()
+      .position(|p| p.name == preset.name)
+      .map(|index| self.presets.remove(index));
+    self.presets.push(preset);
+    self.persist()?;
+    Ok(replaced)
+  }
+
+  pub fn remove(&mut self, name: &str) -> Result<RegionPreset, String> {
+    let index = self
+      .presets
+      .iter()
+      .position(|p| p.name == name)
+      .ok_or_else(|| format!("Region preset '{}' not found", name))?;
+    let removed = self.presets.remove(index);
+    self.persist()?;
+    Ok(removed)
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.presets)
+      .map_err(|e| format!("Failed to serialize region presets: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save region presets: {}", e))
+  }
+}
+
+impl Default for PresetStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,124 @@
+// Native printing of captures - renders a page-fitting HTML layout in a webview window
+// and hands it to the platform print dialog via the webview's print support.
+use crate::capture_store::StoredCapture;
+use base64::Engine;
+use tauri::webview::PageLoadEvent;
+use tauri::{WebviewUrl, WebviewWindowBuilder};
+use tracing::{error, info};
+
+const PRINT_WINDOW_LABEL: &str = "print";
+
+#[derive(Clone, serde::Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintOptions {
+  pub include_ocr_text: bool,
+  pub title: Option<String>,
+}
+
+pub fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// Build a self-contained HTML page that fits the capture on one page
+pub fn build_print_html(capture: &StoredCapture, options: &PrintOptions) -> String {
+  // Wide captures print in landscape so they aren't shrunk unnecessarily
+  let landscape = capture
+    .bounds
+    .as_ref()
+    .map(|b| b.width > b.height)
+    .unwrap_or(false);
+  let title = options
+    .title
+    .clone()
+    .unwrap_or_else(|| format!("FrameSense capture {}", capture.created_at));
+
+  let text_section = match (&capture.ocr_text, options.include_ocr_text) {
+    (Some(text), true) if !text.trim().is_empty() => format!(
+      "<section class=\"ocr\"><h2>Recognized text</h2><pre>{}</pre></section>",
+      escape_html(text)
+    ),
+    _ => String::new(),
+  };
+
+  format!(
+    r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+  @page {{ size: {orientation}; margin: 12mm; }}
+  body {{ margin: 0; font-family: -apple-system, "Segoe UI", sans-serif; color: #111; }}
+  header {{ font-size: 10pt; color: #555; margin-bottom: 6mm; }}
+  .capture {{ display: block; max-width: 100%; max-height: {image_height}; margin: 0 auto; object-fit: contain; }}
+  .ocr {{ page-break-inside: auto; margin-top: 6mm; }}
+  .ocr h2 {{ font-size: 11pt; margin: 0 0 2mm; }}
+  .ocr pre {{ white-space: pre-wrap; font-size: 9pt; }}
+</style>
+</head>
+<body>
+<header>{title} &middot; {created_at}</header>
+<img class="capture" src="{image}">
+{text_section}
+</body>
+</html>"#,
+    title = escape_html(&title),
+    orientation = if landscape { "landscape" } else { "portrait" },
+    // Leave room for the OCR text below the image when it is included
+    image_height = if text_section.is_empty() {
+      "90vh"
+    } else {
+      "60vh"
+    },
+    created_at = escape_html(&capture.created_at),
+    image = capture.image_data,
+    text_section = text_section,
+  )
+}
+
+/// Open a print preview window for the capture and show the native print dialog once loaded
+pub fn print_capture(
+  app: &tauri::AppHandle,
+  capture: &StoredCapture,
+  options: &PrintOptions,
+) -> Result<(), String> {
+  let html = build_print_html(capture, options);
+  let data_url = format!(
+    "data:text/html;base64,{}",
+    base64::engine::general_purpose::STANDARD.encode(html.as_bytes())
+  );
+  let url = url::Url::parse(&data_url).map_err(|e| format!("Invalid print document: {}", e))?;
+
+  // An open print window is reused: closing it and building a new one under the same
+  // label races the old window's teardown. Its page-load hook prints the new document.
+  if let Some(existing) = tauri::Manager::get_webview_window(app, PRINT_WINDOW_LABEL) {
+    existing
+      .navigate(url)
+      .map_err(|e| format!("Failed to load print document: {}", e))?;
+    let _ = existing.set_focus();
+    info!("🖨️ Print window reused for capture {}", capture.id);
+    return Ok(());
+  }
+
+  WebviewWindowBuilder::new(app, PRINT_WINDOW_LABEL, WebviewUrl::External(url))
+    .title("Print Capture")
+    .inner_size(820.0, 1000.0)
+    .center()
+    .on_page_load(|window, payload| {
+      if payload.event() == PageLoadEvent::Finished {
+        info!("🖨️ Print document loaded - opening print dialog");
+        if let Err(e) = window.print() {
+          error!("❌ Failed to open print dialog: {}", e);
+        }
+      }
+    })
+    .build()
+    .map_err(|e| format!("Failed to create print window: {}", e))?;
+
+  info!("🖨️ Print window created for capture {}", capture.id);
+  Ok(())
+}
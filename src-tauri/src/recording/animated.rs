@@ -0,0 +1,223 @@
+// Short animated captures of a region, returned as GIF (image crate) or WebP (via ffmpeg)
+use crate::overlay::{CaptureBounds, ScreenCapture};
+use base64::Engine;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const MAX_DURATION_SECS: f64 = 30.0;
+const MAX_FPS: u32 = 30;
+// GIF quantization speed: 1 = best quality, 30 = fastest
+const GIF_ENCODER_SPEED: i32 = 10;
+// Captured frames waiting for the encoder; capture pauses when it falls this far behind
+const FRAME_QUEUE: usize = 4;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimatedFormat {
+  Gif,
+  Webp,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct AnimatedCapture {
+  pub image_data: String, // data:image/gif;base64,... or data:image/webp;base64,...
+  pub format: AnimatedFormat,
+  pub frames: usize,
+  pub width: u32,
+  pub height: u32,
+  pub size_bytes: usize,
+}
+
+/// Capture `bounds` for `duration_secs` at `fps` and encode as an animated image.
+/// Blocking - run it on a blocking pool.
+pub fn record_animated(
+  bounds: &CaptureBounds,
+  duration_secs: f64,
+  fps: u32,
+  format: AnimatedFormat,
+) -> Result<AnimatedCapture, String> {
+  if !(0.0..=MAX_DURATION_SECS).contains(&duration_secs) || duration_secs == 0.0 {
+    return Err(format!(
+      "Duration must be between 0 and {} seconds",
+      MAX_DURATION_SECS
+    ));
+  }
+  let fps = fps.clamp(1, MAX_FPS);
+  let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+  let frame_count = (duration_secs * fps as f64).ceil() as usize;
+
+  info!(
+    "🎞️ Recording {:?}: {}x{} for {:.1}s @ {}fps ({} frames)",
+    format, bounds.width, bounds.height, duration_secs, fps, frame_count
+  );
+
+  // Frames go through a short queue to an encoder thread as they are captured, so
+  // memory stays at a few frames instead of the whole recording (900 4K frames is GBs)
+  let (sender, receiver) = mpsc::sync_channel::<RgbaImage>(FRAME_QUEUE);
+  let encoder = std::thread::spawn(move || encode_frames(receiver, format, fps));
+
+  let mut size: Option<(u32, u32)> = None;
+  let mut capture_error = None;
+  for _ in 0..frame_count {
+    let frame_start = Instant::now();
+    match ScreenCapture::capture_area_rgba(bounds) {
+      // A resolution change mid-recording: skip frames that no longer fit
+      Ok(frame) if size.is_some_and(|size| size != frame.dimensions()) => {}
+      Ok(frame) => {
+        size = Some(frame.dimensions());
+        if sender.send(frame).is_err() {
+          // The encoder gave up; its error is reported below
+          break;
+        }
+      }
+      Err(e) => {
+        capture_error = Some(e);
+        break;
+      }
+    }
+    let elapsed = frame_start.elapsed();
+    if elapsed < frame_interval {
+      std::thread::sleep(frame_interval - elapsed);
+    }
+  }
+  drop(sender);
+
+  let encoded = encoder
+    .join()
+    .map_err(|_| "Frame encoder thread panicked".to_string())?;
+  if let Some(e) = capture_error {
+    return Err(e);
+  }
+  let (bytes, frame_total) = encoded?;
+  let (width, height) = size.ok_or("No frames captured")?;
+
+  let mime = match format {
+    AnimatedFormat::Gif => "image/gif",
+    AnimatedFormat::Webp => "image/webp",
+  };
+
+  info!(
+    "✅ Animated capture encoded: {} frames, {}KB",
+    frame_total,
+    bytes.len() / 1024
+  );
+
+  Ok(AnimatedCapture {
+    image_data: format!(
+      "data:{};base64,{}",
+      mime,
+      base64::engine::general_purpose::STANDARD.encode(&bytes)
+    ),
+    format,
+    frames: frame_total,
+    width,
+    height,
+    size_bytes: bytes.len(),
+  })
+}
+
+// Runs on its own thread; returns the file and the number of frames in it
+fn encode_frames(
+  frames: mpsc::Receiver<RgbaImage>,
+  format: AnimatedFormat,
+  fps: u32,
+) -> Result<(Vec<u8>, usize), String> {
+  match format {
+    AnimatedFormat::Gif => encode_gif(frames, fps),
+    AnimatedFormat::Webp => encode_webp(frames, fps),
+  }
+}
+
+fn encode_gif(frames: mpsc::Receiver<RgbaImage>, fps: u32) -> Result<(Vec<u8>, usize), String> {
+  let mut buffer = Vec::new();
+  let mut count = 0;
+  {
+    let mut encoder = GifEncoder::new_with_speed(&mut buffer, GIF_ENCODER_SPEED);
+    encoder
+      .set_repeat(Repeat::Infinite)
+      .map_err(|e| format!("Failed to configure GIF: {}", e))?;
+    let delay = Delay::from_numer_denom_ms(1000, fps);
+    for image in frames {
+      encoder
+        .encode_frame(Frame::from_parts(image, 0, 0, delay))
+        .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+      count += 1;
+    }
+  }
+  Ok((buffer, count))
+}
+
+// The image crate can't write animated WebP, so frames are piped through ffmpeg. It is
+// started with the first frame, once the size is known.
+fn encode_webp(frames: mpsc::Receiver<RgbaImage>, fps: u32) -> Result<(Vec<u8>, usize), String> {
+  let Ok(first) = frames.recv() else {
+    return Ok((Vec::new(), 0));
+  };
+  let (width, height) = first.dimensions();
+  let mut child = Command::new("ffmpeg")
+    .args([
+      "-loglevel",
+      "error",
+      "-f",
+      "rawvideo",
+      "-pix_fmt",
+      "rgba",
+      "-s",
+      &format!("{}x{}", width, height),
+      "-r",
+      &fps.to_string(),
+      "-i",
+      "-",
+      "-c:v",
+      "libwebp",
+      "-lossless",
+      "0",
+      "-quality",
+      "80",
+      "-loop",
+      "0",
+      "-f",
+      "webp",
+      "-",
+    ])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|_| "ffmpeg was not found - install it to create WebP animations".to_string())?;
+
+  let mut stdin = child.stdin.take().ok_or("Failed to open ffmpeg input")?;
+  let mut count = 0;
+  // Write frames from this thread while ffmpeg's output is drained by wait_with_output
+  // on another, so a full stdout pipe can't deadlock us
+  let writer = std::thread::spawn(move || -> Result<usize, String> {
+    for frame in std::iter::once(first).chain(frames) {
+      stdin
+        .write_all(frame.as_raw())
+        .map_err(|e| format!("ffmpeg stopped accepting frames: {}", e))?;
+      count += 1;
+    }
+    Ok(count)
+  });
+
+  let output = child
+    .wait_with_output()
+    .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+  let count = writer
+    .join()
+    .map_err(|_| "Frame writer thread panicked".to_string())??;
+
+  if !output.status.success() {
+    return Err(format!(
+      "ffmpeg WebP encoding failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+  Ok((output.stdout, count))
+}
@@ -0,0 +1,398 @@
+// Screen recording - captures a region (or the primary screen) at a fixed FPS and pipes
+// raw frames into ffmpeg, which encodes them to H.264 MP4.
+use tracing::info;
+
+pub mod animated;
+
+use crate::overlay::{CaptureBounds, ScreenCapture};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+pub use animated::{AnimatedCapture, AnimatedFormat};
+
+const DEFAULT_FPS: u32 = 30;
+const MAX_FPS: u32 = 60;
+// When capture falls behind, repeat the last frame at most this many times to keep real time
+const MAX_CATCH_UP_FRAMES: u64 = 5;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RecordingOptions {
+  pub bounds: Option<CaptureBounds>, // None = primary screen
+  pub fps: Option<u32>,
+  pub output_path: Option<String>,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingState {
+  Recording,
+  Paused,
+  Stopped,
+  Error,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct RecordingStatus {
+  pub state: RecordingState,
+  pub frames: u64,
+  pub duration_secs: f64,
+  pub output_path: String,
+  pub message: Option<String>,
+}
+
+struct RecordingControl {
+  stop: AtomicBool,
+  paused: AtomicBool,
+  frames: AtomicU64,
+}
+
+struct ActiveRecording {
+  control: Arc<RecordingControl>,
+  handle: JoinHandle<Result<RecordingStatus, String>>,
+  output_path: PathBuf,
+  started_at: Instant,
+}
+
+/// Owns the (single) active screen recording
+pub struct ScreenRecorder {
+  active: Option<ActiveRecording>,
+}
+
+impl ScreenRecorder {
+  pub fn new() -> Self {
+    Self { active: None }
+  }
+
+  pub fn is_recording(&self) -> bool {
+    self
+      .active
+      .as_ref()
+      .is_some_and(|active| !active.handle.is_finished())
+  }
+
+  // A recording that ended on its own (capture or ffmpeg error) has already reported it;
+  // forget it so the next one can start
+  fn clear_finished(&mut self) {
+    if let Some(active) = self.active.take_if(|active| active.handle.is_finished()) {
+      let _ = active.handle.join();
+    }
+  }
+
+  pub fn start(
+    &mut self,
+    app: tauri::AppHandle,
+    options: RecordingOptions,
+    default_dir: PathBuf,
+  ) -> Result<RecordingStatus, String> {
+    self.clear_finished();
+    if self.is_recording() {
+      return Err("A recording is already in progress".to_string());
+    }
+    ensure_ffmpeg_available()?;
+
+    let fps = options.fps.unwrap_or(DEFAULT_FPS).clamp(1, MAX_FPS);
+    let bounds = match options.bounds {
+      Some(bounds) => bounds,
+      None => primary_screen_bounds()?,
+    };
+    let output_path = match options.output_path {
+      Some(path) => PathBuf::from(path),
+      None => {
+        std::fs::create_dir_all(&default_dir)
+          .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+        default_dir.join(format!(
+          "FrameSense Recording {}.mp4",
+          chrono::Local::now().format("%Y-%m-%d at %H.%M.%S")
+        ))
+      }
+    };
+
+    info!(
+      "🎬 Starting recording: {}x{} at ({}, {}) @ {}fps → {:?}",
+      bounds.width, bounds.height, bounds.x, bounds.y, fps, output_path
+    );
+
+    let control = Arc::new(RecordingControl {
+      stop: AtomicBool::new(false),
+      paused: AtomicBool::new(false),
+      frames: AtomicU64::new(0),
+    });
+
+    let thread_control = control.clone();
+    let thread_path = output_path.clone();
+    let handle = std::thread::spawn(move || {
+      let result = record_loop(&app, &thread_control, bounds, fps, &thread_path);
+      if let Err(e) = &result {
+        crate::crash_reports::report_error("Screen recording", e);
+        let _ = app.emit(
+          "recording-status",
+          RecordingStatus {
+            state: RecordingState::Error,
+            frames: thread_control.frames.load(Ordering::Relaxed),
+            duration_secs: 0.0,
+            output_path: thread_path.to_string_lossy().to_string(),
+            message: Some(e.clone()),
+          },
+        );
+      }
+      result
+    });
+
+    self.active = Some(ActiveRecording {
+      control,
+      handle,
+      output_path: output_path.clone(),
+      started_at: Instant::now(),
+    });
+
+    Ok(RecordingStatus {
+      state: RecordingState::Recording,
+      frames: 0,
+      duration_secs: 0.0,
+      output_path: output_path.to_string_lossy().to_string(),
+      message: None,
+    })
+  }
+
+  pub fn set_paused(&mut self, paused: bool) -> Result<RecordingStatus, String> {
+    self.clear_finished();
+    let active = self.active.as_ref().ok_or("No recording in progress")?;
+    active.control.paused.store(paused, Ordering::Relaxed);
+    info!("🎬 Recording {}", if paused { "paused" } else { "resumed" });
+    Ok(self.status_of(active))
+  }
+
+  pub fn status(&mut self) -> Option<RecordingStatus> {
+    self.clear_finished();
+    self.active.as_ref().map(|active| self.status_of(active))
+  }
+
+  fn status_of(&self, active: &ActiveRecording) -> RecordingStatus {
+    let paused = active.control.paused.load(Ordering::Relaxed);
+    RecordingStatus {
+      state: if paused {
+        RecordingState::Paused
+      } else {
+        RecordingState::Recording
+      },
+      frames: active.control.frames.load(Ordering::Relaxed),
+      duration_secs: active.started_at.elapsed().as_secs_f64(),
+      output_path: active.output_path.to_string_lossy().to_string(),
+      message: None,
+    }
+  }
+
+  /// Signal the recording thread to stop and hand back its join handle.
+  /// Joining can take a moment (ffmpeg finalizes the file), so callers should
+  /// do it outside of any lock.
+  pub fn stop(&mut self) -> Result<JoinHandle<Result<RecordingStatus, String>>, String> {
+    let active = self.active.take().ok_or("No recording in progress")?;
+    active.control.stop.store(true, Ordering::Relaxed);
+    Ok(active.handle)
+  }
+}
+
+impl Default for ScreenRecorder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+fn ensure_ffmpeg_available() -> Result<(), String> {
+  Command::new("ffmpeg")
+    .arg("-version")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map_err(|_| "ffmpeg was not found - install it to record the screen".to_string())
+    .and_then(|status| {
+      if status.success() {
+        Ok(())
+      } else {
+        Err("ffmpeg is installed but not working".to_string())
+      }
+    })
+}
+
+/// Bounds of the primary screen in overlay coordinates (relative to the total screen area)
+pub fn primary_screen_bounds() -> Result<CaptureBounds, String> {
+  let total_area = ScreenCapture::get_total_screen_area()?;
+  let screens = screenshots::Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
+  let screen = screens
+    .iter()
+    .find(|s| s.display_info.is_primary)
+    .or_else(|| screens.first())
+    .ok_or("No screens found")?;
+  Ok(CaptureBounds {
+    x: screen.display_info.x - total_area.min_x,
+    y: screen.display_info.y - total_area.min_y,
+    width: screen.display_info.width,
+    height: screen.display_info.height,
+  })
+}
+
+fn spawn_ffmpeg(width: u32, height: u32, fps: u32, output: &Path) -> Result<Child, String> {
+  Command::new("ffmpeg")
+    .args([
+      "-y",
+      "-loglevel",
+      "error",
+      "-f",
+      "rawvideo",
+      "-pix_fmt",
+      "rgba",
+      "-s",
+      &format!("{}x{}", width, height),
+      "-r",
+      &fps.to_string(),
+      "-i",
+      "-",
+      // H.264 needs even dimensions
+      "-vf",
+      "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+      "-c:v",
+      "libx264",
+      "-preset",
+      "veryfast",
+      "-pix_fmt",
+      "yuv420p",
+      "-movflags",
+      "+faststart",
+    ])
+    .arg(output)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("Failed to start ffmpeg: {}", e))
+}
+
+type Encoder = (Child, ChildStdin, (u32, u32));
+
+fn record_loop(
+  app: &tauri::AppHandle,
+  control: &RecordingControl,
+  bounds: CaptureBounds,
+  fps: u32,
+  output_path: &Path,
+) -> Result<RecordingStatus, String> {
+  let started_at = Instant::now();
+  let mut ffmpeg: Option<Encoder> = None;
+  let captured = capture_frames(app, control, bounds, fps, output_path, &mut ffmpeg);
+
+  // Closing stdin tells ffmpeg the stream ended so it can finalize the MP4 - or exit
+  // after an error, so it's never left running
+  let finished = ffmpeg.map(|(child, stdin, _)| {
+    drop(stdin);
+    child
+      .wait_with_output()
+      .map_err(|e| format!("Failed to finalize recording: {}", e))
+  });
+  let recorded_time = captured.map_err(|e| match &finished {
+    Some(Ok(output)) if !output.stderr.is_empty() => {
+      format!("{} ({})", e, String::from_utf8_lossy(&output.stderr).trim())
+    }
+    _ => e,
+  })?;
+  let output = finished.ok_or("Recording stopped before any frame was captured")??;
+  if !output.status.success() {
+    return Err(format!(
+      "ffmpeg failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    ));
+  }
+
+  let status = RecordingStatus {
+    state: RecordingState::Stopped,
+    frames: control.frames.load(Ordering::Relaxed),
+    duration_secs: recorded_time.as_secs_f64(),
+    output_path: output_path.to_string_lossy().to_string(),
+    message: None,
+  };
+  info!(
+    "✅ Recording saved: {} frames, {:.1}s of video in {:.1}s → {:?}",
+    status.frames,
+    status.duration_secs,
+    started_at.elapsed().as_secs_f64(),
+    output_path
+  );
+  let _ = app.emit("recording-status", &status);
+  Ok(status)
+}
+
+// Capture and write frames until stopped; returns the recorded (unpaused) time
+fn capture_frames(
+  app: &tauri::AppHandle,
+  control: &RecordingControl,
+  bounds: CaptureBounds,
+  fps: u32,
+  output_path: &Path,
+  ffmpeg: &mut Option<Encoder>,
+) -> Result<Duration, String> {
+  let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+  let mut recorded_time = Duration::ZERO; // Excludes paused time
+  let mut last_status_emit = Instant::now();
+
+  while !control.stop.load(Ordering::Relaxed) {
+    if control.paused.load(Ordering::Relaxed) {
+      std::thread::sleep(Duration::from_millis(50));
+      continue;
+    }
+
+    let frame_start = Instant::now();
+    let frame = ScreenCapture::capture_area_rgba(&bounds)?;
+
+    // ffmpeg is started lazily: the physical frame size is only known after the first capture
+    if ffmpeg.is_none() {
+      let (width, height) = frame.dimensions();
+      let mut child = spawn_ffmpeg(width, height, fps, output_path)?;
+      let stdin = child.stdin.take().ok_or("Failed to open ffmpeg input")?;
+      *ffmpeg = Some((child, stdin, (width, height)));
+    }
+    let (_, stdin, size) = ffmpeg.as_mut().unwrap();
+    // Display configuration changed mid-recording - mismatched frames are skipped, and
+    // the next matching one is repeated to fill the gap
+    if frame.dimensions() == *size {
+      // Write the frame once, plus repeats if capture fell behind real time
+      let written = control.frames.load(Ordering::Relaxed);
+      let expected = (recorded_time.as_secs_f64() * fps as f64) as u64 + 1;
+      let copies = expected
+        .saturating_sub(written)
+        .clamp(1, MAX_CATCH_UP_FRAMES);
+      for _ in 0..copies {
+        stdin
+          .write_all(frame.as_raw())
+          .map_err(|e| format!("ffmpeg stopped accepting frames: {}", e))?;
+      }
+      control.frames.fetch_add(copies, Ordering::Relaxed);
+    }
+
+    if last_status_emit.elapsed() >= Duration::from_secs(1) {
+      last_status_emit = Instant::now();
+      let _ = app.emit(
+        "recording-status",
+        RecordingStatus {
+          state: RecordingState::Recording,
+          frames: control.frames.load(Ordering::Relaxed),
+          duration_secs: recorded_time.as_secs_f64(),
+          output_path: output_path.to_string_lossy().to_string(),
+          message: None,
+        },
+      );
+    }
+
+    let elapsed = frame_start.elapsed();
+    if elapsed < frame_interval {
+      std::thread::sleep(frame_interval - elapsed);
+    }
+    recorded_time += frame_start.elapsed();
+  }
+  Ok(recorded_time)
+}
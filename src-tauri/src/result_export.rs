@@ -0,0 +1,205 @@
+// Exporting recognized (OCR) or analyzed (AI) text for notes and documentation. Each
+// file starts with metadata - YAML frontmatter in Markdown, a header block in plain text,
+// document properties plus a details paragraph in DOCX - followed by the text itself.
+use crate::print::escape_html;
+use crate::CaptureBounds;
+use serde::Deserialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use zip::write::SimpleFileOptions;
+
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultFormat {
+  Markdown,
+  Txt,
+  Docx,
+}
+
+impl ResultFormat {
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ResultFormat::Markdown => "md",
+      ResultFormat::Txt => "txt",
+      ResultFormat::Docx => "docx",
+    }
+  }
+}
+
+/// Text to export plus where it came from
+pub struct ResultDocument {
+  pub id: String,
+  // "ocr" or "analysis"
+  pub kind: &'static str,
+  pub title: String,
+  pub created_at: String,
+  pub source_bounds: Option<CaptureBounds>,
+  pub source_app: Option<String>,
+  pub model: Option<String>,
+  pub sections: Vec<ResultSection>,
+}
+
+pub struct ResultSection {
+  pub heading: String,
+  pub text: String,
+}
+
+impl ResultDocument {
+  // (key, value) pairs shared by every format
+  fn metadata(&self) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+      ("id", self.id.clone()),
+      ("type", self.kind.to_string()),
+      ("created", self.created_at.clone()),
+      ("exported", chrono::Utc::now().to_rfc3339()),
+    ];
+    if let Some(bounds) = &self.source_bounds {
+      fields.push((
+        "source_bounds",
+        format!(
+          "{},{},{},{}",
+          bounds.x, bounds.y, bounds.width, bounds.height
+        ),
+      ));
+    }
+    if let Some(app) = &self.source_app {
+      fields.push(("source_app", app.clone()));
+    }
+    if let Some(model) = &self.model {
+      fields.push(("model", model.clone()));
+    }
+    fields
+  }
+}
+
+/// Write `document` to `path` (the format's extension is added when missing)
+pub fn export_result(
+  document: &ResultDocument,
+  format: ResultFormat,
+  path: &Path,
+) -> Result<PathBuf, String> {
+  let path = if path.extension().is_none() {
+    path.with_extension(format.extension())
+  } else {
+    path.to_path_buf()
+  };
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create export folder: {}", e))?;
+  }
+
+  let bytes = match format {
+    ResultFormat::Markdown => render_markdown(document).into_bytes(),
+    ResultFormat::Txt => render_text(document).into_bytes(),
+    ResultFormat::Docx => render_docx(document)?,
+  };
+  fs::write(&path, bytes).map_err(|e| format!("Failed to write export: {}", e))?;
+  info!("📤 Exported result {} to {}", document.id, path.display());
+  Ok(path)
+}
+
+fn render_markdown(document: &ResultDocument) -> String {
+  let mut out = String::from("---\n");
+  for (key, value) in document.metadata() {
+    out.push_str(&format!("{}: {}\n", key, yaml_string(&value)));
+  }
+  out.push_str(&format!("---\n\n# {}\n", document.title));
+  for section in &document.sections {
+    out.push_str(&format!(
+      "\n## {}\n\n{}\n",
+      section.heading,
+      section.text.trim()
+    ));
+  }
+  out
+}
+
+// Quote values YAML would otherwise misread (colons, leading symbols, numbers...)
+fn yaml_string(value: &str) -> String {
+  format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn render_text(document: &ResultDocument) -> String {
+  let mut out = String::new();
+  for (key, value) in document.metadata() {
+    out.push_str(&format!("{}: {}\n", key, value));
+  }
+  out.push_str(&format!("\n{}\n", document.title));
+  for section in &document.sections {
+    out.push_str(&format!(
+      "\n{}\n{}\n\n{}\n",
+      section.heading,
+      "-".repeat(section.heading.chars().count()),
+      section.text.trim()
+    ));
+  }
+  out
+}
+
+// Minimal WordprocessingML package: content types, relationships, core properties and
+// the document body. Headings are bold runs so no styles part is needed.
+fn render_docx(document: &ResultDocument) -> Result<Vec<u8>, String> {
+  let mut body = String::new();
+  body.push_str(&docx_paragraph(&document.title, Some(36)));
+  let details: Vec<String> = document
+    .metadata()
+    .into_iter()
+    .map(|(key, value)| format!("{}: {}", key, value))
+    .collect();
+  for line in details {
+    body.push_str(&docx_paragraph(&line, None));
+  }
+  for section in &document.sections {
+    body.push_str(&docx_paragraph(&section.heading, Some(28)));
+    for line in section.text.trim().lines() {
+      body.push_str(&docx_paragraph(line, None));
+    }
+  }
+
+  let document_xml = format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body>{}</w:body></w:document>",
+    body
+  );
+  let core_xml = format!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"><dc:title>{}</dc:title><dc:creator>FrameSense</dc:creator><dc:description>{}</dc:description><dcterms:created xsi:type=\"dcterms:W3CDTF\">{}</dcterms:created></cp:coreProperties>",
+    escape_html(&document.title),
+    escape_html(&format!("{} {}", document.kind, document.id)),
+    escape_html(&document.created_at)
+  );
+  let parts = [
+    ("[Content_Types].xml", CONTENT_TYPES_XML.to_string()),
+    ("_rels/.rels", RELS_XML.to_string()),
+    ("docProps/core.xml", core_xml),
+    ("word/document.xml", document_xml),
+  ];
+
+  let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+  let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+  for (name, content) in parts {
+    zip
+      .start_file(name, options)
+      .and_then(|_| zip.write_all(content.as_bytes()).map_err(Into::into))
+      .map_err(|e| format!("Failed to build DOCX: {}", e))?;
+  }
+  zip
+    .finish()
+    .map(|cursor| cursor.into_inner())
+    .map_err(|e| format!("Failed to build DOCX: {}", e))
+}
+
+// A paragraph; `size` (half-points) makes it a bold heading
+fn docx_paragraph(text: &str, size: Option<u32>) -> String {
+  let properties = size
+    .map(|size| format!("<w:rPr><w:b/><w:sz w:val=\"{}\"/></w:rPr>", size))
+    .unwrap_or_default();
+  format!(
+    "<w:p><w:r>{}<w:t xml:space=\"preserve\">{}</w:t></w:r></w:p>",
+    properties,
+    escape_html(text)
+  )
+}
+
+const CONTENT_TYPES_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\"><Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/><Default Extension=\"xml\" ContentType=\"application/xml\"/><Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/><Override PartName=\"/docProps/core.xml\" ContentType=\"application/vnd.openxmlformats-package.core-properties+xml\"/></Types>";
+
+const RELS_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/><Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" Target=\"docProps/core.xml\"/></Relationships>";
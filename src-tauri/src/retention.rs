@@ -0,0 +1,72 @@
+// Data retention - purges local captures, OCR text, conversations and logs older than a
+// number of days, either on demand or daily from settings, and wipes every local folder
+// for "Delete all local data" (privacy-conscious users, GDPR erasure requests).
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PurgeTarget {
+  Captures,
+  OcrText,
+  Conversations,
+  Logs,
+}
+
+impl PurgeTarget {
+  pub const ALL: [PurgeTarget; 4] = [
+    PurgeTarget::Captures,
+    PurgeTarget::OcrText,
+    PurgeTarget::Conversations,
+    PurgeTarget::Logs,
+  ];
+}
+
+/// What a purge removed, per kind of data
+#[derive(Clone, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeSummary {
+  pub captures: usize,
+  pub ocr_texts: usize,
+  pub conversations: usize,
+  pub log_files: usize,
+  pub crash_reports: usize,
+}
+
+impl PurgeSummary {
+  pub fn total(&self) -> usize {
+    self.captures + self.ocr_texts + self.conversations + self.log_files + self.crash_reports
+  }
+}
+
+pub fn cutoff(days: u32) -> DateTime<Utc> {
+  Utc::now() - chrono::Duration::days(days as i64)
+}
+
+/// Whether an RFC3339 timestamp lies before `cutoff`; unreadable dates count as old
+pub fn is_before(timestamp: &str, cutoff: DateTime<Utc>) -> bool {
+  DateTime::parse_from_rfc3339(timestamp)
+    .map(|time| time.with_timezone(&Utc) < cutoff)
+    .unwrap_or(true)
+}
+
+/// Remove each folder with everything in it; missing folders are fine
+pub fn wipe_dirs(dirs: &[PathBuf]) -> Result<(), String> {
+  let mut failed = Vec::new();
+  for dir in dirs.iter().filter(|dir| dir.exists()) {
+    match std::fs::remove_dir_all(dir) {
+      Ok(()) => info!("🧹 Removed {}", dir.display()),
+      Err(e) => {
+        warn!("⚠️ Failed to remove {}: {}", dir.display(), e);
+        failed.push(dir.display().to_string());
+      }
+    }
+  }
+  if failed.is_empty() {
+    Ok(())
+  } else {
+    Err(format!("Failed to remove {}", failed.join(", ")))
+  }
+}
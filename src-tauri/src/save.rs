@@ -0,0 +1,533 @@
+// Saving captures to disk with a configurable default directory and filename template.
+// Templates support {date}, {time}, {app} and {monitor}; existing files are never
+// overwritten - " (2)", " (3)"... is appended instead. Capture metadata can optionally
+// be embedded as PNG text chunks.
+use crate::capture_store::{decode_data_url, CaptureMetadata, StoredCapture};
+use crate::overlay::{CaptureBounds, CoordinateMapper, ScreenCapture};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const SETTINGS_FILE: &str = "save_settings.json";
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "FrameSense {date} at {time}";
+const MAX_COLLISION_SUFFIX: u32 = 9999;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSettings {
+  // None = Pictures/FrameSense
+  pub directory: Option<String>,
+  pub filename_template: String,
+  // Write monitor, scale, app and timestamp into the PNG (iTXt chunks)
+  #[serde(default)]
+  pub embed_metadata: bool,
+}
+
+impl Default for SaveSettings {
+  fn default() -> Self {
+    Self {
+      directory: None,
+      filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+      embed_metadata: false,
+    }
+  }
+}
+
+pub struct SaveManager {
+  settings: SaveSettings,
+  storage_path: Option<PathBuf>,
+}
+
+impl SaveManager {
+  pub fn new() -> Self {
+    Self {
+      settings: SaveSettings::default(),
+      storage_path: None,
+    }
+  }
+
+  // Load saved settings from `dir` and persist future changes there
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(SETTINGS_FILE);
+    if let Ok(json) = fs::read_to_string(&path) {
+      match serde_json::from_str(&json) {
+        Ok(settings) => self.settings = settings,
+        Err(e) => warn!("⚠️ Ignoring unreadable save settings: {}", e),
+      }
+    }
+    self.storage_path = Some(path);
+    self
+  }
+
+  pub fn settings(&self) -> &SaveSettings {
+    &self.settings
+  }
+
+  pub fn update_settings(&mut self, settings: SaveSettings) -> Result<(), String> {
+    if settings.filename_template.trim().is_empty() {
+      return Err("Filename template cannot be empty".to_string());
+    }
+    if settings.filename_template.contains(['/', '\\']) {
+      return Err("Filename template cannot contain path separators".to_string());
+    }
+    self.settings = settings;
+    self.persist()
+  }
+
+  pub fn save_directory(&self) -> PathBuf {
+    match &self.settings.directory {
+      Some(directory) if !directory.trim().is_empty() => PathBuf::from(directory),
+      _ => dirs::picture_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("FrameSense"),
+    }
+  }
+
+  /// Write the capture as PNG. `path` may be a directory (filename from the template)
+  /// or a full file path; without it the default directory is used.
+  pub fn save(&self, capture: &StoredCapture, path: Option<PathBuf>) -> Result<PathBuf, String> {
+    let mut png = decode_data_url(&capture.image_data)?;
+    if self.settings.embed_metadata {
+      if let Some(metadata) = &capture.metadata {
+        png = embed_png_metadata(png, metadata)?;
+      }
+    }
+
+    let target = match path {
+      Some(path) if !path.is_dir() => {
+        // An explicit file path (e.g. from a save dialog) is used as-is
+        if path.extension().is_some() {
+          path
+        } else {
+          path.with_extension("png")
+        }
+      }
+      path => {
+        let directory = path.unwrap_or_else(|| self.save_directory());
+        let file_stem = render_template(&self.settings.filename_template, capture);
+        unique_path(&directory, &file_stem, "png")?
+      }
+    };
+
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&target, &png).map_err(|e| format!("Failed to save capture: {}", e))?;
+
+    info!(
+      "💾 Capture {} saved to {:?} ({}KB)",
+      capture.id,
+      target,
+      png.len() / 1024
+    );
+    Ok(target)
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.settings)
+      .map_err(|e| format!("Failed to serialize save settings: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save settings: {}", e))
+  }
+}
+
+impl Default for SaveManager {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Expand {date}, {time}, {app} and {monitor} and make the result filename-safe
+pub fn render_template(template: &str, capture: &StoredCapture) -> String {
+  let created_at = chrono::DateTime::parse_from_rfc3339(&capture.created_at)
+    .map(|time| time.with_timezone(&chrono::Local))
+    .unwrap_or_else(|_| chrono::Local::now());
+  let app = capture
+    .source_app
+    .clone()
+    .unwrap_or_else(|| "Unknown".to_string());
+  let monitor = capture
+    .bounds
+    .as_ref()
+    .and_then(monitor_name)
+    .unwrap_or_else(|| "Display".to_string());
+
+  let rendered = template
+    .replace("{date}", &created_at.format("%Y-%m-%d").to_string())
+    .replace("{time}", &created_at.format("%H.%M.%S").to_string())
+    .replace("{app}", &app)
+    .replace("{monitor}", &monitor);
+  let sanitized = sanitize_filename(&rendered);
+  if sanitized.is_empty() {
+    "FrameSense capture".to_string()
+  } else {
+    sanitized
+  }
+}
+
+// Name of the monitor a selection is on, e.g. "Display 2"
+fn monitor_name(bounds: &CaptureBounds) -> Option<String> {
+  let display_id = CoordinateMapper::current()
+    .ok()?
+    .to_physical(bounds)
+    .display_id;
+  ScreenCapture::get_screen_info()
+    .ok()?
+    .into_iter()
+    .find(|screen| screen.id == display_id)
+    .map(|screen| screen.name)
+}
+
+fn sanitize_filename(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| match c {
+      '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+      c if c.is_control() => '-',
+      c => c,
+    })
+    .collect::<String>()
+    .trim()
+    .trim_end_matches('.')
+    .to_string()
+}
+
+/// Insert the capture metadata as UTF-8 text chunks right after the PNG header
+pub fn embed_png_metadata(png: Vec<u8>, metadata: &CaptureMetadata) -> Result<Vec<u8>, String> {
+  const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+  // Signature + IHDR (length, type, 13 bytes of data, CRC)
+  const HEADER_END: usize = 8 + 4 + 4 + 13 + 4;
+  if png.len() < HEADER_END || png[..8] != SIGNATURE || &png[12..16] != b"IHDR" {
+    return Err("Capture is not a PNG image".to_string());
+  }
+
+  let json = serde_json::to_string(metadata)
+    .map_err(|e| format!("Failed to serialize capture metadata: {}", e))?;
+  let entries = [
+    ("Software", "FrameSense".to_string()),
+    ("Creation Time", metadata.captured_at.clone()),
+    ("FrameSense", json),
+  ];
+
+  let mut output = Vec::with_capacity(png.len() + 512);
+  output.extend_from_slice(&png[..HEADER_END]);
+  for (keyword, text) in entries {
+    // iTXt: keyword, NUL, uncompressed, no method, empty language and translated keyword
+    let mut data = Vec::with_capacity(keyword.len() + text.len() + 5);
+    data.extend_from_slice(keyword.as_bytes());
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+    data.extend_from_slice(text.as_bytes());
+
+    let mut chunk = Vec::with_capacity(data.len() + 4);
+    chunk.extend_from_slice(b"iTXt");
+    chunk.extend_from_slice(&data);
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(&chunk);
+    output.extend_from_slice(&crc32fast::hash(&chunk).to_be_bytes());
+  }
+  output.extend_from_slice(&png[HEADER_END..]);
+  Ok(output)
+}
+
+// "name.png", then "name (2).png", "name (3).png"...
+fn unique_path(directory: &Path, file_stem: &str, extension: &str) -> Result<PathBuf, String> {
+  let candidate = directory.join(format!("{}.{}", file_stem, extension));
+  if !candidate.exists() {
+    return Ok(candidate);
+  }
+  (2..=MAX_COLLISION_SUFFIX)
+    .map(|n| directory.join(format!("{} ({}).{}", file_stem, n, extension)))
+    .find(|candidate| !candidate.exists())
+    .ok_or_else(|| format!("Too many files named '{}' in {:?}", file_stem, directory))
+}
+
+/// Name of the application in front, used for {app} in filename templates
+#[cfg(target_os = "macos")]
+pub fn frontmost_app_name() -> Option<String> {
+  use cocoa::base::{id, nil};
+  use objc::{class, msg_send, sel, sel_impl};
+  use std::ffi::CStr;
+
+  unsafe {
+    let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let application: id = msg_send![workspace, frontmostApplication];
+    if application == nil {
+      return None;
+    }
+    let name: id = msg_send![application, localizedName];
+    if name == nil {
+      return None;
+    }
+    let utf8: *const std::os::raw::c_char = msg_send![name, UTF8String];
+    if utf8.is_null() {
+      return None;
+    }
+    Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+  }
+}
+
+/// Name of the application in front, used for {app} in filename templates
+#[cfg(target_os = "windows")]
+pub fn frontmost_app_name() -> Option<String> {
+  use std::ffi::c_void;
+
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetForegroundWindow() -> *mut c_void;
+    fn GetWindowThreadProcessId(hwnd: *mut c_void, process_id: *mut u32) -> u32;
+  }
+
+  unsafe {
+    let window = GetForegroundWindow();
+    if window.is_null() {
+      return None;
+    }
+    let mut process_id = 0u32;
+    GetWindowThreadProcessId(window, &mut process_id);
+    process_name(process_id)
+  }
+}
+
+// Executable name (without extension) of a process
+#[cfg(target_os = "windows")]
+pub(crate) fn process_name(process_id: u32) -> Option<String> {
+  use std::ffi::c_void;
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, process_id: u32) -> *mut c_void;
+    fn QueryFullProcessImageNameW(
+      process: *mut c_void,
+      flags: u32,
+      name: *mut u16,
+      size: *mut u32,
+    ) -> i32;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+  }
+  const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+  unsafe {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+    if process.is_null() {
+      return None;
+    }
+    let mut buffer = [0u16; 1024];
+    let mut size = buffer.len() as u32;
+    let ok = QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size);
+    CloseHandle(process);
+    if ok == 0 {
+      return None;
+    }
+    let path = String::from_utf16_lossy(&buffer[..size as usize]);
+    Path::new(&path)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().into_owned())
+  }
+}
+
+/// Name of the application in front, used for {app} in filename templates
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn frontmost_app_name() -> Option<String> {
+  // X11 only - xdotool reports the pid of the active window
+  let output = std::process::Command::new("xdotool")
+    .args(["getactivewindow", "getwindowpid"])
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  let pid = String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .parse()
+    .ok()?;
+  process_name(pid)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn process_name(pid: u32) -> Option<String> {
+  fs::read_to_string(format!("/proc/{}/comm", pid))
+    .ok()
+    .map(|name| name.trim().to_string())
+    .filter(|name| !name.is_empty())
+}
+
+/// Name of the application whose window is under the mouse cursor, ignoring our own
+/// windows (the selection overlay is usually still on top when this runs)
+#[cfg(target_os = "macos")]
+pub fn app_under_cursor() -> Option<String> {
+  use cocoa::base::{id, nil};
+  use cocoa::foundation::NSString;
+  use objc::{msg_send, sel, sel_impl};
+  use std::ffi::{c_void, CStr};
+
+  #[repr(C)]
+  #[derive(Default)]
+  struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+  }
+
+  #[link(name = "CoreGraphics", kind = "framework")]
+  extern "C" {
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> id;
+    fn CGRectMakeWithDictionaryRepresentation(dict: id, rect: *mut CGRect) -> bool;
+    fn CFRelease(cf: *const c_void);
+  }
+  const ON_SCREEN_ONLY: u32 = 1 << 0;
+  const EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+
+  let (cursor_x, cursor_y) = crate::overlay::color_picker::cursor_position()?;
+  let (cursor_x, cursor_y) = (cursor_x as f64, cursor_y as f64);
+  let own_pid = std::process::id() as i64;
+
+  unsafe {
+    // Front-to-back list of on-screen windows
+    let windows = CGWindowListCopyWindowInfo(ON_SCREEN_ONLY | EXCLUDE_DESKTOP_ELEMENTS, 0);
+    if windows == nil {
+      return None;
+    }
+    let pid_key = NSString::alloc(nil).init_str("kCGWindowOwnerPID");
+    let layer_key = NSString::alloc(nil).init_str("kCGWindowLayer");
+    let bounds_key = NSString::alloc(nil).init_str("kCGWindowBounds");
+    let name_key = NSString::alloc(nil).init_str("kCGWindowOwnerName");
+
+    let mut result = None;
+    let count: usize = msg_send![windows, count];
+    for index in 0..count {
+      let window: id = msg_send![windows, objectAtIndex: index];
+      let pid: id = msg_send![window, objectForKey: pid_key];
+      let layer: id = msg_send![window, objectForKey: layer_key];
+      let bounds: id = msg_send![window, objectForKey: bounds_key];
+      if pid == nil || layer == nil || bounds == nil {
+        continue;
+      }
+      let pid: i64 = msg_send![pid, longLongValue];
+      let layer: i64 = msg_send![layer, longLongValue];
+      // Layer 0 = normal app windows (skips menu bar, dock, overlays)
+      if pid == own_pid || layer != 0 {
+        continue;
+      }
+
+      let mut rect = CGRect::default();
+      if !CGRectMakeWithDictionaryRepresentation(bounds, &mut rect) {
+        continue;
+      }
+      let inside = cursor_x >= rect.x
+        && cursor_x < rect.x + rect.width
+        && cursor_y >= rect.y
+        && cursor_y < rect.y + rect.height;
+      if inside {
+        let name: id = msg_send![window, objectForKey: name_key];
+        if name != nil {
+          let utf8: *const std::os::raw::c_char = msg_send![name, UTF8String];
+          if !utf8.is_null() {
+            result = Some(CStr::from_ptr(utf8).to_string_lossy().into_owned());
+          }
+        }
+        break;
+      }
+    }
+
+    for key in [pid_key, layer_key, bounds_key, name_key] {
+      let _: () = msg_send![key, release];
+    }
+    CFRelease(windows as *const c_void);
+    result
+  }
+}
+
+/// Name of the application whose window is under the mouse cursor, ignoring our own
+/// windows (the selection overlay is usually still on top when this runs)
+#[cfg(target_os = "windows")]
+pub fn app_under_cursor() -> Option<String> {
+  use std::ffi::c_void;
+
+  #[repr(C)]
+  struct Rect {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+  }
+
+  #[link(name = "user32")]
+  extern "system" {
+    fn GetTopWindow(hwnd: *mut c_void) -> *mut c_void;
+    fn GetWindow(hwnd: *mut c_void, cmd: u32) -> *mut c_void;
+    fn IsWindowVisible(hwnd: *mut c_void) -> i32;
+    fn GetWindowRect(hwnd: *mut c_void, rect: *mut Rect) -> i32;
+    fn GetWindowThreadProcessId(hwnd: *mut c_void, process_id: *mut u32) -> u32;
+  }
+  const GW_HWNDNEXT: u32 = 2;
+
+  let (x, y) = crate::overlay::color_picker::cursor_position()?;
+  let own_pid = std::process::id();
+
+  unsafe {
+    // Walk top-level windows in z-order, topmost first
+    let mut window = GetTopWindow(std::ptr::null_mut());
+    while !window.is_null() {
+      let mut rect = Rect {
+        left: 0,
+        top: 0,
+        right: 0,
+        bottom: 0,
+      };
+      if IsWindowVisible(window) != 0
+        && GetWindowRect(window, &mut rect) != 0
+        && x >= rect.left
+        && x < rect.right
+        && y >= rect.top
+        && y < rect.bottom
+      {
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(window, &mut process_id);
+        if process_id != own_pid {
+          return process_name(process_id);
+        }
+      }
+      window = GetWindow(window, GW_HWNDNEXT);
+    }
+  }
+  None
+}
+
+/// Name of the application whose window is under the mouse cursor, ignoring our own
+/// windows (the selection overlay is usually still on top when this runs)
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn app_under_cursor() -> Option<String> {
+  // X11 only - "X=1 Y=2 SCREEN=0 WINDOW=1234" with --shell, one per line
+  let output = std::process::Command::new("xdotool")
+    .args(["getmouselocation", "--shell"])
+    .output()
+    .ok()?;
+  let text = String::from_utf8_lossy(&output.stdout).to_string();
+  let window = text.lines().find_map(|line| line.strip_prefix("WINDOW="))?;
+
+  let output = std::process::Command::new("xdotool")
+    .args(["getwindowpid", window.trim()])
+    .output()
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  let pid: u32 = String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .parse()
+    .ok()?;
+  if pid == std::process::id() {
+    return None;
+  }
+  process_name(pid)
+}
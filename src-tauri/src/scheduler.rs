@@ -0,0 +1,248 @@
+// Background scheduler - owns the app's recurring work (session refresh, tier checks,
+// overlay cleanup, ...) as named periodic tasks. Each task runs on the async runtime;
+// settings can turn tasks off or change their interval, and `list` reports what ran when.
+// A task never runs twice at once, and rescheduling it lets a run in progress finish.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+// Shortest interval settings may ask for
+pub const MIN_INTERVAL_SECS: u64 = 5;
+
+pub type TaskFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+type TaskJob = Arc<dyn Fn(tauri::AppHandle) -> TaskFuture + Send + Sync>;
+// Held for the duration of a run, shared by the schedule loop and `run_now`
+type RunLock = Arc<tokio::sync::Mutex<()>>;
+
+/// Per-task overrides saved in settings (`backgroundTasks.<name>`)
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskSettings {
+  pub enabled: Option<bool>,
+  pub interval_secs: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+  pub name: String,
+  pub description: String,
+  pub enabled: bool,
+  pub interval_secs: u64,
+  pub default_interval_secs: u64,
+  pub running: bool,
+  pub run_count: u64,
+  pub last_run_at: Option<String>,
+  pub last_duration_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct TaskStats {
+  running: bool,
+  run_count: u64,
+  last_run_at: Option<String>,
+  last_duration_ms: Option<u64>,
+}
+
+struct Task {
+  description: String,
+  default_interval: Duration,
+  interval: Duration,
+  enabled: bool,
+  // Run once right away instead of waiting a full interval first
+  run_at_start: bool,
+  job: TaskJob,
+  stats: Arc<Mutex<TaskStats>>,
+  run_lock: RunLock,
+  // Dropping it ends the schedule loop once any current run is done
+  stop: Option<watch::Sender<()>>,
+}
+
+pub struct Scheduler {
+  tasks: BTreeMap<String, Task>,
+  app: Option<tauri::AppHandle>,
+}
+
+impl Scheduler {
+  pub fn new() -> Self {
+    Self {
+      tasks: BTreeMap::new(),
+      app: None,
+    }
+  }
+
+  /// Add a task; it starts with the scheduler (or immediately if that already happened)
+  pub fn register<F, Fut>(
+    &mut self,
+    name: &str,
+    description: &str,
+    interval: Duration,
+    run_at_start: bool,
+    job: F,
+  ) where
+    F: Fn(tauri::AppHandle) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    let job: TaskJob = Arc::new(move |app| Box::pin(job(app)) as TaskFuture);
+    self.tasks.insert(
+      name.to_string(),
+      Task {
+        description: description.to_string(),
+        default_interval: interval,
+        interval,
+        enabled: true,
+        run_at_start,
+        job,
+        stats: Arc::new(Mutex::new(TaskStats::default())),
+        run_lock: RunLock::default(),
+        stop: None,
+      },
+    );
+    self.restart(name);
+  }
+
+  /// Spawn every enabled task
+  pub fn start(&mut self, app: tauri::AppHandle) {
+    self.app = Some(app);
+    let names: Vec<String> = self.tasks.keys().cloned().collect();
+    for name in names {
+      self.restart(&name);
+    }
+    info!("⏱️ Scheduler started with {} task(s)", self.tasks.len());
+  }
+
+  /// Apply saved overrides; tasks without one go back to their defaults
+  pub fn configure(&mut self, overrides: &BTreeMap<String, TaskSettings>) {
+    for name in overrides
+      .keys()
+      .filter(|name| !self.tasks.contains_key(*name))
+    {
+      warn!("⚠️ Settings mention unknown background task '{}'", name);
+    }
+    let names: Vec<String> = self.tasks.keys().cloned().collect();
+    for name in names {
+      let task = self.tasks.get_mut(&name).unwrap();
+      let task_settings = overrides.get(&name).cloned().unwrap_or_default();
+      let enabled = task_settings.enabled.unwrap_or(true);
+      let interval = task_settings
+        .interval_secs
+        .map(Duration::from_secs)
+        .unwrap_or(task.default_interval);
+      if task.enabled != enabled || task.interval != interval {
+        task.enabled = enabled;
+        task.interval = interval;
+        info!(
+          "⏱️ Task '{}' {} every {}s",
+          name,
+          if enabled { "runs" } else { "paused, would run" },
+          interval.as_secs()
+        );
+        self.restart(&name);
+      }
+    }
+  }
+
+  pub fn list(&self) -> Vec<TaskInfo> {
+    self
+      .tasks
+      .iter()
+      .map(|(name, task)| {
+        let stats = task.stats.lock().unwrap();
+        TaskInfo {
+          name: name.clone(),
+          description: task.description.clone(),
+          enabled: task.enabled,
+          interval_secs: task.interval.as_secs(),
+          default_interval_secs: task.default_interval.as_secs(),
+          running: stats.running,
+          run_count: stats.run_count,
+          last_run_at: stats.last_run_at.clone(),
+          last_duration_ms: stats.last_duration_ms,
+        }
+      })
+      .collect()
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.tasks.contains_key(name)
+  }
+
+  /// Run a task once now, outside its schedule
+  pub fn run_now(&self, name: &str) -> Result<(), String> {
+    let task = self
+      .tasks
+      .get(name)
+      .ok_or_else(|| format!("Unknown background task '{}'", name))?;
+    let app = self
+      .app
+      .clone()
+      .ok_or_else(|| "Scheduler is not running yet".to_string())?;
+    let (job, stats, run_lock) = (task.job.clone(), task.stats.clone(), task.run_lock.clone());
+    tauri::async_runtime::spawn(run_once(job, stats, run_lock, app));
+    Ok(())
+  }
+
+  // Stop the task's loop and, if it is enabled and the scheduler runs, start a new one
+  fn restart(&mut self, name: &str) {
+    let Some(task) = self.tasks.get_mut(name) else {
+      return;
+    };
+    task.stop = None;
+    let Some(app) = self.app.clone() else {
+      return;
+    };
+    if !task.enabled {
+      return;
+    }
+    let (job, stats, run_lock) = (task.job.clone(), task.stats.clone(), task.run_lock.clone());
+    let (interval, mut skip_wait) = (task.interval, task.run_at_start);
+    let (stop, mut stopped) = watch::channel(());
+    task.stop = Some(stop);
+    let name = name.to_string();
+    tauri::async_runtime::spawn(async move {
+      debug!("⏱️ Task '{}' scheduled every {}s", name, interval.as_secs());
+      loop {
+        if !skip_wait {
+          tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = stopped.changed() => break,
+          }
+        }
+        skip_wait = false;
+        if stopped.has_changed().is_err() {
+          break;
+        }
+        run_once(job.clone(), stats.clone(), run_lock.clone(), app.clone()).await;
+      }
+      debug!("⏱️ Task '{}' unscheduled", name);
+    });
+  }
+}
+
+async fn run_once(
+  job: TaskJob,
+  stats: Arc<Mutex<TaskStats>>,
+  run_lock: RunLock,
+  app: tauri::AppHandle,
+) {
+  let _running = run_lock.lock().await;
+  let started = Instant::now();
+  stats.lock().unwrap().running = true;
+  job(app).await;
+  let mut stats = stats.lock().unwrap();
+  stats.running = false;
+  stats.run_count += 1;
+  stats.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+  stats.last_duration_ms = Some(started.elapsed().as_millis() as u64);
+}
+
+impl Default for Scheduler {
+  fn default() -> Self {
+    Self::new()
+  }
+}
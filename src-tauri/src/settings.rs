@@ -0,0 +1,335 @@
+// App settings - one typed, versioned settings.json for values that used to be hard-coded
+// (backend URL, global shortcuts, screenshot cache limits, overlay pooling, log levels,
+// background task schedules). Changes are broadcast as `settings-changed`; the backend
+// subsystems and the frontend both react to it.
+use crate::channels::ChannelSettings;
+use crate::logging::LogLevels;
+use crate::notes::NotesSettings;
+use crate::overlay::screen_capture::PngCompression;
+use crate::scheduler::{TaskSettings, MIN_INTERVAL_SECS};
+use crate::share::ShareSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri_plugin_global_shortcut::Shortcut;
+use tracing::warn;
+
+const SETTINGS_FILE: &str = "settings.json";
+pub const SETTINGS_VERSION: u32 = 1;
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+pub const DEFAULT_API_URL: &str = "https://api.finalyze.pro";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShortcutSettings {
+  pub toggle_window: String,
+  pub capture: String,
+  pub repeat_capture: Option<String>, // off unless the user picks one
+}
+
+impl Default for ShortcutSettings {
+  fn default() -> Self {
+    Self {
+      toggle_window: "Alt+Space".to_string(),
+      capture: "Alt+C".to_string(),
+      repeat_capture: None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShortcutAction {
+  ToggleWindow,
+  Capture,
+  RepeatCapture,
+}
+
+impl ShortcutSettings {
+  /// Every enabled binding with its accelerator string
+  pub fn bindings(&self) -> Vec<(ShortcutAction, &str)> {
+    let mut bindings = vec![
+      (ShortcutAction::ToggleWindow, self.toggle_window.as_str()),
+      (ShortcutAction::Capture, self.capture.as_str()),
+    ];
+    if let Some(repeat_capture) = &self.repeat_capture {
+      bindings.push((ShortcutAction::RepeatCapture, repeat_capture.as_str()));
+    }
+    bindings
+  }
+
+  /// Bindings that parse; validation keeps invalid ones out of saved settings
+  pub fn parsed(&self) -> Vec<(ShortcutAction, Shortcut)> {
+    self
+      .bindings()
+      .into_iter()
+      .filter_map(|(action, accelerator)| Some((action, accelerator.parse().ok()?)))
+      .collect()
+  }
+
+  pub fn action_for(&self, shortcut: &Shortcut) -> Option<ShortcutAction> {
+    self
+      .parsed()
+      .into_iter()
+      .find(|(_, bound)| bound == shortcut)
+      .map(|(action, _)| action)
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotCacheSettings {
+  pub ttl_secs: u64,
+  pub max_size_mb: u64,
+}
+
+impl Default for ScreenshotCacheSettings {
+  fn default() -> Self {
+    Self {
+      ttl_secs: 30,
+      max_size_mb: 50,
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureSettings {
+  // Fast keeps big regions snappy; Best gives the smallest files
+  pub png_compression: PngCompression,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlaySettings {
+  // Pooled overlay windows are freed after this long unused (None = keep them)
+  pub idle_timeout_secs: Option<u64>,
+}
+
+impl Default for OverlaySettings {
+  fn default() -> Self {
+    Self {
+      idle_timeout_secs: Some(300),
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+  // Notify when a capture or answer is ready while the main window is in the background
+  pub on_completion: bool,
+}
+
+impl Default for NotificationSettings {
+  fn default() -> Self {
+    Self {
+      on_completion: true,
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionSettings {
+  // Captures, OCR text, conversations and logs older than this are purged daily (None = keep)
+  pub days: Option<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportSettings {
+  // User consent to send crash reports to the backend; off until they opt in
+  pub upload: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+  pub version: u32,
+  pub api_url: String,
+  pub shortcuts: ShortcutSettings,
+  pub screenshot_cache: ScreenshotCacheSettings,
+  pub capture: CaptureSettings,
+  pub overlay: OverlaySettings,
+  pub logging: LogLevels,
+  pub crash_reports: CrashReportSettings,
+  pub retention: RetentionSettings,
+  pub notifications: NotificationSettings,
+  pub share: ShareSettings,
+  pub notes: NotesSettings,
+  pub channels: ChannelSettings,
+  // Overrides by task name; tasks not listed keep their defaults
+  pub background_tasks: BTreeMap<String, TaskSettings>,
+}
+
+impl Default for AppSettings {
+  fn default() -> Self {
+    Self {
+      version: SETTINGS_VERSION,
+      api_url: DEFAULT_API_URL.to_string(),
+      shortcuts: ShortcutSettings::default(),
+      screenshot_cache: ScreenshotCacheSettings::default(),
+      capture: CaptureSettings::default(),
+      overlay: OverlaySettings::default(),
+      logging: LogLevels::default(),
+      crash_reports: CrashReportSettings::default(),
+      retention: RetentionSettings::default(),
+      notifications: NotificationSettings::default(),
+      share: ShareSettings::default(),
+      notes: NotesSettings::default(),
+      channels: ChannelSettings::default(),
+      background_tasks: BTreeMap::new(),
+    }
+  }
+}
+
+impl AppSettings {
+  pub fn validate(&self) -> Result<(), String> {
+    let api_url = url::Url::parse(&self.api_url).map_err(|e| format!("Invalid API URL: {}", e))?;
+    if !matches!(api_url.scheme(), "http" | "https") {
+      return Err("API URL must use http or https".to_string());
+    }
+
+    let mut seen: Vec<Shortcut> = Vec::new();
+    for (_, accelerator) in self.shortcuts.bindings() {
+      let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+      if seen.contains(&shortcut) {
+        return Err(format!("Shortcut '{}' is used more than once", accelerator));
+      }
+      seen.push(shortcut);
+    }
+
+    if self.screenshot_cache.ttl_secs == 0 {
+      return Err("Cache TTL must be at least 1 second".to_string());
+    }
+    if self.screenshot_cache.max_size_mb == 0 {
+      return Err("Cache size must be at least 1MB".to_string());
+    }
+    self.logging.validate()?;
+    self.share.validate()?;
+    self.notes.validate()?;
+    self.channels.validate()?;
+    if self.retention.days == Some(0) {
+      return Err("Retention must be at least 1 day".to_string());
+    }
+    for (name, task) in &self.background_tasks {
+      if task
+        .interval_secs
+        .is_some_and(|secs| secs < MIN_INTERVAL_SECS)
+      {
+        return Err(format!(
+          "Interval for '{}' must be at least {} seconds",
+          name, MIN_INTERVAL_SECS
+        ));
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Payload of `settings-changed`
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+  pub previous: AppSettings,
+  pub settings: AppSettings,
+}
+
+pub struct SettingsStore {
+  settings: AppSettings,
+  storage_path: Option<PathBuf>,
+}
+
+impl SettingsStore {
+  pub fn new() -> Self {
+    Self {
+      settings: AppSettings::default(),
+      storage_path: None,
+    }
+  }
+
+  // Load saved settings from `dir` and persist future changes there. Files from older
+  // versions are upgraded (missing fields take their defaults) and rewritten.
+  pub fn with_storage_dir(mut self, dir: PathBuf) -> Self {
+    let path = dir.join(SETTINGS_FILE);
+    self.storage_path = Some(path.clone());
+    let json = match fs::read_to_string(&path) {
+      Ok(json) => json,
+      Err(_) => return self,
+    };
+    let settings: AppSettings = match serde_json::from_str(&json) {
+      Ok(settings) => settings,
+      Err(e) => {
+        warn!("⚠️ Ignoring unreadable settings: {}", e);
+        return self;
+      }
+    };
+    if let Err(e) = settings.validate() {
+      warn!("⚠️ Ignoring invalid settings: {}", e);
+      return self;
+    }
+    if settings.version > SETTINGS_VERSION {
+      warn!(
+        "⚠️ Settings were saved by a newer version ({}), unknown fields are ignored",
+        settings.version
+      );
+    }
+    let outdated = settings.version < SETTINGS_VERSION;
+    self.settings = AppSettings {
+      version: SETTINGS_VERSION,
+      ..settings
+    };
+    if outdated {
+      if let Err(e) = self.persist() {
+        warn!("⚠️ Failed to upgrade settings file: {}", e);
+      }
+    }
+    self
+  }
+
+  pub fn settings(&self) -> &AppSettings {
+    &self.settings
+  }
+
+  /// Validate and save `settings`, returning the change to broadcast
+  pub fn update(&mut self, settings: AppSettings) -> Result<SettingsChanged, String> {
+    let settings = AppSettings {
+      version: SETTINGS_VERSION,
+      ..settings
+    };
+    settings.validate()?;
+    let previous = std::mem::replace(&mut self.settings, settings);
+    if let Err(e) = self.persist() {
+      self.settings = previous;
+      return Err(e);
+    }
+    Ok(SettingsChanged {
+      previous,
+      settings: self.settings.clone(),
+    })
+  }
+
+  fn persist(&self) -> Result<(), String> {
+    let path = match &self.storage_path {
+      Some(path) => path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&self.settings)
+      .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to save settings: {}", e))
+  }
+}
+
+impl Default for SettingsStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
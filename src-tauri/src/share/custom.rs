@@ -0,0 +1,48 @@
+// The user's own upload endpoint: the PNG is POSTed as the request body (with the
+// optional token as a bearer header) and the answer is `{"url": "..."}` or the bare URL
+use super::{upload_error, DestinationKind, ShareDestination, UPLOAD_TIMEOUT};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+
+pub struct CustomDestination {
+  url: String,
+  token: Option<String>,
+  network: NetworkSettings,
+}
+
+impl CustomDestination {
+  pub fn new(url: String, token: Option<String>, network: NetworkSettings) -> Self {
+    Self {
+      url,
+      token,
+      network,
+    }
+  }
+}
+
+impl ShareDestination for CustomDestination {
+  async fn upload(&self, png: Vec<u8>, name: &str) -> Result<String, String> {
+    let mut request = network::http_client(&self.network)?
+      .post(&self.url)
+      .header("Content-Type", "image/png")
+      .header("X-Filename", name)
+      .body(png)
+      .timeout(UPLOAD_TIMEOUT);
+    if let Some(token) = &self.token {
+      request = request.bearer_auth(token);
+    }
+    let response = request.send_with_retry().await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+      return Err(upload_error(DestinationKind::Custom, status, &body));
+    }
+
+    let url = match serde_json::from_str::<serde_json::Value>(&body) {
+      Ok(json) => json["url"].as_str().map(str::to_string),
+      Err(_) => Some(body.trim().to_string()),
+    };
+    url
+      .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+      .ok_or_else(|| "The share endpoint did not answer with a URL".to_string())
+  }
+}
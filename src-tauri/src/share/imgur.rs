@@ -0,0 +1,56 @@
+// Anonymous Imgur uploads (https://apidocs.imgur.com/#c85c9dfc-7487-4de2-9ecd-66f727cf3139)
+use super::{upload_error, DestinationKind, ShareDestination, UPLOAD_TIMEOUT};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use base64::Engine;
+use serde::Deserialize;
+
+const ENDPOINT: &str = "https://api.imgur.com/3/image";
+
+#[derive(Deserialize)]
+struct UploadResponse {
+  data: UploadData,
+}
+
+#[derive(Deserialize)]
+struct UploadData {
+  link: String,
+}
+
+pub struct ImgurDestination {
+  client_id: String,
+  network: NetworkSettings,
+}
+
+impl ImgurDestination {
+  pub fn new(client_id: String, network: NetworkSettings) -> Self {
+    Self { client_id, network }
+  }
+}
+
+impl ShareDestination for ImgurDestination {
+  async fn upload(&self, png: Vec<u8>, name: &str) -> Result<String, String> {
+    let image = base64::engine::general_purpose::STANDARD.encode(&png);
+    let response = network::http_client(&self.network)?
+      .post(ENDPOINT)
+      .header(
+        "Authorization",
+        format!("Client-ID {}", self.client_id.trim()),
+      )
+      .form(&[
+        ("image", image.as_str()),
+        ("type", "base64"),
+        ("name", name),
+      ])
+      .timeout(UPLOAD_TIMEOUT)
+      .send_with_retry()
+      .await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+      return Err(upload_error(DestinationKind::Imgur, status, &body));
+    }
+    let parsed: UploadResponse =
+      serde_json::from_str(&body).map_err(|e| format!("Unexpected Imgur response: {}", e))?;
+    Ok(parsed.data.link)
+  }
+}
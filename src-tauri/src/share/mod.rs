@@ -0,0 +1,225 @@
+// Sharing - uploads a capture to a destination the user set up (Imgur, an S3-compatible
+// bucket, or their own HTTP endpoint) and returns a link to it. Destination settings live
+// in `AppSettings.share`; secrets (S3 secret key, endpoint token) are stored by name in
+// the API key store, never in settings.json.
+mod custom;
+mod imgur;
+mod s3;
+
+use crate::network::NetworkSettings;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Large captures on slow uplinks
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+// Characters of the random object name; unguessable enough for unlisted links
+const SHORT_ID_CHARS: usize = 10;
+
+/// A place captures can be uploaded to
+pub trait ShareDestination {
+  /// Upload a PNG under `name` and return the public URL
+  async fn upload(&self, png: Vec<u8>, name: &str) -> Result<String, String>;
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DestinationKind {
+  Imgur,
+  S3,
+  Custom,
+}
+
+impl DestinationKind {
+  pub const ALL: [DestinationKind; 3] = [
+    DestinationKind::Imgur,
+    DestinationKind::S3,
+    DestinationKind::Custom,
+  ];
+
+  pub fn display_name(&self) -> &'static str {
+    match self {
+      DestinationKind::Imgur => "Imgur",
+      DestinationKind::S3 => "S3-compatible bucket",
+      DestinationKind::Custom => "Custom endpoint",
+    }
+  }
+
+  // Name of the destination's secret in the API key store
+  pub fn secret_name(&self) -> String {
+    match self {
+      DestinationKind::Imgur => "share-imgur".to_string(),
+      DestinationKind::S3 => "share-s3".to_string(),
+      DestinationKind::Custom => "share-custom".to_string(),
+    }
+  }
+
+  // Imgur's client id is public; the custom endpoint's token is optional
+  pub fn needs_secret(&self) -> bool {
+    *self == DestinationKind::S3
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImgurSettings {
+  // Register an application at https://api.imgur.com/oauth2/addclient
+  pub client_id: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct S3Settings {
+  // e.g. https://s3.eu-north-1.amazonaws.com or https://<account>.r2.cloudflarestorage.com
+  pub endpoint: String,
+  pub region: String,
+  pub bucket: String,
+  pub access_key_id: String,
+  // Folder inside the bucket, e.g. "captures"
+  pub prefix: String,
+  // Where uploaded objects are publicly served (CDN / custom domain); defaults to
+  // <endpoint>/<bucket>
+  pub public_url: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CustomSettings {
+  // Receives the PNG as the POST body and answers with `{"url": ...}` or the bare URL
+  pub url: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShareSettings {
+  // Used when `share_capture` gets no destination
+  pub default_destination: Option<DestinationKind>,
+  pub imgur: ImgurSettings,
+  pub s3: S3Settings,
+  pub custom: CustomSettings,
+}
+
+impl ShareSettings {
+  pub fn validate(&self) -> Result<(), String> {
+    for (label, value) in [
+      ("S3 endpoint", &self.s3.endpoint),
+      ("Custom share URL", &self.custom.url),
+    ] {
+      if !value.is_empty() {
+        validate_url(label, value)?;
+      }
+    }
+    if let Some(public_url) = &self.s3.public_url {
+      validate_url("S3 public URL", public_url)?;
+    }
+    Ok(())
+  }
+
+  /// Everything but the secret is filled in for `kind`
+  pub fn is_configured(&self, kind: DestinationKind) -> bool {
+    match kind {
+      DestinationKind::Imgur => !self.imgur.client_id.trim().is_empty(),
+      DestinationKind::S3 => [
+        &self.s3.endpoint,
+        &self.s3.region,
+        &self.s3.bucket,
+        &self.s3.access_key_id,
+      ]
+      .iter()
+      .all(|value| !value.trim().is_empty()),
+      DestinationKind::Custom => !self.custom.url.trim().is_empty(),
+    }
+  }
+}
+
+fn validate_url(label: &str, value: &str) -> Result<(), String> {
+  let url = url::Url::parse(value).map_err(|e| format!("Invalid {}: {}", label, e))?;
+  if !matches!(url.scheme(), "http" | "https") {
+    return Err(format!("{} must use http or https", label));
+  }
+  Ok(())
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationInfo {
+  pub id: DestinationKind,
+  pub name: String,
+  pub needs_secret: bool,
+  pub has_secret: bool,
+  pub configured: bool,
+  pub is_default: bool,
+}
+
+/// Upload `png` to `kind`. `secret` comes from the API key store.
+pub async fn upload(
+  kind: DestinationKind,
+  settings: &ShareSettings,
+  secret: Option<String>,
+  network: NetworkSettings,
+  png: Vec<u8>,
+) -> Result<String, String> {
+  if !settings.is_configured(kind) {
+    return Err(format!("Set up {} in settings first", kind.display_name()));
+  }
+  let name = format!("{}.png", short_id()?);
+  match kind {
+    DestinationKind::Imgur => {
+      imgur::ImgurDestination::new(settings.imgur.client_id.clone(), network)
+        .upload(png, &name)
+        .await
+    }
+    DestinationKind::S3 => {
+      let secret =
+        secret.ok_or_else(|| format!("Add the {} secret key first", kind.display_name()))?;
+      s3::S3Destination::new(settings.s3.clone(), secret, network)
+        .upload(png, &name)
+        .await
+    }
+    DestinationKind::Custom => {
+      custom::CustomDestination::new(settings.custom.url.clone(), secret, network)
+        .upload(png, &name)
+        .await
+    }
+  }
+}
+
+// Lowercase letters and digits from the OS random source
+fn short_id() -> Result<String, String> {
+  const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+  let mut bytes = [0u8; SHORT_ID_CHARS];
+  SystemRandom::new()
+    .fill(&mut bytes)
+    .map_err(|_| "Failed to generate a share id".to_string())?;
+  Ok(
+    bytes
+      .iter()
+      .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+      .collect(),
+  )
+}
+
+// Message from an upload that answered with an error status
+fn upload_error(kind: DestinationKind, status: reqwest::StatusCode, body: &str) -> String {
+  match status.as_u16() {
+    401 | 403 => format!("{} rejected the credentials", kind.display_name()),
+    413 => format!("The capture is too large for {}", kind.display_name()),
+    429 => format!(
+      "{} rate limit reached, try again later",
+      kind.display_name()
+    ),
+    _ => {
+      let detail = body.trim();
+      if detail.is_empty() || detail.len() > 300 {
+        format!("{} upload failed: {}", kind.display_name(), status)
+      } else {
+        format!(
+          "{} upload failed: {} {}",
+          kind.display_name(),
+          status,
+          detail
+        )
+      }
+    }
+  }
+}
@@ -0,0 +1,168 @@
+// S3-compatible buckets (AWS S3, Cloudflare R2, MinIO, Backblaze B2...). Objects are
+// PUT with path-style addressing and an AWS Signature Version 4 header, signed with ring
+// so no AWS SDK is needed.
+use super::{upload_error, DestinationKind, S3Settings, ShareDestination, UPLOAD_TIMEOUT};
+use crate::network::{self, NetworkSettings, SendWithRetry};
+use ring::{digest, hmac};
+
+const SERVICE: &str = "s3";
+const SIGNED_HEADERS: &str = "content-type;host;x-amz-content-sha256;x-amz-date";
+
+pub struct S3Destination {
+  settings: S3Settings,
+  secret_access_key: String,
+  network: NetworkSettings,
+}
+
+impl S3Destination {
+  pub fn new(settings: S3Settings, secret_access_key: String, network: NetworkSettings) -> Self {
+    Self {
+      settings,
+      secret_access_key,
+      network,
+    }
+  }
+
+  fn object_key(&self, name: &str) -> String {
+    let prefix = self.settings.prefix.trim_matches('/');
+    if prefix.is_empty() {
+      name.to_string()
+    } else {
+      format!("{}/{}", prefix, name)
+    }
+  }
+
+  fn public_url(&self, key: &str) -> String {
+    match &self.settings.public_url {
+      Some(base) if !base.trim().is_empty() => {
+        format!("{}/{}", base.trim_end_matches('/'), key)
+      }
+      _ => format!(
+        "{}/{}/{}",
+        self.settings.endpoint.trim_end_matches('/'),
+        self.settings.bucket,
+        key
+      ),
+    }
+  }
+}
+
+impl ShareDestination for S3Destination {
+  async fn upload(&self, png: Vec<u8>, name: &str) -> Result<String, String> {
+    let endpoint = url::Url::parse(self.settings.endpoint.trim_end_matches('/'))
+      .map_err(|e| format!("Invalid S3 endpoint: {}", e))?;
+    let host = match (endpoint.host_str(), endpoint.port()) {
+      (Some(host), Some(port)) => format!("{}:{}", host, port),
+      (Some(host), None) => host.to_string(),
+      (None, _) => return Err("S3 endpoint has no host".to_string()),
+    };
+    let key = self.object_key(name);
+    let path = format!(
+      "{}/{}/{}",
+      endpoint.path().trim_end_matches('/'),
+      uri_encode(&self.settings.bucket),
+      key.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+    );
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let payload_hash = hex(digest::digest(&digest::SHA256, &png).as_ref());
+    let authorization = sign(SigningInput {
+      access_key_id: &self.settings.access_key_id,
+      secret_access_key: &self.secret_access_key,
+      region: &self.settings.region,
+      host: &host,
+      path: &path,
+      amz_date: &amz_date,
+      payload_hash: &payload_hash,
+    });
+
+    let response = network::http_client(&self.network)?
+      .put(format!("{}://{}{}", endpoint.scheme(), host, path))
+      .header("Content-Type", "image/png")
+      .header("x-amz-content-sha256", &payload_hash)
+      .header("x-amz-date", &amz_date)
+      .header("Authorization", authorization)
+      .body(png)
+      .timeout(UPLOAD_TIMEOUT)
+      .send_with_retry()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      return Err(upload_error(
+        DestinationKind::S3,
+        status,
+        &s3_message(&body),
+      ));
+    }
+    Ok(self.public_url(&key))
+  }
+}
+
+struct SigningInput<'a> {
+  access_key_id: &'a str,
+  secret_access_key: &'a str,
+  region: &'a str,
+  host: &'a str,
+  path: &'a str,
+  amz_date: &'a str,
+  payload_hash: &'a str,
+}
+
+// Authorization header value for a PUT of an image/png object
+fn sign(input: SigningInput) -> String {
+  let date = &input.amz_date[..8];
+  let canonical_request = format!(
+    "PUT\n{}\n\ncontent-type:image/png\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+    input.path, input.host, input.payload_hash, input.amz_date, SIGNED_HEADERS, input.payload_hash
+  );
+  let scope = format!("{}/{}/{}/aws4_request", date, input.region, SERVICE);
+  let string_to_sign = format!(
+    "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+    input.amz_date,
+    scope,
+    hex(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref())
+  );
+
+  let mut signing_key = format!("AWS4{}", input.secret_access_key).into_bytes();
+  for part in [date, input.region, SERVICE, "aws4_request"] {
+    signing_key = hmac_sha256(&signing_key, part.as_bytes());
+  }
+  let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+  format!(
+    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+    input.access_key_id, scope, SIGNED_HEADERS, signature
+  )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+  let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+  hmac::sign(&key, data).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// SigV4 encoding: everything but unreserved characters is percent-encoded
+fn uri_encode(segment: &str) -> String {
+  segment
+    .bytes()
+    .map(|b| match b {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+        (b as char).to_string()
+      }
+      _ => format!("%{:02X}", b),
+    })
+    .collect()
+}
+
+// S3 errors are XML: <Error><Code>..</Code><Message>..</Message></Error>
+fn s3_message(body: &str) -> String {
+  body
+    .split_once("<Message>")
+    .and_then(|(_, rest)| rest.split_once("</Message>"))
+    .map(|(message, _)| message.to_string())
+    .unwrap_or_default()
+}
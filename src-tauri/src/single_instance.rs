@@ -0,0 +1,241 @@
+// Single instance - the first launch listens on a localhost socket and records its port
+// (plus a random token) in the app data dir. Later launches hand their command-line
+// arguments to it and exit, so shortcuts and the tray are only ever registered once.
+// The lock file is created exclusively (O_EXCL), so two simultaneous launches can't both
+// become primary; one left behind by a crashed instance is detected and replaced.
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+const LOCK_FILE: &str = "instance.lock";
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_MESSAGE_BYTES: u64 = 64 * 1024;
+// A running instance that doesn't answer is given this many chances (CONNECT_TIMEOUT apart)
+const CLAIM_ATTEMPTS: u32 = 5;
+// An empty or unreadable lock this old isn't one being written by a starting instance
+const UNREADABLE_LOCK_STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// What a second launch forwards to the running instance
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchRequest {
+  pub args: Vec<String>,
+  pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Message {
+  token: String,
+  request: LaunchRequest,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LockInfo {
+  port: u16,
+  token: String,
+  pid: u32,
+}
+
+pub enum Instance {
+  // This is the only instance; keep the listener and pass it to `listen`
+  Primary(PrimaryInstance),
+  // Another instance was running and has received our arguments
+  Forwarded,
+}
+
+pub struct PrimaryInstance {
+  listener: TcpListener,
+  token: String,
+}
+
+fn lock_path(dir: &Path) -> PathBuf {
+  dir.join(LOCK_FILE)
+}
+
+/// Forward `request` to a running instance, or become the primary one
+pub fn acquire(dir: &Path, request: &LaunchRequest) -> Result<Instance, String> {
+  // Listen before claiming the lock, so the port in it accepts connections right away
+  let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+    .map_err(|e| format!("Failed to open instance socket: {}", e))?;
+  let port = listener
+    .local_addr()
+    .map_err(|e| format!("Failed to read instance socket address: {}", e))?
+    .port();
+  let token = random_token()?;
+  let lock = LockInfo {
+    port,
+    token: token.clone(),
+    pid: std::process::id(),
+  };
+  let json = serde_json::to_string(&lock).map_err(|e| format!("Failed to write lock: {}", e))?;
+  fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+  let path = lock_path(dir);
+
+  for _ in 0..CLAIM_ATTEMPTS {
+    match fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&path)
+    {
+      Ok(mut file) => {
+        file
+          .write_all(json.as_bytes())
+          .map_err(|e| format!("Failed to write instance lock: {}", e))?;
+        return Ok(Instance::Primary(PrimaryInstance { listener, token }));
+      }
+      Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+      Err(e) => return Err(format!("Failed to create instance lock: {}", e)),
+    }
+
+    let Some(holder) = read_lock(&path) else {
+      // Just created by a launch that hasn't written it yet, or left corrupted
+      if lock_age(&path).is_some_and(|age| age > UNREADABLE_LOCK_STALE_AFTER) {
+        info!("🔒 Removing unreadable instance lock");
+        remove_lock(&path)?;
+      } else {
+        std::thread::sleep(Duration::from_millis(100));
+      }
+      continue;
+    };
+    match forward(&holder, request) {
+      Ok(()) => {
+        info!(
+          "📨 FrameSense is already running (pid {}) - arguments forwarded",
+          holder.pid
+        );
+        return Ok(Instance::Forwarded);
+      }
+      // Nothing listens on the port or the process is gone: a crashed instance's lock
+      Err(e) if e.kind() == io::ErrorKind::ConnectionRefused || !process_alive(holder.pid) => {
+        info!(
+          "🔒 Previous instance (pid {}) is gone ({}), taking over",
+          holder.pid, e
+        );
+        remove_lock(&path)?;
+      }
+      // Alive but busy (e.g. still starting): give it a moment
+      Err(e) => {
+        warn!(
+          "⚠️ Running instance (pid {}) did not answer: {}",
+          holder.pid, e
+        );
+        std::thread::sleep(CONNECT_TIMEOUT);
+      }
+    }
+  }
+  Err("Another instance holds the lock but is not responding".to_string())
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+  fs::read_to_string(path)
+    .ok()
+    .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn lock_age(path: &Path) -> Option<Duration> {
+  let modified = fs::metadata(path)
+    .and_then(|metadata| metadata.modified())
+    .ok()?;
+  SystemTime::now().duration_since(modified).ok()
+}
+
+fn remove_lock(path: &Path) -> Result<(), String> {
+  match fs::remove_file(path) {
+    // Another launch may have removed it first
+    Err(e) if e.kind() != io::ErrorKind::NotFound => {
+      Err(format!("Failed to remove stale instance lock: {}", e))
+    }
+    _ => Ok(()),
+  }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+  // Signal 0 only checks that the process exists
+  Command::new("kill")
+    .args(["-0", &pid.to_string()])
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map(|status| status.success())
+    .unwrap_or(true)
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+  Command::new("tasklist")
+    .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+    .stderr(Stdio::null())
+    .output()
+    .map(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)))
+    .unwrap_or(true)
+}
+
+fn forward(lock: &LockInfo, request: &LaunchRequest) -> io::Result<()> {
+  let address = SocketAddr::from((Ipv4Addr::LOCALHOST, lock.port));
+  let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+  let message = Message {
+    token: lock.token.clone(),
+    request: request.clone(),
+  };
+  let mut line = serde_json::to_string(&message)?;
+  line.push('\n');
+  stream.write_all(line.as_bytes())?;
+  // The primary answers "ok" once it accepted the token
+  let mut reply = String::new();
+  stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+  BufReader::new(stream).read_line(&mut reply)?;
+  if reply.trim() == "ok" {
+    Ok(())
+  } else {
+    Err(io::Error::other("instance did not accept the request"))
+  }
+}
+
+/// Accept launch requests from later instances on a background thread
+pub fn listen(primary: PrimaryInstance, on_request: impl Fn(LaunchRequest) + Send + 'static) {
+  std::thread::spawn(move || {
+    for stream in primary.listener.incoming() {
+      let stream = match stream {
+        Ok(stream) => stream,
+        Err(e) => {
+          warn!("⚠️ Instance socket error: {}", e);
+          continue;
+        }
+      };
+      let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+      let mut line = String::new();
+      let mut reader = BufReader::new(&stream).take(MAX_MESSAGE_BYTES);
+      if reader.read_line(&mut line).is_err() {
+        continue;
+      }
+      let message = match serde_json::from_str::<Message>(&line) {
+        Ok(message) if message.token == primary.token => message,
+        _ => {
+          warn!("⚠️ Ignoring invalid message on the instance socket");
+          continue;
+        }
+      };
+      let _ = (&stream).write_all(b"ok\n");
+      info!(
+        "📨 Second launch forwarded {} argument(s)",
+        message.request.args.len()
+      );
+      on_request(message.request);
+    }
+  });
+}
+
+fn random_token() -> Result<String, String> {
+  let mut bytes = [0u8; 16];
+  SystemRandom::new()
+    .fill(&mut bytes)
+    .map_err(|_| "Failed to generate instance token".to_string())?;
+  Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
@@ -0,0 +1,333 @@
+// Text-to-speech for OCR / AI results using the platform synthesizer:
+// `say` (AVSpeechSynthesizer) on macOS, SAPI via PowerShell on Windows and
+// speech-dispatcher (`spd-say`) on Linux. One utterance plays at a time. Pausing
+// suspends the synthesizer processes on macOS/Linux and asks SAPI to pause on Windows.
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+use tracing::info;
+
+const MAX_TEXT_CHARS: usize = 20_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechState {
+  Speaking,
+  Paused,
+  Finished,
+  Stopped,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechStatus {
+  pub id: u64,
+  pub state: SpeechState,
+  pub chars: usize,
+}
+
+struct ActiveSpeech {
+  id: u64,
+  child: Arc<Mutex<Child>>,
+  chars: usize,
+  paused: bool,
+}
+
+/// Owns the (single) utterance currently being spoken
+pub struct SpeechPlayer {
+  active: Option<ActiveSpeech>,
+  next_id: u64,
+}
+
+impl SpeechPlayer {
+  pub fn new() -> Self {
+    Self {
+      active: None,
+      next_id: 1,
+    }
+  }
+
+  /// Speak `text`, replacing anything already playing. `rate` is relative to the
+  /// voice's normal speed (1.0), `voice` is a platform voice name.
+  pub fn speak(
+    &mut self,
+    app: tauri::AppHandle,
+    text: &str,
+    voice: Option<&str>,
+    rate: Option<f32>,
+  ) -> Result<SpeechStatus, String> {
+    let text = text.trim();
+    if text.is_empty() {
+      return Err("Nothing to read aloud".to_string());
+    }
+    let text: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    let rate = rate.unwrap_or(1.0).clamp(0.25, 4.0);
+    let voice = voice.map(str::trim).filter(|v| !v.is_empty());
+
+    self.stop();
+    let child = spawn_synthesizer(&text, voice, rate)?;
+
+    let id = self.next_id;
+    self.next_id += 1;
+    let child = Arc::new(Mutex::new(child));
+    let chars = text.chars().count();
+    self.active = Some(ActiveSpeech {
+      id,
+      child: child.clone(),
+      chars,
+      paused: false,
+    });
+    watch_until_finished(app, id, chars, child);
+
+    info!("🔊 Speaking {} characters", chars);
+    Ok(SpeechStatus {
+      id,
+      state: SpeechState::Speaking,
+      chars,
+    })
+  }
+
+  pub fn set_paused(&mut self, paused: bool) -> Result<SpeechStatus, String> {
+    let active = self.active.as_mut().ok_or("Nothing is being read aloud")?;
+    if active.paused != paused {
+      signal_pause(&mut active.child.lock().unwrap(), paused)?;
+      active.paused = paused;
+      info!("🔊 Speech {}", if paused { "paused" } else { "resumed" });
+    }
+    Ok(SpeechStatus {
+      id: active.id,
+      state: if paused {
+        SpeechState::Paused
+      } else {
+        SpeechState::Speaking
+      },
+      chars: active.chars,
+    })
+  }
+
+  /// Stop speaking. Returns the status of the stopped utterance, if any.
+  pub fn stop(&mut self) -> Option<SpeechStatus> {
+    let active = self.active.take()?;
+    {
+      let mut child = active.child.lock().unwrap();
+      if active.paused {
+        // A stopped process can't handle the kill on every platform until resumed
+        let _ = signal_pause(&mut child, false);
+      }
+      let _ = child.kill();
+      let _ = child.wait();
+    }
+    cancel_queued_speech();
+    Some(SpeechStatus {
+      id: active.id,
+      state: SpeechState::Stopped,
+      chars: active.chars,
+    })
+  }
+
+  pub fn status(&mut self) -> Option<SpeechStatus> {
+    let active = self.active.as_ref()?;
+    let finished = !matches!(active.child.lock().unwrap().try_wait(), Ok(None));
+    if finished {
+      self.active = None;
+      return None;
+    }
+    Some(SpeechStatus {
+      id: active.id,
+      state: if active.paused {
+        SpeechState::Paused
+      } else {
+        SpeechState::Speaking
+      },
+      chars: active.chars,
+    })
+  }
+}
+
+impl Default for SpeechPlayer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+// Emit "speech-status" once the synthesizer exits on its own
+fn watch_until_finished(app: tauri::AppHandle, id: u64, chars: usize, child: Arc<Mutex<Child>>) {
+  std::thread::spawn(move || loop {
+    std::thread::sleep(POLL_INTERVAL);
+    match child.lock().unwrap().try_wait() {
+      Ok(None) => continue,
+      Ok(Some(status)) if status.success() => {
+        let _ = app.emit(
+          "speech-status",
+          SpeechStatus {
+            id,
+            state: SpeechState::Finished,
+            chars,
+          },
+        );
+        break;
+      }
+      // Killed by stop() (which reports itself) or failed
+      _ => break,
+    }
+  });
+}
+
+fn spawn_synthesizer(text: &str, voice: Option<&str>, rate: f32) -> Result<Child, String> {
+  let mut command = synthesizer_command(voice, rate);
+  let mut child = command
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .map_err(|e| format!("Text-to-speech is not available: {}", e))?;
+  // Text goes through stdin so it's never mistaken for options and has no length limit
+  send_text(&mut child, text)
+    .map_err(|e| format!("Failed to send text to the synthesizer: {}", e))?;
+  Ok(child)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn send_text(child: &mut Child, text: &str) -> std::io::Result<()> {
+  // Closing stdin marks the end of the text
+  match child.stdin.take() {
+    Some(mut stdin) => stdin.write_all(text.as_bytes()),
+    None => Ok(()),
+  }
+}
+
+// The script reads one base64 line of text, then pause/resume commands until it's done
+#[cfg(target_os = "windows")]
+fn send_text(child: &mut Child, text: &str) -> std::io::Result<()> {
+  use base64::Engine;
+
+  match child.stdin.as_mut() {
+    Some(stdin) => {
+      let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+      writeln!(stdin, "{}", encoded)?;
+      stdin.flush()
+    }
+    None => Ok(()),
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn synthesizer_command(voice: Option<&str>, rate: f32) -> Command {
+  // `say` rate is in words per minute; ~175 is the system default
+  let mut command = Command::new("say");
+  command.args(["-r", &((175.0 * rate).round() as u32).to_string()]);
+  if let Some(voice) = voice {
+    command.args(["-v", voice]);
+  }
+  command
+}
+
+#[cfg(target_os = "windows")]
+fn synthesizer_command(voice: Option<&str>, rate: f32) -> Command {
+  // SAPI rate runs from -10 to 10 with 0 as normal
+  let sapi_rate = ((rate - 1.0) * 10.0).round().clamp(-10.0, 10.0) as i32;
+  let select_voice = voice
+    .map(|v| format!("$s.SelectVoice('{}');", v.replace('\'', "''")))
+    .unwrap_or_default();
+  let script = format!(
+    "Add-Type -AssemblyName System.Speech; \
+     $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; {} $s.Rate = {}; \
+     $text = [Text.Encoding]::UTF8.GetString([Convert]::FromBase64String([Console]::In.ReadLine())); \
+     $p = $s.SpeakAsync($text); $read = [Console]::In.ReadLineAsync(); \
+     while (-not $p.IsCompleted) {{ \
+       if ($read -eq $null) {{ Start-Sleep -Milliseconds 100 }} \
+       elseif ($read.Wait(100)) {{ \
+         $line = $read.Result; $read = $null; \
+         if ($line -eq 'pause') {{ $s.Pause() }} elseif ($line -eq 'resume') {{ $s.Resume() }} \
+         if ($line -ne $null) {{ $read = [Console]::In.ReadLineAsync() }} \
+       }} \
+     }}",
+    select_voice, sapi_rate
+  );
+  let mut command = Command::new("powershell");
+  command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+  command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn synthesizer_command(voice: Option<&str>, rate: f32) -> Command {
+  // spd-say rate runs from -100 to 100 with 0 as normal; -e reads the text from stdin
+  // and -w keeps the process alive until speech-dispatcher has finished speaking
+  let spd_rate = ((rate - 1.0) * 100.0).round().clamp(-100.0, 100.0) as i32;
+  let mut command = Command::new("spd-say");
+  command.args(["-w", "-e", "-r", &spd_rate.to_string()]);
+  if let Some(voice) = voice {
+    command.args(["-y", voice]);
+  }
+  command
+}
+
+// `say` synthesizes in-process, so suspending it pauses the audio
+#[cfg(not(target_os = "windows"))]
+fn signal_pause(child: &mut Child, paused: bool) -> Result<(), String> {
+  let status = Command::new("kill")
+    .args([pause_signal(paused), &child.id().to_string()])
+    .status()
+    .map_err(|e| format!("Failed to pause speech: {}", e))?;
+  if !status.success() {
+    return Err("Failed to pause speech".to_string());
+  }
+  pause_output_modules(paused);
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn signal_pause(child: &mut Child, paused: bool) -> Result<(), String> {
+  let stdin = child
+    .stdin
+    .as_mut()
+    .ok_or("Speech can't be paused - stop it instead")?;
+  writeln!(stdin, "{}", if paused { "pause" } else { "resume" })
+    .and_then(|_| stdin.flush())
+    .map_err(|e| format!("Failed to pause speech: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn pause_signal(paused: bool) -> &'static str {
+  if paused {
+    "-STOP"
+  } else {
+    "-CONT"
+  }
+}
+
+// spd-say only hands the text over; the audio comes from speech-dispatcher's output
+// modules (sd_espeak-ng, sd_generic, ...) running as the same user
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn pause_output_modules(paused: bool) {
+  use std::os::unix::fs::MetadataExt;
+
+  let Ok(uid) = std::fs::metadata("/proc/self").map(|meta| meta.uid()) else {
+    return;
+  };
+  let _ = Command::new("pkill")
+    .args([pause_signal(paused), "-U", &uid.to_string(), "^sd_"])
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status();
+}
+
+#[cfg(target_os = "macos")]
+fn pause_output_modules(_paused: bool) {}
+
+// speech-dispatcher keeps speaking after spd-say is killed unless the queue is cancelled
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn cancel_queued_speech() {
+  let _ = Command::new("spd-say")
+    .arg("-C")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status();
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn cancel_queued_speech() {}
@@ -0,0 +1,288 @@
+// Differential persistence for AppState: small fields go to app_state.json,
+// the (large) screenshot goes to its own file, and nothing is rewritten unless it changed.
+// The file carries a schema version; older files are migrated step by step on load and
+// unreadable ones are moved aside so the app starts with a clean state.
+use crate::capture_store::decode_data_url;
+use crate::{AppState, CaptureBounds};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+pub const STATE_RESTORED_EVENT: &str = "state-restored";
+
+const STATE_FILE: &str = "app_state.json";
+// Larger restored bounds than this are treated as garbage
+const MAX_BOUNDS_SIDE: u32 = 32768;
+const SCREENSHOT_FILE: &str = "app_state_screenshot.png";
+
+/// Current schema of app_state.json. Files without a version are version 1.
+pub const STATE_VERSION: u32 = 2;
+
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+// MIGRATIONS[n] upgrades a version n + 1 document to version n + 2
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+// What actually ends up in app_state.json - the screenshot is only referenced by file name
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct PersistedFields {
+  #[serde(default)]
+  pub version: u32,
+  pub last_bounds: Option<CaptureBounds>,
+  pub last_window_closed_time: Option<u64>,
+  pub screenshot_file: Option<String>,
+}
+
+// v1 was written before versioning; it had the same fields, so only the version is added
+fn migrate_v1_to_v2(document: &mut Value) -> Result<(), String> {
+  let fields = document
+    .as_object_mut()
+    .ok_or_else(|| "app state is not a JSON object".to_string())?;
+  fields.insert("version".to_string(), Value::from(2));
+  Ok(())
+}
+
+// Bring a parsed document up to STATE_VERSION; also returns the version it was saved with
+fn migrate(mut document: Value) -> Result<(PersistedFields, u32), String> {
+  let version = match document.get("version") {
+    None => 1,
+    Some(version) => version
+      .as_u64()
+      .filter(|v| *v >= 1)
+      .ok_or_else(|| format!("invalid state version {}", version))? as u32,
+  };
+  if version > STATE_VERSION {
+    warn!(
+      "⚠️ App state was saved by a newer version ({}), unknown fields are ignored",
+      version
+    );
+  }
+  for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+    migration(&mut document).map_err(|e| format!("migration from v{} failed: {}", step + 1, e))?;
+  }
+  let mut fields: PersistedFields =
+    serde_json::from_value(document).map_err(|e| format!("unreadable fields: {}", e))?;
+  fields.version = STATE_VERSION;
+  Ok((fields, version))
+}
+
+/// Payload of `state-restored`: what came back from the last session
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StateRestored {
+  pub has_screenshot: bool,
+  pub last_bounds: Option<CaptureBounds>,
+  pub last_window_closed_time: Option<u64>,
+}
+
+impl StateRestored {
+  pub fn from_state(state: &AppState) -> Self {
+    Self {
+      has_screenshot: state.screenshot_data.is_some(),
+      last_bounds: state.last_bounds.clone(),
+      last_window_closed_time: state.last_window_closed_time,
+    }
+  }
+}
+
+#[derive(Clone, Serialize, Debug, Default)]
+pub struct SaveStats {
+  pub fields_written: bool,
+  pub screenshot_written: bool,
+  pub screenshot_removed: bool,
+  pub bytes_written: usize,
+}
+
+pub struct StateStore {
+  dir: PathBuf,
+  last_fields_json: Option<String>,
+  last_screenshot_hash: Option<u64>,
+}
+
+impl StateStore {
+  pub fn new(dir: PathBuf) -> Self {
+    Self {
+      dir,
+      last_fields_json: None,
+      last_screenshot_hash: None,
+    }
+  }
+
+  pub fn state_file(&self) -> PathBuf {
+    self.dir.join(STATE_FILE)
+  }
+
+  pub fn screenshot_file(&self) -> PathBuf {
+    self.dir.join(SCREENSHOT_FILE)
+  }
+
+  /// Restore the saved state. Old schema versions are migrated and written back; a
+  /// corrupt file is kept as a backup and the state starts empty.
+  pub fn load(&mut self) -> AppState {
+    let json = match fs::read_to_string(self.state_file()) {
+      Ok(json) => json,
+      Err(_) => return AppState::default(),
+    };
+    let (fields, saved_version) = match serde_json::from_str::<Value>(&json)
+      .map_err(|e| e.to_string())
+      .and_then(migrate)
+    {
+      Ok(migrated) => migrated,
+      Err(e) => {
+        self.reset_corrupt(&e);
+        return AppState::default();
+      }
+    };
+
+    let screenshot_data = fields.screenshot_file.as_ref().and_then(|name| {
+      // Only our own file name is accepted, never a path from the JSON
+      if name != SCREENSHOT_FILE {
+        warn!("⚠️ Ignoring unexpected screenshot file '{}'", name);
+        return None;
+      }
+      match fs::read(self.screenshot_file()) {
+        Ok(png) if is_valid_png(&png) => Some(format!(
+          "data:image/png;base64,{}",
+          base64::engine::general_purpose::STANDARD.encode(png)
+        )),
+        Ok(_) => {
+          warn!("⚠️ Saved screenshot is not a readable PNG, dropping it");
+          None
+        }
+        Err(e) => {
+          warn!("⚠️ Saved screenshot is missing: {}", e);
+          None
+        }
+      }
+    });
+    let state = AppState {
+      screenshot_data,
+      last_bounds: fields.last_bounds.filter(|bounds| {
+        let valid = (1..=MAX_BOUNDS_SIDE).contains(&bounds.width)
+          && (1..=MAX_BOUNDS_SIDE).contains(&bounds.height);
+        if !valid {
+          warn!("⚠️ Dropping invalid saved bounds {:?}", bounds);
+        }
+        valid
+      }),
+      // A close time in the future comes from a broken clock or file; forget it
+      last_window_closed_time: fields
+        .last_window_closed_time
+        .filter(|time| *time <= chrono::Utc::now().timestamp().max(0) as u64 + 60),
+      capture_source_app: None,
+    };
+
+    // The screenshot on disk is current; a migrated file is rewritten in the new format
+    self.last_screenshot_hash = state.screenshot_data.as_deref().map(Self::hash_str);
+    if saved_version < STATE_VERSION {
+      match self.save(&state) {
+        Ok(_) => info!(
+          "📦 App state migrated from v{} to v{}",
+          saved_version, STATE_VERSION
+        ),
+        Err(e) => warn!("⚠️ Failed to rewrite migrated app state: {}", e),
+      }
+    }
+    info!("📦 App state restored");
+    state
+  }
+
+  // Move an unreadable app_state.json aside (app_state.json.corrupt-<time>) and start over
+  fn reset_corrupt(&mut self, reason: &str) {
+    let backup = self.dir.join(format!(
+      "{}.corrupt-{}",
+      STATE_FILE,
+      chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    match fs::rename(self.state_file(), &backup) {
+      Ok(()) => warn!(
+        "⚠️ App state was unreadable ({}), backed up to {:?} and reset",
+        reason, backup
+      ),
+      Err(e) => warn!(
+        "⚠️ App state was unreadable ({}) and could not be backed up: {}",
+        reason, e
+      ),
+    }
+    let _ = fs::remove_file(self.screenshot_file());
+    self.last_fields_json = None;
+    self.last_screenshot_hash = None;
+  }
+
+  /// Persist only the parts of `state` that changed since the last save
+  pub fn save(&mut self, state: &AppState) -> Result<SaveStats, String> {
+    fs::create_dir_all(&self.dir)
+      .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    let mut stats = SaveStats::default();
+
+    // 1. Screenshot blob - compared by hash so an unchanged capture is never rewritten
+    match &state.screenshot_data {
+      Some(data) => {
+        let hash = Self::hash_str(data);
+        if self.last_screenshot_hash != Some(hash) || !self.screenshot_file().exists() {
+          let png = decode_data_url(data)?;
+          Self::write_atomic(&self.screenshot_file(), &png)?;
+          stats.screenshot_written = true;
+          stats.bytes_written += png.len();
+          self.last_screenshot_hash = Some(hash);
+        }
+      }
+      None => {
+        if self.screenshot_file().exists() {
+          fs::remove_file(self.screenshot_file())
+            .map_err(|e| format!("Failed to remove old screenshot: {}", e))?;
+          stats.screenshot_removed = true;
+        }
+        self.last_screenshot_hash = None;
+      }
+    }
+
+    // 2. Small fields - compared by their serialized form
+    let fields = PersistedFields {
+      version: STATE_VERSION,
+      last_bounds: state.last_bounds.clone(),
+      last_window_closed_time: state.last_window_closed_time,
+      screenshot_file: state
+        .screenshot_data
+        .as_ref()
+        .map(|_| SCREENSHOT_FILE.to_string()),
+    };
+    let fields_json = serde_json::to_string_pretty(&fields)
+      .map_err(|e| format!("Failed to serialize app state: {}", e))?;
+
+    if self.last_fields_json.as_deref() != Some(fields_json.as_str()) {
+      Self::write_atomic(&self.state_file(), fields_json.as_bytes())?;
+      stats.fields_written = true;
+      stats.bytes_written += fields_json.len();
+      self.last_fields_json = Some(fields_json);
+    }
+
+    Ok(stats)
+  }
+
+  fn hash_str(data: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  // Write to a temp file and rename, so a crash never leaves a half-written file behind
+  fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes).map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+  }
+}
+
+// Header check only; enough to reject truncated or foreign files
+fn is_valid_png(bytes: &[u8]) -> bool {
+  image::io::Reader::with_format(std::io::Cursor::new(bytes), image::ImageFormat::Png)
+    .into_dimensions()
+    .is_ok_and(|(width, height)| width > 0 && height > 0)
+}
@@ -0,0 +1,279 @@
+// Watch mode - captures a region at an interval and emits `region-changed` when its
+// content differs from the previous capture by more than a threshold (build
+// dashboards, queues, ...). Several regions can be watched at once.
+use crate::overlay::{CaptureBounds, ScreenCapture};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tracing::{info, warn};
+
+const MIN_INTERVAL_MS: u64 = 250;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOptions {
+  pub bounds: CaptureBounds,
+  #[serde(default = "default_interval_ms")]
+  pub interval_ms: u64,
+  // Fraction of pixels (0.0 - 1.0) that must change to count as a change
+  #[serde(default = "default_threshold")]
+  pub threshold: f32,
+  // Per-channel difference below which a pixel counts as unchanged (ignores noise)
+  #[serde(default = "default_pixel_tolerance")]
+  pub pixel_tolerance: u8,
+  #[serde(default)]
+  pub include_image: bool,
+}
+
+fn default_interval_ms() -> u64 {
+  2000
+}
+
+fn default_threshold() -> f32 {
+  0.01
+}
+
+fn default_pixel_tolerance() -> u8 {
+  24
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchInfo {
+  pub id: String,
+  pub options: WatchOptions,
+  pub started_at: String,
+  pub changes: u64,
+}
+
+#[derive(Clone, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionChangedEvent {
+  pub watch_id: String,
+  pub changed_ratio: f32,
+  pub changed_bounds: Option<CaptureBounds>, // Overlay coordinates
+  pub image_data: Option<String>,
+  pub timestamp: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImageDiff {
+  pub changed_ratio: f32,
+  // Bounding box of changed pixels, in image pixels: (x, y, width, height)
+  pub changed_rect: Option<(u32, u32, u32, u32)>,
+}
+
+struct ActiveWatch {
+  info: WatchInfo,
+  stop: Arc<AtomicBool>,
+}
+
+pub struct RegionWatcher {
+  watches: HashMap<String, ActiveWatch>,
+  next_id: u64,
+}
+
+impl RegionWatcher {
+  pub fn new() -> Self {
+    Self {
+      watches: HashMap::new(),
+      next_id: 1,
+    }
+  }
+
+  /// Start watching a region; returns the watch id
+  pub fn start(
+    &mut self,
+    app: tauri::AppHandle,
+    mut options: WatchOptions,
+  ) -> Result<String, String> {
+    if options.bounds.width == 0 || options.bounds.height == 0 {
+      return Err("Watch region must have a non-zero size".to_string());
+    }
+    if !(0.0..=1.0).contains(&options.threshold) {
+      return Err("Threshold must be between 0.0 and 1.0".to_string());
+    }
+    options.interval_ms = options.interval_ms.max(MIN_INTERVAL_MS);
+
+    let id = format!("watch-{}", self.next_id);
+    self.next_id += 1;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    self.watches.insert(
+      id.clone(),
+      ActiveWatch {
+        info: WatchInfo {
+          id: id.clone(),
+          options: options.clone(),
+          started_at: chrono::Utc::now().to_rfc3339(),
+          changes: 0,
+        },
+        stop: stop.clone(),
+      },
+    );
+
+    info!(
+      "👀 Watching region {}x{} at ({}, {}) every {}ms ({})",
+      options.bounds.width,
+      options.bounds.height,
+      options.bounds.x,
+      options.bounds.y,
+      options.interval_ms,
+      id
+    );
+    tauri::async_runtime::spawn(watch_loop(app, id.clone(), options, stop));
+    Ok(id)
+  }
+
+  pub fn stop(&mut self, id: &str) -> Result<(), String> {
+    let watch = self
+      .watches
+      .remove(id)
+      .ok_or_else(|| format!("Watch '{}' not found", id))?;
+    watch.stop.store(true, Ordering::SeqCst);
+    info!("👀 Stopped watch {}", id);
+    Ok(())
+  }
+
+  pub fn stop_all(&mut self) {
+    for (_, watch) in self.watches.drain() {
+      watch.stop.store(true, Ordering::SeqCst);
+    }
+  }
+
+  pub fn list(&self) -> Vec<WatchInfo> {
+    let mut watches: Vec<WatchInfo> = self.watches.values().map(|w| w.info.clone()).collect();
+    watches.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    watches
+  }
+
+  fn record_change(&mut self, id: &str) {
+    if let Some(watch) = self.watches.get_mut(id) {
+      watch.info.changes += 1;
+    }
+  }
+}
+
+impl Default for RegionWatcher {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+async fn watch_loop(
+  app: tauri::AppHandle,
+  id: String,
+  options: WatchOptions,
+  stop: Arc<AtomicBool>,
+) {
+  let mut previous: Option<RgbaImage> = None;
+
+  while !stop.load(Ordering::SeqCst) {
+    let bounds = options.bounds.clone();
+    let frame =
+      tauri::async_runtime::spawn_blocking(move || ScreenCapture::capture_area_rgba(&bounds))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+
+    match frame {
+      Ok(frame) => match &previous {
+        Some(previous_frame) => {
+          let diff = diff_images(previous_frame, &frame, options.pixel_tolerance);
+          // Compare against the last reported frame so slow drift still adds up
+          if diff.changed_ratio > options.threshold {
+            info!(
+              "👀 Region changed ({}): {:.1}% of pixels",
+              id,
+              diff.changed_ratio * 100.0
+            );
+            emit_change(&app, &id, &options, &frame, &diff);
+            previous = Some(frame);
+          }
+        }
+        None => previous = Some(frame),
+      },
+      Err(e) => warn!("⚠️ Watch {} capture failed: {}", id, e),
+    }
+
+    tokio::time::sleep(Duration::from_millis(options.interval_ms)).await;
+  }
+}
+
+fn emit_change(
+  app: &tauri::AppHandle,
+  id: &str,
+  options: &WatchOptions,
+  frame: &RgbaImage,
+  diff: &ImageDiff,
+) {
+  if let Some(watcher) = tauri::Manager::try_state::<crate::SharedRegionWatcher>(app) {
+    watcher.lock().unwrap().record_change(id);
+  }
+
+  // Map the changed pixel rectangle back into overlay coordinates
+  let scale_x = frame.width() as f64 / options.bounds.width as f64;
+  let scale_y = frame.height() as f64 / options.bounds.height as f64;
+  let changed_bounds = diff
+    .changed_rect
+    .map(|(x, y, width, height)| CaptureBounds {
+      x: options.bounds.x + (x as f64 / scale_x).floor() as i32,
+      y: options.bounds.y + (y as f64 / scale_y).floor() as i32,
+      width: (width as f64 / scale_x).ceil() as u32,
+      height: (height as f64 / scale_y).ceil() as u32,
+    });
+
+  let image_data = if options.include_image {
+    ScreenCapture::encode_rgba_to_base64(frame.clone()).ok()
+  } else {
+    None
+  };
+
+  let _ = app.emit(
+    "region-changed",
+    RegionChangedEvent {
+      watch_id: id.to_string(),
+      changed_ratio: diff.changed_ratio,
+      changed_bounds,
+      image_data,
+      timestamp: chrono::Utc::now().to_rfc3339(),
+    },
+  );
+}
+
+/// Fraction of pixels that differ by more than `tolerance` in any channel, and the
+/// bounding box of those pixels. Images of different sizes count as fully changed.
+pub fn diff_images(previous: &RgbaImage, current: &RgbaImage, tolerance: u8) -> ImageDiff {
+  if previous.dimensions() != current.dimensions() {
+    return ImageDiff {
+      changed_ratio: 1.0,
+      changed_rect: Some((0, 0, current.width(), current.height())),
+    };
+  }
+
+  let (width, height) = current.dimensions();
+  let mut changed = 0u64;
+  let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0u32, 0u32);
+
+  for (x, y, pixel) in current.enumerate_pixels() {
+    let old = previous.get_pixel(x, y);
+    let differs = (0..4).any(|c| pixel[c].abs_diff(old[c]) > tolerance);
+    if differs {
+      changed += 1;
+      min_x = min_x.min(x);
+      min_y = min_y.min(y);
+      max_x = max_x.max(x);
+      max_y = max_y.max(y);
+    }
+  }
+
+  let total = (width as u64 * height as u64).max(1);
+  ImageDiff {
+    changed_ratio: changed as f32 / total as f32,
+    changed_rect: (changed > 0).then(|| (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)),
+  }
+}
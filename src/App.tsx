@@ -383,7 +383,7 @@ function App() {
 				console.log('✅ ALT+C: Screenshot saved for AI analysis!');
 
 				// 🔍 NEW: Run automatic OCR in background (SILENT)
-				runAutomaticOCR(result.imageData);
+				runAutomaticOCR(result.imageData, result.captureId);
 
 				// STEG 1: Auto-activate ChatBox after screenshot
 				console.log('🔄 ALT+C: About to call handleAskAI() - current chatBoxOpen:', chatBoxOpen);
@@ -866,11 +866,11 @@ function App() {
 	};
 
 	// 🔍 Automatic OCR function - runs silently after screenshot
-	const runAutomaticOCR = async (imageData: string) => {
+	const runAutomaticOCR = async (imageData: string, captureId?: string) => {
 		console.log('🔍 Running automatic OCR in background...');
 
 		try {
-			const ocrResult = await invoke('extract_text_ocr', {imageData}) as OCRResult;
+			const ocrResult = await invoke('extract_text_ocr', {imageData, captureId}) as OCRResult;
 			setOcrContext(ocrResult);
 
 			if (ocrResult.has_text) {
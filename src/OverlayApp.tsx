@@ -54,12 +54,12 @@ function OverlayApp() {
 	const handleSelectionCancel = async () => {
 		console.log('❌ Overlay selection cancelled');
 		
-		// Close the overlay window - try optimized first, then regular
+		// Dedicated cancel path - hides (or destroys) the overlay and restores the main window
 		try {
-			await invoke('close_transparent_overlay_optimized');
-			console.log('✅ Optimized overlay window closed and main window restored');
+			await invoke('cancel_capture');
+			console.log('✅ Capture cancelled and main window restored');
 		} catch (error) {
-			console.warn('⚠️ Optimized close failed, trying regular close:', error);
+			console.warn('⚠️ Cancel failed, trying regular close:', error);
 			try {
 				await invoke('close_transparent_overlay');
 				console.log('✅ Regular overlay window closed');
@@ -1,11 +1,42 @@
 import React, { useState, useRef, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 
 interface DragOverlayProps {
 	onSelectionComplete: (result: any) => void;
 	onCancel: () => void;
 }
 
+// Each display has its own overlay window, which gets only its display's part of the
+// frozen desktop snapshot
+interface FrozenFrame {
+	imageData: string | null;
+}
+
+// Session constraint set through set_selection_constraints (mirrors SelectionConstraint in Rust)
+type SelectionConstraint =
+	| { type: 'free' }
+	| { type: 'aspectRatio'; width: number; height: number }
+	| { type: 'fixedSize'; width: number; height: number };
+
+// Mirrors OverlayConfig in Rust
+interface OverlayConfig {
+	backend: 'webview' | 'native';
+	dimOpacity: number;
+	selectionBorderColor: string;
+	showCrosshair: boolean;
+	showDimensions: boolean;
+}
+
+const DEFAULT_OVERLAY_CONFIG: OverlayConfig = {
+	backend: 'webview',
+	dimOpacity: 0,
+	selectionBorderColor: '#3B82F6',
+	showCrosshair: false,
+	showDimensions: true,
+};
+
 interface SelectionBox {
 	startX: number;
 	startY: number;
@@ -13,10 +44,83 @@ interface SelectionBox {
 	endY: number;
 }
 
+// Same fitting as Rust: anchored at the top-left, aspect ratios shrink to fit the drag
+const applyConstraint = (
+	rect: { left: number; top: number; width: number; height: number },
+	constraint: SelectionConstraint,
+) => {
+	if (constraint.type === 'fixedSize') {
+		return { ...rect, width: constraint.width, height: constraint.height };
+	}
+	if (constraint.type === 'aspectRatio') {
+		const ratio = constraint.width / constraint.height;
+		const fittedHeight = Math.round(rect.width / ratio);
+		return fittedHeight <= rect.height
+			? { ...rect, height: fittedHeight }
+			: { ...rect, width: Math.round(rect.height * ratio) };
+	}
+	return rect;
+};
+
 const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel }) => {
 	const [isDragging, setIsDragging] = useState(false);
 	const [selectionBox, setSelectionBox] = useState<SelectionBox | null>(null);
 	const overlayRef = useRef<HTMLDivElement>(null);
+	// Freeze-screen mode: snapshot of the desktop to select on instead of the live screen
+	const [frozenFrame, setFrozenFrame] = useState<FrozenFrame | null>(null);
+	// Bounds echoed back from Rust are ignored while this window is the one dragging
+	const draggingRef = useRef(false);
+	// Lasso mode (toggled with L): the drag draws a freehand path instead of a rectangle
+	const [lassoMode, setLassoMode] = useState(false);
+	const [lassoPath, setLassoPath] = useState<{ x: number; y: number }[]>([]);
+	// Window mode (toggled with W): a click without dragging captures the window under it
+	const [windowMode, setWindowMode] = useState(false);
+	const [constraint, setConstraint] = useState<SelectionConstraint>({ type: 'free' });
+	// Appearance preferences stored by Rust (get_overlay_config)
+	const [config, setConfig] = useState<OverlayConfig>(DEFAULT_OVERLAY_CONFIG);
+	const [cursor, setCursor] = useState<{ x: number; y: number } | null>(null);
+
+	React.useEffect(() => {
+		const overlayWindow = getCurrentWebviewWindow();
+		invoke<FrozenFrame>('get_frozen_frame')
+			.then(frame => setFrozenFrame(frame))
+			.catch(() => setFrozenFrame(null));
+
+		invoke<OverlayConfig>('get_overlay_config')
+			.then(setConfig)
+			.catch(() => setConfig(DEFAULT_OVERLAY_CONFIG));
+		const unlistenConfig = listen<OverlayConfig>('overlay-config-changed', (event) => {
+			setConfig(event.payload);
+		});
+
+		invoke<SelectionConstraint>('get_selection_constraints')
+			.then(setConstraint)
+			.catch(() => setConstraint({ type: 'free' }));
+		const unlistenConstraint = overlayWindow.listen<{ constraint: SelectionConstraint }>('selection-constraint-changed', (event) => {
+			setConstraint(event.payload.constraint);
+		});
+
+		const unlisten = overlayWindow.listen<FrozenFrame>('overlay-frozen-frame', (event) => {
+			console.log(event.payload.imageData ? '🧊 Frozen frame received' : '🧊 Frozen frame cleared');
+			setFrozenFrame(event.payload);
+			setSelectionBox(null);
+			setIsDragging(false);
+			draggingRef.current = false;
+		});
+		// Keyboard adjustments and drags on other displays are tracked in Rust,
+		// which sends back the bounds in this window's coordinates
+		const unlistenBounds = overlayWindow.listen<{ bounds: { x: number; y: number; width: number; height: number } }>('selection-bounds-changed', (event) => {
+			if (draggingRef.current) return;
+			const { x, y, width, height } = event.payload.bounds;
+			setSelectionBox({ startX: x, startY: y, endX: x + width, endY: y + height });
+		});
+		return () => {
+			unlisten.then(fn => fn());
+			unlistenBounds.then(fn => fn());
+			unlistenConstraint.then(fn => fn());
+			unlistenConfig.then(fn => fn());
+		};
+	}, []);
 
 	const handleMouseDown = useCallback((e: React.MouseEvent) => {
 		e.preventDefault();
@@ -30,32 +134,55 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 		const startY = e.clientY - rect.top;
 
 		setIsDragging(true);
+		draggingRef.current = true;
+		if (lassoMode) {
+			setLassoPath([{ x: startX, y: startY }]);
+		}
 		setSelectionBox({
 			startX,
 			startY,
 			endX: startX,
 			endY: startY,
 		});
-	}, []);
+	}, [lassoMode]);
 
 	const handleMouseMove = useCallback((e: React.MouseEvent) => {
 		e.preventDefault();
 		e.stopPropagation();
-		
-		if (!isDragging || !selectionBox) return;
 
 		const rect = overlayRef.current?.getBoundingClientRect();
 		if (!rect) return;
+		if (config.showCrosshair) {
+			setCursor({ x: e.clientX - rect.left, y: e.clientY - rect.top });
+		}
+
+		if (!isDragging || !selectionBox) return;
 
 		const endX = e.clientX - rect.left;
 		const endY = e.clientY - rect.top;
 
+		if (lassoMode) {
+			setLassoPath(prev => [...prev, { x: endX, y: endY }]);
+			setSelectionBox(prev => prev ? { ...prev, endX, endY } : null);
+			return;
+		}
+
 		setSelectionBox(prev => prev ? {
 			...prev,
 			endX,
 			endY,
 		} : null);
-	}, [isDragging, selectionBox]);
+
+		// Let the overlays on the other displays draw their part of the selection
+		invoke('set_selection_bounds', {
+			bounds: {
+				x: Math.round(Math.min(selectionBox.startX, endX)),
+				y: Math.round(Math.min(selectionBox.startY, endY)),
+				width: Math.round(Math.abs(endX - selectionBox.startX)),
+				height: Math.round(Math.abs(endY - selectionBox.startY)),
+			},
+		}).catch(() => {});
+	}, [isDragging, selectionBox, lassoMode, config.showCrosshair]);
 
 	const handleMouseUp = useCallback(async (e: React.MouseEvent) => {
 		e.preventDefault();
@@ -65,6 +192,21 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 
 		console.log('🖱️ Mouse up - ending drag');
 		setIsDragging(false);
+		draggingRef.current = false;
+
+		if (lassoMode) {
+			const points = lassoPath.map(p => ({ x: Math.round(p.x), y: Math.round(p.y) }));
+			setLassoPath([]);
+			try {
+				console.log(`➰ Lasso selection with ${points.length} points`);
+				await invoke('process_lasso_selection', { points });
+				onSelectionComplete({ success: true });
+			} catch (error) {
+				console.error('❌ Lasso selection failed:', error);
+				onCancel();
+			}
+			return;
+		}
 
 		// Calculate final bounds in viewport coordinates
 		const viewportX = Math.min(selectionBox.startX, selectionBox.endX);
@@ -72,8 +214,23 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 		const width = Math.abs(selectionBox.endX - selectionBox.startX);
 		const height = Math.abs(selectionBox.endY - selectionBox.startY);
 
-		// Minimum selection size
-		if (width < 10 || height < 10) {
+		if (windowMode && width < 10 && height < 10) {
+			try {
+				console.log(`🪟 Capturing window at (${selectionBox.startX}, ${selectionBox.startY})`);
+				await invoke('capture_window_at_point', {
+					x: Math.round(selectionBox.startX),
+					y: Math.round(selectionBox.startY),
+				});
+				onSelectionComplete({ success: true });
+			} catch (error) {
+				console.error('❌ Window capture failed:', error);
+				onCancel();
+			}
+			return;
+		}
+
+		// Minimum selection size (a fixed-size selection can be placed with a click)
+		if (constraint.type !== 'fixedSize' && (width < 10 || height < 10)) {
 			console.log('❌ Selection too small, cancelling');
 			onCancel();
 			return;
@@ -82,9 +239,7 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 		try {
 			console.log(`📐 Viewport selection: ${width}x${height} at (${viewportX}, ${viewportY})`);
 
-			// 🔧 FIX: Since overlay window covers entire screen at position (0,0),
-			// viewport coordinates ARE already absolute screen coordinates!
-			// No need to get window position and add offsets.
+			// Viewport coordinates of this display's overlay - Rust adds the display offset
 			const screenX = Math.round(viewportX);
 			const screenY = Math.round(viewportY);
 			const screenWidth = Math.round(width);
@@ -109,7 +264,7 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 			console.error('❌ Drag selection failed:', error);
 			onCancel();
 		}
-	}, [isDragging, selectionBox, onSelectionComplete, onCancel]);
+	}, [isDragging, selectionBox, lassoMode, lassoPath, windowMode, constraint, onSelectionComplete, onCancel]);
 
 	// Handle clicks that aren't part of a drag (to prevent accidental cancellation)
 	const handleClick = useCallback((e: React.MouseEvent) => {
@@ -128,8 +283,45 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 			console.log('⏹️ Escape pressed - cancelling selection');
 			e.preventDefault();
 			onCancel();
+			return;
+		}
+
+		if ((e.key === 'l' || e.key === 'L') && !isDragging) {
+			e.preventDefault();
+			setLassoMode(prev => !prev);
+			setSelectionBox(null);
+			return;
+		}
+
+		if ((e.key === 'w' || e.key === 'W') && !isDragging) {
+			e.preventDefault();
+			setWindowMode(prev => !prev);
+			return;
 		}
-	}, [onCancel]);
+
+		// Arrow keys nudge the selection (Shift = 10px, Alt = resize), Enter captures it
+		const directions: Record<string, [number, number]> = {
+			ArrowLeft: [-1, 0],
+			ArrowRight: [1, 0],
+			ArrowUp: [0, -1],
+			ArrowDown: [0, 1],
+		};
+		const direction = directions[e.key];
+		if (direction && !isDragging) {
+			e.preventDefault();
+			const step = e.shiftKey ? 10 : 1;
+			invoke('nudge_selection', { dx: direction[0] * step, dy: direction[1] * step, resize: e.altKey })
+				.catch(error => console.error('❌ Failed to adjust selection:', error));
+		} else if (e.key === 'Enter' && selectionBox && !isDragging) {
+			e.preventDefault();
+			invoke('confirm_selection')
+				.then(() => onSelectionComplete({ success: true }))
+				.catch(error => {
+					console.error('❌ Keyboard selection failed:', error);
+					onCancel();
+				});
+		}
+	}, [onCancel, onSelectionComplete, isDragging, selectionBox]);
 
 	React.useEffect(() => {
 		document.addEventListener('keydown', handleKeyDown);
@@ -137,21 +329,21 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 	}, [handleKeyDown]);
 
 	const getSelectionStyle = () => {
-		if (!selectionBox) return {};
+		if (!selectionBox) return null;
 
 		const x = Math.min(selectionBox.startX, selectionBox.endX);
 		const y = Math.min(selectionBox.startY, selectionBox.endY);
 		const width = Math.abs(selectionBox.endX - selectionBox.startX);
 		const height = Math.abs(selectionBox.endY - selectionBox.startY);
 
-		return {
-			left: x,
-			top: y,
-			width,
-			height,
-		};
+		// Only this window's own drag needs constraining; bounds from Rust already are
+		return isDragging
+			? applyConstraint({ left: x, top: y, width, height }, constraint)
+			: { left: x, top: y, width, height };
 	};
 
+	const selectionRect = getSelectionStyle();
+
 	return (
 		<div
 			ref={overlayRef}
@@ -166,9 +358,35 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 			onMouseUp={handleMouseUp}
 			onClick={handleClick}
 		>
+			{/* Frozen screen snapshot */}
+			{frozenFrame?.imageData && (
+				<img
+					src={frozenFrame.imageData}
+					alt=""
+					draggable={false}
+					className="absolute inset-0 w-full h-full pointer-events-none"
+				/>
+			)}
+
+			{/* Dimming: everything outside the selection, or the whole screen before one exists */}
+			{config.dimOpacity > 0 && !(selectionRect && !lassoMode) && (
+				<div
+					className="absolute inset-0 pointer-events-none"
+					style={{ backgroundColor: `rgba(0, 0, 0, ${config.dimOpacity})` }}
+				/>
+			)}
+
+			{/* Crosshair guides */}
+			{config.showCrosshair && cursor && (
+				<>
+					<div className="absolute left-0 right-0 pointer-events-none" style={{ top: cursor.y, height: 1, backgroundColor: config.selectionBorderColor }} />
+					<div className="absolute top-0 bottom-0 pointer-events-none" style={{ left: cursor.x, width: 1, backgroundColor: config.selectionBorderColor }} />
+				</>
+			)}
+
 			{/* Instructions */}
 			<div className="absolute top-4 left-1/2 transform -translate-x-1/2 bg-black bg-opacity-80 text-white px-4 py-2 rounded-lg text-sm pointer-events-none">
-				🖱️ Drag to select area • ⏹️ ESC to cancel
+				{lassoMode ? '➰ Draw around the area to capture' : windowMode ? '🪟 Click a window or drag an area' : '🖱️ Drag to select area'} • L lasso • W window click • ⌨️ Arrows adjust, Enter captures • ⏹️ ESC to cancel
 			</div>
 
 			{/* Close button */}
@@ -184,22 +402,38 @@ const DragOverlay: React.FC<DragOverlayProps> = ({ onSelectionComplete, onCancel
 				×
 			</button>
 
+			{/* Lasso path */}
+			{lassoMode && lassoPath.length > 1 && (
+				<svg className="absolute inset-0 w-full h-full pointer-events-none">
+					<polygon
+						points={lassoPath.map(p => `${p.x},${p.y}`).join(' ')}
+						fill="rgba(59, 130, 246, 0.2)"
+						stroke="rgb(59, 130, 246)"
+						strokeWidth={2}
+					/>
+				</svg>
+			)}
+
 			{/* Selection box */}
-			{selectionBox && (
+			{selectionRect && !lassoMode && (
 				<div
-					className="absolute border-2 border-blue-500 bg-blue-200 bg-opacity-20 pointer-events-none"
-					style={getSelectionStyle()}
+					className={`absolute border-2 pointer-events-none ${config.dimOpacity > 0 ? '' : 'bg-blue-200 bg-opacity-20'}`}
+					style={{
+						...selectionRect,
+						borderColor: config.selectionBorderColor,
+						boxShadow: config.dimOpacity > 0 ? `0 0 0 100vmax rgba(0, 0, 0, ${config.dimOpacity})` : undefined,
+					}}
 				>
 					{/* Selection corners for visual feedback */}
-					<div className="absolute -top-1 -left-1 w-3 h-3 bg-blue-500 rounded-full"></div>
-					<div className="absolute -top-1 -right-1 w-3 h-3 bg-blue-500 rounded-full"></div>
-					<div className="absolute -bottom-1 -left-1 w-3 h-3 bg-blue-500 rounded-full"></div>
-					<div className="absolute -bottom-1 -right-1 w-3 h-3 bg-blue-500 rounded-full"></div>
+					<div className="absolute -top-1 -left-1 w-3 h-3 rounded-full" style={{ backgroundColor: config.selectionBorderColor }}></div>
+					<div className="absolute -top-1 -right-1 w-3 h-3 rounded-full" style={{ backgroundColor: config.selectionBorderColor }}></div>
+					<div className="absolute -bottom-1 -left-1 w-3 h-3 rounded-full" style={{ backgroundColor: config.selectionBorderColor }}></div>
+					<div className="absolute -bottom-1 -right-1 w-3 h-3 rounded-full" style={{ backgroundColor: config.selectionBorderColor }}></div>
 					
 					{/* Size indicator */}
-					{isDragging && (
+					{isDragging && config.showDimensions && (
 						<div className="absolute -bottom-8 left-0 bg-black bg-opacity-80 text-white px-2 py-1 rounded text-xs pointer-events-none">
-							{Math.round(Math.abs(selectionBox.endX - selectionBox.startX))} × {Math.round(Math.abs(selectionBox.endY - selectionBox.startY))}
+							{Math.round(selectionRect.width)} × {Math.round(selectionRect.height)}
 						</div>
 					)}
 				</div>
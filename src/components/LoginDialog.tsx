@@ -12,6 +12,20 @@ export const LoginDialog: React.FC<LoginDialogProps> = ({ isOpen, onClose, onLog
     const [password, setPassword] = useState('');
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState('');
+    // Set once the account asked for a two-factor code
+    const [needsCode, setNeedsCode] = useState(false);
+    const [code, setCode] = useState('');
+    const [trustDevice, setTrustDevice] = useState(false);
+
+    const finishLogin = async (user: User) => {
+        await Promise.resolve(onLoginSuccess(user));
+        onClose();
+        setEmail('');
+        setPassword('');
+        setNeedsCode(false);
+        setCode('');
+        setTrustDevice(false);
+    };
 
     const handleLogin = async (e: React.FormEvent) => {
         e.preventDefault();
@@ -19,11 +33,14 @@ export const LoginDialog: React.FC<LoginDialogProps> = ({ isOpen, onClose, onLog
         setError('');
 
         try {
-            const user = await authService.loginWithDatabase(email, password);
-            await Promise.resolve(onLoginSuccess(user));
-            onClose();
-            setEmail('');
-            setPassword('');
+            const user = needsCode
+                ? await authService.submitTotpCode(code.trim(), trustDevice)
+                : await authService.loginWithDatabase(email, password);
+            if (user) {
+                await finishLogin(user);
+            } else {
+                setNeedsCode(true);
+            }
         } catch (error) {
             console.error('❌ Login failed:', error);
             setError(error instanceof Error ? error.message : String(error));
@@ -52,6 +69,7 @@ export const LoginDialog: React.FC<LoginDialogProps> = ({ isOpen, onClose, onLog
                             onChange={(e) => setEmail(e.target.value)}
                             className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50"
                             placeholder="your@email.com"
+                            disabled={needsCode}
                             required
                         />
                     </div>
@@ -64,9 +82,36 @@ export const LoginDialog: React.FC<LoginDialogProps> = ({ isOpen, onClose, onLog
                             onChange={(e) => setPassword(e.target.value)}
                             className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50"
                             placeholder="Password"
+                            disabled={needsCode}
                             required
                         />
                     </div>
+
+                    {needsCode && (
+                        <div>
+                            <label className="block text-white/80 text-sm mb-2">Authentication code</label>
+                            <input
+                                type="text"
+                                inputMode="numeric"
+                                autoComplete="one-time-code"
+                                value={code}
+                                onChange={(e) => setCode(e.target.value)}
+                                className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50"
+                                placeholder="123456"
+                                autoFocus
+                                required
+                            />
+                            <label className="flex items-center mt-2 text-white/70 text-sm">
+                                <input
+                                    type="checkbox"
+                                    checked={trustDevice}
+                                    onChange={(e) => setTrustDevice(e.target.checked)}
+                                    className="mr-2"
+                                />
+                                Trust this device
+                            </label>
+                        </div>
+                    )}
                     
                     {error && (
                         <div className="text-red-400 text-sm">{error}</div>
@@ -78,11 +123,15 @@ export const LoginDialog: React.FC<LoginDialogProps> = ({ isOpen, onClose, onLog
                             disabled={loading}
                             className="flex-1 bg-blue-600 hover:bg-blue-700 disabled:bg-blue-800 text-white py-2 px-4 rounded-lg font-medium transition-colors"
                         >
-                            {loading ? 'Logging in...' : 'Login'}
+                            {loading ? 'Logging in...' : needsCode ? 'Verify' : 'Login'}
                         </button>
                         <button
                             type="button"
-                            onClick={onClose}
+                            onClick={() => {
+                                setNeedsCode(false);
+                                setCode('');
+                                onClose();
+                            }}
                             className="flex-1 bg-gray-600 hover:bg-gray-700 text-white py-2 px-4 rounded-lg font-medium transition-colors"
                         >
                             Cancel
@@ -30,6 +30,10 @@ export const ProfileDropdown: React.FC<ProfileDropdownProps> = ({
     const [password, setPassword] = useState('');
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState('');
+    // Set once the account asked for a two-factor code
+    const [needsCode, setNeedsCode] = useState(false);
+    const [code, setCode] = useState('');
+    const [trustDevice, setTrustDevice] = useState(false);
     const dropdownRef = useRef<HTMLDivElement>(null);
 
     // Animation state like ChatBox and ModelSelector
@@ -111,11 +115,20 @@ export const ProfileDropdown: React.FC<ProfileDropdownProps> = ({
         setError('');
 
         try {
-            const user = await authService.loginWithDatabase(email, password);
+            const user = needsCode
+                ? await authService.submitTotpCode(code.trim(), trustDevice)
+                : await authService.loginWithDatabase(email, password);
+            if (!user) {
+                setNeedsCode(true);
+                return;
+            }
             await Promise.resolve(onLoginSuccess(user));
             setShowLoginForm(false);
             setEmail('');
             setPassword('');
+            setNeedsCode(false);
+            setCode('');
+            setTrustDevice(false);
             closeProfileDropdown();
         } catch (error) {
             console.error('❌ Login failed:', error);
@@ -284,6 +297,7 @@ export const ProfileDropdown: React.FC<ProfileDropdownProps> = ({
                                                     onChange={(e) => setEmail(e.target.value)}
                                                     className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50 text-sm"
                                                     placeholder="your@email.com"
+                                                    disabled={needsCode}
                                                     required
                                                 />
                                             </div>
@@ -296,9 +310,36 @@ export const ProfileDropdown: React.FC<ProfileDropdownProps> = ({
                                                     onChange={(e) => setPassword(e.target.value)}
                                                     className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50 text-sm"
                                                     placeholder="Password"
+                                                    disabled={needsCode}
                                                     required
                                                 />
                                             </div>
+
+                                            {needsCode && (
+                                                <div>
+                                                    <label className="block text-white/80 text-sm mb-1">Authentication code</label>
+                                                    <input
+                                                        type="text"
+                                                        inputMode="numeric"
+                                                        autoComplete="one-time-code"
+                                                        value={code}
+                                                        onChange={(e) => setCode(e.target.value)}
+                                                        className="w-full px-3 py-2 bg-white/10 border border-white/20 rounded-lg text-white placeholder-white/50 text-sm"
+                                                        placeholder="123456"
+                                                        autoFocus
+                                                        required
+                                                    />
+                                                    <label className="flex items-center mt-2 text-white/70 text-sm">
+                                                        <input
+                                                            type="checkbox"
+                                                            checked={trustDevice}
+                                                            onChange={(e) => setTrustDevice(e.target.checked)}
+                                                            className="mr-2"
+                                                        />
+                                                        Trust this device
+                                                    </label>
+                                                </div>
+                                            )}
                                             
                                             {error && (
                                                 <div className="text-red-400 text-sm">{error}</div>
@@ -310,11 +351,15 @@ export const ProfileDropdown: React.FC<ProfileDropdownProps> = ({
                                                     disabled={loading}
                                                     className="flex-1 bg-blue-600 hover:bg-blue-700 disabled:bg-blue-800 text-white py-2 px-3 rounded-lg font-medium transition-colors text-sm"
                                                 >
-                                                    {loading ? 'Logging in...' : 'Login'}
+                                                    {loading ? 'Logging in...' : needsCode ? 'Verify' : 'Login'}
                                                 </button>
                                                 <button
                                                     type="button"
-                                                    onClick={() => setShowLoginForm(false)}
+                                                    onClick={() => {
+                                                        setShowLoginForm(false);
+                                                        setNeedsCode(false);
+                                                        setCode('');
+                                                    }}
                                                     className="flex-1 bg-gray-600 hover:bg-gray-700 text-white py-2 px-3 rounded-lg font-medium transition-colors text-sm"
                                                 >
                                                     Cancel
@@ -3,6 +3,27 @@ import { fetch } from '@tauri-apps/plugin-http';
 import { Store } from '@tauri-apps/plugin-store';
 import userService, { User } from './user-service';
 
+// User as the backend's auth commands return it
+interface TauriUser {
+    id: string;
+    email: string;
+    name: string;
+    tier: string;
+    token: string;
+    refresh_token?: string | null;
+    usage: { daily: number; total: number; last_reset: string };
+    created_at: string;
+    subscription_status?: string | null;
+    stripe_customer_id?: string | null;
+    usage_daily?: number | null;
+    usage_total?: number | null;
+    updated_at?: string | null;
+}
+
+type TauriLoginResult =
+    | { status: 'success'; user: TauriUser }
+    | { status: 'twoFactorRequired'; email: string };
+
 class AuthService {
     private currentUser: User | null = null;
     private authListeners: Array<(user: User | null) => void> = [];
@@ -81,114 +102,62 @@ class AuthService {
         }
     }
 
-    async loginWithDatabase(email: string, password: string): Promise<User> {
+    // Sign-in goes through the backend's login_user command so two-factor accounts work.
+    // Resolves with null when the account wants a TOTP code (see submitTotpCode).
+    async loginWithDatabase(email: string, password: string): Promise<User | null> {
         try {
             console.log('🔐 Logging in user with backend API:', email);
-
-            // Use Tauri HTTP client for secure requests
-            console.log('🔍 LOGIN: Making request to:', `${this.apiUrl}/api/auth/login`);
-            const response = await fetch(`${this.apiUrl}/api/auth/login`, {
-                method: 'POST',
-                headers: {
-                    'Content-Type': 'application/json',
-                },
-                body: JSON.stringify({ email, password }),
-            });
-
-            if (!response.ok) {
-                const errorData = await response.json();
-                throw new Error(errorData.message || 'Login failed');
-            }
-
-            const data = await response.json();
-
-            if (!data.success || !data.user) {
-                throw new Error('Invalid response from server');
-            }
-
-            const user = data.user;
-
-            // Store access token in memory (short-lived)
-            if (data.access_token) {
-                userService.setAccessToken(data.access_token);
-                console.log('✅ Access token stored in memory');
-            }
-
-            // Store refresh token securely (long-lived)
-            if (data.refresh_token) {
-                await this.saveRefreshToken(data.refresh_token);
-                console.log('✅ Refresh token stored securely');
-            }
-
-            // Fallback for old token format
-            if (data.token && !data.access_token) {
-                userService.setAccessToken(data.token);
-                console.log('✅ Legacy token stored in memory');
-            }
-
-						this.saveUserSessionLocal(user);
-						console.log('✅ DEBUG: User session saved to localStorage');
-
-            // Use tier from backend response
-            // user.tier is already set correctly from backend
-
-            this.currentUser = user;
-
-            console.log('🔍 DEBUG: About to save user session:', {
-                email: user.email,
-                tier: user.tier,
-                hasToken: !!user.token,
-                tokenLength: user.token ? user.token.length : 0
-            });
-
-            // Save user session to BOTH Tauri storage AND in memory
-            try {
-                // Convert user to Tauri-compatible format
-                const tauriUser = {
-                    id: user.id,
-                    email: user.email,
-                    name: user.name,
-                    tier: user.tier,
-                    token: data.token,
-                    usage: {
-                        daily: user.usage_daily || 0,
-                        total: user.usage_total || 0,
-                        last_reset: new Date().toISOString().split('T')[0] // YYYY-MM-DD format
-                    },
-                    created_at: user.created_at,
-                    subscription_status: user.subscription_status,
-                    stripe_customer_id: user.stripe_customer_id,
-                    usage_daily: user.usage_daily,
-                    usage_total: user.usage_total,
-                    updated_at: user.updated_at
-                };
-
-                console.log('🔍 DEBUG: Converted user for Tauri:', {
-                    email: tauriUser.email,
-                    tier: tauriUser.tier,
-                    hasUsage: !!tauriUser.usage,
-                    usageDaily: tauriUser.usage.daily
-                });
-
-                // @ts-ignore - invoke is available in Tauri context
-                await invoke('save_user_session', { user: tauriUser });
-                console.log('✅ DEBUG: User session saved to Tauri storage successfully');
-            } catch (error) {
-                console.error('❌ DEBUG: Failed to save to Tauri storage:', error);
-                console.log('ℹ️ DEBUG: Using localStorage only as fallback');
+            const result = await invoke<TauriLoginResult>('login_user', { email, password });
+            if (result.status === 'twoFactorRequired') {
+                console.log('🔐 Two-factor code required for', result.email);
+                return null;
             }
-
-            // Notify listeners
-            this.notifyAuthListeners(user);
-
-            console.log('✅ User logged in successfully:', user.email, user.tier);
-            return user;
+            return await this.completeLogin(result.user);
         } catch (error) {
             console.error('❌ Login failed:', error);
             throw new Error(`Login failed: ${error}`);
         }
     }
 
+    // Second step of a login that returned null
+    async submitTotpCode(code: string, trustDevice: boolean): Promise<User> {
+        try {
+            const tauriUser = await invoke<TauriUser>('submit_totp_code', { code, trustDevice });
+            return await this.completeLogin(tauriUser);
+        } catch (error) {
+            console.error('❌ Two-factor verification failed:', error);
+            throw new Error(`Verification failed: ${error}`);
+        }
+    }
+
+    // The backend has already saved the session; keep the tokens and notify listeners
+    private async completeLogin(tauriUser: TauriUser): Promise<User> {
+        const user: User = {
+            id: tauriUser.id,
+            email: tauriUser.email,
+            name: tauriUser.name,
+            tier: tauriUser.tier,
+            subscription_status: tauriUser.subscription_status ?? '',
+            stripe_customer_id: tauriUser.stripe_customer_id ?? undefined,
+            usage_daily: tauriUser.usage_daily ?? tauriUser.usage.daily,
+            usage_total: tauriUser.usage_total ?? tauriUser.usage.total,
+            created_at: tauriUser.created_at,
+            updated_at: tauriUser.updated_at ?? tauriUser.created_at,
+            token: tauriUser.token,
+        };
+
+        userService.setAccessToken(tauriUser.token);
+        if (tauriUser.refresh_token) {
+            await this.saveRefreshToken(tauriUser.refresh_token);
+        }
+        this.saveUserSessionLocal(user);
+        this.currentUser = user;
+        this.notifyAuthListeners(user);
+
+        console.log('✅ User logged in successfully:', user.email, user.tier);
+        return user;
+    }
+
 		// Kolla om denna kan fungera istället för att manuellt refresha token vid 401. Tanken är
 	  // att man ska skicka in fetch-funktionen i denna funktion och att den refreshar automatiskt då
 		// Den verkar för tillfället inte fungera
@@ -629,6 +598,18 @@ async logout(): Promise<void> {
 
     // Payment and upgrade functionality
     openUpgradePage(plan?: string): void {
+        // Signed-in users go straight to Stripe checkout; the web page is the fallback
+        if (this.currentUser) {
+            invoke('start_checkout', { plan: plan || 'premium' }).catch((error) => {
+                console.error('❌ Checkout failed, opening upgrade page instead:', error);
+                this.openUpgradePageInBrowser(plan);
+            });
+            return;
+        }
+        this.openUpgradePageInBrowser(plan);
+    }
+
+    private openUpgradePageInBrowser(plan?: string): void {
         const baseUrl = 'https://vely22.vercel.app'; // Hardcoded for simplicity
         const upgradeUrl = plan
             ? `${baseUrl}/payments?plan=${plan}`
@@ -1,4 +1,4 @@
-import type { IAIService, AIRequest, AIResponse } from '../types/ai-types';
+import type { IAIService, AIRequest, AIResponse, ResolvedAIParams } from '../types/ai-types';
 import userService from "./user-service";
 import { authService } from "./auth-service-db";
 import { invoke } from "@tauri-apps/api/core";
@@ -51,11 +51,23 @@ export class OpenAIServiceAPI implements IAIService {
       // Always include conversationId, even if null/undefined, so backend can handle it properly
       formData.append('conversationId', request.conversationId || '');
 
-      // Add model selection from store
+      // Add model selection from store, with per-conversation/per-message overrides resolved in Rust
       const { useAppStore } = await import('../stores/app-store.js');
-      const currentModel = useAppStore.getState().selectedModel;
-      formData.append('model', currentModel);
-      console.log('🤖 DEBUG: Frontend sending model:', currentModel);
+      const params = await invoke<ResolvedAIParams>('resolve_message_params', {
+        conversationId: request.conversationId || null,
+        overrides: {
+          model: request.model,
+          temperature: request.temperature,
+          systemPrompt: request.systemPrompt,
+        },
+        defaultModel: useAppStore.getState().selectedModel,
+      });
+      formData.append('model', params.model);
+      formData.append('temperature', String(params.temperature));
+      if (params.systemPrompt) {
+        formData.append('systemPrompt', params.systemPrompt);
+      }
+      console.log('🤖 DEBUG: Frontend sending model:', params.model, 'temperature:', params.temperature);
 
       console.log('🔍 DEBUG: FormData conversationId value:', request.conversationId || '(empty string for null/undefined)');
 
@@ -14,6 +14,15 @@ export interface AIRequest {
   imageType?: string; // "image/png" etc
   conversationId?: string;
   model?: string; // Selected AI model
+  temperature?: number; // Per-message override of the conversation temperature
+  systemPrompt?: string; // Per-message override of the conversation system prompt
+}
+
+// Effective parameters resolved by Rust (message overrides > conversation params > defaults)
+export interface ResolvedAIParams {
+  model: string;
+  temperature: number;
+  systemPrompt?: string;
 }
 
 export interface AIResponse {